@@ -1,9 +1,15 @@
-use std::io::{BufRead, BufReader};
+mod repeats;
+
+use std::io::BufReader;
 use std::net::TcpStream;
+use std::time::{Duration, Instant};
 
 use adsb_deku::deku::DekuContainerRead;
-use adsb_deku::Frame;
+use adsb_deku::{ErrorKind, Frame};
 use clap::Parser;
+use rsadsb_apps::source::{AvrReader, ReadEvent, StreamEnd, Trailing};
+
+use crate::repeats::Repeats;
 
 #[derive(Debug, Parser)]
 #[clap(
@@ -28,54 +34,107 @@ struct Options {
     /// Display debug of adsb::Frame
     #[clap(long)]
     debug: bool,
+    /// Reconnect to the demodulator when the connection is closed or lost, instead of exiting
+    #[clap(long)]
+    retry_tcp: bool,
+    /// Split bytes after the Mode S frame off the frames, for receivers that append bytes such as
+    /// a signal level within the AVR frame
+    #[clap(long)]
+    split_trailing: bool,
+    /// Count copies of a printed frame received within SECONDS instead of printing them, printing
+    /// a single line with the amount of copies after the SECONDS
+    #[clap(long, value_name = "SECONDS")]
+    collapse_repeats: Option<u64>,
+}
+
+/// Print the frames of `--collapse-repeats` with their amount of copies not printed
+fn print_repeats(frames: Vec<(Vec<u8>, u32)>) {
+    for (bytes, copies) in frames {
+        println!("{} (repeated {copies} more times)", hex::encode(&bytes));
+    }
+}
+
+/// Connect to the demodulator, retrying every second
+fn connect(host: &str, port: u16, trailing: Trailing) -> AvrReader<BufReader<TcpStream>> {
+    loop {
+        match AvrReader::connect((host, port)) {
+            Ok(reader) => return reader.with_trailing(trailing),
+            Err(e) => {
+                eprintln!("[E] unable to connect to {host}:{port}: {e}, retrying");
+                std::thread::sleep(Duration::from_secs(1));
+            },
+        }
+    }
 }
 
 fn main() {
     let options = Options::parse();
-    let stream = TcpStream::connect((options.host, options.port)).unwrap();
-    stream
-        .set_read_timeout(Some(std::time::Duration::from_millis(50)))
-        .unwrap();
-    let mut reader = BufReader::new(stream);
-    let mut input = String::new();
+    let trailing = if options.split_trailing {
+        Trailing::Split
+    } else {
+        Trailing::Keep
+    };
+    let mut reader = AvrReader::connect((options.host.as_str(), options.port))
+        .unwrap()
+        .with_trailing(trailing);
+    let mut repeats = options
+        .collapse_repeats
+        .map(|seconds| Repeats::new(Duration::from_secs(seconds)));
 
     loop {
-        input.clear();
-        if let Ok(len) = reader.read_line(&mut input) {
-            if len == 0 {
+        if let Some(repeats) = &mut repeats {
+            print_repeats(repeats.expire(Instant::now()));
+        }
+        let bytes = match reader.poll_frame() {
+            ReadEvent::Frame(bytes) => bytes,
+            ReadEvent::Idle => continue,
+            ReadEvent::End(end) => {
+                if let Some(repeats) = &mut repeats {
+                    print_repeats(repeats.drain());
+                }
+                eprintln!("[E] {}:{} {end}", options.host, options.port);
+                if options.retry_tcp {
+                    reader = connect(&options.host, options.port, trailing);
+                    continue;
+                }
+                // only a lost connection is an error, a closed one is the end of the input
+                match end {
+                    StreamEnd::Eof => return,
+                    StreamEnd::Disconnected(_) => std::process::exit(1),
+                }
+            },
+        };
+        if let Some(repeats) = &mut repeats {
+            if !repeats.check(Instant::now(), &bytes) {
                 continue;
             }
-            // convert from string hex -> bytes
-            let hex = &mut input.to_string()[1..len - 2].to_string();
-            println!("{}", hex.to_lowercase());
-            let bytes = if let Ok(bytes) = hex::decode(&hex) {
-                bytes
-            } else {
-                continue;
-            };
+        }
+        println!("{}", hex::encode(&bytes));
+        if !reader.trailing().is_empty() {
+            println!("trailing: {}", hex::encode(reader.trailing()));
+        }
 
-            // check for all 0's
-            if bytes.iter().all(|&b| b == 0) {
-                continue;
-            }
+        // check for all 0's
+        if bytes.iter().all(|&b| b == 0) {
+            continue;
+        }
 
-            // decode
-            match Frame::from_bytes((&bytes, 0)) {
-                Ok((_, frame)) => {
-                    if options.debug {
-                        println!("{:#?}", frame);
-                    }
-                    println!("{}", frame);
-                    assert!(
-                        !((frame.to_string() == "") && options.panic_display),
-                        "[E] fmt::Display not implemented"
-                    );
-                },
-                Err(e) => {
-                    assert!(!options.panic_decode, "[E] {}", e);
-                },
-            }
-            input.clear();
+        // decode
+        match Frame::from_bytes((&bytes, 0)) {
+            Ok((_, frame)) => {
+                if options.debug {
+                    println!("{:#?}", frame);
+                }
+                println!("{}", frame);
+                assert!(
+                    !((frame.to_string() == "") && options.panic_display),
+                    "[E] fmt::Display not implemented"
+                );
+            },
+            Err(e) => {
+                let kind = ErrorKind::from_deku(&bytes, &e);
+                assert!(!options.panic_decode, "[E] {kind} error: {e}");
+            },
         }
     }
 }
@@ -0,0 +1,96 @@
+//! Collapsing of identical frames for `--collapse-repeats`
+//!
+//! Aircraft parked with their transponder on send the same few frames over and over, such as
+//! their DF11 all-call reply and identification. Copies of a printed frame received within the
+//! window are only counted, and printed as a single line once the window of the frame has passed.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+pub struct Repeats {
+    window: Duration,
+    /// Printed frames, with the time they were printed and the amount of copies since
+    frames: HashMap<Vec<u8>, (Instant, u32)>,
+    /// Printed frames in order of the time they were printed
+    printed: VecDeque<(Instant, Vec<u8>)>,
+}
+
+impl Repeats {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            frames: HashMap::new(),
+            printed: VecDeque::new(),
+        }
+    }
+
+    /// Returns `true` if `bytes` should be printed, otherwise the copy is counted
+    ///
+    /// Call [`Self::expire`] before, for the copies of the frames whose window has passed.
+    pub fn check(&mut self, now: Instant, bytes: &[u8]) -> bool {
+        if let Some((_, copies)) = self.frames.get_mut(bytes) {
+            *copies += 1;
+            return false;
+        }
+        self.frames.insert(bytes.to_vec(), (now, 0));
+        self.printed.push_back((now, bytes.to_vec()));
+        true
+    }
+
+    /// Remove the frames printed at least `window` before `now`, returning the ones with copies
+    /// and their amount of copies
+    pub fn expire(&mut self, now: Instant) -> Vec<(Vec<u8>, u32)> {
+        let mut expired = vec![];
+        while let Some((printed, _)) = self.printed.front() {
+            if now.saturating_duration_since(*printed) < self.window {
+                break;
+            }
+            // PANIC: checked above
+            let (_, bytes) = self.printed.pop_front().unwrap();
+            if let Some((_, copies)) = self.frames.remove(&bytes) {
+                if copies > 0 {
+                    expired.push((bytes, copies));
+                }
+            }
+        }
+        expired
+    }
+
+    /// Remove all frames, returning the ones with copies and their amount of copies
+    pub fn drain(&mut self) -> Vec<(Vec<u8>, u32)> {
+        self.printed.clear();
+        let mut remaining: Vec<_> = self
+            .frames
+            .drain()
+            .filter(|(_, (_, copies))| *copies > 0)
+            .map(|(bytes, (_, copies))| (bytes, copies))
+            .collect();
+        remaining.sort();
+        remaining
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapse() {
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        let mut repeats = Repeats::new(Duration::from_secs(10));
+
+        assert!(repeats.check(at(0), &[0x5d, 0x01]));
+        assert!(repeats.check(at(1), &[0x8d, 0x02]));
+        assert!(!repeats.check(at(2), &[0x5d, 0x01]));
+        assert!(!repeats.check(at(3), &[0x5d, 0x01]));
+        assert!(repeats.expire(at(9)).is_empty());
+
+        // window of the first frame has passed, the second frame had no copies
+        assert_eq!(repeats.expire(at(11)), vec![(vec![0x5d, 0x01], 2)]);
+        assert!(repeats.check(at(12), &[0x5d, 0x01]));
+        assert!(!repeats.check(at(13), &[0x5d, 0x01]));
+        assert_eq!(repeats.drain(), vec![(vec![0x5d, 0x01], 1)]);
+        assert!(repeats.check(at(14), &[0x5d, 0x01]));
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+
+use adsb_deku::deku::DekuContainerRead;
+use adsb_deku::{Frame, Squawk, ICAO};
+use anyhow::{Context, Result};
+use clap::Parser;
+use rsadsb_apps::source::AvrReader;
+use rsadsb_common::Airplanes;
+
+#[derive(Debug, Parser)]
+#[clap(
+    version,
+    name = "capture_diff",
+    author = "wcampbell0x2a",
+    about = "Decode two AVR captures and report the differences in tracked aircraft"
+)]
+struct Options {
+    /// Known-good AVR capture
+    before: PathBuf,
+    /// AVR capture to compare against `before`
+    after: PathBuf,
+    /// Antenna location latitude, used for aircraft position algorithms
+    #[clap(long)]
+    lat: f64,
+    /// Antenna location longitude, used for aircraft position algorithms
+    #[clap(long)]
+    long: f64,
+}
+
+/// Derived state of an aircraft at the end of a capture
+#[derive(Debug, Default, PartialEq)]
+struct Summary {
+    messages: u32,
+    callsign: Option<String>,
+    squawk: Option<Squawk>,
+    /// Amount of previous positions in the track
+    track_len: usize,
+    /// Last (latitude, longitude, altitude)
+    position: Option<(f64, f64, u16)>,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "messages: {}, callsign: {:?}, squawk: {:?}, track: {}",
+            self.messages,
+            self.callsign,
+            self.squawk.map(|squawk| squawk.to_string()),
+            self.track_len
+        )?;
+        if let Some((lat, long, alt)) = self.position {
+            write!(f, ", position: ({lat:.5}, {long:.5}) {alt} ft")?;
+        }
+        Ok(())
+    }
+}
+
+fn summary(airplanes: &Airplanes, icao: ICAO) -> Option<Summary> {
+    let state = airplanes.get(icao)?;
+    let position = airplanes.aircraft_details(icao).map(|details| {
+        (
+            details.position.latitude,
+            details.position.longitude,
+            details.altitude,
+        )
+    });
+    Some(Summary {
+        messages: state.num_messages,
+        callsign: state.callsign.clone(),
+        squawk: state.squawk,
+        track_len: state.track.as_ref().map_or(0, Vec::len),
+        position,
+    })
+}
+
+/// Decode all frames of the AVR capture in `reader`
+fn decode<R: BufRead>(reader: R, lat_long: (f64, f64)) -> Result<Airplanes> {
+    let mut airplanes = Airplanes::new();
+
+    for bytes in AvrReader::new(reader) {
+        let bytes = bytes?;
+        if let Ok((_, frame)) = Frame::from_bytes((&bytes, 0)) {
+            if let Ok(frame) = frame.validate() {
+                airplanes.action(frame, lat_long);
+            }
+        }
+    }
+
+    Ok(airplanes)
+}
+
+/// Lines describing every aircraft that differs between `before` and `after`
+fn diff(before: &Airplanes, after: &Airplanes) -> Vec<String> {
+    let icaos: BTreeSet<ICAO> = before.keys().chain(after.keys()).copied().collect();
+    icaos
+        .into_iter()
+        .filter_map(|icao| match (summary(before, icao), summary(after, icao)) {
+            (Some(before), Some(after)) if before != after => {
+                Some(format!("~ {icao}\n    - {before}\n    + {after}"))
+            },
+            (Some(before), None) => Some(format!("- {icao}: {before}")),
+            (None, Some(after)) => Some(format!("+ {icao}: {after}")),
+            _ => None,
+        })
+        .collect()
+}
+
+fn main() -> Result<()> {
+    let options = Options::parse();
+    let lat_long = (options.lat, options.long);
+
+    let mut decoded = vec![];
+    for capture in [&options.before, &options.after] {
+        let file =
+            File::open(capture).with_context(|| format!("unable to open {}", capture.display()))?;
+        let airplanes = decode(BufReader::new(file), lat_long)
+            .with_context(|| format!("unable to read {}", capture.display()))?;
+        decoded.push(airplanes);
+    }
+    // PANIC: both captures were decoded above
+    let after = decoded.pop().unwrap();
+    let before = decoded.pop().unwrap();
+
+    let differences = diff(&before, &after);
+    for line in &differences {
+        println!("{line}");
+    }
+    println!(
+        "{} aircraft before, {} aircraft after, {} differ",
+        before.len(),
+        after.len(),
+        differences.len()
+    );
+
+    if !differences.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_captures() {
+        let before = b"*8da2c1bd587ba2adb31799cb802b;\n*5da039b46d7d81;\n";
+        let after = b"*8da2c1bd587ba2adb31799cb802b;\n*8da2c1bd587ba2adb31799cb802b;\n";
+        let before = decode(&before[..], (0.0, 0.0)).unwrap();
+        let after = decode(&after[..], (0.0, 0.0)).unwrap();
+
+        assert_eq!(
+            diff(&before, &after),
+            vec![
+                "- A039B4: messages: 1, callsign: None, squawk: None, track: 0".to_string(),
+                "~ A2C1BD\n    - messages: 1, callsign: None, squawk: None, track: 1\n    + \
+                 messages: 2, callsign: None, squawk: None, track: 1"
+                    .to_string(),
+            ]
+        );
+        assert!(diff(&after, &after).is_empty());
+    }
+}
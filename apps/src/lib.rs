@@ -0,0 +1,6 @@
+//! Common functionality shared between the `radar`, `1090` and `sync` applications
+
+pub mod source;
+pub mod sync;
+pub mod throttle;
+pub mod timestamp;
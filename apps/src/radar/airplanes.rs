@@ -1,24 +1,53 @@
+use std::time::SystemTime;
+
+use adsb_deku::ICAO;
 use rsadsb_common::{AirplaneDetails, Airplanes};
-use tui::layout::{Constraint, Rect};
+use tui::layout::{Constraint, Direction, Layout, Rect};
 use tui::style::{Color, Modifier, Style};
 use tui::widgets::{Block, Borders, Row, Table, TableState};
 
-use crate::DEFAULT_PRECISION;
+use crate::vertical_profile::build_profile;
+use crate::{Settings, DEFAULT_PRECISION};
+
+/// Aircraft in the order of the rows of the Airplanes tab
+///
+/// Sorted by ICAO address, or with `Settings::sort_cpa` by the distance of the closest point of
+/// approach with the aircraft without one last.
+pub fn airplane_order(settings: &Settings, adsb_airplanes: &Airplanes) -> Vec<ICAO> {
+    let mut keys: Vec<ICAO> = adsb_airplanes.keys().copied().collect();
+    if settings.sort_cpa {
+        let point = settings.cpa_point();
+        keys.sort_by_cached_key(|key| {
+            adsb_airplanes
+                .get(*key)
+                .and_then(|state| state.closest_approach(point))
+                .map_or(u64::MAX, |approach| (approach.distance * 1000.0) as u64)
+        });
+    }
+    keys
+}
 
 /// Render Airplanes tab for tui display
+///
+/// With `Settings::vertical_profile`, the vertical profile of the selected aircraft is shown below
+/// the table.
 pub fn build_tab_airplanes<A: tui::backend::Backend>(
     f: &mut tui::Frame<A>,
     chunks: Vec<Rect>,
+    settings: &Settings,
     adsb_airplanes: &Airplanes,
     airplanes_state: &mut TableState,
+    now: SystemTime,
 ) {
+    let order = airplane_order(settings, adsb_airplanes);
     let mut rows = vec![];
     // make a vec of all strings to get a total amount of airplanes with
     // position information
     let empty = "".to_string();
-    for key in adsb_airplanes.keys() {
-        let state = adsb_airplanes.get(*key).unwrap();
-        let aircraft_details = adsb_airplanes.aircraft_details(*key);
+    let cpa_point = settings.cpa_point();
+    for &key in &order {
+        let state = adsb_airplanes.get(key).unwrap();
+        let aircraft_details = adsb_airplanes.aircraft_details(key);
         let mut lat = empty.clone();
         let mut lon = empty.clone();
         let mut alt = empty.clone();
@@ -40,7 +69,29 @@ pub fn build_tab_airplanes<A: tui::backend::Backend>(
             .heading
             .map_or_else(|| "".to_string(), |heading| format!("{heading:>7.1}"));
 
-        rows.push(Row::new(vec![
+        // highlight aircraft inside the alert zone
+        let style = if settings
+            .alert_zone
+            .as_ref()
+            .map_or(false, |alert_zone| alert_zone.contains(key))
+        {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default()
+        };
+
+        let (cpa, cpa_time) = state.closest_approach(cpa_point).map_or_else(
+            || (empty.clone(), empty.clone()),
+            |approach| {
+                let seconds = approach.time.as_secs();
+                (
+                    format!("{:>7.1}", approach.distance),
+                    format!("{:>3}:{:02}", seconds / 60, seconds % 60),
+                )
+            },
+        );
+
+        let row = Row::new(vec![
             format!("{key}"),
             state.callsign.as_ref().unwrap_or(&empty).clone(),
             lat,
@@ -54,8 +105,12 @@ pub fn build_tab_airplanes<A: tui::backend::Backend>(
                 .speed
                 .map_or_else(|| "".into(), |v| format!("{v:>5.0}")),
             format!("{:>8}", s_kilo_distance),
+            cpa,
+            cpa_time,
             format!("{:>4}", state.num_messages),
-        ]));
+        ])
+        .style(style);
+        rows.push(row);
     }
 
     let rows_len = rows.len();
@@ -81,13 +136,19 @@ pub fn build_tab_airplanes<A: tui::backend::Backend>(
                 "   FPM",
                 "Speed",
                 "Distance",
+                "    CPA",
+                "CPA in",
                 "Msgs",
             ])
             .bottom_margin(1),
         )
         .block(
             Block::default()
-                .title(format!("Airplanes({rows_len})"))
+                .title(if settings.sort_cpa {
+                    format!("Airplanes({rows_len}) - sorted by CPA")
+                } else {
+                    format!("Airplanes({rows_len})")
+                })
                 .borders(Borders::ALL),
         )
         .widths(&[
@@ -100,10 +161,26 @@ pub fn build_tab_airplanes<A: tui::backend::Backend>(
             Constraint::Length(6),
             Constraint::Length(5),
             Constraint::Length(8),
+            Constraint::Length(7),
+            Constraint::Length(6),
             Constraint::Length(6),
         ])
         .column_spacing(1)
         .highlight_style(Style::default().add_modifier(Modifier::BOLD))
         .highlight_symbol(">> ");
-    f.render_stateful_widget(table, chunks[1], &mut airplanes_state.clone());
+
+    let selected = airplanes_state
+        .selected()
+        .and_then(|selected| order.get(selected));
+    match (settings.vertical_profile, selected) {
+        (Some(axis), Some(icao)) => {
+            let vertical_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(chunks[1]);
+            f.render_stateful_widget(table, vertical_chunks[0], &mut airplanes_state.clone());
+            build_profile(f, vertical_chunks[1], settings, *icao, axis, now);
+        },
+        _ => f.render_stateful_widget(table, chunks[1], &mut airplanes_state.clone()),
+    }
 }
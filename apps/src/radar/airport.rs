@@ -1,7 +1,10 @@
 use std::fs::File;
 
+use anyhow::{Context, Result};
 use serde::Deserialize;
 
+/// Row from the airportsdata csv file, not all columns are currently used
+#[allow(dead_code)]
 #[derive(Clone, Debug, Deserialize)]
 pub struct Airport {
     pub icao: String,
@@ -17,13 +20,13 @@ pub struct Airport {
 }
 
 impl Airport {
-    pub fn from_file(filename: &str, time_zones: &Option<String>) -> Vec<Self> {
+    pub fn from_file(filename: &str, time_zones: &Option<String>) -> Result<Vec<Self>> {
         let mut airports = vec![];
-        let f = File::open(filename).unwrap();
+        let f = File::open(filename).with_context(|| format!("unable to open {filename}"))?;
 
         let mut rdr = csv::Reader::from_reader(f);
         for result in rdr.deserialize() {
-            let record: Self = result.unwrap();
+            let record: Self = result.with_context(|| format!("unable to read {filename}"))?;
 
             if let Some(ref time_zones) = time_zones {
                 for tz in time_zones.split(',') {
@@ -37,6 +40,6 @@ impl Airport {
                 continue;
             }
         }
-        airports
+        Ok(airports)
     }
 }
@@ -0,0 +1,174 @@
+//! Traffic alert zone around ownship
+//!
+//! When `--alert-range` is used, aircraft within the range and within `--alert-altitude` of our
+//! own altitude (from gpsd) are highlighted. Each aircraft entering the zone rings the terminal
+//! bell and runs the optional `--alert-command`, with a callout of the aircraft for
+//! text-to-speech.
+
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use adsb_deku::ICAO;
+use rsadsb_common::{AirplaneDetails, Airplanes};
+use tracing::{error, info};
+use tui::style::Color;
+use tui::widgets::canvas::{Context, Points};
+
+use crate::callout::Observer;
+use crate::Settings;
+
+/// Kilometers in a nautical mile
+pub const KM_PER_NM: f64 = 1.852;
+
+/// Feet in a meter
+pub const FEET_PER_METER: f64 = 3.28084;
+
+/// Amount of points used to draw the edge of the zone
+const EDGE_POINTS: usize = 90;
+
+pub struct AlertZone {
+    /// Horizontal radius in NM
+    range: f64,
+    /// Vertical distance in ft above and below ownship
+    altitude: u16,
+    /// Command run with `sh -c` for every aircraft entering the zone
+    command: Option<String>,
+    /// Aircraft currently inside the zone
+    intruders: BTreeSet<ICAO>,
+}
+
+impl AlertZone {
+    pub fn new(range: f64, altitude: u16, command: Option<String>) -> Self {
+        Self {
+            range,
+            altitude,
+            command,
+            intruders: BTreeSet::new(),
+        }
+    }
+
+    /// Aircraft is currently inside the zone
+    pub fn contains(&self, icao: ICAO) -> bool {
+        self.intruders.contains(&icao)
+    }
+
+    /// Update the aircraft inside the zone, alerting for every aircraft that entered it
+    ///
+    /// Without a known altitude of the `observer`, only the horizontal range is checked.
+    pub fn update(&mut self, airplanes: &Airplanes, observer: &Observer) {
+        let mut intruders = BTreeSet::new();
+        for icao in airplanes.keys() {
+            if let Some(details) = airplanes.aircraft_details(*icao) {
+                if intrudes(self.range, self.altitude, &details, observer.altitude) {
+                    if !self.intruders.contains(icao) {
+                        let vertical_rate = airplanes.get(*icao).and_then(|state| state.vert_speed);
+                        let callout = observer.callout(&details, vertical_rate);
+                        self.alert(*icao, &details, &callout);
+                    }
+                    intruders.insert(*icao);
+                }
+            }
+        }
+        self.intruders = intruders;
+    }
+
+    /// Ring the terminal bell and run the alert command
+    fn alert(&self, icao: ICAO, details: &AirplaneDetails, callout: &str) {
+        let distance = details.kilo_distance / KM_PER_NM;
+        info!(
+            "[{icao}] entered alert zone: {distance:.1} NM, {} ft",
+            details.altitude
+        );
+
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+
+        if let Some(command) = &self.command {
+            let child = Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .env("RSADSB_ICAO", icao.to_string())
+                .env("RSADSB_DISTANCE", format!("{distance:.1}"))
+                .env("RSADSB_ALTITUDE", details.altitude.to_string())
+                .env("RSADSB_CALLOUT", callout)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+            match child {
+                // reap the child without blocking the tui
+                Ok(mut child) => {
+                    std::thread::spawn(move || child.wait());
+                },
+                Err(e) => error!("unable to run --alert-command: {e}"),
+            }
+        }
+    }
+
+    /// Draw the edge of the zone around ownship
+    pub fn draw(&self, ctx: &mut Context<'_>, settings: &Settings) {
+        let (lat, long) = (settings.lat, settings.long);
+        // degrees of latitude are ~60 NM apart, degrees of longitude shrink towards the poles
+        let lat_range = self.range / 60.0;
+        let long_range = lat_range / lat.to_radians().cos();
+        let coords: Vec<(f64, f64)> = (0..EDGE_POINTS)
+            .map(|i| {
+                let angle = (i as f64 / EDGE_POINTS as f64) * std::f64::consts::TAU;
+                settings.to_xy(
+                    lat + lat_range * angle.cos(),
+                    long + long_range * angle.sin(),
+                )
+            })
+            .collect();
+        ctx.draw(&Points {
+            coords: &coords,
+            color: Color::Yellow,
+        });
+    }
+}
+
+/// Aircraft is within `range` NM and, if the altitude of ownship is known, `altitude` ft of
+/// ownship
+fn intrudes(
+    range: f64,
+    altitude: u16,
+    details: &AirplaneDetails,
+    ownship_altitude: Option<f64>,
+) -> bool {
+    let horizontal = details.kilo_distance / KM_PER_NM <= range;
+    let vertical = ownship_altitude.map_or(true, |ownship_altitude| {
+        (f64::from(details.altitude) - ownship_altitude).abs() <= f64::from(altitude)
+    });
+    horizontal && vertical
+}
+
+#[cfg(test)]
+mod tests {
+    use adsb_deku::cpr;
+
+    use super::*;
+
+    fn details(kilo_distance: f64, altitude: u16) -> AirplaneDetails {
+        AirplaneDetails {
+            position: cpr::Position {
+                latitude: 0.0,
+                longitude: 0.0,
+            },
+            altitude,
+            kilo_distance,
+            heading: None,
+            track: None,
+        }
+    }
+
+    #[test]
+    fn intruding() {
+        // 5 NM, 2000 ft
+        assert!(intrudes(5.0, 2000, &details(9.0, 3000), Some(1500.0)));
+        assert!(!intrudes(5.0, 2000, &details(9.5, 3000), Some(1500.0)));
+        assert!(!intrudes(5.0, 2000, &details(9.0, 3600), Some(1500.0)));
+        // unknown ownship altitude
+        assert!(intrudes(5.0, 2000, &details(9.0, 30000), None));
+    }
+}
@@ -0,0 +1,147 @@
+//! Spoken-style traffic callouts, such as "Traffic, 3 o'clock, 5 miles, 2000 feet above,
+//! descending"
+//!
+//! The clock position is relative to the heading of the [`Observer`], chosen with
+//! `--callout-reference`, so the callouts can be passed by `--alert-command` to a text-to-speech
+//! program.
+
+use rsadsb_common::AirplaneDetails;
+
+use crate::alert::KM_PER_NM;
+
+/// Vertical rate in ft/min above which traffic is called out as climbing or descending
+const VERTICAL_RATE: i16 = 300;
+
+/// Position, altitude and orientation callouts are made from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observer {
+    /// (lat, long)
+    pub position: (f64, f64),
+    /// Altitude in ft, if known from gpsd
+    pub altitude: Option<f64>,
+    /// Heading in degrees of 12 o'clock
+    pub heading: f64,
+}
+
+impl Observer {
+    /// Callout of an aircraft, with its vertical rate in ft/min if known
+    ///
+    /// Without a known altitude of the observer, the altitude of the aircraft is called out
+    /// instead of the difference.
+    pub fn callout(&self, details: &AirplaneDetails, vertical_rate: Option<i16>) -> String {
+        let position = (details.position.latitude, details.position.longitude);
+        let relative = bearing(self.position, position) - self.heading;
+        let mut callout = format!("Traffic, {} o'clock", clock(relative));
+
+        let miles = (details.kilo_distance / KM_PER_NM).round().max(1.0);
+        if miles > 1.0 {
+            callout.push_str(&format!(", {miles} miles"));
+        } else {
+            callout.push_str(", 1 mile");
+        }
+
+        let altitude = f64::from(details.altitude);
+        match self.altitude {
+            Some(observer) => {
+                // rounded to 100 ft, as read by controllers
+                let difference = ((altitude - observer) / 100.0).round() * 100.0;
+                if difference > 0.0 {
+                    callout.push_str(&format!(", {difference} feet above"));
+                } else if difference < 0.0 {
+                    callout.push_str(&format!(", {} feet below", -difference));
+                } else {
+                    callout.push_str(", same altitude");
+                }
+            },
+            None => callout.push_str(&format!(", {} feet", details.altitude)),
+        }
+
+        match vertical_rate {
+            Some(rate) if rate >= VERTICAL_RATE => callout.push_str(", climbing"),
+            Some(rate) if rate <= -VERTICAL_RATE => callout.push_str(", descending"),
+            _ => (),
+        }
+        callout
+    }
+}
+
+/// Initial great circle bearing in degrees from `from` to `to` (lat, long)
+fn bearing(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (from.0.to_radians(), to.0.to_radians());
+    let delta_long = (to.1 - from.1).to_radians();
+    let y = delta_long.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * delta_long.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+/// Clock position of a relative bearing in degrees, 12 for straight ahead
+fn clock(relative: f64) -> u8 {
+    // 30 degrees per hour
+    match (relative.rem_euclid(360.0) / 30.0).round() as u8 % 12 {
+        0 => 12,
+        hour => hour,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use adsb_deku::cpr;
+
+    use super::*;
+
+    fn details(latitude: f64, longitude: f64, altitude: u16) -> AirplaneDetails {
+        let kilo_distance = ((latitude * latitude + longitude * longitude).sqrt()) * 111.19;
+        AirplaneDetails {
+            position: cpr::Position {
+                latitude,
+                longitude,
+            },
+            altitude,
+            kilo_distance,
+            heading: None,
+            track: None,
+        }
+    }
+
+    #[test]
+    fn callout() {
+        let observer = Observer {
+            position: (0.0, 0.0),
+            altitude: Some(3000.0),
+            heading: 0.0,
+        };
+        // ~5 NM east
+        assert_eq!(
+            observer.callout(&details(0.0, 0.0833, 5020), Some(-800)),
+            "Traffic, 3 o'clock, 5 miles, 2000 feet above, descending"
+        );
+        // facing east, north is 9 o'clock
+        let observer = Observer {
+            heading: 90.0,
+            ..observer
+        };
+        assert_eq!(
+            observer.callout(&details(0.01, 0.0, 2960), Some(100)),
+            "Traffic, 9 o'clock, 1 mile, same altitude"
+        );
+        // still facing east, south is 3 o'clock
+        let observer = Observer {
+            altitude: None,
+            ..observer
+        };
+        assert_eq!(
+            observer.callout(&details(-0.1, 0.0, 1500), Some(500)),
+            "Traffic, 3 o'clock, 6 miles, 1500 feet, climbing"
+        );
+    }
+
+    #[test]
+    fn clock_positions() {
+        assert_eq!(clock(0.0), 12);
+        assert_eq!(clock(14.0), 12);
+        assert_eq!(clock(16.0), 1);
+        assert_eq!(clock(-90.0), 9);
+        assert_eq!(clock(350.0), 12);
+        assert_eq!(clock(725.0), 12);
+    }
+}
@@ -1,8 +1,12 @@
+use std::fs::{self, File, OpenOptions};
 use std::net::Ipv4Addr;
 use std::num::ParseFloatError;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use clap::Parser;
+use rsadsb_apps::source::Format;
+use rsadsb_apps::timestamp::Timezone;
 
 /// Parsing struct for the --locations clap parameter
 #[derive(Debug, Clone, PartialEq)]
@@ -32,6 +36,132 @@ impl FromStr for Location {
     }
 }
 
+/// Parsing struct for the --cpa-point clap parameter, "lat,long"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub lat: f64,
+    pub long: f64,
+}
+
+impl FromStr for Point {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (lat, long) = s
+            .split_once(',')
+            .ok_or_else(|| format!("invalid point: {s}, expected lat,long"))?;
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .map_err(|e| format!("invalid point: {s}, {e}"))
+        };
+        Ok(Self {
+            lat: parse(lat)?,
+            long: parse(long)?,
+        })
+    }
+}
+
+/// Preset of the tracking limits, see `--profile`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    /// Airliners and general aviation
+    Default,
+    /// High-altitude balloons and suborbital launches: wider range and speed limits, slower
+    /// pruning, and GNSS altitude over barometric altitude
+    HighAltitude,
+}
+
+impl Profile {
+    /// Seconds since the last message before an aircraft is removed, at least
+    pub const fn min_filter_time(self) -> u64 {
+        match self {
+            Self::Default => 0,
+            Self::HighAltitude => 600,
+        }
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "default" => Ok(Self::Default),
+            "high-altitude" => Ok(Self::HighAltitude),
+            _ => Err(format!(
+                "invalid profile: {s}, expected default or high-altitude"
+            )),
+        }
+    }
+}
+
+/// Source of the Mode S frames, see `--source`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// AVR frames from `--host` and `--port`
+    Tcp,
+    /// Frames piped into stdin, in the given format or detected from the first byte
+    Stdin(Option<Format>),
+}
+
+impl FromStr for Source {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        let (source, format) = match lower.split_once(',') {
+            Some((source, format)) => (source, Some(format)),
+            None => (lower.as_str(), None),
+        };
+        let format = match format {
+            None => None,
+            Some("avr") => Some(Format::Avr),
+            Some("beast") => Some(Format::Beast),
+            Some("hex") => Some(Format::Hex),
+            Some(_) => {
+                return Err(format!(
+                    "invalid source format: {s}, expected avr, beast or hex"
+                ))
+            },
+        };
+        match (source, format) {
+            ("tcp", None) => Ok(Self::Tcp),
+            ("stdin", format) => Ok(Self::Stdin(format)),
+            _ => Err(format!(
+                "invalid source: {s}, expected tcp or stdin[,avr|beast|hex]"
+            )),
+        }
+    }
+}
+
+/// Parsing struct for the --callout-reference clap parameter, the heading of 12 o'clock in
+/// traffic callouts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalloutReference {
+    /// 12 o'clock is north
+    North,
+    /// 12 o'clock is the heading pointing up on the Map tab, following its rotation
+    Map,
+    /// 12 o'clock is a fixed heading in degrees, such as a runway heading or the course of ownship
+    Heading(f64),
+}
+
+impl FromStr for CalloutReference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "north" => Ok(Self::North),
+            "map" => Ok(Self::Map),
+            heading => heading.parse::<f64>().map(Self::Heading).map_err(|_| {
+                format!("invalid callout reference: {s}, expected north, map or a heading")
+            }),
+        }
+    }
+}
+
 const AFTER_TEST: &str = r#"Environment Variables:
     RUST_LOG: See "https://docs.rs/tracing-subscriber/latest/tracing_subscriber/fmt/index.html#filtering-events-with-environment-variables"
 "#;
@@ -53,6 +183,22 @@ pub struct Opts {
     #[clap(long, default_value = "30002")]
     pub port: u16,
 
+    /// Source of the frames: `tcp` for `--host` and `--port`, or `stdin` for frames piped from
+    /// another tool, such as `nc host 30002 | radar --source stdin`. The format of stdin is
+    /// detected from the first byte, or set with `stdin,avr`, `stdin,beast` or `stdin,hex`
+    #[clap(long, default_value = "tcp")]
+    pub source: Source,
+
+    /// port of a dump978 server serving raw UAT frames on `--host`, such as 30978. UAT targets are
+    /// tracked along with the 1090 targets of the same address
+    #[clap(long)]
+    pub uat_port: Option<u16>,
+
+    /// Split bytes after the Mode S frame off the frames from `--port`, for receivers that append
+    /// bytes such as a signal level within the AVR frame
+    #[clap(long)]
+    pub split_trailing: bool,
+
     /// Antenna location latitude, this use for aircraft position algorithms.
     ///
     /// This is overwritten when using the `--gpsd` option.
@@ -77,6 +223,12 @@ pub struct Opts {
     #[clap(long)]
     pub disable_icao: bool,
 
+    /// Draw with ASCII symbols only, for serial consoles and terminals without braille support.
+    ///
+    /// Otherwise detected from TERM and the locale. Colors are removed with NO_COLOR.
+    #[clap(long)]
+    pub ascii: bool,
+
     /// Disable display of angles on aircraft within Map display showing the direction of the aircraft.
     #[clap(long)]
     pub disable_heading: bool,
@@ -116,6 +268,15 @@ pub struct Opts {
     #[clap(long)]
     pub limit_parsing: bool,
 
+    /// Only decode every Nth frame, reducing CPU usage on low-power devices
+    #[clap(long, default_value = "1")]
+    pub decode_every: u32,
+
+    /// Decode at most this amount of frames per second, reducing CPU usage on low-power devices
+    /// during traffic spikes
+    #[clap(long)]
+    pub max_decode_rate: Option<u32>,
+
     /// Import downloaded csv file for FAA Airport from <https://github.com/mborsetti/airportsdata>
     #[clap(long)]
     pub airports: Option<String>,
@@ -127,6 +288,237 @@ pub struct Opts {
     /// retry TCP connection to dump1090 instance if connecton is lost/disconnected
     #[clap(long)]
     pub retry_tcp: bool,
+
+    /// Exclude frames tagged as ghost frames (from test equipment or self-interrogation) from
+    /// tracking and stats. Ghost frames with a bad PI field are never tracked
+    #[clap(long)]
+    pub exclude_ghosts: bool,
+
+    /// comma seperated interrogator codes (`(CL << 4) | IC`) of local ground test equipment or
+    /// your own interrogator. DF11 replies to these are tagged as ghost frames
+    #[clap(long, use_value_delimiter = true)]
+    pub test_interrogators: Vec<u8>,
+
+    /// Don't track altitude, speed and vertical rate values outside of the `--min-altitude`,
+    /// `--max-altitude`, `--max-speed` and `--max-vertical-rate` bounds
+    #[clap(long)]
+    pub sanity_check: bool,
+
+    /// Lowest plausible altitude in feet, for `--sanity-check`
+    #[clap(long, default_value = "-1500", allow_hyphen_values = true)]
+    pub min_altitude: i32,
+
+    /// Highest plausible altitude in feet, for `--sanity-check`
+    #[clap(long, default_value = "60000")]
+    pub max_altitude: i32,
+
+    /// Highest plausible ground speed in knots, for `--sanity-check`
+    #[clap(long, default_value = "1200")]
+    pub max_speed: f64,
+
+    /// Highest plausible climb or descent rate in feet per minute, for `--sanity-check`
+    #[clap(long, default_value = "20000")]
+    pub max_vertical_rate: u16,
+
+    /// Tracking limits: `default`, or `high-altitude` for high-altitude balloons and suborbital
+    /// launches. `high-altitude` accepts positions further from the receiver and further apart,
+    /// prefers GNSS altitude, relaxes the `--sanity-check` bounds, and keeps aircraft for at least
+    /// 10 minutes after their last message
+    #[clap(long, default_value = "default")]
+    pub profile: Profile,
+
+    /// Time zone of displayed and exported times: `utc`, `local`, or a fixed offset such as
+    /// `+02:00`
+    #[clap(long, default_value = "utc")]
+    pub timezone: Timezone,
+
+    /// Rotate Map and Coverage so that this heading in degrees points up, such as a runway heading,
+    /// instead of north
+    #[clap(long, default_value = "0")]
+    pub rotation: f64,
+
+    /// Move the center of Map and Coverage to the right, in percent of half the width
+    #[clap(long, default_value = "0", allow_hyphen_values = true)]
+    pub origin_x: f64,
+
+    /// Move the center of Map and Coverage up, in percent of half the height
+    #[clap(long, default_value = "0", allow_hyphen_values = true)]
+    pub origin_y: f64,
+
+    /// Radius in NM of the traffic alert zone around your position. Aircraft inside the zone are
+    /// highlighted and ring the terminal bell
+    #[clap(long)]
+    pub alert_range: Option<f64>,
+
+    /// Height in ft above and below your gpsd altitude of the traffic alert zone. Ignored while
+    /// your altitude is unknown
+    #[clap(long, default_value = "2000")]
+    pub alert_altitude: u16,
+
+    /// Command run with `sh -c` when an aircraft enters the traffic alert zone, with the
+    /// environment variables RSADSB_ICAO, RSADSB_DISTANCE (NM), RSADSB_ALTITUDE (ft) and
+    /// RSADSB_CALLOUT, such as "Traffic, 3 o'clock, 5 miles, 2000 feet above, descending"
+    #[clap(long)]
+    pub alert_command: Option<String>,
+
+    /// Heading of 12 o'clock in the RSADSB_CALLOUT of `--alert-command`: `north`, `map` for the
+    /// heading pointing up on the Map tab, or a heading in degrees
+    #[clap(long, default_value = "north")]
+    pub callout_reference: CalloutReference,
+
+    /// Minutes of recent traffic recorded for playback on the Map tab
+    #[clap(long, default_value = "10")]
+    pub history_minutes: u64,
+
+    /// Csv file of airspace sectors to count traffic in, with the columns `name,floor,ceiling,polygon`.
+    /// Floor and ceiling are in ft, and the polygon is the corners as "lat long;lat long;lat long".
+    /// The entries, occupancy time and peak amount of aircraft of every sector are appended to
+    /// sectors.csv in `--log-folder`
+    #[clap(long)]
+    pub sectors: Option<PathBuf>,
+
+    /// Minutes between the exports of the `--sectors` counts
+    #[clap(long, default_value = "15")]
+    pub sector_interval: u64,
+
+    /// Minutes between the exports of ground speed statistics by emitter category and altitude
+    /// band, appended to performance.csv in `--log-folder`. Disabled by default
+    #[clap(long)]
+    pub performance_interval: Option<u64>,
+
+    /// Days the daily radar.log files are kept in `--log-folder`, along with the history of all
+    /// aircraft downsampled to one snapshot a minute in daily history.<date>.csv files. Without
+    /// it, no history is written to disk and the log files are kept forever
+    #[clap(long)]
+    pub retention_days: Option<u64>,
+
+    /// Seconds between the positions of the tracks resampled by interpolation, appended to
+    /// tracks.csv in `--log-folder` once an aircraft is removed and when quitting. Disabled by
+    /// default
+    #[clap(long)]
+    pub track_step: Option<u64>,
+
+    /// Point (lat,long) for the closest point of approach of the aircraft in the Airplanes tab,
+    /// such as your house or an airfield. Defaults to the antenna location
+    #[clap(long, allow_hyphen_values = true)]
+    pub cpa_point: Option<Point>,
+}
+
+impl Opts {
+    /// Problems of the configuration that would fail the tui later, each with the options
+    /// involved. Empty if the configuration is usable
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = vec![];
+        let mut check = |ok: bool, problem: &str| {
+            if !ok {
+                problems.push(problem.to_string());
+            }
+        };
+
+        check(
+            (-90.0..=90.0).contains(&self.lat),
+            "--lat must be between -90 and 90 degrees",
+        );
+        check(
+            (-180.0..=180.0).contains(&self.long),
+            "--long must be between -180 and 180 degrees",
+        );
+        for location in &self.locations {
+            check(
+                (-90.0..=90.0).contains(&location.lat) && (-180.0..=180.0).contains(&location.long),
+                &format!("--locations {} is not a valid position", location.name),
+            );
+        }
+        check(
+            self.scale.is_finite() && self.scale > 0.0,
+            "--scale must be above 0",
+        );
+        check(self.rotation.is_finite(), "--rotation must be a number");
+        check(
+            (-100.0..=100.0).contains(&self.origin_x) && (-100.0..=100.0).contains(&self.origin_y),
+            "--origin-x and --origin-y must be between -100 and 100 percent",
+        );
+
+        check(self.port != 0, "--port must not be 0");
+        check(
+            self.source == Source::Tcp || !self.retry_tcp,
+            "--retry-tcp needs --source tcp",
+        );
+        check(
+            self.uat_port != Some(0) && self.uat_port != Some(self.port),
+            "--uat-port must not be 0 or the same as --port",
+        );
+
+        check(self.decode_every != 0, "--decode-every must be at least 1");
+        check(
+            self.max_decode_rate != Some(0),
+            "--max-decode-rate must be at least 1",
+        );
+        check(
+            self.min_altitude < self.max_altitude,
+            "--min-altitude must be below --max-altitude",
+        );
+        check(self.max_speed > 0.0, "--max-speed must be above 0");
+        check(
+            self.alert_range.map_or(true, |range| range > 0.0),
+            "--alert-range must be above 0",
+        );
+        check(
+            self.alert_command.is_none() || self.alert_range.is_some(),
+            "--alert-command needs an --alert-range",
+        );
+        check(
+            self.airports_tz_filter.is_none() || self.airports.is_some(),
+            "--airports-tz-filter needs --airports",
+        );
+        check(
+            self.sectors.is_none() || self.sector_interval != 0,
+            "--sector-interval must be at least 1 minute",
+        );
+        check(
+            self.performance_interval != Some(0),
+            "--performance-interval must be at least 1 minute",
+        );
+        check(
+            self.track_step != Some(0),
+            "--track-step must be at least 1 second",
+        );
+
+        if let Some(airports) = &self.airports {
+            check(
+                File::open(airports).is_ok(),
+                &format!("--airports {airports} can't be read"),
+            );
+        }
+        if let Some(sectors) = &self.sectors {
+            check(
+                File::open(sectors).is_ok(),
+                &format!("--sectors {} can't be read", sectors.display()),
+            );
+        }
+        check(
+            is_writable(Path::new(&self.log_folder)),
+            &format!("--log-folder {} can't be written", self.log_folder),
+        );
+
+        problems
+    }
+}
+
+/// `folder` exists or can be created, and files can be created in it
+fn is_writable(folder: &Path) -> bool {
+    if fs::create_dir_all(folder).is_err() {
+        return false;
+    }
+    let probe = folder.join(".radar-write-test");
+    let writable = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&probe)
+        .is_ok();
+    let _ = fs::remove_file(&probe);
+    writable
 }
 
 #[cfg(test)]
@@ -140,6 +532,9 @@ mod tests {
         let exp_opt = Opts {
             host: Ipv4Addr::LOCALHOST,
             port: 30002,
+            source: Source::Tcp,
+            uat_port: None,
+            split_trailing: false,
             lat: 35.0,
             long: -80.0,
             locations: vec![],
@@ -151,12 +546,38 @@ mod tests {
             log_folder: "logs".to_string(),
             touchscreen: false,
             limit_parsing: false,
+            decode_every: 1,
+            max_decode_rate: None,
             airports: None,
             airports_tz_filter: None,
             disable_icao: false,
+            ascii: false,
             disable_heading: false,
             disable_track: false,
             retry_tcp: false,
+            exclude_ghosts: false,
+            test_interrogators: vec![],
+            sanity_check: false,
+            min_altitude: -1500,
+            max_altitude: 60000,
+            max_speed: 1200.0,
+            max_vertical_rate: 20000,
+            profile: Profile::Default,
+            timezone: Timezone::Utc,
+            rotation: 0.0,
+            origin_x: 0.0,
+            origin_y: 0.0,
+            alert_range: None,
+            alert_altitude: 2000,
+            alert_command: None,
+            callout_reference: CalloutReference::North,
+            history_minutes: 10,
+            cpa_point: None,
+            sectors: None,
+            sector_interval: 15,
+            performance_interval: None,
+            retention_days: None,
+            track_step: None,
         };
         assert_eq!(exp_opt, opt);
 
@@ -172,6 +593,9 @@ mod tests {
         let exp_opt = Opts {
             host: Ipv4Addr::LOCALHOST,
             port: 30002,
+            source: Source::Tcp,
+            uat_port: None,
+            split_trailing: false,
             lat: 35.0,
             long: -80.0,
             locations: vec![
@@ -194,13 +618,120 @@ mod tests {
             log_folder: "logs".to_string(),
             touchscreen: false,
             limit_parsing: false,
+            decode_every: 1,
+            max_decode_rate: None,
             airports: None,
             airports_tz_filter: None,
             disable_icao: false,
+            ascii: false,
             disable_heading: false,
             disable_track: false,
             retry_tcp: false,
+            exclude_ghosts: false,
+            test_interrogators: vec![],
+            sanity_check: false,
+            min_altitude: -1500,
+            max_altitude: 60000,
+            max_speed: 1200.0,
+            max_vertical_rate: 20000,
+            profile: Profile::Default,
+            timezone: Timezone::Utc,
+            rotation: 0.0,
+            origin_x: 0.0,
+            origin_y: 0.0,
+            alert_range: None,
+            alert_altitude: 2000,
+            alert_command: None,
+            callout_reference: CalloutReference::North,
+            history_minutes: 10,
+            cpa_point: None,
+            sectors: None,
+            sector_interval: 15,
+            performance_interval: None,
+            retention_days: None,
+            track_step: None,
         };
         assert_eq!(exp_opt, opt);
+
+        let t_str = [
+            "radar",
+            "--lat=35.00",
+            "--long=-80.00",
+            "--profile=high-altitude",
+        ];
+        let opt = Opts::try_parse_from(t_str).unwrap();
+        assert_eq!(opt.profile, Profile::HighAltitude);
+        let t_str = ["radar", "--lat=35.00", "--long=-80.00", "--profile=rocket"];
+        assert!(Opts::try_parse_from(t_str).is_err());
+
+        let t_str = [
+            "radar",
+            "--lat=35.00",
+            "--long=-80.00",
+            "--cpa-point=35.5,-80.25",
+        ];
+        let opt = Opts::try_parse_from(t_str).unwrap();
+        assert_eq!(
+            opt.cpa_point,
+            Some(Point {
+                lat: 35.5,
+                long: -80.25
+            })
+        );
+        let t_str = ["radar", "--lat=35.00", "--long=-80.00", "--cpa-point=35.5"];
+        assert!(Opts::try_parse_from(t_str).is_err());
+    }
+
+    #[test]
+    fn test_source() {
+        assert_eq!("tcp".parse(), Ok(Source::Tcp));
+        assert_eq!("stdin".parse(), Ok(Source::Stdin(None)));
+        assert_eq!(
+            "Stdin,Beast".parse(),
+            Ok(Source::Stdin(Some(Format::Beast)))
+        );
+        assert_eq!("stdin,hex".parse(), Ok(Source::Stdin(Some(Format::Hex))));
+        assert!("tcp,avr".parse::<Source>().is_err());
+        assert!("stdin,uat".parse::<Source>().is_err());
+        assert!("file".parse::<Source>().is_err());
+    }
+
+    #[test]
+    fn test_callout_reference() {
+        assert_eq!("north".parse(), Ok(CalloutReference::North));
+        assert_eq!("Map".parse(), Ok(CalloutReference::Map));
+        assert_eq!("270".parse(), Ok(CalloutReference::Heading(270.0)));
+        assert!("up".parse::<CalloutReference>().is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let log_folder = std::env::temp_dir();
+        let log_folder = format!("--log-folder={}", log_folder.display());
+        let t_str = ["radar", "--lat=35.00", "--long=-80.00", &log_folder];
+        let opt = Opts::try_parse_from(t_str).unwrap();
+        assert!(opt.validate().is_empty());
+
+        let t_str = [
+            "radar",
+            "--lat=135.00",
+            "--long=-80.00",
+            "--uat-port=30002",
+            "--alert-command=true",
+            "--airports=does-not-exist.csv",
+            "--track-step=0",
+            &log_folder,
+        ];
+        let opt = Opts::try_parse_from(t_str).unwrap();
+        assert_eq!(
+            opt.validate(),
+            [
+                "--lat must be between -90 and 90 degrees",
+                "--uat-port must not be 0 or the same as --port",
+                "--alert-command needs an --alert-range",
+                "--track-step must be at least 1 second",
+                "--airports does-not-exist.csv can't be read",
+            ]
+        );
     }
 }
@@ -78,6 +78,7 @@ pub fn build_tab_coverage<A: tui::backend::Backend>(
 ) {
     let canvas = Canvas::default()
         .block(Block::default().title("Coverage").borders(Borders::ALL))
+        .marker(settings.capabilities.marker())
         .x_bounds([MAX_PLOT_LOW, MAX_PLOT_HIGH])
         .y_bounds([MAX_PLOT_LOW, MAX_PLOT_HIGH])
         .paint(|ctx| {
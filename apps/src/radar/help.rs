@@ -31,6 +31,7 @@ pub fn build_tab_help<A: tui::backend::Backend>(f: &mut tui::Frame<A>, chunks: &
         Row::new(vec!["F3", "Move to Airplanes screen"]),
         Row::new(vec!["F4", "Move to Stats screen"]),
         Row::new(vec!["F5", "Move to Help screen"]),
+        Row::new(vec!["F6", "Move to Log screen"]),
         Row::new(vec!["l", "control --disable-lat-long"]),
         Row::new(vec!["i", "control --disable-icao"]),
         Row::new(vec!["h", "control --disable-heading"]),
@@ -59,7 +60,13 @@ pub fn build_tab_help<A: tui::backend::Backend>(f: &mut tui::Frame<A>, chunks: &
         Row::new(vec!["Down", "Move map down"]),
         Row::new(vec!["Left", "Move map left"]),
         Row::new(vec!["Right", "Move map right"]),
-        Row::new(vec!["Enter", "Map position reset"]),
+        Row::new(vec!["[", "Rotate map clockwise"]),
+        Row::new(vec!["]", "Rotate map counterclockwise"]),
+        Row::new(vec!["w a s d", "Move map center up, left, down, right"]),
+        Row::new(vec!["Enter", "Map position, rotation and center reset"]),
+        Row::new(vec!["p", "Map: toggle playback of recent traffic"]),
+        Row::new(vec![", .", "Map playback: step 10 seconds back, forward"]),
+        Row::new(vec!["< >", "Map playback: step 1 minute back, forward"]),
     ];
     let table = Table::new(rows)
         .style(Style::default().fg(Color::White))
@@ -78,6 +85,11 @@ pub fn build_tab_help<A: tui::backend::Backend>(f: &mut tui::Frame<A>, chunks: &
         Row::new(vec!["Up", "Move selection upward"]),
         Row::new(vec!["Down", "Move selection downward"]),
         Row::new(vec!["Enter", "Center Map tab on selected aircraft"]),
+        Row::new(vec!["c", "Sort by closest point of approach"]),
+        Row::new(vec![
+            "v x",
+            "Toggle vertical profile of selected aircraft, by time or distance",
+        ]),
     ];
     let table = Table::new(rows)
         .style(Style::default().fg(Color::White))
@@ -0,0 +1,233 @@
+//! Recent traffic for playback on the Map tab and the vertical profile of the Airplanes tab
+//!
+//! While running, the position of every tracked aircraft is recorded every second for the last
+//! `--history-minutes`. Recording continues while the operator scrubs through the recorded
+//! traffic in playback mode.
+
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+use adsb_deku::ICAO;
+use rsadsb_common::{AirplaneDetails, Airplanes};
+
+/// Time between two recorded snapshots
+pub const INTERVAL: Duration = Duration::from_secs(1);
+
+/// Recorded aircraft of a snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct Target {
+    pub icao: ICAO,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: u16,
+    pub heading: Option<f32>,
+    /// Distance from the receiver in km
+    pub kilo_distance: f64,
+}
+
+/// All aircraft with a position at `time`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub time: SystemTime,
+    pub targets: Vec<Target>,
+}
+
+pub struct History {
+    /// Oldest snapshot first
+    snapshots: VecDeque<Snapshot>,
+    /// Snapshots older than this, compared to the newest, are removed
+    max_age: Duration,
+}
+
+impl History {
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            snapshots: VecDeque::new(),
+            max_age,
+        }
+    }
+
+    /// Record a snapshot of `airplanes`, if the last snapshot is at least `INTERVAL` old
+    pub fn record(&mut self, now: SystemTime, airplanes: &Airplanes) {
+        if let Some(newest) = self.newest() {
+            if now
+                .duration_since(newest)
+                .map_or(true, |age| age < INTERVAL)
+            {
+                return;
+            }
+        }
+
+        let targets = airplanes
+            .keys()
+            .filter_map(|icao| {
+                airplanes.aircraft_details(*icao).map(
+                    |AirplaneDetails {
+                         position,
+                         altitude,
+                         kilo_distance,
+                         heading,
+                         ..
+                     }| Target {
+                        icao: *icao,
+                        latitude: position.latitude,
+                        longitude: position.longitude,
+                        altitude,
+                        heading,
+                        kilo_distance,
+                    },
+                )
+            })
+            .collect();
+        self.snapshots.push_back(Snapshot { time: now, targets });
+
+        // remove snapshots older than max_age
+        while let Some(oldest) = self.snapshots.front() {
+            match now.duration_since(oldest.time) {
+                Ok(age) if age > self.max_age => {
+                    self.snapshots.pop_front();
+                },
+                _ => break,
+            }
+        }
+    }
+
+    /// Time of the oldest snapshot
+    pub fn oldest(&self) -> Option<SystemTime> {
+        self.snapshots.front().map(|snapshot| snapshot.time)
+    }
+
+    /// Time of the newest snapshot
+    pub fn newest(&self) -> Option<SystemTime> {
+        self.snapshots.back().map(|snapshot| snapshot.time)
+    }
+
+    /// Latest snapshot recorded at or before `time`, or the oldest snapshot if all are newer
+    pub fn at(&self, time: SystemTime) -> Option<&Snapshot> {
+        let index = self
+            .snapshots
+            .partition_point(|snapshot| snapshot.time <= time);
+        self.snapshots.get(index.saturating_sub(1))
+    }
+
+    /// Recorded positions of `icao` with their time, oldest first
+    pub fn track(&self, icao: ICAO) -> Vec<(SystemTime, &Target)> {
+        self.snapshots
+            .iter()
+            .filter_map(|snapshot| {
+                snapshot
+                    .targets
+                    .iter()
+                    .find(|target| target.icao == icao)
+                    .map(|target| (snapshot.time, target))
+            })
+            .collect()
+    }
+
+    /// Move `time` by `seconds`, staying within the recorded snapshots
+    pub fn step(&self, time: SystemTime, seconds: i64) -> Option<SystemTime> {
+        let (oldest, newest) = (self.oldest()?, self.newest()?);
+        let offset = Duration::from_secs(seconds.unsigned_abs());
+        let time = if seconds < 0 {
+            time.checked_sub(offset).unwrap_or(oldest)
+        } else {
+            time.checked_add(offset).unwrap_or(newest)
+        };
+        Some(time.clamp(oldest, newest))
+    }
+
+    /// Position of `time` between the oldest (0.0) and newest (1.0) snapshot
+    pub fn ratio(&self, time: SystemTime) -> f64 {
+        let (oldest, newest) = match (self.oldest(), self.newest()) {
+            (Some(oldest), Some(newest)) => (oldest, newest),
+            _ => return 1.0,
+        };
+        let span = newest.duration_since(oldest).unwrap_or_default();
+        if span.is_zero() {
+            return 1.0;
+        }
+        let elapsed = time.duration_since(oldest).unwrap_or_default();
+        (elapsed.as_secs_f64() / span.as_secs_f64()).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(seconds: &[u64]) -> History {
+        let mut history = History::new(Duration::from_secs(60));
+        for second in seconds {
+            history.record(
+                SystemTime::UNIX_EPOCH + Duration::from_secs(*second),
+                &Airplanes::new(),
+            );
+        }
+        history
+    }
+
+    fn at(second: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(second)
+    }
+
+    #[test]
+    fn record_interval_and_prune() {
+        let history = history(&[0, 0, 1, 30, 61, 90]);
+        // duplicate snapshot within INTERVAL is skipped, 0 and 1 are older than max_age
+        assert_eq!(history.snapshots.len(), 3);
+        assert_eq!(history.oldest(), Some(at(30)));
+        assert_eq!(history.newest(), Some(at(90)));
+    }
+
+    #[test]
+    fn snapshot_at() {
+        let history = history(&[10, 20, 30]);
+        assert_eq!(history.at(at(5)).unwrap().time, at(10));
+        assert_eq!(history.at(at(20)).unwrap().time, at(20));
+        assert_eq!(history.at(at(29)).unwrap().time, at(20));
+        assert_eq!(history.at(at(100)).unwrap().time, at(30));
+        assert!(History::new(Duration::from_secs(60)).at(at(0)).is_none());
+    }
+
+    #[test]
+    fn track() {
+        let target = |icao, altitude| Target {
+            icao,
+            latitude: 52.0,
+            longitude: 4.0,
+            altitude,
+            heading: None,
+            kilo_distance: 10.0,
+        };
+        let (a, b) = (ICAO([0xa0, 0x00, 0x01]), ICAO([0xa0, 0x00, 0x02]));
+        let mut history = history(&[]);
+        history.snapshots.push_back(Snapshot {
+            time: at(10),
+            targets: vec![target(a, 3000)],
+        });
+        history.snapshots.push_back(Snapshot {
+            time: at(11),
+            targets: vec![target(b, 5000)],
+        });
+        history.snapshots.push_back(Snapshot {
+            time: at(12),
+            targets: vec![target(b, 5000), target(a, 2500)],
+        });
+        let track = history.track(a);
+        assert_eq!(track.len(), 2);
+        assert_eq!((track[0].0, track[0].1.altitude), (at(10), 3000));
+        assert_eq!((track[1].0, track[1].1.altitude), (at(12), 2500));
+    }
+
+    #[test]
+    fn step_and_ratio() {
+        let history = history(&[10, 20, 30]);
+        assert_eq!(history.step(at(30), -10), Some(at(20)));
+        assert_eq!(history.step(at(20), -60), Some(at(10)));
+        assert_eq!(history.step(at(20), 60), Some(at(30)));
+        assert_eq!(history.ratio(at(10)), 0.0);
+        assert_eq!(history.ratio(at(20)), 0.5);
+        assert_eq!(history.ratio(at(30)), 1.0);
+        assert_eq!(history.ratio(at(0)), 0.0);
+    }
+}
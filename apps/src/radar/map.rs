@@ -1,9 +1,13 @@
+use std::time::SystemTime;
+
+use adsb_deku::ICAO;
+use rsadsb_apps::timestamp;
 use rsadsb_common::{AirplaneDetails, Airplanes};
-use tui::layout::Rect;
+use tui::layout::{Constraint, Direction, Layout, Rect};
 use tui::style::{Color, Style};
 use tui::text::Span;
-use tui::widgets::canvas::{Canvas, Line, Points};
-use tui::widgets::{Block, Borders};
+use tui::widgets::canvas::{Canvas, Context, Line, Points};
+use tui::widgets::{Block, Borders, LineGauge};
 
 use crate::{draw_lines, draw_locations, Settings, DEFAULT_PRECISION, MAX_PLOT_HIGH, MAX_PLOT_LOW};
 
@@ -13,17 +17,29 @@ pub fn build_tab_map<A: tui::backend::Backend>(
     chunks: Vec<Rect>,
     settings: &Settings,
     adsb_airplanes: &Airplanes,
+    now: SystemTime,
 ) {
+    if let Some(time) = settings.playback {
+        build_playback(f, &chunks, settings, time, now);
+        return;
+    }
+
     let canvas = Canvas::default()
         .block(Block::default().title("Map").borders(Borders::ALL))
+        .marker(settings.capabilities.marker())
         .x_bounds([MAX_PLOT_LOW, MAX_PLOT_HIGH])
         .y_bounds([MAX_PLOT_LOW, MAX_PLOT_HIGH])
         .paint(|ctx| {
-            draw_lines(ctx);
+            draw_lines(ctx, settings);
 
             // draw locations
             draw_locations(ctx, settings);
 
+            // draw traffic alert zone
+            if let Some(alert_zone) = &settings.alert_zone {
+                alert_zone.draw(ctx, settings);
+            }
+
             // draw ADSB tab airplanes
             for key in adsb_airplanes.keys() {
                 let aircraft_details = adsb_airplanes.aircraft_details(*key);
@@ -34,8 +50,6 @@ pub fn build_tab_map<A: tui::backend::Backend>(
                     ..
                 }) = aircraft_details
                 {
-                    let (x, y) = settings.to_xy(position.latitude, position.longitude);
-
                     // draw previous positions ("track")
                     if !settings.opts.disable_track {
                         if let Some(track) = track {
@@ -54,85 +68,172 @@ pub fn build_tab_map<A: tui::backend::Backend>(
                         }
                     }
 
-                    // make wings for the angle directions facing toward the heading. This tried to
-                    // account for the angles not showing up around the 90 degree mark, of which I
-                    // add degrees of the angle before displaying
-                    if !settings.opts.disable_heading {
-                        if let Some(heading) = heading {
-                            const ANGLE: f32 = 20.0;
-                            const LENGTH: f32 = 8.0;
-
-                            let addition_heading = (heading % 90.0) / 10.0;
-                            let angle: f32 = ANGLE + addition_heading;
-
-                            let heading = heading + 180.0 % 360.0;
-                            // wrap around the angle since we are are subtracting
-                            let n_heading = if heading > angle {
-                                heading - angle
-                            } else {
-                                (360.0 + heading) - angle
-                            };
-
-                            // move the first point out, so that the green point of the aircraft
-                            // _usually_ shows.
-                            let y_1 = y + f64::from(2.0 * (n_heading.to_radians()).cos());
-                            let x_1 = x + f64::from(2.0 * (n_heading.to_radians()).sin());
-
-                            // draw the line out from the aircraft at an angle
-                            let y_2 = y + f64::from(LENGTH * (n_heading.to_radians()).cos());
-                            let x_2 = x + f64::from(LENGTH * (n_heading.to_radians()).sin());
-
-                            ctx.draw(&Line {
-                                x1: x_1,
-                                x2: x_2,
-                                y1: y_1,
-                                y2: y_2,
-                                color: Color::Blue,
-                            });
-
-                            // repeat for the other side (addition, so just modding)
-                            let n_heading = (heading + angle) % 360.0;
-                            let y_1 = y + f64::from(2.0 * (n_heading.to_radians()).cos());
-                            let x_1 = x + f64::from(2.0 * (n_heading.to_radians()).sin());
-                            let y_2 = y + f64::from(LENGTH * (n_heading.to_radians()).cos());
-                            let x_2 = x + f64::from(LENGTH * (n_heading.to_radians()).sin());
-
-                            ctx.draw(&Line {
-                                x1: x_1,
-                                x2: x_2,
-                                y1: y_1,
-                                y2: y_2,
-                                color: Color::Blue,
-                            });
-                        }
-                    }
+                    draw_aircraft(
+                        ctx,
+                        settings,
+                        *key,
+                        position.latitude,
+                        position.longitude,
+                        heading,
+                    );
+                }
+            }
+        });
+    f.render_widget(canvas, chunks[1]);
+}
 
-                    let name = if settings.opts.disable_lat_long {
-                        format!("{key}").into_boxed_str()
-                    } else {
-                        format!(
-                            "{key} ({:.DEFAULT_PRECISION$}, {:.DEFAULT_PRECISION$})",
-                            position.latitude, position.longitude
-                        )
-                        .into_boxed_str()
-                    };
-
-                    if !settings.opts.disable_icao {
-                        // draw plane ICAO name
-                        ctx.print(
-                            x,
-                            y + 20.0,
-                            Span::styled(name.to_string(), Style::default().fg(Color::White)),
-                        );
-                    }
+/// Render the recorded traffic at `time`, with a scrub bar showing `time` within the recording
+/// and its age at `now`
+fn build_playback<A: tui::backend::Backend>(
+    f: &mut tui::Frame<A>,
+    chunks: &[Rect],
+    settings: &Settings,
+    time: SystemTime,
+    now: SystemTime,
+) {
+    let playback_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(3)].as_ref())
+        .split(chunks[1]);
 
-                    // draw dot on actual lat/lon
-                    ctx.draw(&Points {
-                        coords: &[(x, y)],
-                        color: Color::Blue,
-                    });
+    let snapshot = settings.history.at(time);
+    let canvas = Canvas::default()
+        .block(
+            Block::default()
+                .title("Map - Playback")
+                .borders(Borders::ALL),
+        )
+        .marker(settings.capabilities.marker())
+        .x_bounds([MAX_PLOT_LOW, MAX_PLOT_HIGH])
+        .y_bounds([MAX_PLOT_LOW, MAX_PLOT_HIGH])
+        .paint(|ctx| {
+            draw_lines(ctx, settings);
+            draw_locations(ctx, settings);
+
+            if let Some(snapshot) = snapshot {
+                for target in &snapshot.targets {
+                    draw_aircraft(
+                        ctx,
+                        settings,
+                        target.icao,
+                        target.latitude,
+                        target.longitude,
+                        target.heading,
+                    );
                 }
             }
         });
-    f.render_widget(canvas, chunks[1]);
+    f.render_widget(canvas, playback_chunks[0]);
+
+    // scrub bar, with the time and age of the shown traffic
+    let age = now.duration_since(time).unwrap_or_default().as_secs();
+    let label = format!(
+        "{} (-{}:{:02}) ",
+        timestamp::short(time, settings.utc_offset),
+        age / 60,
+        age % 60
+    );
+    let gauge = LineGauge::default()
+        .block(
+            Block::default()
+                .title("Playback (p: live, ,/.: 10s, </>: 1min)")
+                .borders(Borders::ALL),
+        )
+        .gauge_style(Style::default().fg(Color::Green))
+        .label(label)
+        .ratio(settings.history.ratio(time));
+    f.render_widget(gauge, playback_chunks[1]);
+}
+
+/// Draw the heading, name and position of an aircraft
+fn draw_aircraft(
+    ctx: &mut Context<'_>,
+    settings: &Settings,
+    key: ICAO,
+    latitude: f64,
+    longitude: f64,
+    heading: Option<f32>,
+) {
+    let (x, y) = settings.to_xy(latitude, longitude);
+
+    // make wings for the angle directions facing toward the heading. This tried to
+    // account for the angles not showing up around the 90 degree mark, of which I
+    // add degrees of the angle before displaying
+    if !settings.opts.disable_heading {
+        if let Some(heading) = heading {
+            // heading as shown on the rotated screen
+            let heading = (heading - settings.rotation as f32).rem_euclid(360.0);
+            const ANGLE: f32 = 20.0;
+            const LENGTH: f32 = 8.0;
+
+            let addition_heading = (heading % 90.0) / 10.0;
+            let angle: f32 = ANGLE + addition_heading;
+
+            let heading = heading + 180.0 % 360.0;
+            // wrap around the angle since we are are subtracting
+            let n_heading = if heading > angle {
+                heading - angle
+            } else {
+                (360.0 + heading) - angle
+            };
+
+            // move the first point out, so that the green point of the aircraft
+            // _usually_ shows.
+            let y_1 = y + f64::from(2.0 * (n_heading.to_radians()).cos());
+            let x_1 = x + f64::from(2.0 * (n_heading.to_radians()).sin());
+
+            // draw the line out from the aircraft at an angle
+            let y_2 = y + f64::from(LENGTH * (n_heading.to_radians()).cos());
+            let x_2 = x + f64::from(LENGTH * (n_heading.to_radians()).sin());
+
+            ctx.draw(&Line {
+                x1: x_1,
+                x2: x_2,
+                y1: y_1,
+                y2: y_2,
+                color: Color::Blue,
+            });
+
+            // repeat for the other side (addition, so just modding)
+            let n_heading = (heading + angle) % 360.0;
+            let y_1 = y + f64::from(2.0 * (n_heading.to_radians()).cos());
+            let x_1 = x + f64::from(2.0 * (n_heading.to_radians()).sin());
+            let y_2 = y + f64::from(LENGTH * (n_heading.to_radians()).cos());
+            let x_2 = x + f64::from(LENGTH * (n_heading.to_radians()).sin());
+
+            ctx.draw(&Line {
+                x1: x_1,
+                x2: x_2,
+                y1: y_1,
+                y2: y_2,
+                color: Color::Blue,
+            });
+        }
+    }
+
+    let name = if settings.opts.disable_lat_long {
+        format!("{key}").into_boxed_str()
+    } else {
+        format!("{key} ({latitude:.DEFAULT_PRECISION$}, {longitude:.DEFAULT_PRECISION$})")
+            .into_boxed_str()
+    };
+
+    if !settings.opts.disable_icao {
+        // draw plane ICAO name
+        ctx.print(
+            x,
+            y + 20.0,
+            Span::styled(name.to_string(), Style::default().fg(Color::White)),
+        );
+    }
+
+    // draw dot on actual lat/lon, highlighting aircraft inside the alert zone
+    let intruder = settings
+        .alert_zone
+        .as_ref()
+        .map_or(false, |alert_zone| alert_zone.contains(key));
+    ctx.draw(&Points {
+        coords: &[(x, y)],
+        color: if intruder { Color::Red } else { Color::Blue },
+    });
 }
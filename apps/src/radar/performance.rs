@@ -0,0 +1,171 @@
+//! Ground speed statistics by emitter category and altitude band of `--performance-interval`
+//!
+//! The statistics are appended to `performance.csv` in `--log-folder` every
+//! `--performance-interval` minutes, and aggregating starts again for the next period.
+
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use rsadsb_apps::timestamp;
+use rsadsb_common::performance::PerformanceStats;
+use rsadsb_common::Airplanes;
+use serde::Serialize;
+use time::UtcOffset;
+use tracing::error;
+
+/// Height of the altitude bands in ft
+const BAND_HEIGHT: u16 = 5000;
+
+/// Time between two samples of all aircraft
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Row of `performance.csv`, with the ground speeds of a band for one period
+#[derive(Debug, PartialEq, Serialize)]
+struct SpeedRow {
+    start: String,
+    end: String,
+    /// Emitter category, empty for aircraft without an identification
+    category: String,
+    /// Lowest altitude of the band in ft
+    floor: u16,
+    /// Highest altitude of the band in ft
+    ceiling: u16,
+    samples: u64,
+    /// Ground speeds in kt
+    min_speed: f32,
+    mean_speed: f64,
+    max_speed: f32,
+}
+
+pub struct PerformanceExport {
+    stats: PerformanceStats,
+    /// Time between exports
+    interval: Duration,
+    /// Start of the current period
+    start: SystemTime,
+    path: PathBuf,
+    /// Offset of the exported times, from `--timezone`
+    utc_offset: UtcOffset,
+}
+
+impl PerformanceExport {
+    pub fn new(
+        interval: Duration,
+        log_folder: &str,
+        utc_offset: UtcOffset,
+        now: SystemTime,
+    ) -> Self {
+        Self {
+            stats: PerformanceStats::new(BAND_HEIGHT, SAMPLE_INTERVAL),
+            interval,
+            start: now,
+            path: Path::new(log_folder).join("performance.csv"),
+            utc_offset,
+        }
+    }
+
+    /// Sample the ground speeds, exporting the statistics once the period is over
+    pub fn update(&mut self, now: SystemTime, airplanes: &Airplanes) {
+        let since_epoch = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        self.stats.update(since_epoch, airplanes);
+        if now.duration_since(self.start).unwrap_or_default() >= self.interval {
+            if let Err(e) = self.export(now) {
+                error!("unable to export performance statistics: {e:?}");
+            }
+            self.stats.reset();
+            self.start = now;
+        }
+    }
+
+    /// Append the statistics of the current period
+    fn export(&self, now: SystemTime) -> Result<()> {
+        let exists = self.path.exists();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("unable to open {}", self.path.display()))?;
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(!exists)
+            .from_writer(file);
+        for row in self.rows(now) {
+            writer.serialize(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn rows(&self, now: SystemTime) -> Vec<SpeedRow> {
+        let start = timestamp::iso8601(self.start, self.utc_offset);
+        let end = timestamp::iso8601(now, self.utc_offset);
+        let height = self.stats.band_height();
+        self.stats
+            .bands()
+            .map(|((category, floor), speeds)| SpeedRow {
+                start: start.clone(),
+                end: end.clone(),
+                category: category
+                    .map(|category| category.to_string())
+                    .unwrap_or_default(),
+                floor: *floor,
+                ceiling: floor.saturating_add(height - 1),
+                samples: speeds.samples,
+                min_speed: speeds.min,
+                mean_speed: speeds.mean().unwrap_or_default(),
+                max_speed: speeds.max,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use adsb_deku::deku::DekuContainerRead;
+    use adsb_deku::{Frame, ICAO};
+
+    use super::*;
+
+    #[test]
+    fn rows() {
+        let mut airplanes = Airplanes::new();
+        // identification, position at 38000 ft and velocity
+        for frame in [
+            "8d40621d232cc371c32ce0b57d4c",
+            "8d40621d58c382d690c8ac2863a7",
+            "8d40621d58c386435cc412692ad6",
+            "8d40621d994409940838174550b1",
+        ] {
+            let bytes = hex::decode(frame).unwrap();
+            let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+            airplanes.action(frame.validate().unwrap(), (52.25, 3.91));
+        }
+
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_646_136_000);
+        let mut export =
+            PerformanceExport::new(Duration::from_secs(3600), "logs", UtcOffset::UTC, start);
+        export.update(start, &airplanes);
+        let speed = airplanes
+            .get(ICAO([0x40, 0x62, 0x1d]))
+            .unwrap()
+            .speed
+            .unwrap();
+        assert_eq!(
+            export.rows(start + Duration::from_secs(3600)),
+            vec![SpeedRow {
+                start: "2022-03-01T12:00:00Z".to_string(),
+                end: "2022-03-01T13:00:00Z".to_string(),
+                category: "large".to_string(),
+                floor: 35000,
+                ceiling: 39999,
+                samples: 1,
+                min_speed: speed,
+                mean_speed: f64::from(speed),
+                max_speed: speed,
+            }]
+        );
+    }
+}
@@ -6,7 +6,7 @@ mod airport;
 use crate::airport::Airport;
 
 mod cli;
-use crate::cli::Opts;
+use crate::cli::{Opts, Profile, Source};
 
 mod coverage;
 use crate::coverage::{build_tab_coverage, populate_coverage};
@@ -20,15 +20,41 @@ use crate::stats::{build_tab_stats, Stats};
 mod help;
 use crate::help::build_tab_help;
 
+mod raw_log;
+use crate::raw_log::{build_tab_log, RawLog};
+
 mod airplanes;
+
+mod alert;
+
+mod callout;
+
+mod history;
+
+mod performance;
+
+mod retention;
+
+mod sectors;
+
+mod terminal;
+
+mod tracks;
+
+mod vertical_profile;
+
+#[cfg(test)]
+mod snapshot;
+
 use std::io::{self, BufRead, BufReader, BufWriter};
 use std::net::{SocketAddr, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use adsb_deku::deku::DekuContainerRead;
-use adsb_deku::{Frame, ICAO};
-use anyhow::{Context, Result};
+use adsb_deku::{ErrorKind, Frame, ICAO};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
 use crossterm::event::{
     poll, read, EnableMouseCapture, Event, KeyCode, KeyEvent, MouseButton, MouseEvent,
@@ -37,7 +63,13 @@ use crossterm::event::{
 use crossterm::terminal::enable_raw_mode;
 use crossterm::ExecutableCommand;
 use gpsd_proto::{get_data, handshake, ResponseData};
-use rsadsb_common::{AirplaneDetails, Airplanes};
+use rsadsb_apps::source::{AvrReader, BackgroundReader, ReadEvent, Trailing, READ_TIMEOUT};
+use rsadsb_apps::throttle::Throttle;
+use rsadsb_apps::timestamp;
+use rsadsb_common::ghost::GhostDetector;
+use rsadsb_common::sanity::Bounds;
+use rsadsb_common::uat::UatFrame;
+use rsadsb_common::{AirplaneDetails, Airplanes, Limits};
 use time::UtcOffset;
 use tracing::{debug, error, info, trace};
 use tracing_subscriber::EnvFilter;
@@ -50,11 +82,21 @@ use tui::widgets::canvas::{Line, Points};
 use tui::widgets::{Block, Borders, Paragraph, TableState, Tabs};
 use tui::Terminal;
 
-use crate::airplanes::build_tab_airplanes;
+use crate::airplanes::{airplane_order, build_tab_airplanes};
+use crate::alert::{AlertZone, FEET_PER_METER};
+use crate::callout::Observer;
+use crate::cli::CalloutReference;
+use crate::history::History;
+use crate::performance::PerformanceExport;
+use crate::retention::Retention;
+use crate::sectors::{read_sectors, SectorExport};
+use crate::terminal::{Capabilities, Fallback};
+use crate::tracks::TrackExport;
+use crate::vertical_profile::ProfileAxis;
 
 /// Amount of zoom out from your original lat/long position
 const MAX_PLOT_HIGH: f64 = 400.0;
-const MAX_PLOT_LOW: f64 = MAX_PLOT_HIGH * -1.0;
+const MAX_PLOT_LOW: f64 = -MAX_PLOT_HIGH;
 
 mod scale {
     /// Diff between scale changes
@@ -64,6 +106,18 @@ mod scale {
     pub const DEFAULT: f64 = 500_000.0;
 }
 
+/// Diff in degrees between rotation changes
+const ROTATION_CHANGE: f64 = 5.0;
+
+/// Diff in canvas units between origin changes, 5% of the half width/height
+const ORIGIN_CHANGE: f64 = MAX_PLOT_HIGH * 0.05;
+
+/// Seconds moved by a playback step, see `Settings::step_playback`
+const PLAYBACK_STEP: i64 = 10;
+
+/// Seconds moved by a large playback step, see `Settings::step_playback`
+const PLAYBACK_LARGE_STEP: i64 = 60;
+
 /// tui top bar margin
 const TUI_START_MARGIN: u16 = 1;
 
@@ -81,6 +135,7 @@ enum Tab {
     Airplanes,
     Stats,
     Help,
+    Log,
 }
 
 impl Tab {
@@ -90,7 +145,8 @@ impl Tab {
             Self::Coverage => Self::Airplanes,
             Self::Airplanes => Self::Stats,
             Self::Stats => Self::Help,
-            Self::Help => Self::Map,
+            Self::Help => Self::Log,
+            Self::Log => Self::Map,
         }
     }
 }
@@ -142,10 +198,36 @@ pub struct Settings {
     airports: Option<Vec<Airport>>,
     /// DateTime offset
     utc_offset: UtcOffset,
+    /// current altitude in ft from gpsd
+    ownship_altitude: Option<f64>,
+    /// traffic alert zone around ownship, if `--alert-range` is set
+    alert_zone: Option<AlertZone>,
+    /// current heading in degrees pointing up
+    rotation: f64,
+    /// current position of the center in the canvas
+    origin: (f64, f64),
+    /// recent traffic, recorded for playback
+    history: History,
+    /// when Some(), the Map tab shows the recorded traffic at this time
+    playback: Option<SystemTime>,
+    /// sort the Airplanes tab by closest point of approach instead of ICAO address
+    sort_cpa: bool,
+    /// when Some(), the Airplanes tab shows the vertical profile of the selected aircraft
+    vertical_profile: Option<ProfileAxis>,
+    /// recently received frames, for the Log tab
+    raw_log: RawLog,
+    /// symbols and colors supported by the terminal
+    capabilities: Capabilities,
 }
 
 impl Settings {
-    fn new(opts: Opts, utc_offset: UtcOffset) -> Self {
+    fn new(opts: Opts, utc_offset: UtcOffset, capabilities: Capabilities) -> Self {
+        let alert_zone = opts
+            .alert_range
+            .map(|range| AlertZone::new(range, opts.alert_altitude, opts.alert_command.clone()));
+        let rotation = opts.rotation;
+        let origin = Self::origin_from_opts(&opts);
+        let history = History::new(Duration::from_secs(opts.history_minutes * 60));
         Self {
             quit: None,
             tab_selection: Tab::Map,
@@ -158,16 +240,51 @@ impl Settings {
             last_mouse_dragging: None,
             airports: None,
             utc_offset,
+            ownship_altitude: None,
+            alert_zone,
+            rotation,
+            origin,
+            history,
+            playback: None,
+            sort_cpa: false,
+            vertical_profile: None,
+            raw_log: RawLog::default(),
+            capabilities,
+        }
+    }
+
+    /// Position, altitude and `--callout-reference` heading of traffic callouts
+    fn observer(&self) -> Observer {
+        let heading = match self.opts.callout_reference {
+            CalloutReference::North => 0.0,
+            CalloutReference::Map => self.rotation,
+            CalloutReference::Heading(heading) => heading,
+        };
+        Observer {
+            position: (self.lat, self.long),
+            altitude: self.ownship_altitude,
+            heading,
         }
     }
 
+    /// Position of the center in the canvas from `--origin-x` and `--origin-y`
+    fn origin_from_opts(opts: &Opts) -> (f64, f64) {
+        (
+            opts.origin_x / 100.0 * MAX_PLOT_HIGH,
+            opts.origin_y / 100.0 * MAX_PLOT_HIGH,
+        )
+    }
+
     /// Convert new lat/long into mercator using current location from `Settings`
     fn to_xy(&self, latitude: f64, longitude: f64) -> (f64, f64) {
         // TODO save before, it's kinda costly
         let (local_x, local_y) = self.local_lat_lon();
         let (x, y) = self.to_mercator(latitude, longitude);
-        let (x, y) = (x - local_x, y - local_y);
-        (x, y * -1.0)
+        let (x, y) = (x - local_x, local_y - y);
+        // rotate counterclockwise, so that `rotation` points up
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let (x, y) = (x * cos - y * sin, x * sin + y * cos);
+        (x + self.origin.0, y + self.origin.1)
     }
 
     /// Calculate mercator for local lat/long
@@ -198,34 +315,59 @@ impl Settings {
     }
 
     fn lat_increase(&mut self) {
-        if let Some(lat) = &mut self.custom_lat {
-            *lat += 0.005;
-        } else {
-            self.custom_lat = Some(self.lat + 0.005);
-        }
+        self.move_view(0.005, 0.0);
     }
 
     fn lat_decrease(&mut self) {
-        if let Some(lat) = &mut self.custom_lat {
-            *lat -= 0.005;
-        } else {
-            self.custom_lat = Some(self.lat - 0.005);
-        }
+        self.move_view(-0.005, 0.0);
     }
 
     fn long_increase(&mut self) {
-        if let Some(long) = &mut self.custom_long {
-            *long -= 0.03;
-        } else {
-            self.custom_long = Some(self.long - 0.03);
-        }
+        self.move_view(0.0, -0.03);
     }
 
     fn long_decrease(&mut self) {
-        if let Some(long) = &mut self.custom_long {
-            *long += 0.03;
-        } else {
-            self.custom_long = Some(self.long + 0.03);
+        self.move_view(0.0, 0.03);
+    }
+
+    /// Move the current position `up` and `right` on the screen, in degrees of lat/long
+    fn move_view(&mut self, up: f64, right: f64) {
+        // rotate clockwise, back from the screen into north/east
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let north = up * cos - right * sin;
+        let east = up * sin + right * cos;
+        self.custom_lat = Some(self.custom_lat.unwrap_or(self.lat) + north);
+        self.custom_long = Some(self.custom_long.unwrap_or(self.long) + east);
+    }
+
+    fn rotate(&mut self, degrees: f64) {
+        self.rotation = (self.rotation + degrees).rem_euclid(360.0);
+    }
+
+    fn move_origin(&mut self, x: f64, y: f64) {
+        self.origin.0 += x;
+        self.origin.1 += y;
+    }
+
+    /// Point (lat, long) of the closest point of approach, `--cpa-point` or the antenna location
+    fn cpa_point(&self) -> (f64, f64) {
+        self.opts
+            .cpa_point
+            .map_or((self.lat, self.long), |point| (point.lat, point.long))
+    }
+
+    /// Start playback at the newest recorded traffic, or go back to the live traffic
+    fn toggle_playback(&mut self) {
+        self.playback = match self.playback {
+            Some(_) => None,
+            None => self.history.newest(),
+        };
+    }
+
+    /// Move the playback time by `seconds`, staying within the recorded traffic
+    fn step_playback(&mut self, seconds: i64) {
+        if let Some(time) = self.playback {
+            self.playback = self.history.step(time, seconds);
         }
     }
 
@@ -233,6 +375,8 @@ impl Settings {
         self.custom_lat = None;
         self.custom_long = None;
         self.scale = self.opts.scale;
+        self.rotation = self.opts.rotation;
+        self.origin = Self::origin_from_opts(&self.opts);
     }
 }
 
@@ -244,12 +388,32 @@ struct TuiInfo {
 }
 
 fn main() -> Result<()> {
-    // grab the local offset from localtime_r while we are a single thread for safety
-    let utc_offset = time::OffsetDateTime::now_local().unwrap().offset();
-
     // Parse arguments
     let opts = Opts::parse();
 
+    // report all problems of the configuration at once, before the tui takes over the terminal
+    let problems = opts.validate();
+    if !problems.is_empty() {
+        let mut msg = String::from("invalid configuration:");
+        for problem in problems {
+            msg.push_str("\n  - ");
+            msg.push_str(&problem);
+        }
+        bail!(msg);
+    }
+
+    // grab the local offset from localtime_r while we are a single thread for safety
+    let utc_offset = opts
+        .timezone
+        .offset()
+        .context("unable to determine local time zone")?;
+
+    let sectors = opts.sectors.as_deref().map(read_sectors).transpose()?;
+    let airports = match &opts.airports {
+        Some(airports) => Airport::from_file(airports, &opts.airports_tz_filter)?,
+        None => vec![],
+    };
+
     // Generate logs file and start logging
     let file_appender = tracing_appender::rolling::daily(&opts.log_folder, "radar.log");
     let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
@@ -270,9 +434,11 @@ fn main() -> Result<()> {
     );
 
     // empty containers
-    let mut input = String::new();
     let mut coverage_airplanes: Vec<(f64, f64, u32, ICAO)> = Vec::new();
-    let mut adsb_airplanes = Airplanes::new();
+    let mut adsb_airplanes = match opts.profile {
+        Profile::Default => Airplanes::new(),
+        Profile::HighAltitude => Airplanes::with_limits(Limits::high_altitude()),
+    };
 
     // setup tui params
     let mut stdout = io::stdout();
@@ -284,25 +450,67 @@ fn main() -> Result<()> {
 
     // setup tui variables
     let mut airplanes_state = TableState::default();
-    let filter_time = opts.filter_time;
+    let filter_time = opts.filter_time.max(opts.profile.min_filter_time());
 
     // create settings, dropping opts to prevent bad usage of variable
-    let mut settings = Settings::new(opts.clone(), utc_offset);
+    let capabilities = Capabilities::detect(opts.ascii);
+    let mut settings = Settings::new(opts.clone(), utc_offset, capabilities);
 
     // Setup non-blocking TcpStream, display a tui display saying as such and setup the quit
     // if the user wants to quit
     let socket = SocketAddr::from((opts.host, opts.port));
-    let mut tcp_reader = match init_tcp_reader(&mut terminal, &mut settings, socket)? {
-        Some(tcp_reader) => tcp_reader,
-        None => return Ok(()),
+    let mut reader = match settings.opts.source {
+        Source::Tcp => match init_tcp_reader(&mut terminal, &mut settings, socket)? {
+            Some(reader) => reader,
+            None => return Ok(()),
+        },
+        Source::Stdin(format) => {
+            let stdin: Box<dyn BufRead> = Box::new(BufReader::new(BackgroundReader::stdin()));
+            let reader = match format {
+                Some(format) => AvrReader::with_format(stdin, format),
+                None => AvrReader::detect(stdin),
+            };
+            reader.with_trailing(trailing(&settings.opts))
+        },
     };
+    // stdin ended, the traffic read so far is still shown
+    let mut stdin_ended = false;
 
-    let mut airports = vec![];
-    if let Some(airport) = &settings.opts.airports {
-        airports = Airport::from_file(airport, &settings.opts.airports_tz_filter);
-    }
     settings.airports = Some(airports);
 
+    let mut sector_export = sectors.map(|sectors| {
+        let interval = Duration::from_secs(settings.opts.sector_interval * 60);
+        SectorExport::new(
+            sectors,
+            interval,
+            &settings.opts.log_folder,
+            settings.utc_offset,
+            SystemTime::now(),
+        )
+    });
+
+    let mut performance_export = settings.opts.performance_interval.map(|minutes| {
+        PerformanceExport::new(
+            Duration::from_secs(minutes * 60),
+            &settings.opts.log_folder,
+            settings.utc_offset,
+            SystemTime::now(),
+        )
+    });
+
+    let mut retention = settings
+        .opts
+        .retention_days
+        .map(|days| Retention::new(days, &settings.opts.log_folder, settings.utc_offset));
+
+    let track_export = settings.opts.track_step.map(|secs| {
+        TrackExport::new(
+            Duration::from_secs(secs),
+            &settings.opts.log_folder,
+            settings.utc_offset,
+        )
+    });
+
     // This next group of functions and variables handle if `gpsd_ip` is set from the command
     // line.
     //
@@ -321,7 +529,40 @@ fn main() -> Result<()> {
         });
     }
 
+    // UAT frames from dump978, read within a thread to not block on reading 1090 frames
+    let uat_frames = settings.opts.uat_port.map(|uat_port| {
+        let (sender, receiver) = mpsc::channel();
+        let socket = SocketAddr::from((settings.opts.host, uat_port));
+        std::thread::spawn(move || uat_thread(socket, &sender));
+        receiver
+    });
+
     let mut stats = Stats::default();
+    let ghost_detector = GhostDetector::new(settings.opts.test_interrogators.clone());
+    let bounds = settings.opts.sanity_check.then(|| {
+        let bounds = Bounds {
+            min_altitude: settings.opts.min_altitude,
+            max_altitude: settings.opts.max_altitude,
+            max_speed: settings.opts.max_speed,
+            max_vertical_rate: settings.opts.max_vertical_rate,
+        };
+        match settings.opts.profile {
+            Profile::Default => bounds,
+            // only relax the bounds given on the command line
+            Profile::HighAltitude => {
+                let high_altitude = Bounds::high_altitude();
+                Bounds {
+                    min_altitude: bounds.min_altitude.min(high_altitude.min_altitude),
+                    max_altitude: bounds.max_altitude.max(high_altitude.max_altitude),
+                    max_speed: bounds.max_speed.max(high_altitude.max_speed),
+                    max_vertical_rate: bounds
+                        .max_vertical_rate
+                        .max(high_altitude.max_vertical_rate),
+                }
+            },
+        }
+    });
+    let mut throttle = Throttle::new(settings.opts.decode_every, settings.opts.max_decode_rate);
 
     // Startup main loop
     info!("tui setup");
@@ -331,11 +572,11 @@ fn main() -> Result<()> {
             Some(QuitReason::TcpDisconnect) => {
                 // if --retry-tcp has been used, try to generate a new tcp connection
                 if settings.opts.retry_tcp {
-                    tcp_reader = match init_tcp_reader(&mut terminal, &mut settings, socket)? {
+                    reader = match init_tcp_reader(&mut terminal, &mut settings, socket)? {
                         // a new connection to a dump1090 instance has been found/set. use it
-                        Some(tcp_reader) => {
+                        Some(reader) => {
                             settings.quit = None;
-                            tcp_reader
+                            reader
                         },
                         // the settings.quit has been set within init_tcp_reader. This continues
                         // to the next loop, which checks for the settings.quit being set
@@ -350,34 +591,50 @@ fn main() -> Result<()> {
             None => (),
         }
 
-        // check the Mutex from the gpsd thread, update lat/long
+        // check the Mutex from the gpsd thread, update lat/long/altitude
         if let Ok(lat_long) = gps_lat_long.lock() {
-            if let Some((lat, long)) = *lat_long {
-                settings.lat = lat as f64;
-                settings.long = long as f64;
+            if let Some((lat, long, altitude)) = *lat_long {
+                settings.lat = lat;
+                settings.long = long;
+                settings.ownship_altitude = altitude;
             }
         }
 
-        if let Ok(len) = tcp_reader.read_line(&mut input) {
-            // a length of 0 would indicate a broken pipe/input, quit program
-            if len == 0 {
+        if let Some(uat_frames) = &uat_frames {
+            read_uat_frames(uat_frames, &mut adsb_airplanes, &mut stats, &settings);
+        }
+
+        let event = reader.poll_frame();
+        stats.resyncs += reader.take_resyncs();
+        if let ReadEvent::End(end) = event {
+            if settings.opts.source == Source::Tcp {
+                // closed or failed connection, quit program or reconnect with --retry-tcp
+                info!("dump1090 {end}");
                 settings.quit = Some(QuitReason::TcpDisconnect);
                 continue;
             }
-
-            // convert from string hex -> bytes
-            let hex = &mut input.to_string()[1..len - 2].to_string();
-            debug!("bytes: {hex}");
-            let bytes = if let Ok(bytes) = hex::decode(&hex) {
-                bytes
-            } else {
-                continue;
-            };
+            if !stdin_ended {
+                info!("stdin {end}");
+                stdin_ended = true;
+            }
+            // wait as long as a read would have
+            std::thread::sleep(READ_TIMEOUT);
+        }
+        if let ReadEvent::Frame(bytes) = event {
+            debug!("bytes: {}", hex::encode(&bytes));
+            if !reader.trailing().is_empty() {
+                debug!("trailing bytes: {}", hex::encode(reader.trailing()));
+            }
 
             // check for all 0's
             if bytes.iter().all(|&b| b == 0) {
                 continue;
             }
+            settings.raw_log.push(SystemTime::now(), &bytes);
+
+            // skip decoding with --decode-every and --max-decode-rate, still updating the tui
+            let decode = throttle.allow();
+            stats.dropped_frames += throttle.take_dropped();
 
             // decode
             // first check if the option is selected that limits the parsing by first checking the
@@ -387,30 +644,91 @@ fn main() -> Result<()> {
             } else {
                 true
             };
-            if df_adsb {
+            if decode && df_adsb {
                 // parse the entire DF frame
                 let frame = Frame::from_bytes((&bytes, 0));
                 match frame {
                     Ok((left_over, frame)) => {
                         debug!("ADS-B Frame: {frame}");
-                        let airplane_added =
-                            adsb_airplanes.action(frame, (settings.lat, settings.long));
                         if left_over.1 != 0 {
                             error!("{left_over:x?}");
                         }
-                        // update stats
-                        stats.update(&adsb_airplanes, airplane_added);
+                        if !left_over.0.is_empty() {
+                            stats.count_error(ErrorKind::Framing);
+                        }
+                        if let Some(kind) = frame.error_kind() {
+                            stats.count_error(kind);
+                        }
+                        if frame
+                            .capability()
+                            .map_or(false, |capability| capability.is_reserved())
+                        {
+                            stats.reserved_capability += 1;
+                        }
+                        let ghost = ghost_detector.check(&frame);
+                        if let Some(ghost) = ghost {
+                            info!("ghost frame, {ghost}");
+                            stats.ghost_frames += 1;
+                        }
+                        let suspect = bounds.and_then(|bounds| bounds.check(&frame));
+                        if let Some(suspect) = suspect {
+                            info!("suspect frame, {suspect}");
+                            stats.suspect_frames += 1;
+                        }
+                        if suspect.is_none() && (ghost.is_none() || !settings.opts.exclude_ghosts) {
+                            // frames with a bad parity are counted as ghosts, but never tracked
+                            if let Ok(frame) = frame.validate() {
+                                let airplane_added =
+                                    adsb_airplanes.action(frame, (settings.lat, settings.long));
+                                // update stats
+                                stats.update(&adsb_airplanes, airplane_added);
+                            }
+                        }
+                    },
+                    Err(e) => {
+                        stats.count_error(ErrorKind::from_deku(&bytes, &e));
+                        error!("{e:?}");
                     },
-                    Err(e) => error!("{e:?}"),
                 }
             }
         }
-        input.clear();
+
+        for event in adsb_airplanes.take_events() {
+            info!("{event}");
+        }
 
         populate_coverage(&adsb_airplanes, &mut coverage_airplanes);
 
-        // remove airplanes that timed-out
-        adsb_airplanes.prune(filter_time);
+        // alert for aircraft entering the traffic alert zone
+        let observer = settings.observer();
+        if let Some(alert_zone) = &mut settings.alert_zone {
+            alert_zone.update(&adsb_airplanes, &observer);
+        }
+
+        // remove airplanes that timed-out, exporting their --track-step tracks
+        let removed = adsb_airplanes.prune(filter_time);
+        if let Some(track_export) = &track_export {
+            track_export.export(removed.iter().map(|(icao, state)| (icao, state)));
+        }
+
+        // record traffic for playback, also while playing back
+        let now = SystemTime::now();
+        settings.history.record(now, &adsb_airplanes);
+
+        // downsample the history to disk and remove old files with --retention-days
+        if let Some(retention) = &mut retention {
+            retention.update(now, &settings.history);
+        }
+
+        // count traffic in the --sectors
+        if let Some(sector_export) = &mut sector_export {
+            sector_export.update(now, &adsb_airplanes);
+        }
+
+        // ground speed statistics of --performance-interval
+        if let Some(performance_export) = &mut performance_export {
+            performance_export.update(now, &adsb_airplanes);
+        }
 
         // draw crossterm tui display
         let tui_info = draw(
@@ -421,6 +739,7 @@ fn main() -> Result<()> {
             &coverage_airplanes,
             &mut airplanes_state,
             &stats,
+            now,
         );
 
         // handle crossterm events
@@ -458,6 +777,9 @@ fn main() -> Result<()> {
     //
     // PANIC: this won't panic, because main loop will continue until this is Some
     let reason = settings.quit.unwrap();
+    if let Some(track_export) = &track_export {
+        track_export.export(adsb_airplanes.iter());
+    }
     terminal.clear()?;
     let mut stdout = io::stdout();
     crossterm::execute!(
@@ -475,14 +797,14 @@ fn main() -> Result<()> {
 /// Try and connect to a dump1090 instance while showing a tui display.
 ///
 /// Returns:
-///   `Ok(Some(tcp_reader))`: Success, new tcp connection wrapped in a `BufReader`
+///   `Ok(Some(reader))`: Success, new tcp connection wrapped in an `AvrReader`
 ///   `Ok(None)`:             User quit method has been used
 ///   `Err()`:                Some other system error has occurred
 fn init_tcp_reader(
     terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
     settings: &mut Settings,
     socket: SocketAddr,
-) -> Result<Option<BufReader<TcpStream>>> {
+) -> Result<Option<AvrReader<Box<dyn BufRead>>>> {
     let ip = socket.ip();
     let port = socket.port();
 
@@ -513,11 +835,9 @@ fn init_tcp_reader(
                         settings.quit = Some(QuitReason::UserRequested);
                         return Ok(None);
                     },
-                    KeyCode::Char('c') => {
-                        if modifiers == crossterm::event::KeyModifiers::CONTROL {
-                            settings.quit = Some(QuitReason::UserRequested);
-                            return Ok(None);
-                        }
+                    KeyCode::Char('c') if modifiers == crossterm::event::KeyModifiers::CONTROL => {
+                        settings.quit = Some(QuitReason::UserRequested);
+                        return Ok(None);
                     },
                     // unknown key
                     _ => (),
@@ -527,14 +847,24 @@ fn init_tcp_reader(
 
         // try and connect to initial dump1090 instance
         if let Ok(stream) = TcpStream::connect_timeout(&socket, Duration::from_secs(10)) {
-            stream
-                .set_read_timeout(Some(std::time::Duration::from_millis(50)))
-                .unwrap();
-            return Ok(Some(BufReader::new(stream)));
+            stream.set_read_timeout(Some(READ_TIMEOUT))?;
+            let stream: Box<dyn BufRead> = Box::new(BufReader::new(stream));
+            return Ok(Some(
+                AvrReader::new(stream).with_trailing(trailing(&settings.opts)),
+            ));
         }
     }
 }
 
+/// Handling of the bytes after the Mode S frame, from `--split-trailing`
+const fn trailing(opts: &Opts) -> Trailing {
+    if opts.split_trailing {
+        Trailing::Split
+    } else {
+        Trailing::Keep
+    }
+}
+
 /// Handle a `KeyEvent`
 fn handle_keyevent(
     key_event: KeyEvent,
@@ -552,12 +882,11 @@ fn handle_keyevent(
         (KeyCode::F(3), _) => settings.tab_selection = Tab::Airplanes,
         (KeyCode::F(4), _) => settings.tab_selection = Tab::Stats,
         (KeyCode::F(5), _) => settings.tab_selection = Tab::Help,
+        (KeyCode::F(6), _) => settings.tab_selection = Tab::Log,
         (KeyCode::Tab, _) => settings.tab_selection = settings.tab_selection.next_tab(),
         (KeyCode::Char('q'), _) => settings.quit = Some(QuitReason::UserRequested),
-        (KeyCode::Char('c'), _) => {
-            if modifiers == crossterm::event::KeyModifiers::CONTROL {
-                settings.quit = Some(QuitReason::UserRequested);
-            }
+        (KeyCode::Char('c'), _) if modifiers == crossterm::event::KeyModifiers::CONTROL => {
+            settings.quit = Some(QuitReason::UserRequested);
         },
         (KeyCode::Char('l'), _) => settings.opts.disable_lat_long ^= true,
         (KeyCode::Char('i'), _) => settings.opts.disable_icao ^= true,
@@ -570,7 +899,19 @@ fn handle_keyevent(
         (KeyCode::Down, Tab::Map | Tab::Coverage) => settings.lat_decrease(),
         (KeyCode::Left, Tab::Map | Tab::Coverage) => settings.long_increase(),
         (KeyCode::Right, Tab::Map | Tab::Coverage) => settings.long_decrease(),
+        (KeyCode::Char('['), Tab::Map | Tab::Coverage) => settings.rotate(-ROTATION_CHANGE),
+        (KeyCode::Char(']'), Tab::Map | Tab::Coverage) => settings.rotate(ROTATION_CHANGE),
+        (KeyCode::Char('w'), Tab::Map | Tab::Coverage) => settings.move_origin(0.0, ORIGIN_CHANGE),
+        (KeyCode::Char('s'), Tab::Map | Tab::Coverage) => settings.move_origin(0.0, -ORIGIN_CHANGE),
+        (KeyCode::Char('a'), Tab::Map | Tab::Coverage) => settings.move_origin(-ORIGIN_CHANGE, 0.0),
+        (KeyCode::Char('d'), Tab::Map | Tab::Coverage) => settings.move_origin(ORIGIN_CHANGE, 0.0),
         (KeyCode::Enter, Tab::Map | Tab::Coverage) => settings.reset(),
+        // Map
+        (KeyCode::Char('p'), Tab::Map) => settings.toggle_playback(),
+        (KeyCode::Char(','), Tab::Map) => settings.step_playback(-PLAYBACK_STEP),
+        (KeyCode::Char('.'), Tab::Map) => settings.step_playback(PLAYBACK_STEP),
+        (KeyCode::Char('<'), Tab::Map) => settings.step_playback(-PLAYBACK_LARGE_STEP),
+        (KeyCode::Char('>'), Tab::Map) => settings.step_playback(PLAYBACK_LARGE_STEP),
         // Airplanes
         (KeyCode::Up, Tab::Airplanes) => {
             let index = airplanes_state
@@ -585,10 +926,20 @@ fn handle_keyevent(
                 .map_or(0, |selected| selected + 1);
             airplanes_state.select(Some(index));
         },
+        (KeyCode::Char('c'), Tab::Airplanes) => settings.sort_cpa ^= true,
+        (KeyCode::Char('v'), Tab::Airplanes) => {
+            settings.vertical_profile = match settings.vertical_profile {
+                Some(_) => None,
+                None => Some(ProfileAxis::Time),
+            };
+        },
+        (KeyCode::Char('x'), Tab::Airplanes) => {
+            settings.vertical_profile = settings.vertical_profile.map(ProfileAxis::toggle);
+        },
         (KeyCode::Enter, Tab::Airplanes) => {
             if let Some(selected) = airplanes_state.selected() {
-                let key = adsb_airplanes.keys().nth(selected).unwrap();
-                let aircraft_details = adsb_airplanes.aircraft_details(*key);
+                let key = airplane_order(settings, adsb_airplanes)[selected];
+                let aircraft_details = adsb_airplanes.aircraft_details(key);
                 if let Some(AirplaneDetails { position, .. }) = aircraft_details {
                     settings.custom_lat = Some(position.latitude);
                     settings.custom_long = Some(position.longitude);
@@ -613,12 +964,15 @@ fn handle_mouseevent(mouse_event: MouseEvent, settings: &mut Settings, tui_info:
                 (20..=34, TUI_START_MARGIN..=TUI_BAR_WIDTH) => {
                     settings.tab_selection = Tab::Airplanes;
                 },
-                (36..=42, TUI_START_MARGIN..=TUI_BAR_WIDTH) => {
+                (36..=41, TUI_START_MARGIN..=TUI_BAR_WIDTH) => {
                     settings.tab_selection = Tab::Stats;
                 },
                 (42..=48, TUI_START_MARGIN..=TUI_BAR_WIDTH) => {
                     settings.tab_selection = Tab::Help;
                 },
+                (50..=54, TUI_START_MARGIN..=TUI_BAR_WIDTH) => {
+                    settings.tab_selection = Tab::Log;
+                },
                 _ => (),
             }
             // left touchscreen (if enabled)
@@ -652,7 +1006,7 @@ fn handle_mouseevent(mouse_event: MouseEvent, settings: &mut Settings, tui_info:
             // check tab
             match settings.tab_selection {
                 Tab::Map | Tab::Coverage => (),
-                Tab::Airplanes | Tab::Stats | Tab::Help => return,
+                Tab::Airplanes | Tab::Stats | Tab::Help | Tab::Log => return,
             }
 
             // check bounds below tab selection
@@ -669,23 +1023,11 @@ fn handle_mouseevent(mouse_event: MouseEvent, settings: &mut Settings, tui_info:
             }
 
             // if we have a previous mouse drag without a mouse lift, change the current position
-            if let Some((column, row)) = &settings.last_mouse_dragging {
-                let up =
-                    f64::from(i32::from(mouse_event.row).wrapping_sub(i32::from(*row))) * 0.020;
-                if let Some(lat) = &mut settings.custom_lat {
-                    *lat += up;
-                } else {
-                    settings.custom_lat = Some(settings.lat + up);
-                }
-
-                let left =
-                    f64::from(i32::from(mouse_event.column).wrapping_sub(i32::from(*column)))
-                        * 0.020;
-                if let Some(long) = &mut settings.custom_long {
-                    *long -= left;
-                } else {
-                    settings.custom_long = Some(settings.long - left);
-                }
+            if let Some((column, row)) = settings.last_mouse_dragging {
+                let up = f64::from(i32::from(mouse_event.row).wrapping_sub(i32::from(row))) * 0.020;
+                let left = f64::from(i32::from(mouse_event.column).wrapping_sub(i32::from(column)))
+                    * 0.020;
+                settings.move_view(up, -left);
             }
             settings.last_mouse_dragging = Some((mouse_event.column, mouse_event.row));
         },
@@ -698,14 +1040,17 @@ fn handle_mouseevent(mouse_event: MouseEvent, settings: &mut Settings, tui_info:
     }
 }
 
-fn draw(
+/// Draw the tui, with `now` as the current time shown
+#[allow(clippy::too_many_arguments)]
+fn draw<B: Backend>(
     version: &str,
-    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    terminal: &mut Terminal<B>,
     adsb_airplanes: &Airplanes,
     settings: &Settings,
     coverage_airplanes: &[(f64, f64, u32, ICAO)],
     airplanes_state: &mut TableState,
     stats: &Stats,
+    now: SystemTime,
 ) -> TuiInfo {
     let mut tui_info = TuiInfo::default();
 
@@ -721,7 +1066,7 @@ fn draw(
 
             // render tabs
             let airplane_len = format!("Airplanes({})", adsb_airplanes.len());
-            let titles = ["Map", "Coverage", &airplane_len, "Stats", "Help"]
+            let titles = ["Map", "Coverage", &airplane_len, "Stats", "Help", "Log"]
                 .iter()
                 .copied()
                 .map(Spans::from)
@@ -743,8 +1088,11 @@ fn draw(
                 .block(
                     Block::default()
                         .title(format!(
-                            "rsadsb/radar(v{}) - ({:.DEFAULT_PRECISION$},{:.DEFAULT_PRECISION$}) {view_type}",
-                            version, lat, long
+                            "rsadsb/radar(v{}) - ({:.DEFAULT_PRECISION$},{:.DEFAULT_PRECISION$}) {view_type} - {}",
+                            version,
+                            lat,
+                            long,
+                            timestamp::short(now, settings.utc_offset)
                         ))
                         .borders(Borders::ALL),
                 )
@@ -764,13 +1112,18 @@ fn draw(
                 coverage_airplanes,
                 airplanes_state,
                 stats,
+                now,
             );
+
+            // replace what the terminal can't display
+            f.render_widget(Fallback(settings.capabilities), f.size());
         })
         .unwrap();
 
     tui_info
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_bottom_chunks<A: tui::backend::Backend>(
     f: &mut tui::Frame<A>,
     chunks: Vec<Rect>,
@@ -779,6 +1132,7 @@ fn draw_bottom_chunks<A: tui::backend::Backend>(
     coverage_airplanes: &[(f64, f64, u32, ICAO)],
     airplanes_state: &mut TableState,
     stats: &Stats,
+    now: SystemTime,
 ) -> TuiInfo {
     let mut tui_info = TuiInfo::default();
 
@@ -823,29 +1177,38 @@ fn draw_bottom_chunks<A: tui::backend::Backend>(
 
     // render the bottom cavas depending on the chosen tab
     match settings.tab_selection {
-        Tab::Map => build_tab_map(f, bottom_chunks, settings, adsb_airplanes),
+        Tab::Map => build_tab_map(f, bottom_chunks, settings, adsb_airplanes, now),
         Tab::Coverage => build_tab_coverage(f, bottom_chunks, settings, coverage_airplanes),
-        Tab::Airplanes => build_tab_airplanes(f, bottom_chunks, adsb_airplanes, airplanes_state),
-        Tab::Stats => build_tab_stats(f, bottom_chunks, stats, settings),
+        Tab::Airplanes => build_tab_airplanes(
+            f,
+            bottom_chunks,
+            settings,
+            adsb_airplanes,
+            airplanes_state,
+            now,
+        ),
+        Tab::Stats => build_tab_stats(f, bottom_chunks, stats, settings, adsb_airplanes),
         Tab::Help => build_tab_help(f, &bottom_chunks),
+        Tab::Log => build_tab_log(f, &bottom_chunks, settings),
     }
 
     tui_info
 }
 
-/// Draw vertical and horizontal lines
-fn draw_lines(ctx: &mut tui::widgets::canvas::Context<'_>) {
+/// Draw vertical and horizontal lines through the center
+fn draw_lines(ctx: &mut tui::widgets::canvas::Context<'_>, settings: &Settings) {
+    let (x, y) = settings.origin;
     ctx.draw(&Line {
         x1: MAX_PLOT_HIGH,
-        y1: 0.0,
+        y1: y,
         x2: MAX_PLOT_LOW,
-        y2: 0.0,
+        y2: y,
         color: Color::White,
     });
     ctx.draw(&Line {
-        x1: 0.0,
+        x1: x,
         y1: MAX_PLOT_HIGH,
-        x2: 0.0,
+        x2: x,
         y2: MAX_PLOT_LOW,
         color: Color::White,
     });
@@ -889,9 +1252,57 @@ pub fn draw_locations(ctx: &mut tui::widgets::canvas::Context<'_>, settings: &Se
     }
 }
 
+/// lat, long, and altitude in ft if known, from gpsd
+type GpsPosition = (f64, f64, Option<f64>);
+
+/// function ran within a thread for sending the raw UAT frames of dump978 to `sender`
+fn uat_thread(socket: SocketAddr, sender: &Sender<Vec<u8>>) {
+    let reader = match AvrReader::connect_uat(socket) {
+        Ok(reader) => reader,
+        Err(e) => {
+            error!("could not connect to dump978 @ {socket}: {e}");
+            return;
+        },
+    };
+    info!("[dump978] connected");
+    for frame in reader {
+        match frame {
+            Ok(frame) => {
+                if sender.send(frame).is_err() {
+                    return;
+                }
+            },
+            Err(e) => {
+                error!("[dump978] {e}");
+                return;
+            },
+        }
+    }
+    info!("[dump978] end of stream");
+}
+
+/// Track all UAT frames received since the last call
+fn read_uat_frames(
+    uat_frames: &Receiver<Vec<u8>>,
+    adsb_airplanes: &mut Airplanes,
+    stats: &mut Stats,
+    settings: &Settings,
+) {
+    while let Ok(bytes) = uat_frames.try_recv() {
+        debug!("UAT bytes: {}", hex::encode(&bytes));
+        stats.uat_frames += 1;
+        if let Some(frame) = UatFrame::from_bytes(&bytes) {
+            let airplane_added = adsb_airplanes.action_uat(&frame, (settings.lat, settings.long));
+            stats.update(adsb_airplanes, airplane_added);
+        } else {
+            stats.count_error(ErrorKind::Framing);
+        }
+    }
+}
+
 /// function ran within a thread for updating `gps_lat_long` when the gpsd shows a new `lat_long`
 /// position.
-fn gpsd_thread(gpsd_ip: &str, gps_lat_long: Arc<Mutex<Option<(f64, f64)>>>) {
+fn gpsd_thread(gpsd_ip: &str, gps_lat_long: Arc<Mutex<Option<GpsPosition>>>) {
     let gpsd_port = 2947;
     if let Ok(stream) = TcpStream::connect((gpsd_ip, gpsd_port))
         .with_context(|| format!("unable to connect to gpsd server @ {gpsd_ip}:{gpsd_port}"))
@@ -908,8 +1319,12 @@ fn gpsd_thread(gpsd_ip: &str, gps_lat_long: Arc<Mutex<Option<(f64, f64)>>>) {
                 // only update if the operator hasn't set a lat/long position already
                 if let Ok(mut lat_long) = gps_lat_long.lock() {
                     if let (Some(lat), Some(lon)) = (data.lat, data.lon) {
-                        info!("[gpsd] lat: {lat},  long:{lon}");
-                        *lat_long = Some((lat, lon));
+                        let altitude = data
+                            .alt_msl
+                            .or(data.alt)
+                            .map(|alt| f64::from(alt) * FEET_PER_METER);
+                        info!("[gpsd] lat: {lat},  long:{lon}, alt: {altitude:?}");
+                        *lat_long = Some((lat, lon, altitude));
                     }
                 }
             }
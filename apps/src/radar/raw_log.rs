@@ -0,0 +1,133 @@
+//! Log tab, the most recently received raw frames
+//!
+//! Every frame is shown in hex, colored by its downlink format and followed by its [`Peek`]
+//! classification, so the stream can be scanned without decoding every frame.
+
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+use adsb_deku::Peek;
+use time::OffsetDateTime;
+use tui::layout::Rect;
+use tui::style::{Color, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph};
+
+use crate::Settings;
+
+/// Amount of frames kept for the Log tab
+const CAPACITY: usize = 256;
+
+/// Downlink formats of each color, shown in the legend
+const LEGEND: [(&str, Color); 7] = [
+    ("ACAS", Color::Red),
+    ("surveillance", Color::Cyan),
+    ("all-call", Color::Blue),
+    ("ADS-B", Color::Green),
+    ("military", Color::Magenta),
+    ("Comm-B", Color::Yellow),
+    ("other", Color::Gray),
+];
+
+/// Color of a frame with downlink format `df`, see [`LEGEND`]
+const fn color(df: u8) -> Color {
+    match df {
+        0 | 16 => LEGEND[0].1,
+        4 | 5 => LEGEND[1].1,
+        11 => LEGEND[2].1,
+        17 | 18 => LEGEND[3].1,
+        19 => LEGEND[4].1,
+        20 | 21 => LEGEND[5].1,
+        _ => LEGEND[6].1,
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RawLog {
+    /// Oldest frame first
+    frames: VecDeque<(SystemTime, Vec<u8>)>,
+}
+
+impl RawLog {
+    /// Add a received frame, removing the oldest frame when full
+    pub fn push(&mut self, time: SystemTime, bytes: &[u8]) {
+        if self.frames.len() == CAPACITY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back((time, bytes.to_vec()));
+    }
+
+    /// Line of a frame: time, hex and classification
+    fn line(time: SystemTime, bytes: &[u8], settings: &Settings) -> Spans<'static> {
+        let time = OffsetDateTime::from(time).to_offset(settings.utc_offset);
+        let time = format!(
+            "{:02}:{:02}:{:02} ",
+            time.hour(),
+            time.minute(),
+            time.second()
+        );
+        let peek = Peek::new(bytes);
+        let style = Style::default().fg(peek.map_or(Color::Gray, |peek| color(peek.df)));
+        // pad short frames, so that the classifications line up
+        let hex = format!("{:<28}", hex::encode(bytes));
+        let kind = peek.map(|peek| format!("  {peek}")).unwrap_or_default();
+        Spans::from(vec![
+            Span::styled(time, Style::default().fg(Color::White)),
+            Span::styled(hex, style),
+            Span::styled(kind, style),
+        ])
+    }
+}
+
+/// Render Log tab for tui display, the newest frame at the bottom
+pub fn build_tab_log<A: tui::backend::Backend>(
+    f: &mut tui::Frame<A>,
+    chunks: &[Rect],
+    settings: &Settings,
+) {
+    let area = chunks[1];
+    let rows = usize::from(area.height.saturating_sub(2));
+    let skip = settings.raw_log.frames.len().saturating_sub(rows);
+    let lines: Vec<Spans> = settings
+        .raw_log
+        .frames
+        .iter()
+        .skip(skip)
+        .map(|(time, bytes)| RawLog::line(*time, bytes, settings))
+        .collect();
+
+    let mut title = vec![Span::raw("Log - ")];
+    for (i, (name, color)) in LEGEND.iter().enumerate() {
+        if i != 0 {
+            title.push(Span::raw(" "));
+        }
+        title.push(Span::styled(*name, Style::default().fg(*color)));
+    }
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(Color::White))
+        .block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(paragraph, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn capacity() {
+        let mut log = RawLog::default();
+        for i in 0..=CAPACITY {
+            log.push(
+                SystemTime::UNIX_EPOCH + Duration::from_secs(i as u64),
+                &[0x58],
+            );
+        }
+        assert_eq!(log.frames.len(), CAPACITY);
+        assert_eq!(
+            log.frames.front().unwrap().0,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1)
+        );
+    }
+}
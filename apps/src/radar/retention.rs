@@ -0,0 +1,225 @@
+//! Retention tiers of the recorded traffic, so that unattended installs don't fill their disk
+//!
+//! - Memory: a snapshot of all aircraft every second for `--history-minutes`, see [`History`].
+//! - Disk: with `--retention-days`, the newest snapshot every [`DISK_INTERVAL`] is appended to the
+//!   daily `history.<date>.csv` in `--log-folder`. These and the daily `radar.log.<date>` files
+//!   are removed once they are older than `--retention-days`.
+//! - Aggregates: `sectors.csv` and `performance.csv` are small and kept forever.
+//!
+//! Dates of the file names are in UTC, as the daily `radar.log` files. The times in the files are
+//! in `--timezone`.
+
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use rsadsb_apps::timestamp;
+use serde::Serialize;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime, UtcOffset};
+use tracing::{error, info};
+
+use crate::history::{History, Snapshot};
+
+/// Time between two snapshots written to disk
+pub const DISK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Date in the name of the daily files
+const DATE: &[FormatItem<'_>] = format_description!("[year]-[month]-[day]");
+
+/// Row of `history.<date>.csv`, an aircraft of a snapshot
+#[derive(Debug, PartialEq, Serialize)]
+struct TargetRow {
+    time: String,
+    icao: String,
+    latitude: f64,
+    longitude: f64,
+    /// Altitude in ft
+    altitude: u16,
+    heading: Option<f32>,
+}
+
+pub struct Retention {
+    folder: PathBuf,
+    /// Daily files older than this are removed
+    days: u64,
+    /// Time of the last snapshot written to disk
+    last_sample: Option<SystemTime>,
+    /// Date the old daily files were last removed
+    last_cleanup: Option<Date>,
+    /// Offset of the written times, from `--timezone`
+    utc_offset: UtcOffset,
+}
+
+impl Retention {
+    pub fn new(days: u64, log_folder: &str, utc_offset: UtcOffset) -> Self {
+        Self {
+            folder: Path::new(log_folder).to_path_buf(),
+            days,
+            last_sample: None,
+            last_cleanup: None,
+            utc_offset,
+        }
+    }
+
+    /// Write the newest snapshot of `history` every `DISK_INTERVAL`, and remove the old daily
+    /// files once a day
+    pub fn update(&mut self, now: SystemTime, history: &History) {
+        let due = self.last_sample.map_or(true, |last| {
+            now.duration_since(last)
+                .map_or(false, |elapsed| elapsed >= DISK_INTERVAL)
+        });
+        if due {
+            if let Some(snapshot) = history.at(now) {
+                if let Err(e) = self.write(snapshot) {
+                    error!("unable to write history: {e:?}");
+                }
+            }
+            self.last_sample = Some(now);
+        }
+
+        let today = OffsetDateTime::from(now).date();
+        if self.last_cleanup != Some(today) {
+            if let Err(e) = self.remove_old(today) {
+                error!("unable to remove old files: {e:?}");
+            }
+            self.last_cleanup = Some(today);
+        }
+    }
+
+    /// Append `snapshot` to the file of its date
+    fn write(&self, snapshot: &Snapshot) -> Result<()> {
+        let date = OffsetDateTime::from(snapshot.time).date().format(DATE)?;
+        let path = self.folder.join(format!("history.{date}.csv"));
+        let exists = path.exists();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("unable to open {}", path.display()))?;
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(!exists)
+            .from_writer(file);
+        let time = timestamp::iso8601(snapshot.time, self.utc_offset);
+        for target in &snapshot.targets {
+            writer.serialize(TargetRow {
+                time: time.clone(),
+                icao: target.icao.to_string(),
+                latitude: target.latitude,
+                longitude: target.longitude,
+                altitude: target.altitude,
+                heading: target.heading,
+            })?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Remove the daily files older than `days` before `today`
+    fn remove_old(&self, today: Date) -> Result<()> {
+        let oldest = today - time::Duration::days(self.days.try_into().unwrap_or(i64::MAX));
+        for entry in fs::read_dir(&self.folder)
+            .with_context(|| format!("unable to read {}", self.folder.display()))?
+        {
+            let path = entry?.path();
+            let date = path.file_name().and_then(|name| file_date(name.to_str()?));
+            if matches!(date, Some(date) if date < oldest) {
+                info!("removing {}", path.display());
+                fs::remove_file(&path)
+                    .with_context(|| format!("unable to remove {}", path.display()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Date of a daily `radar.log.<date>` or `history.<date>.csv` file
+fn file_date(name: &str) -> Option<Date> {
+    let date = name
+        .strip_prefix("radar.log.")
+        .or_else(|| name.strip_prefix("history.")?.strip_suffix(".csv"))?;
+    Date::parse(date, DATE).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use adsb_deku::deku::DekuContainerRead;
+    use adsb_deku::Frame;
+    use rsadsb_common::Airplanes;
+    use time::macros::date;
+
+    use super::*;
+
+    /// 2022-03-01 12:00:00 UTC
+    fn now() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_646_136_000)
+    }
+
+    #[test]
+    fn file_dates() {
+        assert_eq!(
+            file_date("radar.log.2022-03-01"),
+            Some(date!(2022 - 03 - 01))
+        );
+        assert_eq!(
+            file_date("history.2022-02-27.csv"),
+            Some(date!(2022 - 02 - 27))
+        );
+        assert_eq!(file_date("radar.log"), None);
+        assert_eq!(file_date("sectors.csv"), None);
+        assert_eq!(file_date("performance.2022-02-27.csv"), None);
+    }
+
+    #[test]
+    fn update() {
+        let folder = std::env::temp_dir().join(format!("radar-retention-{}", std::process::id()));
+        fs::create_dir_all(&folder).unwrap();
+        for name in [
+            "radar.log.2022-02-26",
+            "radar.log.2022-02-27",
+            "history.2022-02-26.csv",
+            "sectors.csv",
+        ] {
+            fs::write(folder.join(name), "").unwrap();
+        }
+
+        let mut airplanes = Airplanes::new();
+        for bytes in [
+            hex::decode("8d40621d58c382d690c8ac2863a7").unwrap(),
+            hex::decode("8d40621d58c386435cc412692ad6").unwrap(),
+        ] {
+            let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+            airplanes.action(frame.validate().unwrap(), (52.25, 3.91));
+        }
+        let mut history = History::new(Duration::from_secs(600));
+        history.record(now(), &airplanes);
+
+        let mut retention = Retention::new(2, folder.to_str().unwrap(), UtcOffset::UTC);
+        retention.update(now(), &history);
+        // within DISK_INTERVAL
+        history.record(now() + Duration::from_secs(30), &airplanes);
+        retention.update(now() + Duration::from_secs(30), &history);
+
+        let mut names: Vec<_> = fs::read_dir(&folder)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "history.2022-03-01.csv",
+                "radar.log.2022-02-27",
+                "sectors.csv"
+            ]
+        );
+        let csv = fs::read_to_string(folder.join("history.2022-03-01.csv")).unwrap();
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.starts_with("time,icao,latitude,longitude,altitude,heading\n"));
+        assert!(csv.contains("2022-03-01T12:00:00Z,40621D,"));
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,208 @@
+//! Traffic counting in the airspace sectors of `--sectors`
+//!
+//! The counts of every sector are appended to `sectors.csv` in `--log-folder` every
+//! `--sector-interval` minutes, and counting starts again for the next period.
+
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{bail, Context, Result};
+use rsadsb_apps::timestamp;
+use rsadsb_common::sector::{Sector, SectorCounter};
+use rsadsb_common::Airplanes;
+use serde::{Deserialize, Serialize};
+use time::UtcOffset;
+use tracing::error;
+
+/// Row of the `--sectors` csv file
+#[derive(Debug, Deserialize)]
+struct SectorRow {
+    name: String,
+    /// Floor in ft
+    floor: u16,
+    /// Ceiling in ft
+    ceiling: u16,
+    /// Corners as "lat long;lat long;lat long"
+    polygon: String,
+}
+
+impl SectorRow {
+    fn into_sector(self) -> Result<Sector> {
+        let mut polygon = vec![];
+        for corner in self.polygon.split(';') {
+            let mut coords = corner.split_whitespace().map(str::parse::<f64>);
+            match (coords.next(), coords.next(), coords.next()) {
+                (Some(Ok(lat)), Some(Ok(long)), None) => polygon.push((lat, long)),
+                _ => bail!(
+                    "invalid corner of sector {}: {corner:?}, expected \"lat long\"",
+                    self.name
+                ),
+            }
+        }
+        if polygon.len() < 3 {
+            bail!("sector {} needs at least 3 corners", self.name);
+        }
+        Ok(Sector {
+            name: self.name,
+            polygon,
+            floor: self.floor,
+            ceiling: self.ceiling,
+        })
+    }
+}
+
+/// Row of `sectors.csv`, with the counts of a sector for one period
+#[derive(Debug, PartialEq, Serialize)]
+struct CountRow {
+    start: String,
+    end: String,
+    sector: String,
+    entries: u64,
+    /// Time spent inside the sector by all aircraft
+    occupancy_seconds: u64,
+    peak: usize,
+}
+
+/// Read the sectors of a csv file with the columns `name,floor,ceiling,polygon`
+pub fn read_sectors(path: &Path) -> Result<Vec<Sector>> {
+    let file = File::open(path).with_context(|| format!("unable to open {}", path.display()))?;
+    let mut sectors = vec![];
+    for row in csv::Reader::from_reader(file).deserialize() {
+        let row: SectorRow = row.with_context(|| format!("unable to read {}", path.display()))?;
+        sectors.push(row.into_sector()?);
+    }
+    Ok(sectors)
+}
+
+pub struct SectorExport {
+    counter: SectorCounter,
+    /// Time between exports
+    interval: Duration,
+    /// Start of the current period
+    start: SystemTime,
+    path: PathBuf,
+    /// Offset of the exported times, from `--timezone`
+    utc_offset: UtcOffset,
+}
+
+impl SectorExport {
+    pub fn new(
+        sectors: Vec<Sector>,
+        interval: Duration,
+        log_folder: &str,
+        utc_offset: UtcOffset,
+        now: SystemTime,
+    ) -> Self {
+        Self {
+            counter: SectorCounter::new(sectors),
+            interval,
+            start: now,
+            path: Path::new(log_folder).join("sectors.csv"),
+            utc_offset,
+        }
+    }
+
+    /// Update the counts, exporting them once the period is over
+    pub fn update(&mut self, now: SystemTime, airplanes: &Airplanes) {
+        let since_epoch = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        self.counter.update(since_epoch, airplanes);
+        if now.duration_since(self.start).unwrap_or_default() >= self.interval {
+            if let Err(e) = self.export(now) {
+                error!("unable to export sector counts: {e:?}");
+            }
+            self.counter.reset();
+            self.start = now;
+        }
+    }
+
+    /// Append the counts of the current period
+    fn export(&self, now: SystemTime) -> Result<()> {
+        let exists = self.path.exists();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("unable to open {}", self.path.display()))?;
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(!exists)
+            .from_writer(file);
+        for row in self.rows(now) {
+            writer.serialize(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn rows(&self, now: SystemTime) -> Vec<CountRow> {
+        let start = timestamp::iso8601(self.start, self.utc_offset);
+        let end = timestamp::iso8601(now, self.utc_offset);
+        self.counter
+            .sectors()
+            .map(|(sector, stats)| CountRow {
+                start: start.clone(),
+                end: end.clone(),
+                sector: sector.name.clone(),
+                entries: stats.entries,
+                occupancy_seconds: stats.occupancy.as_secs(),
+                peak: stats.peak,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sector_row() {
+        let row = SectorRow {
+            name: "runway 27 approach".to_string(),
+            floor: 0,
+            ceiling: 3000,
+            polygon: "52.0 4.0; 52.0 4.2;52.1 4.2".to_string(),
+        };
+        let sector = row.into_sector().unwrap();
+        assert_eq!(sector.polygon, vec![(52.0, 4.0), (52.0, 4.2), (52.1, 4.2)]);
+
+        let row = SectorRow {
+            name: "invalid".to_string(),
+            floor: 0,
+            ceiling: 3000,
+            polygon: "52.0 4.0;52.0,4.2;52.1 4.2".to_string(),
+        };
+        assert!(row.into_sector().is_err());
+    }
+
+    #[test]
+    fn rows() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_646_136_000);
+        let sector = Sector {
+            name: "north".to_string(),
+            polygon: vec![(0.0, 0.0), (0.0, 1.0), (1.0, 1.0)],
+            floor: 0,
+            ceiling: 1000,
+        };
+        let export = SectorExport::new(
+            vec![sector],
+            Duration::from_secs(900),
+            "logs",
+            UtcOffset::UTC,
+            start,
+        );
+        assert_eq!(
+            export.rows(start + Duration::from_secs(900)),
+            vec![CountRow {
+                start: "2022-03-01T12:00:00Z".to_string(),
+                end: "2022-03-01T12:15:00Z".to_string(),
+                sector: "north".to_string(),
+                entries: 0,
+                occupancy_seconds: 0,
+                peak: 0,
+            }]
+        );
+    }
+}
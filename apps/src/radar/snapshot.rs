@@ -0,0 +1,213 @@
+//! Golden snapshots of the tui, rendered from a bundled capture with a fixed clock
+//!
+//! Every tab is drawn into a `TestBackend` and compared against `tests/snapshots/<name>.txt`,
+//! holding the symbol of every cell followed by the foreground color of every cell. After an
+//! intended change of the tui, write the snapshots again with `UPDATE_SNAPSHOTS=1 cargo test`.
+
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use adsb_deku::deku::DekuContainerRead;
+use adsb_deku::{Frame, ICAO};
+use clap::Parser;
+use rsadsb_apps::source::AvrReader;
+use rsadsb_common::Airplanes;
+use time::UtcOffset;
+use tui::backend::TestBackend;
+use tui::buffer::Buffer;
+use tui::style::Color;
+use tui::widgets::TableState;
+use tui::Terminal;
+
+use crate::coverage::populate_coverage;
+use crate::stats::Stats;
+use crate::terminal::Capabilities;
+use crate::vertical_profile::ProfileAxis;
+use crate::{draw, Opts, Settings, Tab};
+
+/// Frames of two aircraft with a position, callsign or velocity, one with a bad CRC, and DF11
+/// frames with a valid and a reserved CA
+const CAPTURE: &[u8] = include_bytes!("../../tests/snapshots/capture.avr");
+
+/// Time shown by the tui, 2022-03-01 12:00:00 UTC
+const NOW: Duration = Duration::from_secs(1_646_136_000);
+
+const WIDTH: u16 = 120;
+const HEIGHT: u16 = 36;
+
+/// Everything needed to draw the tui, as it would be after reading `CAPTURE`
+struct Radar {
+    settings: Settings,
+    airplanes: Airplanes,
+    coverage_airplanes: Vec<(f64, f64, u32, ICAO)>,
+    stats: Stats,
+}
+
+impl Radar {
+    fn new(args: &[&str]) -> Self {
+        let base = ["radar", "--lat=52.0", "--long=4.0", "--scale=0.5"];
+        let opts = Opts::try_parse_from(base.iter().chain(args)).unwrap();
+        let mut settings = Settings::new(opts, UtcOffset::UTC, Capabilities::FULL);
+        let mut airplanes = Airplanes::new();
+        let mut coverage_airplanes = vec![];
+        let mut stats = Stats::default();
+        for bytes in AvrReader::new(CAPTURE) {
+            let bytes = bytes.unwrap();
+            settings.raw_log.push(now(), &bytes);
+            let (_, frame) = Frame::from_bytes((&bytes, 0)).unwrap();
+            if let Some(kind) = frame.error_kind() {
+                stats.count_error(kind);
+            }
+            if frame
+                .capability()
+                .map_or(false, |capability| capability.is_reserved())
+            {
+                stats.reserved_capability += 1;
+            }
+            if let Ok(frame) = frame.validate() {
+                airplanes.action(frame, (settings.lat, settings.long));
+            }
+            populate_coverage(&airplanes, &mut coverage_airplanes);
+        }
+        settings.history.record(now(), &airplanes);
+        Self {
+            settings,
+            airplanes,
+            coverage_airplanes,
+            stats,
+        }
+    }
+
+    /// Draw `tab` at `NOW`
+    fn render(&mut self, tab: Tab, selected: Option<usize>) -> Buffer {
+        self.settings.tab_selection = tab;
+        let mut airplanes_state = TableState::default();
+        airplanes_state.select(selected);
+        let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+        draw(
+            "0.0.0",
+            &mut terminal,
+            &self.airplanes,
+            &self.settings,
+            &self.coverage_airplanes,
+            &mut airplanes_state,
+            &self.stats,
+            now(),
+        );
+        terminal.backend().buffer().clone()
+    }
+}
+
+fn now() -> SystemTime {
+    SystemTime::UNIX_EPOCH + NOW
+}
+
+/// Single character for the colors used by the tui
+fn color(color: Color) -> char {
+    match color {
+        Color::Reset => '.',
+        Color::White => 'w',
+        Color::Green => 'g',
+        Color::Blue => 'b',
+        Color::Red => 'r',
+        Color::Yellow => 'y',
+        Color::Gray => 'a',
+        Color::Cyan => 'c',
+        Color::Magenta => 'm',
+        _ => '?',
+    }
+}
+
+/// Rows of symbols without trailing whitespace, an empty line, and rows of colors
+fn snapshot(buffer: &Buffer) -> String {
+    let width = usize::from(buffer.area.width);
+    let mut out = String::new();
+    for row in buffer.content.chunks(width) {
+        let symbols: String = row.iter().map(|cell| cell.symbol.as_str()).collect();
+        writeln!(out, "{}", symbols.trim_end()).unwrap();
+    }
+    out.push('\n');
+    for row in buffer.content.chunks(width) {
+        let colors: String = row.iter().map(|cell| color(cell.fg)).collect();
+        writeln!(out, "{colors}").unwrap();
+    }
+    out
+}
+
+/// Compare `buffer` against the snapshot `name`, or write it with `UPDATE_SNAPSHOTS` set
+fn assert_snapshot(name: &str, buffer: &Buffer) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"));
+    let actual = snapshot(buffer);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("unable to read {}: {e}", path.display()));
+    assert!(
+        expected == actual,
+        "{name} differs from {}, run with UPDATE_SNAPSHOTS=1 if intended\n{actual}",
+        path.display()
+    );
+}
+
+#[test]
+fn map() {
+    let mut radar = Radar::new(&["--locations", "(Home,52.1,4.1)"]);
+    assert_snapshot("map", &radar.render(Tab::Map, None));
+}
+
+#[test]
+fn map_ascii() {
+    let mut radar = Radar::new(&["--locations", "(Home,52.1,4.1)"]);
+    radar.settings.capabilities = Capabilities {
+        unicode: false,
+        braille: false,
+        color: false,
+    };
+    assert_snapshot("map_ascii", &radar.render(Tab::Map, None));
+}
+
+#[test]
+fn map_playback() {
+    let mut radar = Radar::new(&["--disable-lat-long"]);
+    radar.settings.toggle_playback();
+    assert_snapshot("map_playback", &radar.render(Tab::Map, None));
+}
+
+#[test]
+fn coverage() {
+    let mut radar = Radar::new(&[]);
+    assert_snapshot("coverage", &radar.render(Tab::Coverage, None));
+}
+
+#[test]
+fn airplanes() {
+    let mut radar = Radar::new(&[]);
+    assert_snapshot("airplanes", &radar.render(Tab::Airplanes, Some(0)));
+    radar.settings.sort_cpa = true;
+    assert_snapshot("airplanes_cpa", &radar.render(Tab::Airplanes, None));
+    radar.settings.vertical_profile = Some(ProfileAxis::Distance);
+    assert_snapshot("airplanes_profile", &radar.render(Tab::Airplanes, Some(0)));
+}
+
+#[test]
+fn stats() {
+    let mut radar = Radar::new(&[]);
+    assert_snapshot("stats", &radar.render(Tab::Stats, None));
+}
+
+#[test]
+fn help() {
+    let mut radar = Radar::new(&[]);
+    assert_snapshot("help", &radar.render(Tab::Help, None));
+}
+
+#[test]
+fn log() {
+    let mut radar = Radar::new(&[]);
+    assert_snapshot("log", &radar.render(Tab::Log, None));
+}
@@ -1,7 +1,9 @@
+use std::collections::BTreeMap;
 use std::time::SystemTime;
 
-use adsb_deku::ICAO;
-use rsadsb_common::{Added, AirplaneCoor, Airplanes};
+use adsb_deku::{ErrorKind, ICAO};
+use rsadsb_apps::timestamp;
+use rsadsb_common::{Added, AirplaneCoor, Airplanes, Link};
 use tracing::info;
 use tui::layout::{Constraint, Rect};
 use tui::style::{Color, Style};
@@ -14,9 +16,27 @@ pub struct Stats {
     most_distance: Option<(SystemTime, ICAO, AirplaneCoor)>,
     most_airplanes: Option<(SystemTime, u32)>,
     total_airplanes: u32,
+    /// Amount of times corrupted input was skipped over to find the next frame
+    pub resyncs: u64,
+    /// Amount of frames tagged as ghost frames
+    pub ghost_frames: u64,
+    /// Amount of frames not decoded because of `--decode-every` or `--max-decode-rate`
+    pub dropped_frames: u64,
+    /// Amount of frames with values outside of the `--sanity-check` bounds
+    pub suspect_frames: u64,
+    /// Amount of frames with a reserved CA field value, from non-compliant transponders
+    pub reserved_capability: u64,
+    /// Amount of frames read from `--uat-port`
+    pub uat_frames: u64,
+    /// Amount of frames that could not be decoded or used, by kind of error
+    errors: BTreeMap<ErrorKind, u64>,
 }
 
 impl Stats {
+    pub fn count_error(&mut self, kind: ErrorKind) {
+        *self.errors.entry(kind).or_default() += 1;
+    }
+
     pub fn update(&mut self, airplanes: &Airplanes, airplane_added: Added) {
         // Update most_distance
         let current_distance = self.most_distance.map_or(0.0, |most_distance| {
@@ -58,8 +78,8 @@ pub fn build_tab_stats<A: tui::backend::Backend>(
     chunks: Vec<Rect>,
     stats: &Stats,
     settings: &Settings,
+    airplanes: &Airplanes,
 ) {
-    let format = time::format_description::parse("[month]/[day] [hour]:[minute]:[second]").unwrap();
     let mut rows: Vec<Row> = vec![];
     // Most distance
     let (time, value) = if let Some((time, key, value)) = stats.most_distance {
@@ -69,12 +89,8 @@ pub fn build_tab_stats<A: tui::backend::Backend>(
         let distance = format!("{:.DEFAULT_PRECISION$}", value.kilo_distance.unwrap());
 
         // display time
-        let datetime = time::OffsetDateTime::from(time);
         (
-            datetime
-                .to_offset(settings.utc_offset)
-                .format(&format)
-                .unwrap(),
+            timestamp::short(time, settings.utc_offset),
             format!("[{key}]: {distance}km {lat},{lon}"),
         )
     } else {
@@ -85,12 +101,8 @@ pub fn build_tab_stats<A: tui::backend::Backend>(
     // Most airplanes tracked at one time
     let (time, value) = if let Some((time, most_airplanes)) = stats.most_airplanes {
         // display time
-        let datetime = time::OffsetDateTime::from(time);
         (
-            datetime
-                .to_offset(settings.utc_offset)
-                .format(&format)
-                .unwrap(),
+            timestamp::short(time, settings.utc_offset),
             most_airplanes.to_string(),
         )
     } else {
@@ -106,13 +118,96 @@ pub fn build_tab_stats<A: tui::backend::Backend>(
         &total_airplanes_s,
     ]));
 
+    // Resyncs on corrupted input
+    let resyncs_s = stats.resyncs.to_string();
+    rows.push(Row::new(vec!["Input Resyncs", "All Time", &resyncs_s]));
+
+    // Ghost frames
+    let ghost_frames_s = stats.ghost_frames.to_string();
+    rows.push(Row::new(vec!["Ghost Frames", "All Time", &ghost_frames_s]));
+
+    // Frames not decoded by the throttle
+    let dropped_frames_s = stats.dropped_frames.to_string();
+    rows.push(Row::new(vec![
+        "Dropped Frames",
+        "All Time",
+        &dropped_frames_s,
+    ]));
+
+    // Frames not tracked because of `--sanity-check`
+    let suspect_frames_s = stats.suspect_frames.to_string();
+    rows.push(Row::new(vec![
+        "Suspect Frames",
+        "All Time",
+        &suspect_frames_s,
+    ]));
+
+    // Frames from non-compliant transponders
+    let reserved_capability_s = stats.reserved_capability.to_string();
+    rows.push(Row::new(vec![
+        "Reserved CA",
+        "All Time",
+        &reserved_capability_s,
+    ]));
+
+    // Links of the currently tracked aircraft, with --uat-port
+    if settings.opts.uat_port.is_some() {
+        rows.push(Row::new(vec![
+            "UAT Frames".to_string(),
+            "All Time".to_string(),
+            stats.uat_frames.to_string(),
+        ]));
+
+        let (mut es1090, mut uat, mut both) = (0, 0, 0);
+        for (_, state) in airplanes.iter() {
+            match state.links.single_link() {
+                Some(Link::Es1090) => es1090 += 1,
+                Some(Link::Uat) => uat += 1,
+                None => both += 1,
+            }
+        }
+        rows.push(Row::new(vec![
+            "1090 Only".to_string(),
+            "Current".to_string(),
+            es1090.to_string(),
+        ]));
+        rows.push(Row::new(vec![
+            "UAT Only".to_string(),
+            "Current".to_string(),
+            uat.to_string(),
+        ]));
+        rows.push(Row::new(vec![
+            "1090 + UAT".to_string(),
+            "Current".to_string(),
+            both.to_string(),
+        ]));
+    }
+
+    // Frames that could not be decoded or used
+    for kind in ErrorKind::ALL {
+        let label = match kind {
+            ErrorKind::Framing => "Framing Errors",
+            ErrorKind::Crc => "CRC Errors",
+            ErrorKind::Parse => "Parse Errors",
+            ErrorKind::Range => "Range Errors",
+            ErrorKind::Unsupported => "Unsupported Errors",
+            _ => "Other Errors",
+        };
+        let errors_s = stats.errors.get(&kind).copied().unwrap_or(0).to_string();
+        rows.push(Row::new(vec![
+            label.to_string(),
+            "All Time".to_string(),
+            errors_s,
+        ]));
+    }
+
     // draw table
     let table = Table::new(rows)
         .style(Style::default().fg(Color::White))
         .header(Row::new(vec!["Type", "DateTime", "Value"]).bottom_margin(1))
         .block(Block::default().title("Stats").borders(Borders::ALL))
         .widths(&[
-            Constraint::Length(16),
+            Constraint::Length(18),
             Constraint::Length(15),
             Constraint::Length(200),
         ])
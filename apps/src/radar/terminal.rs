@@ -0,0 +1,162 @@
+//! Capabilities of the terminal, for drawing over plain serial consoles and odd terminal emulators
+//!
+//! Without unicode support, everything is drawn with ASCII symbols. Without braille support, the
+//! canvas of the Map and Coverage tabs draws one point per cell. Without color support, all colors
+//! are removed.
+
+use std::env;
+
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::Color;
+use tui::symbols::Marker;
+use tui::widgets::Widget;
+
+/// Terminals only supporting ASCII and no colors
+const ASCII_TERMS: [&str; 4] = ["dumb", "vt52", "vt100", "vt220"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Symbols outside of ASCII, such as box drawing characters
+    pub unicode: bool,
+    /// Braille characters, for up to 8 points per cell on a canvas
+    pub braille: bool,
+    pub color: bool,
+}
+
+impl Capabilities {
+    pub const FULL: Self = Self {
+        unicode: true,
+        braille: true,
+        color: true,
+    };
+
+    /// Detect from `TERM`, `NO_COLOR` and the locale, only using ASCII symbols with `ascii`
+    pub fn detect(ascii: bool) -> Self {
+        let var = |name| env::var(name).ok().filter(|value| !value.is_empty());
+        let locale = var("LC_ALL")
+            .or_else(|| var("LC_CTYPE"))
+            .or_else(|| var("LANG"));
+        let mut capabilities = Self::from_env(
+            var("TERM").as_deref(),
+            locale.as_deref(),
+            var("NO_COLOR").is_some(),
+        );
+        if ascii {
+            capabilities.unicode = false;
+            capabilities.braille = false;
+        }
+        capabilities
+    }
+
+    fn from_env(term: Option<&str>, locale: Option<&str>, no_color: bool) -> Self {
+        let ascii_term = term.map_or(false, |term| ASCII_TERMS.contains(&term));
+        // without a locale, such as on Windows, unicode is assumed to be supported
+        let utf8 = locale.map_or(true, |locale| {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        });
+        let unicode = !ascii_term && utf8;
+        Self {
+            unicode,
+            // the fonts of the linux console have no braille characters
+            braille: unicode && term != Some("linux"),
+            color: !ascii_term && !no_color,
+        }
+    }
+
+    /// Marker of the canvas
+    pub const fn marker(self) -> Marker {
+        if self.braille {
+            Marker::Braille
+        } else {
+            Marker::Dot
+        }
+    }
+}
+
+/// Replaces the symbols and colors of the drawn cells not supported by the terminal, rendered
+/// after all other widgets
+pub struct Fallback(pub Capabilities);
+
+impl Widget for Fallback {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.0 == Capabilities::FULL {
+            return;
+        }
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = buf.get_mut(x, y);
+                if !self.0.unicode && !cell.symbol.is_ascii() {
+                    let symbol = ascii(&cell.symbol);
+                    cell.set_char(symbol);
+                }
+                if !self.0.color {
+                    cell.fg = Color::Reset;
+                    cell.bg = Color::Reset;
+                }
+            }
+        }
+    }
+}
+
+/// ASCII replacement of a symbol drawn by the tui
+fn ascii(symbol: &str) -> char {
+    match symbol.chars().next() {
+        Some('─' | '━' | '═') => '-',
+        Some('│' | '┃' | '║') => '|',
+        Some('┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼') => '+',
+        Some('•') => '*',
+        Some('▄' | '█') => '#',
+        Some('\u{2800}') => ' ',
+        Some('\u{2801}'..='\u{28ff}') => '.',
+        _ => '?',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect() {
+        let utf8 = Some("en_US.UTF-8");
+        assert_eq!(
+            Capabilities::from_env(Some("xterm-256color"), utf8, false),
+            Capabilities::FULL
+        );
+        assert_eq!(
+            Capabilities::from_env(None, None, false),
+            Capabilities::FULL
+        );
+
+        let linux = Capabilities::from_env(Some("linux"), utf8, false);
+        assert!(linux.unicode && !linux.braille && linux.color);
+
+        let c_locale = Capabilities::from_env(Some("xterm"), Some("C"), true);
+        assert!(!c_locale.unicode && !c_locale.braille && !c_locale.color);
+
+        let serial = Capabilities::from_env(Some("vt100"), utf8, false);
+        assert!(!serial.unicode && !serial.braille && !serial.color);
+    }
+
+    #[test]
+    fn fallback() {
+        let area = Rect::new(0, 0, 4, 1);
+        let mut buf = Buffer::empty(area);
+        buf.set_string(0, 0, "┌─⣿•", tui::style::Style::default().fg(Color::Red));
+        Fallback(Capabilities {
+            unicode: false,
+            braille: false,
+            color: false,
+        })
+        .render(area, &mut buf);
+        let symbols: String = buf
+            .content
+            .iter()
+            .map(|cell| cell.symbol.as_str())
+            .collect();
+        assert_eq!(symbols, "+-.*");
+        assert_eq!(buf.get(0, 0).fg, Color::Reset);
+    }
+}
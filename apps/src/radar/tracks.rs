@@ -0,0 +1,135 @@
+//! Tracks resampled at fixed time steps of `--track-step`
+//!
+//! Once an aircraft is removed after `--filter-time`, and for all aircraft when quitting, its
+//! track is resampled every `--track-step` seconds and appended to `tracks.csv` in `--log-folder`.
+
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use adsb_deku::ICAO;
+use anyhow::{Context, Result};
+use rsadsb_apps::timestamp;
+use rsadsb_common::AirplaneState;
+use serde::Serialize;
+use time::UtcOffset;
+use tracing::error;
+
+/// Row of `tracks.csv`, a resampled position of an aircraft
+#[derive(Debug, PartialEq, Serialize)]
+struct TrackRow {
+    time: String,
+    icao: String,
+    latitude: f64,
+    longitude: f64,
+    /// Altitude in ft
+    altitude: Option<u16>,
+}
+
+pub struct TrackExport {
+    /// Time between two resampled positions
+    step: Duration,
+    path: PathBuf,
+    /// Offset of the exported times, from `--timezone`
+    utc_offset: UtcOffset,
+}
+
+impl TrackExport {
+    pub fn new(step: Duration, log_folder: &str, utc_offset: UtcOffset) -> Self {
+        Self {
+            step,
+            path: Path::new(log_folder).join("tracks.csv"),
+            utc_offset,
+        }
+    }
+
+    /// Append the resampled tracks of `aircraft`
+    pub fn export<'a>(&self, aircraft: impl IntoIterator<Item = (&'a ICAO, &'a AirplaneState)>) {
+        let rows: Vec<TrackRow> = aircraft
+            .into_iter()
+            .flat_map(|(icao, state)| self.rows(*icao, state))
+            .collect();
+        if rows.is_empty() {
+            return;
+        }
+        if let Err(e) = self.write(&rows) {
+            error!("unable to export tracks: {e:?}");
+        }
+    }
+
+    fn write(&self, rows: &[TrackRow]) -> Result<()> {
+        let exists = self.path.exists();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("unable to open {}", self.path.display()))?;
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(!exists)
+            .from_writer(file);
+        for row in rows {
+            writer.serialize(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn rows(&self, icao: ICAO, state: &AirplaneState) -> Vec<TrackRow> {
+        state
+            .resampled_track(self.step)
+            .into_iter()
+            .map(|sample| TrackRow {
+                time: timestamp::iso8601(sample.time, self.utc_offset),
+                icao: icao.to_string(),
+                latitude: sample.position.latitude,
+                longitude: sample.position.longitude,
+                altitude: sample.altitude,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use adsb_deku::cpr::Position;
+    use rsadsb_common::AirplaneCoor;
+
+    use super::*;
+
+    fn coor(secs: u64, latitude: f64) -> AirplaneCoor {
+        AirplaneCoor {
+            position: Some(Position {
+                latitude,
+                longitude: 3.9,
+            }),
+            last_time: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_646_136_000 + secs)),
+            ..AirplaneCoor::default()
+        }
+    }
+
+    #[test]
+    fn rows() {
+        let state = AirplaneState {
+            track: Some(vec![coor(0, 52.0)]),
+            coords: coor(2, 52.2),
+            ..AirplaneState::default()
+        };
+        let offset = UtcOffset::from_hms(2, 0, 0).unwrap();
+        let export = TrackExport::new(Duration::from_secs(1), "logs", offset);
+        let rows = export.rows(ICAO([0x40, 0x62, 0x1d]), &state);
+        let times: Vec<_> = rows.iter().map(|row| row.time.as_str()).collect();
+        assert_eq!(
+            times,
+            [
+                "2022-03-01T14:00:00+02:00",
+                "2022-03-01T14:00:01+02:00",
+                "2022-03-01T14:00:02+02:00"
+            ]
+        );
+        assert!((rows[1].latitude - 52.1).abs() < 1e-9);
+        assert_eq!(rows[1].icao, "40621D");
+        assert_eq!(rows[1].altitude, None);
+    }
+}
@@ -0,0 +1,163 @@
+//! Vertical profile of the selected aircraft on the Airplanes tab
+//!
+//! The altitudes recorded by the [`History`] are plotted against the time before now, or against
+//! the distance from the receiver, such as to follow a descent on approach without exporting the
+//! recorded traffic.
+
+use std::time::SystemTime;
+
+use adsb_deku::ICAO;
+use tui::layout::Rect;
+use tui::style::{Color, Style};
+use tui::text::Span;
+use tui::widgets::{Axis, Block, Borders, Chart, Dataset, GraphType};
+
+use crate::history::History;
+use crate::Settings;
+
+/// Altitude step of the y axis in ft
+const ALTITUDE_STEP: f64 = 1000.0;
+
+/// Horizontal axis of the vertical profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileAxis {
+    /// Minutes before now
+    Time,
+    /// Distance from the receiver in km
+    Distance,
+}
+
+impl ProfileAxis {
+    pub const fn toggle(self) -> Self {
+        match self {
+            Self::Time => Self::Distance,
+            Self::Distance => Self::Time,
+        }
+    }
+
+    const fn title(self) -> &'static str {
+        match self {
+            Self::Time => "Minutes ago",
+            Self::Distance => "Distance (km)",
+        }
+    }
+}
+
+/// Recorded altitudes of `icao` as (x, altitude in ft), oldest first
+pub fn points(
+    history: &History,
+    icao: ICAO,
+    axis: ProfileAxis,
+    now: SystemTime,
+) -> Vec<(f64, f64)> {
+    history
+        .track(icao)
+        .into_iter()
+        .map(|(time, target)| {
+            let x = match axis {
+                ProfileAxis::Time => {
+                    -now.duration_since(time).unwrap_or_default().as_secs_f64() / 60.0
+                },
+                ProfileAxis::Distance => target.kilo_distance,
+            };
+            (x, f64::from(target.altitude))
+        })
+        .collect()
+}
+
+/// Lowest and highest x, with a span of at least 1.0 so that a single point is drawn
+fn x_bounds(points: &[(f64, f64)], axis: ProfileAxis) -> [f64; 2] {
+    let (low, high) = points
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(low, high), (x, _)| {
+            (low.min(*x), high.max(*x))
+        });
+    let high = match axis {
+        ProfileAxis::Time => 0.0,
+        ProfileAxis::Distance if points.is_empty() => 1.0,
+        ProfileAxis::Distance => high,
+    };
+    if points.is_empty() || high - low < 1.0 {
+        [high - 1.0, high]
+    } else {
+        [low, high]
+    }
+}
+
+/// From 0 ft to the highest altitude, rounded up to the next `ALTITUDE_STEP`
+fn y_bounds(points: &[(f64, f64)]) -> [f64; 2] {
+    let highest = points.iter().fold(0.0_f64, |high, (_, y)| high.max(*y));
+    [
+        0.0,
+        (highest / ALTITUDE_STEP)
+            .floor()
+            .mul_add(ALTITUDE_STEP, ALTITUDE_STEP),
+    ]
+}
+
+fn labels(bounds: [f64; 2], precision: usize) -> Vec<Span<'static>> {
+    let [low, high] = bounds;
+    [low, (low + high) / 2.0, high]
+        .iter()
+        .map(|value| Span::raw(format!("{value:.precision$}")))
+        .collect()
+}
+
+/// Render the vertical profile of `icao` in `area`
+pub fn build_profile<A: tui::backend::Backend>(
+    f: &mut tui::Frame<A>,
+    area: Rect,
+    settings: &Settings,
+    icao: ICAO,
+    axis: ProfileAxis,
+    now: SystemTime,
+) {
+    let points = points(&settings.history, icao, axis, now);
+    let x_bounds = x_bounds(&points, axis);
+    let y_bounds = y_bounds(&points);
+    let dataset = Dataset::default()
+        .marker(settings.capabilities.marker())
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(Color::Cyan))
+        .data(&points);
+    let chart = Chart::new(vec![dataset])
+        .block(
+            Block::default()
+                .title(format!("Vertical profile - {icao}"))
+                .borders(Borders::ALL),
+        )
+        .x_axis(
+            Axis::default()
+                .title(axis.title())
+                .style(Style::default().fg(Color::Gray))
+                .bounds(x_bounds)
+                .labels(labels(x_bounds, 1)),
+        )
+        .y_axis(
+            Axis::default()
+                .title("Altitude (ft)")
+                .style(Style::default().fg(Color::Gray))
+                .bounds(y_bounds)
+                .labels(labels(y_bounds, 0)),
+        );
+    f.render_widget(chart, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        let points = [(-4.0, 3000.0), (-2.5, 2400.0), (-1.0, 1800.0)];
+        assert_eq!(x_bounds(&points, ProfileAxis::Time), [-4.0, 0.0]);
+        assert_eq!(y_bounds(&points), [0.0, 4000.0]);
+
+        let points = [(12.0, 3000.0), (8.5, 2000.0)];
+        assert_eq!(x_bounds(&points, ProfileAxis::Distance), [8.5, 12.0]);
+        assert_eq!(x_bounds(&points[..1], ProfileAxis::Distance), [11.0, 12.0]);
+        assert_eq!(x_bounds(&[], ProfileAxis::Time), [-1.0, 0.0]);
+        assert_eq!(x_bounds(&[], ProfileAxis::Distance), [0.0, 1.0]);
+        assert_eq!(y_bounds(&[]), [0.0, 1000.0]);
+    }
+}
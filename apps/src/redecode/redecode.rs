@@ -0,0 +1,235 @@
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use adsb_deku::deku::DekuContainerRead;
+use adsb_deku::{ErrorKind, Frame};
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use rsadsb_apps::source::AvrReader;
+use rsadsb_common::Airplanes;
+use serde::Serialize;
+
+#[derive(Debug, Parser)]
+#[clap(
+    version,
+    name = "redecode",
+    author = "wcampbell0x2a",
+    about = "Decode archived AVR captures again and regenerate their aircraft and frame tables"
+)]
+struct Options {
+    /// AVR captures, or directories of AVR captures, to decode
+    #[clap(required = true)]
+    captures: Vec<PathBuf>,
+    /// Directory the tables are written to, replacing the tables of a previous run
+    #[clap(long)]
+    output: PathBuf,
+    /// Antenna location latitude, used for aircraft position algorithms
+    #[clap(long)]
+    lat: f64,
+    /// Antenna location longitude, used for aircraft position algorithms
+    #[clap(long)]
+    long: f64,
+}
+
+/// Row of `<capture>.frames.csv`, for every frame of the capture
+#[derive(Debug, PartialEq, Serialize)]
+struct FrameRow {
+    frame: String,
+    df: u8,
+    icao: Option<String>,
+    /// Why the frame isn't used for tracking, empty if it is
+    error: Option<String>,
+}
+
+/// Row of `<capture>.aircraft.csv`, with the state of every aircraft at the end of the capture
+#[derive(Debug, PartialEq, Serialize)]
+struct AircraftRow {
+    icao: String,
+    callsign: Option<String>,
+    squawk: Option<String>,
+    messages: u32,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    altitude: Option<u16>,
+    /// Amount of previous positions in the track
+    track: usize,
+}
+
+/// Decode all frames of an AVR capture
+fn decode<R: BufRead>(reader: R, lat_long: (f64, f64)) -> Result<(Vec<FrameRow>, Airplanes)> {
+    let mut airplanes = Airplanes::new();
+    let mut frames = vec![];
+
+    for bytes in AvrReader::new(reader) {
+        let bytes = bytes?;
+        let mut row = FrameRow {
+            frame: hex::encode(&bytes),
+            df: bytes.first().map_or(0, |b| b >> 3),
+            icao: None,
+            error: None,
+        };
+        match Frame::from_bytes((&bytes, 0)) {
+            Ok((_, frame)) => {
+                row.icao = frame.icao().map(|icao| icao.to_string());
+                row.error = frame.error_kind().map(|kind| kind.to_string());
+                if let Ok(frame) = frame.validate() {
+                    airplanes.action(frame, lat_long);
+                }
+            },
+            Err(e) => row.error = Some(ErrorKind::from_deku(&bytes, &e).to_string()),
+        }
+        frames.push(row);
+    }
+
+    Ok((frames, airplanes))
+}
+
+fn aircraft_rows(airplanes: &Airplanes) -> Vec<AircraftRow> {
+    airplanes
+        .iter()
+        .map(|(icao, state)| {
+            let details = airplanes.aircraft_details(*icao);
+            AircraftRow {
+                icao: icao.to_string(),
+                callsign: state.callsign.clone(),
+                squawk: state.squawk.map(|squawk| squawk.to_string()),
+                messages: state.num_messages,
+                latitude: details.as_ref().map(|details| details.position.latitude),
+                longitude: details.as_ref().map(|details| details.position.longitude),
+                altitude: details.as_ref().map(|details| details.altitude),
+                track: state.track.as_ref().map_or(0, Vec::len),
+            }
+        })
+        .collect()
+}
+
+/// Write `rows` as csv to `path`
+fn write_table<T: Serialize>(path: &Path, rows: &[T]) -> Result<()> {
+    let mut writer = csv::Writer::from_path(path)
+        .with_context(|| format!("unable to create {}", path.display()))?;
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// All captures of `paths`, with the files of directories in order of their names
+fn captures(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut captures = vec![];
+    for path in paths {
+        if path.is_dir() {
+            let mut files = vec![];
+            for entry in
+                fs::read_dir(path).with_context(|| format!("unable to read {}", path.display()))?
+            {
+                let entry = entry?.path();
+                if entry.is_file() {
+                    files.push(entry);
+                }
+            }
+            files.sort();
+            captures.extend(files);
+        } else {
+            captures.push(path.clone());
+        }
+    }
+    Ok(captures)
+}
+
+fn main() -> Result<()> {
+    let options = Options::parse();
+    let lat_long = (options.lat, options.long);
+
+    fs::create_dir_all(&options.output)
+        .with_context(|| format!("unable to create {}", options.output.display()))?;
+
+    let captures = captures(&options.captures)?;
+    let mut names = BTreeSet::new();
+    for capture in &captures {
+        let name = capture
+            .file_stem()
+            .with_context(|| format!("{} is not a file", capture.display()))?;
+        if !names.insert(name.to_owned()) {
+            bail!(
+                "{} has the same name as another capture, its tables would be overwritten",
+                capture.display()
+            );
+        }
+    }
+
+    let version = env!("CARGO_PKG_VERSION");
+    println!(
+        "decoding {} captures with adsb_deku v{version}",
+        captures.len()
+    );
+    for capture in &captures {
+        let file =
+            File::open(capture).with_context(|| format!("unable to open {}", capture.display()))?;
+        let (frames, airplanes) = decode(BufReader::new(file), lat_long)
+            .with_context(|| format!("unable to read {}", capture.display()))?;
+
+        // PANIC: checked above
+        let name = capture.file_stem().unwrap().to_string_lossy();
+        let aircraft = aircraft_rows(&airplanes);
+        write_table(&options.output.join(format!("{name}.frames.csv")), &frames)?;
+        write_table(
+            &options.output.join(format!("{name}.aircraft.csv")),
+            &aircraft,
+        )?;
+
+        let errors = frames.iter().filter(|row| row.error.is_some()).count();
+        println!(
+            "{}: {} frames, {errors} not used, {} aircraft",
+            capture.display(),
+            frames.len(),
+            aircraft.len()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_capture() {
+        let capture =
+            b"*8da2c1bd587ba2adb31799cb802b;\n*8da2c1bd587ba2adb31799cb802c;\n*5da039b46d7d81;\n";
+        let (frames, airplanes) = decode(&capture[..], (0.0, 0.0)).unwrap();
+        assert_eq!(
+            frames,
+            vec![
+                FrameRow {
+                    frame: "8da2c1bd587ba2adb31799cb802b".to_string(),
+                    df: 17,
+                    icao: Some("A2C1BD".to_string()),
+                    error: None,
+                },
+                FrameRow {
+                    frame: "8da2c1bd587ba2adb31799cb802c".to_string(),
+                    df: 17,
+                    icao: Some("A2C1BD".to_string()),
+                    error: Some("CRC".to_string()),
+                },
+                FrameRow {
+                    frame: "5da039b46d7d81".to_string(),
+                    df: 11,
+                    icao: Some("A039B4".to_string()),
+                    error: None,
+                },
+            ]
+        );
+
+        let aircraft = aircraft_rows(&airplanes);
+        assert_eq!(aircraft.len(), 2);
+        assert_eq!(aircraft[1].icao, "A2C1BD");
+        assert_eq!(aircraft[1].messages, 1);
+        assert_eq!(aircraft[1].latitude, None);
+        assert_eq!(aircraft[0].icao, "A039B4");
+    }
+}
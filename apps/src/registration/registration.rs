@@ -0,0 +1,52 @@
+use adsb_deku::registration::{icao_to_n_number, n_number_to_icao};
+use adsb_deku::ICAO;
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[clap(
+    version,
+    name = "registration",
+    author = "wcampbell0x2a",
+    about = "Convert between ICAO addresses and US N-number registrations"
+)]
+struct Options {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print the ICAO address of US N-numbers, such as N12345
+    Icao {
+        #[clap(required = true)]
+        n_numbers: Vec<String>,
+    },
+    /// Print the US N-number of ICAO addresses in hex, such as A061D9
+    NNumber {
+        #[clap(required = true)]
+        icaos: Vec<ICAO>,
+    },
+}
+
+fn main() -> Result<()> {
+    let options = Options::parse();
+    match options.command {
+        Command::Icao { n_numbers } => {
+            for n_number in n_numbers {
+                let icao = n_number_to_icao(&n_number)
+                    .with_context(|| format!("{n_number} is not a valid N-number"))?;
+                println!("{n_number} {icao}");
+            }
+        },
+        Command::NNumber { icaos } => {
+            for icao in icaos {
+                let n_number = icao_to_n_number(icao).with_context(|| {
+                    format!("{icao} is not within the US range A00001..=ADF7C7")
+                })?;
+                println!("{icao} {n_number}");
+            }
+        },
+    }
+    Ok(())
+}
@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use adsb_deku::adsb::ME;
+use adsb_deku::crc::crc24;
+use adsb_deku::deku::{DekuContainerRead, DekuContainerWrite};
+use adsb_deku::{AC13Field, Frame, DF, ICAO};
+use anyhow::{Context, Result};
+use clap::Parser;
+use rsadsb_apps::source::parse_avr_line;
+
+/// Ticks per second of the 12 MHz timestamp of the `@` MLAT AVR format
+const TICKS_PER_SECOND: f64 = 12_000_000.0;
+
+/// Timestamps of the `@` MLAT AVR format are 48 bits
+const TIMESTAMP_MASK: u64 = 0xffff_ffff_ffff;
+
+#[derive(Debug, Parser)]
+#[clap(
+    version,
+    name = "sanitize",
+    author = "wcampbell0x2a",
+    about = "Edit the frames of an AVR capture and fix their parity, for building regression datasets"
+)]
+struct Options {
+    /// AVR capture to edit, with `*` frames or `@` frames with a timestamp
+    capture: PathBuf,
+    /// AVR capture written with the edited frames
+    #[clap(long)]
+    output: PathBuf,
+    /// Replace the address OLD of all frames with NEW, as OLD=NEW
+    #[clap(long)]
+    replace_icao: Vec<IcaoRule>,
+    /// Raise barometric altitudes below this, in ft
+    #[clap(long)]
+    min_altitude: Option<u16>,
+    /// Lower barometric altitudes above this, in ft
+    #[clap(long)]
+    max_altitude: Option<u16>,
+    /// Seconds added to the timestamps of `@` frames
+    #[clap(long, allow_hyphen_values = true)]
+    shift_timestamps: Option<f64>,
+}
+
+/// Parsing struct for the --replace-icao clap parameter, "OLD=NEW"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IcaoRule {
+    old: ICAO,
+    new: ICAO,
+}
+
+impl FromStr for IcaoRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (old, new) = s
+            .split_once('=')
+            .ok_or_else(|| format!("invalid rule: {s}, expected OLD=NEW"))?;
+        let parse = |icao: &str| {
+            icao.parse::<ICAO>()
+                .map_err(|_| format!("invalid rule: {s}, {icao} is not an icao address"))
+        };
+        Ok(Self {
+            old: parse(old)?,
+            new: parse(new)?,
+        })
+    }
+}
+
+/// Edits applied to every frame
+#[derive(Debug, Default)]
+struct Rules {
+    icao: BTreeMap<ICAO, ICAO>,
+    min_altitude: Option<u16>,
+    max_altitude: Option<u16>,
+    /// 12 MHz ticks added to timestamps
+    shift_ticks: i64,
+}
+
+impl Rules {
+    fn from_options(options: &Options) -> Self {
+        Self {
+            icao: options
+                .replace_icao
+                .iter()
+                .map(|rule| (rule.old, rule.new))
+                .collect(),
+            min_altitude: options.min_altitude,
+            max_altitude: options.max_altitude,
+            shift_ticks: options
+                .shift_timestamps
+                .map_or(0, |seconds| (seconds * TICKS_PER_SECOND).round() as i64),
+        }
+    }
+
+    fn replace_icao(&self, icao: &mut ICAO) -> bool {
+        match self.icao.get(icao) {
+            Some(new) => {
+                *icao = *new;
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn clamp_altitude(&self, altitude: &mut u16) -> bool {
+        let clamped = (*altitude)
+            .max(self.min_altitude.unwrap_or(u16::MIN))
+            .min(self.max_altitude.unwrap_or(u16::MAX));
+        let changed = clamped != *altitude;
+        *altitude = clamped;
+        changed
+    }
+
+    /// AC13Field of `0` is an unavailable altitude, and is kept
+    fn clamp_ac13(&self, ac: &mut AC13Field) -> bool {
+        ac.0 != 0 && self.clamp_altitude(&mut ac.0)
+    }
+
+    /// Apply the edits to `frame`, returning if it was changed
+    fn edit_frame(&self, frame: &mut Frame) -> bool {
+        match &mut frame.df {
+            DF::ADSB(adsb) => {
+                let icao = self.replace_icao(&mut adsb.icao);
+                let altitude = match &mut adsb.me {
+                    ME::AirbornePositionBaroAltitude(altitude) => altitude
+                        .alt
+                        .as_mut()
+                        .map_or(false, |alt| self.clamp_altitude(alt)),
+                    _ => false,
+                };
+                icao | altitude
+            },
+            // non-ICAO addresses of DF18, such as TIS-B track file numbers, are another aircraft
+            DF::TisB { cf, .. }
+                if cf
+                    .address_qualifier()
+                    .map_or(false, |qualifier| qualifier.is_icao()) =>
+            {
+                self.replace_icao(&mut cf.aa)
+            },
+            DF::AllCallReply { icao, .. } => self.replace_icao(icao),
+            DF::ShortAirAirSurveillance { altitude, .. } | DF::LongAirAir { altitude, .. } => {
+                self.clamp_ac13(altitude)
+            },
+            DF::SurveillanceAltitudeReply(reply) => self.clamp_ac13(&mut reply.ac),
+            DF::CommBAltitudeReply(reply) => self.clamp_ac13(&mut reply.ac),
+            _ => false,
+        }
+    }
+
+    /// Edited bytes of a valid frame, or `None` for a frame that isn't valid
+    ///
+    /// The parity is calculated again over the edited bytes. For the Address/Parity formats the
+    /// address is contained within the parity, and is replaced there.
+    fn edit(&self, bytes: &[u8]) -> Option<Vec<u8>> {
+        let (_, mut frame) = Frame::from_bytes((bytes, 0)).ok()?;
+        if frame.error_kind().is_some() {
+            return None;
+        }
+
+        // remainder of the parity: zero for DF17, the interrogator code for DF11, and the
+        // address for the Address/Parity formats
+        let mut overlay = frame.crc;
+        let mut changed = false;
+        if let (true, Some(mut icao)) = (address_parity(&frame.df), frame.icao()) {
+            if self.replace_icao(&mut icao) {
+                overlay = u32::from_be_bytes([0, icao.0[0], icao.0[1], icao.0[2]]);
+                changed = true;
+            }
+        }
+        changed |= self.edit_frame(&mut frame);
+        if !changed {
+            return Some(bytes.to_vec());
+        }
+
+        let mut edited = frame.to_bytes().ok()?;
+        edited.truncate(bytes.len());
+        let n = edited.len();
+        let parity = crc24(&edited[..n - 3]) ^ overlay;
+        edited[n - 3..].copy_from_slice(&parity.to_be_bytes()[1..]);
+        Some(edited)
+    }
+
+    /// Edited AVR `line`, or `None` without any valid frame
+    fn edit_line(&self, line: &str) -> Option<String> {
+        let line = line.trim();
+        if let Some(mlat) = line.strip_prefix('@') {
+            // 12 hex digits of timestamp, followed by the frame
+            let timestamp = u64::from_str_radix(mlat.get(..12)?, 16).ok()?;
+            let bytes = hex::decode(mlat.get(12..)?.strip_suffix(';')?).ok()?;
+            let edited = self.edit(&bytes)?;
+            let timestamp =
+                (timestamp as i64).wrapping_add(self.shift_ticks) as u64 & TIMESTAMP_MASK;
+            return Some(format!("@{timestamp:012x}{};", hex::encode(edited)));
+        }
+
+        let mut frames = Default::default();
+        parse_avr_line(line.as_bytes(), &mut frames);
+        let edited: String = frames
+            .iter()
+            .filter_map(|bytes| self.edit(bytes))
+            .map(|bytes| format!("*{};", hex::encode(bytes)))
+            .collect();
+        (!edited.is_empty()).then(|| edited)
+    }
+}
+
+/// Formats with the address contained within the parity
+const fn address_parity(df: &DF) -> bool {
+    matches!(
+        df,
+        DF::ShortAirAirSurveillance { .. }
+            | DF::SurveillanceAltitudeReply(_)
+            | DF::SurveillanceIdentityReply(_)
+            | DF::LongAirAir { .. }
+            | DF::CommBAltitudeReply(_)
+            | DF::CommBIdentityReply(_)
+            | DF::CommDExtendedLengthMessage(_)
+    )
+}
+
+fn main() -> Result<()> {
+    let options = Options::parse();
+    let rules = Rules::from_options(&options);
+
+    let capture = File::open(&options.capture)
+        .with_context(|| format!("unable to open {}", options.capture.display()))?;
+    let output = File::create(&options.output)
+        .with_context(|| format!("unable to create {}", options.output.display()))?;
+    let mut output = BufWriter::new(output);
+
+    let (mut written, mut dropped) = (0, 0);
+    for line in BufReader::new(capture).lines() {
+        let line = line.with_context(|| format!("unable to read {}", options.capture.display()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match rules.edit_line(&line) {
+            Some(edited) => {
+                writeln!(output, "{edited}")?;
+                written += 1;
+            },
+            None => dropped += 1,
+        }
+    }
+    output.flush()?;
+
+    println!(
+        "{}: {written} lines written, {dropped} lines without a valid frame dropped",
+        options.output.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn icao(s: &str) -> ICAO {
+        s.parse().unwrap()
+    }
+
+    fn decode(line: &str) -> Frame {
+        let mut frames = Default::default();
+        parse_avr_line(line.as_bytes(), &mut frames);
+        let bytes = frames.pop_front().unwrap();
+        Frame::from_bytes((&bytes, 0)).unwrap().1
+    }
+
+    #[test]
+    fn replace_icao() {
+        let mut rules = Rules::default();
+        rules.icao.insert(icao("a2c1bd"), icao("abcdef"));
+        rules.icao.insert(icao("a039b4"), icao("123456"));
+
+        // DF17 and DF11
+        for line in ["*8da2c1bd587ba2adb31799cb802b;", "*5da039b46d7d81;"] {
+            let edited = rules.edit_line(line).unwrap();
+            let frame = decode(&edited);
+            assert_eq!(frame.error_kind(), None, "{edited}");
+            assert_ne!(frame.icao(), Some(icao("a2c1bd")), "{edited}");
+            assert_ne!(frame.icao(), Some(icao("a039b4")), "{edited}");
+        }
+        let frame = decode(&rules.edit_line("*8da2c1bd587ba2adb31799cb802b;").unwrap());
+        assert_eq!(frame.icao(), Some(icao("abcdef")));
+
+        // other addresses are unchanged
+        assert_eq!(
+            rules.edit_line("*8da08f94ea1b785e8f3c088ab467;"),
+            Some("*8da08f94ea1b785e8f3c088ab467;".to_string())
+        );
+    }
+
+    #[test]
+    fn replace_address_parity() {
+        let line = "*20001838ca3e51;";
+        let old = decode(line).icao().unwrap();
+        let mut rules = Rules::default();
+        rules.icao.insert(old, icao("abcdef"));
+        let frame = decode(&rules.edit_line(line).unwrap());
+        assert_eq!(frame.icao(), Some(icao("abcdef")));
+    }
+
+    #[test]
+    fn clamp_altitude() {
+        let rules = Rules {
+            max_altitude: Some(20000),
+            ..Rules::default()
+        };
+        // 23650 ft
+        let frame = decode(&rules.edit_line("*8da2c1bd587ba2adb31799cb802b;").unwrap());
+        assert_eq!(frame.error_kind(), None);
+        if let DF::ADSB(adsb) = frame.df {
+            if let ME::AirbornePositionBaroAltitude(altitude) = adsb.me {
+                assert_eq!(altitude.alt, Some(20000));
+                return;
+            }
+        }
+        unreachable!();
+    }
+
+    #[test]
+    fn shift_timestamps() {
+        let rules = Rules {
+            shift_ticks: -12_000_000,
+            ..Rules::default()
+        };
+        assert_eq!(
+            rules
+                .edit_line("@0000010000008da2c1bd587ba2adb31799cb802b;")
+                .unwrap(),
+            "@00000048e5008da2c1bd587ba2adb31799cb802b;"
+        );
+        // wraps around
+        assert_eq!(
+            rules
+                .edit_line("@0000000000008da2c1bd587ba2adb31799cb802b;")
+                .unwrap(),
+            "@ffffff48e5008da2c1bd587ba2adb31799cb802b;"
+        );
+    }
+
+    #[test]
+    fn invalid_frames() {
+        let rules = Rules::default();
+        // bad parity
+        assert_eq!(rules.edit_line("*8da2c1bd587ba2adb31799cb802c;"), None);
+        assert_eq!(rules.edit_line("garbage"), None);
+    }
+
+    #[test]
+    fn icao_rule() {
+        assert_eq!(
+            "a2c1bd=ABCDEF".parse(),
+            Ok(IcaoRule {
+                old: icao("a2c1bd"),
+                new: icao("abcdef"),
+            })
+        );
+        assert!("a2c1bd".parse::<IcaoRule>().is_err());
+        assert!("a2c1bd=xyz".parse::<IcaoRule>().is_err());
+    }
+}
@@ -0,0 +1,814 @@
+//! Reading of demodulated ADS-B frames from a byte stream
+//!
+//! The AVR format (`*8da2c1bd587ba2adb31799cb802b;`) as emitted by dump1090 on port 30002, the
+//! binary Beast format of port 30005, plain hex lines, and the raw UAT format
+//! (`-00a12345...;rs=1;rssi=-12.5;`) as emitted by dump978 on port 30978, are supported, see
+//! [`Format`]. The format of a Mode S source can also be detected from its first byte, see
+//! [`AvrReader::detect`].
+//!
+//! Some receivers append bytes such as a signal level after the Mode S frame, within the same
+//! `*...;` frame. These can be split off the frame with [`Trailing::Split`], instead of being
+//! returned as part of a frame that fails to decode.
+//!
+//! Links such as serial connections or flaky TCP connections can drop or corrupt bytes, so
+//! instead of failing on a malformed line, the reader scans forward to the next `*` frame marker
+//! and continues from there. Every time data is thrown away, a resync event is counted.
+//!
+//! A source ends either at the end of a file (such as a replayed capture), or when a network
+//! connection is closed or fails. [`AvrReader::poll_frame`] tells these apart from a read timeout
+//! with [`ReadEvent`], and iterating over an [`AvrReader`] ends at the end of the source. Blocking
+//! sources such as stdin are read on a thread by a [`BackgroundReader`], so that their reads time
+//! out like the reads of network sources.
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Read};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+use std::{fmt, thread};
+
+/// Start of frame marker of the AVR format
+const AVR_START: u8 = b'*';
+
+/// End of frame marker of the AVR format
+const AVR_END: u8 = b';';
+
+/// Start of every frame of the Beast format, doubled when it's part of a frame
+const BEAST_ESCAPE: u8 = 0x1a;
+
+/// Start of downlink frame marker of the raw UAT format
+const UAT_DOWNLINK_START: u8 = b'-';
+
+/// Start of uplink frame marker of the raw UAT format
+const UAT_UPLINK_START: u8 = b'+';
+
+/// Read timeout of network sources, so that a poll loop isn't blocked while waiting for frames
+pub const READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Format of the frames of a source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Mode S frames of dump1090: `*8da2c1bd587ba2adb31799cb802b;`
+    Avr,
+    /// UAT frames of dump978: `-00a12345...;rs=1;rssi=-12.5;`, only downlink frames are read and
+    /// uplink frames (starting with `+`) are skipped
+    Uat,
+    /// Binary Mode S frames of dump1090 port 30005, with a timestamp and signal level. Mode A/C
+    /// frames are skipped
+    Beast,
+    /// Mode S frames as plain hex, one per line: `8da2c1bd587ba2adb31799cb802b`
+    Hex,
+}
+
+impl Format {
+    /// Format of a Mode S source starting with `byte`, the first byte that isn't whitespace
+    ///
+    /// Sources starting with anything else than a Beast frame or a hex digit are read as AVR.
+    #[must_use]
+    pub fn detect(byte: u8) -> Self {
+        match byte {
+            BEAST_ESCAPE => Self::Beast,
+            byte if byte.is_ascii_hexdigit() => Self::Hex,
+            _ => Self::Avr,
+        }
+    }
+}
+
+/// Handling of bytes after the Mode S frame of an AVR frame, see [`AvrReader::with_trailing`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trailing {
+    /// Return the bytes as part of the frame, the default
+    Keep,
+    /// Split the bytes off the frame, after the 7 or 14 bytes of its Downlink Format. They are
+    /// available from [`AvrReader::trailing`].
+    Split,
+}
+
+/// Result of [`AvrReader::poll_frame`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReadEvent {
+    Frame(Vec<u8>),
+    /// No frame was available before the read timeout, try again later
+    Idle,
+    /// The source ended, no more frames will be read. Returned for every following poll.
+    End(StreamEnd),
+}
+
+/// Reason a source ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamEnd {
+    /// End of file, or the connection was closed by the other side
+    Eof,
+    /// Reading failed, such as a reset connection
+    Disconnected(io::ErrorKind),
+}
+
+impl fmt::Display for StreamEnd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Eof => write!(f, "end of stream"),
+            Self::Disconnected(kind) => write!(f, "disconnected: {kind:?}"),
+        }
+    }
+}
+
+/// Reader of AVR (or raw UAT, see [`AvrReader::new_uat`]) formatted frames, skipping over
+/// corrupted data
+#[derive(Debug)]
+pub struct AvrReader<R> {
+    reader: R,
+    format: Format,
+    /// Detect `format` from the first byte, see [`AvrReader::detect`]
+    detect: bool,
+    /// Bytes of the current line (or of the undecoded Beast frames), kept between calls if the
+    /// read is interrupted
+    line: Vec<u8>,
+    /// Frames decoded from the last line but not yet returned
+    frames: VecDeque<Vec<u8>>,
+    trailing_mode: Trailing,
+    /// Bytes split off the last returned frame
+    trailing: Vec<u8>,
+    resyncs: u64,
+    /// Set once the source ended
+    end: Option<StreamEnd>,
+}
+
+impl AvrReader<BufReader<TcpStream>> {
+    /// Connect to a demodulator serving AVR frames, such as dump1090 on port 30002
+    ///
+    /// Reads time out after [`READ_TIMEOUT`], see [`Self::poll_frame`].
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        Ok(Self::new(BufReader::new(stream)))
+    }
+
+    /// Connect to a demodulator serving raw UAT frames, such as dump978 on port 30978
+    ///
+    /// Reads time out after [`READ_TIMEOUT`], see [`Self::poll_frame`].
+    pub fn connect_uat<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        Ok(Self::new_uat(BufReader::new(stream)))
+    }
+}
+
+impl<R: BufRead> AvrReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_format(reader, Format::Avr)
+    }
+
+    /// Reader of the raw UAT downlink frames of dump978
+    pub fn new_uat(reader: R) -> Self {
+        Self::with_format(reader, Format::Uat)
+    }
+
+    /// Reader of a Mode S source of unknown format, detected from its first byte with
+    /// [`Format::detect`]
+    pub fn detect(reader: R) -> Self {
+        Self {
+            detect: true,
+            ..Self::with_format(reader, Format::Avr)
+        }
+    }
+
+    pub fn with_format(reader: R, format: Format) -> Self {
+        Self {
+            reader,
+            format,
+            detect: false,
+            line: vec![],
+            frames: VecDeque::new(),
+            trailing_mode: Trailing::Keep,
+            trailing: vec![],
+            resyncs: 0,
+            end: None,
+        }
+    }
+
+    /// Set the handling of bytes after the Mode S frame, only used for the AVR format
+    #[must_use]
+    pub fn with_trailing(mut self, trailing: Trailing) -> Self {
+        self.trailing_mode = trailing;
+        self
+    }
+
+    /// Bytes split off the last returned frame with [`Trailing::Split`], empty if it had none
+    pub fn trailing(&self) -> &[u8] {
+        &self.trailing
+    }
+
+    /// Poll the next frame, telling a read timeout apart from the end of the source
+    ///
+    /// Frames already read are returned before the end of the source.
+    pub fn poll_frame(&mut self) -> ReadEvent {
+        if let Some(frame) = self.next_frame() {
+            return ReadEvent::Frame(frame);
+        }
+        if let Some(end) = self.end {
+            return ReadEvent::End(end);
+        }
+        match self.read_frame() {
+            Ok(Some(frame)) => ReadEvent::Frame(frame),
+            Ok(None) => self.end(StreamEnd::Eof),
+            Err(e) if is_timeout(&e) => ReadEvent::Idle,
+            Err(e) => self.end(StreamEnd::Disconnected(e.kind())),
+        }
+    }
+
+    /// Next frame decoded from the last line, splitting off the trailing bytes
+    fn next_frame(&mut self) -> Option<Vec<u8>> {
+        let mut frame = self.frames.pop_front()?;
+        self.trailing.clear();
+        if self.format == Format::Avr && self.trailing_mode == Trailing::Split {
+            self.trailing = split_trailing(&mut frame);
+        }
+        Some(frame)
+    }
+
+    fn end(&mut self, end: StreamEnd) -> ReadEvent {
+        self.end = Some(end);
+        ReadEvent::End(end)
+    }
+
+    /// Read the next frame from the underlying reader
+    ///
+    /// Returns `Ok(None)` when the end of the stream has been reached. Errors from the underlying
+    /// reader (such as a read timeout) are returned as is, any partially read line is kept and
+    /// completed on the next call.
+    pub fn read_frame(&mut self) -> io::Result<Option<Vec<u8>>> {
+        loop {
+            if let Some(frame) = self.next_frame() {
+                return Ok(Some(frame));
+            }
+
+            if self.detect {
+                let buf = self.reader.fill_buf()?;
+                if buf.is_empty() {
+                    return Ok(None);
+                }
+                match buf.iter().find(|b| !b.is_ascii_whitespace()) {
+                    Some(&byte) => {
+                        self.format = Format::detect(byte);
+                        self.detect = false;
+                    },
+                    None => {
+                        let len = buf.len();
+                        self.reader.consume(len);
+                        continue;
+                    },
+                }
+            }
+
+            if self.format == Format::Beast {
+                let buf = self.reader.fill_buf()?;
+                if buf.is_empty() {
+                    // a partial frame at the end of the stream
+                    if !self.line.is_empty() {
+                        self.line.clear();
+                        self.resyncs += 1;
+                    }
+                    return Ok(None);
+                }
+                let len = buf.len();
+                self.line.extend_from_slice(buf);
+                self.reader.consume(len);
+                self.resyncs += parse_beast(&mut self.line, &mut self.frames);
+                continue;
+            }
+
+            let len = self.reader.read_until(b'\n', &mut self.line)?;
+            if len == 0 {
+                // a partial line at the end of the stream can't be a complete frame
+                if !self.line.is_empty() {
+                    self.line.clear();
+                    self.resyncs += 1;
+                }
+                return Ok(None);
+            }
+
+            self.resyncs += match self.format {
+                Format::Avr => parse_avr_line(&self.line, &mut self.frames),
+                Format::Uat => parse_uat_line(&self.line, &mut self.frames),
+                Format::Hex => parse_hex_line(&self.line, &mut self.frames),
+                Format::Beast => unreachable!("Beast frames aren't read by line"),
+            };
+            self.line.clear();
+        }
+    }
+
+    /// Amount of times corrupted data was skipped over to find the next frame since the last
+    /// call, resetting the count
+    pub fn take_resyncs(&mut self) -> u64 {
+        core::mem::take(&mut self.resyncs)
+    }
+}
+
+/// Frames until the end of the source, waiting through read timeouts
+///
+/// Ends at [`StreamEnd::Eof`]. A [`StreamEnd::Disconnected`] is returned as the last item.
+impl<R: BufRead> Iterator for AvrReader<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.frames.is_empty() && self.end.is_some() {
+            return None;
+        }
+        loop {
+            match self.poll_frame() {
+                ReadEvent::Frame(frame) => return Some(Ok(frame)),
+                ReadEvent::Idle => (),
+                ReadEvent::End(StreamEnd::Eof) => return None,
+                ReadEvent::End(StreamEnd::Disconnected(kind)) => return Some(Err(kind.into())),
+            }
+        }
+    }
+}
+
+/// Reader of a blocking source, read on a thread so that reads time out after [`READ_TIMEOUT`]
+///
+/// The end of the source, or its first error, is returned once all bytes read before it are
+/// returned.
+#[derive(Debug)]
+pub struct BackgroundReader {
+    chunks: Receiver<io::Result<Vec<u8>>>,
+    /// Last received chunk, and the amount of its bytes already returned
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl BackgroundReader {
+    /// Read `reader` on a new thread
+    pub fn spawn<R: Read + Send + 'static>(mut reader: R) -> Self {
+        let (tx, chunks) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buf = [0; 4096];
+            loop {
+                let chunk = match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(len) => Ok(buf[..len].to_vec()),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => Err(e),
+                };
+                let failed = chunk.is_err();
+                if tx.send(chunk).is_err() || failed {
+                    break;
+                }
+            }
+        });
+        Self {
+            chunks,
+            chunk: vec![],
+            pos: 0,
+        }
+    }
+
+    /// Read stdin, such as frames piped from `nc`
+    pub fn stdin() -> Self {
+        Self::spawn(io::stdin())
+    }
+}
+
+impl Read for BackgroundReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.chunk.len() {
+            match self.chunks.recv_timeout(READ_TIMEOUT) {
+                Ok(chunk) => {
+                    self.chunk = chunk?;
+                    self.pos = 0;
+                },
+                Err(RecvTimeoutError::Timeout) => return Err(io::ErrorKind::TimedOut.into()),
+                Err(RecvTimeoutError::Disconnected) => return Ok(0),
+            }
+        }
+        let len = buf.len().min(self.chunk.len() - self.pos);
+        buf[..len].copy_from_slice(&self.chunk[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+/// Error of a read that timed out, instead of a failed source
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::Interrupted
+    )
+}
+
+/// Parse all frames contained within `line`, returning the amount of resyncs needed
+///
+/// Anything before the first `*` marker is skipped over, as well as any frame that isn't
+/// terminated with `;` or doesn't contain valid hex.
+pub fn parse_avr_line(line: &[u8], frames: &mut VecDeque<Vec<u8>>) -> u64 {
+    let mut resyncs = 0;
+    let mut segments = line.split(|&b| b == AVR_START);
+
+    // bytes before the first marker, only whitespace is expected
+    if let Some(leading) = segments.next() {
+        if !leading.iter().all(u8::is_ascii_whitespace) {
+            resyncs += 1;
+        }
+    }
+
+    for segment in segments {
+        match parse_avr_frame(segment) {
+            Some(bytes) => frames.push_back(bytes),
+            None => resyncs += 1,
+        }
+    }
+
+    resyncs
+}
+
+/// Parse the downlink frame of a raw UAT `line`, returning the amount of resyncs needed
+///
+/// Unlike the AVR format, a raw UAT line contains a single frame followed by metadata such as
+/// `rssi=-12.5;`, so only the start of the line is checked for the `-` marker.
+pub fn parse_uat_line(line: &[u8], frames: &mut VecDeque<Vec<u8>>) -> u64 {
+    let start = line.iter().position(|b| !b.is_ascii_whitespace());
+    let line = match start {
+        Some(start) => &line[start..],
+        None => return 0,
+    };
+    match line[0] {
+        UAT_UPLINK_START => 0,
+        UAT_DOWNLINK_START => match parse_avr_frame(&line[1..]) {
+            Some(bytes) => {
+                frames.push_back(bytes);
+                0
+            },
+            None => 1,
+        },
+        _ => 1,
+    }
+}
+
+/// Parse the frame of a plain hex `line`, returning the amount of resyncs needed
+///
+/// Lines with only whitespace are skipped.
+pub fn parse_hex_line(line: &[u8], frames: &mut VecDeque<Vec<u8>>) -> u64 {
+    let start = match line.iter().position(|b| !b.is_ascii_whitespace()) {
+        Some(start) => start,
+        None => return 0,
+    };
+    let end = line
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .unwrap_or(start);
+    match hex::decode(&line[start..=end]) {
+        Ok(bytes) => {
+            frames.push_back(bytes);
+            0
+        },
+        Err(_) => 1,
+    }
+}
+
+/// Parse the complete Beast frames at the start of `buffer`, removing them and returning the
+/// amount of resyncs needed
+///
+/// Every frame starts with `0x1a`, its type, a 6 byte timestamp and a signal level byte, followed
+/// by the 2 bytes of a Mode A/C (`'1'`), 7 bytes of a Mode S short (`'2'`) or 14 bytes of a Mode S
+/// long (`'3'`) frame. A `0x1a` byte within the frame is doubled. Only the Mode S frames are
+/// returned, without their timestamp and signal level. An incomplete frame at the end of `buffer`
+/// is kept for the next call.
+pub fn parse_beast(buffer: &mut Vec<u8>, frames: &mut VecDeque<Vec<u8>>) -> u64 {
+    const HEADER: usize = 7;
+
+    let mut resyncs = 0;
+    let mut start = 0;
+    loop {
+        // skip to the start of the next frame
+        match buffer[start..].iter().position(|&b| b == BEAST_ESCAPE) {
+            Some(0) => (),
+            Some(skip) => {
+                start += skip;
+                resyncs += 1;
+            },
+            None => {
+                if start != buffer.len() {
+                    start = buffer.len();
+                    resyncs += 1;
+                }
+                break;
+            },
+        }
+        let len = match buffer.get(start + 1) {
+            Some(b'1') => 2,
+            Some(b'2') => 7,
+            Some(b'3') => 14,
+            Some(_) => {
+                // status frames, or data after a lost frame start
+                start += 1;
+                resyncs += 1;
+                continue;
+            },
+            None => break,
+        };
+
+        let mut frame = Vec::with_capacity(HEADER + len);
+        let mut i = start + 2;
+        let complete = loop {
+            if frame.len() == HEADER + len {
+                break Some(true);
+            }
+            match (buffer.get(i), buffer.get(i + 1)) {
+                (Some(&BEAST_ESCAPE), Some(&BEAST_ESCAPE)) => {
+                    frame.push(BEAST_ESCAPE);
+                    i += 2;
+                },
+                // start of the next frame, this one is truncated
+                (Some(&BEAST_ESCAPE), Some(_)) => break Some(false),
+                (Some(&BEAST_ESCAPE), None) | (None, _) => break None,
+                (Some(&byte), _) => {
+                    frame.push(byte);
+                    i += 1;
+                },
+            }
+        };
+        match complete {
+            Some(true) => {
+                if len != 2 {
+                    frames.push_back(frame.split_off(HEADER));
+                }
+            },
+            Some(false) => resyncs += 1,
+            None => break,
+        }
+        start = i;
+    }
+    buffer.drain(..start);
+    resyncs
+}
+
+/// Split the bytes after the Mode S frame off `frame`, returning them
+///
+/// Downlink Formats 16 and up are 14 bytes long, the others are 7 bytes long.
+pub fn split_trailing(frame: &mut Vec<u8>) -> Vec<u8> {
+    let len = match frame.first() {
+        Some(first) if first >> 3 >= 16 => 14,
+        Some(_) => 7,
+        None => return vec![],
+    };
+    if frame.len() > len {
+        frame.split_off(len)
+    } else {
+        vec![]
+    }
+}
+
+/// Parse hex bytes up to the `;` marker
+fn parse_avr_frame(segment: &[u8]) -> Option<Vec<u8>> {
+    let end = segment.iter().position(|&b| b == AVR_END)?;
+    let hex = &segment[..end];
+    if hex.is_empty() {
+        return None;
+    }
+    hex::decode(hex).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_all(input: &[u8]) -> (Vec<Vec<u8>>, u64) {
+        let mut reader = AvrReader::new(input);
+        let mut frames = vec![];
+        while let Some(frame) = reader.read_frame().unwrap() {
+            frames.push(frame);
+        }
+        (frames, reader.take_resyncs())
+    }
+
+    #[test]
+    fn valid_lines() {
+        let (frames, resyncs) = read_all(b"*8da2c1bd587ba2adb31799cb802b;\n*5da039b46d7d81;\r\n");
+        assert_eq!(
+            frames,
+            vec![
+                hex::decode("8da2c1bd587ba2adb31799cb802b").unwrap(),
+                hex::decode("5da039b46d7d81").unwrap(),
+            ]
+        );
+        assert_eq!(resyncs, 0);
+    }
+
+    #[test]
+    fn uat_lines() {
+        let input = b"-00a1234538e38f;rs=1;rssi=-12.5;\n+3a8a0d1e8f9aab;rs=3;\n-00a1;\n-zz;\n";
+        let mut reader = AvrReader::new_uat(&input[..]);
+        let mut frames = vec![];
+        while let Some(frame) = reader.read_frame().unwrap() {
+            frames.push(frame);
+        }
+        assert_eq!(
+            frames,
+            vec![
+                hex::decode("00a1234538e38f").unwrap(),
+                hex::decode("00a1").unwrap(),
+            ]
+        );
+        assert_eq!(reader.take_resyncs(), 1);
+    }
+
+    #[test]
+    fn trailing_bytes() {
+        let input = b"*8da2c1bd587ba2adb31799cb802b1f2e;\n*5da039b46d7d81;\n*5da039b46d7d8140;\n";
+
+        // kept as part of the frame by default
+        let (frames, _) = read_all(input);
+        assert_eq!(
+            frames[0],
+            hex::decode("8da2c1bd587ba2adb31799cb802b1f2e").unwrap()
+        );
+
+        let mut reader = AvrReader::new(&input[..]).with_trailing(Trailing::Split);
+        assert_eq!(
+            reader.read_frame().unwrap(),
+            Some(hex::decode("8da2c1bd587ba2adb31799cb802b").unwrap())
+        );
+        assert_eq!(reader.trailing(), [0x1f, 0x2e]);
+        assert_eq!(
+            reader.read_frame().unwrap(),
+            Some(hex::decode("5da039b46d7d81").unwrap())
+        );
+        assert_eq!(reader.trailing(), []);
+        assert_eq!(
+            reader.poll_frame(),
+            ReadEvent::Frame(hex::decode("5da039b46d7d81").unwrap())
+        );
+        assert_eq!(reader.trailing(), [0x40]);
+        assert_eq!(reader.take_resyncs(), 0);
+    }
+
+    /// Reader failing with `kind` after `input`
+    struct Failing {
+        input: &'static [u8],
+        kind: io::ErrorKind,
+    }
+
+    impl io::Read for Failing {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.input.is_empty() {
+                return Err(self.kind.into());
+            }
+            self.input.read(buf)
+        }
+    }
+
+    #[test]
+    fn poll_end_of_file() {
+        let mut reader = AvrReader::new(&b"*5da039b46d7d81;\n"[..]);
+        assert_eq!(
+            reader.poll_frame(),
+            ReadEvent::Frame(hex::decode("5da039b46d7d81").unwrap())
+        );
+        assert_eq!(reader.poll_frame(), ReadEvent::End(StreamEnd::Eof));
+        assert_eq!(reader.poll_frame(), ReadEvent::End(StreamEnd::Eof));
+    }
+
+    #[test]
+    fn poll_disconnect() {
+        let timeout = Failing {
+            input: b"*5da039b46d7d81;\n",
+            kind: io::ErrorKind::WouldBlock,
+        };
+        let mut reader = AvrReader::new(io::BufReader::new(timeout));
+        assert!(matches!(reader.poll_frame(), ReadEvent::Frame(_)));
+        assert_eq!(reader.poll_frame(), ReadEvent::Idle);
+        assert_eq!(reader.poll_frame(), ReadEvent::Idle);
+
+        let reset = Failing {
+            input: b"*5da039b46d7d81;\n",
+            kind: io::ErrorKind::ConnectionReset,
+        };
+        let mut reader = AvrReader::new(io::BufReader::new(reset));
+        assert!(matches!(reader.poll_frame(), ReadEvent::Frame(_)));
+        let end = StreamEnd::Disconnected(io::ErrorKind::ConnectionReset);
+        assert_eq!(reader.poll_frame(), ReadEvent::End(end));
+        assert_eq!(reader.poll_frame(), ReadEvent::End(end));
+    }
+
+    #[test]
+    fn iterate() {
+        let reader = AvrReader::new(&b"*5da039b46d7d81;\n*5da039b46d7d81;\n"[..]);
+        assert_eq!(reader.map(Result::unwrap).count(), 2);
+
+        let reset = Failing {
+            input: b"*5da039b46d7d81;\n",
+            kind: io::ErrorKind::ConnectionReset,
+        };
+        let mut reader = AvrReader::new(io::BufReader::new(reset));
+        assert!(reader.next().unwrap().is_ok());
+        assert_eq!(
+            reader.next().unwrap().unwrap_err().kind(),
+            io::ErrorKind::ConnectionReset
+        );
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn resync_corrupt_lines() {
+        let input = b"\xff\xfe2adb31799cb802b;\n\
+                      *8da2c1bd587ba2adb31799cb802b;\n\
+                      *8dzz;\n\
+                      *8da2c1bd58*5da039b46d7d81;\n\
+                      *5da039b4";
+        let (frames, resyncs) = read_all(input);
+        assert_eq!(
+            frames,
+            vec![
+                hex::decode("8da2c1bd587ba2adb31799cb802b").unwrap(),
+                hex::decode("5da039b46d7d81").unwrap(),
+            ]
+        );
+        assert_eq!(resyncs, 4);
+    }
+
+    /// Beast `frame` of `kind` with a timestamp containing an escaped `0x1a`
+    fn beast(kind: u8, frame: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![BEAST_ESCAPE, kind, 0, 0, 0x1a, 0x1a, 0, 0, 1, 0x40];
+        for &byte in frame {
+            bytes.push(byte);
+            if byte == BEAST_ESCAPE {
+                bytes.push(byte);
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn beast_frames() {
+        let long = hex::decode("8d40621d58c382d690c8ac2863a7").unwrap();
+        let short = hex::decode("5d1a039b46d7d8").unwrap();
+        let mut input = b"xx".to_vec();
+        input.extend(beast(b'3', &long));
+        input.extend(beast(b'1', &[0x12, 0x34]));
+        // truncated by the start of the next frame
+        input.extend(&beast(b'2', &short)[..11]);
+        input.extend(beast(b'2', &short));
+        // incomplete at the end of the stream
+        input.extend(&beast(b'3', &long)[..8]);
+
+        // read a few bytes at a time, splitting frames over reads
+        let reader = BufReader::with_capacity(5, &input[..]);
+        let mut reader = AvrReader::with_format(reader, Format::Beast);
+        let mut frames = vec![];
+        while let Some(frame) = reader.read_frame().unwrap() {
+            frames.push(frame);
+        }
+        assert_eq!(frames, vec![long, short]);
+        assert_eq!(reader.take_resyncs(), 3);
+    }
+
+    #[test]
+    fn detect_format() {
+        assert_eq!(Format::detect(b'*'), Format::Avr);
+        assert_eq!(Format::detect(BEAST_ESCAPE), Format::Beast);
+        assert_eq!(Format::detect(b'8'), Format::Hex);
+        assert_eq!(Format::detect(b'{'), Format::Avr);
+
+        let input = b"\n  8da2c1bd587ba2adb31799cb802b\n\nzz\n5da039b46d7d81\r\n";
+        let mut reader = AvrReader::detect(&input[..]);
+        assert_eq!(
+            reader.by_ref().map(Result::unwrap).collect::<Vec<_>>(),
+            vec![
+                hex::decode("8da2c1bd587ba2adb31799cb802b").unwrap(),
+                hex::decode("5da039b46d7d81").unwrap(),
+            ]
+        );
+        assert_eq!(reader.take_resyncs(), 1);
+
+        let input = beast(b'2', &hex::decode("5da039b46d7d81").unwrap());
+        let mut reader = AvrReader::detect(&input[..]);
+        assert_eq!(
+            reader.poll_frame(),
+            ReadEvent::Frame(hex::decode("5da039b46d7d81").unwrap())
+        );
+    }
+
+    #[test]
+    fn background_reader() {
+        let input = Failing {
+            input: b"*5da039b46d7d81;\n",
+            kind: io::ErrorKind::ConnectionReset,
+        };
+        let mut reader = AvrReader::new(BufReader::new(BackgroundReader::spawn(input)));
+        let mut event = ReadEvent::Idle;
+        while event == ReadEvent::Idle {
+            event = reader.poll_frame();
+        }
+        assert_eq!(
+            event,
+            ReadEvent::Frame(hex::decode("5da039b46d7d81").unwrap())
+        );
+        assert_eq!(
+            reader.poll_frame(),
+            ReadEvent::End(StreamEnd::Disconnected(io::ErrorKind::ConnectionReset))
+        );
+
+        let mut reader = AvrReader::new(BufReader::new(BackgroundReader::spawn(&b""[..])));
+        assert_eq!(reader.poll_frame(), ReadEvent::End(StreamEnd::Eof));
+    }
+}
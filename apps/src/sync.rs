@@ -0,0 +1,487 @@
+//! Compact binary protocol for syncing tracked aircraft over low-bandwidth links
+//!
+//! Instead of forwarding every raw frame, a remote receiver site tracks the aircraft itself and
+//! periodically sends only the values that changed since the last message, such as over
+//! LoRa or a metered cellular link. The [`Encoder`] on the receiver site and the [`Decoder`] on
+//! the other end both keep the last synced [`Snapshot`] of every aircraft.
+//!
+//! Values are quantized before being compared, so noise below the resolution of the protocol is
+//! never sent. Values that become unknown are not sent, the last known value is kept until the
+//! aircraft is removed.
+//!
+//! # Format
+//! All values are big endian. Every message is prefixed by its `u16` length when written to a
+//! stream, see [`write_message`] and [`read_message`].
+//!
+//! | Field    | Size  | Description                                             |
+//! | -------- | ----- | ------------------------------------------------------- |
+//! | kind     | 1     | `0`: full state, the previous state is cleared. `1`: delta |
+//! | updates  | 2     | amount of updated aircraft                              |
+//! | update   | 4+    | ICAO (3), field mask (1), each field in the mask in order |
+//! | removals | 2     | amount of removed aircraft                              |
+//! | removal  | 3     | ICAO                                                    |
+//!
+//! | Mask | Field      | Encoding                                        |
+//! | ---- | ---------- | ----------------------------------------------- |
+//! | 0x01 | position   | `i32` latitude, `i32` longitude in 1e-5 degrees |
+//! | 0x02 | altitude   | `u16` ft                                        |
+//! | 0x04 | heading    | `u16` in 0.01 degrees                           |
+//! | 0x08 | speed      | `u16` in 0.1 kt                                 |
+//! | 0x10 | vert_speed | `i16` ft/min                                    |
+//! | 0x20 | on_ground  | `u8`                                            |
+//! | 0x40 | callsign   | `u8` length, ASCII                              |
+//! | 0x80 | squawk     | `u16`                                           |
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use adsb_deku::ICAO;
+use rsadsb_common::Airplanes;
+
+const KIND_FULL: u8 = 0;
+const KIND_DELTA: u8 = 1;
+
+const POSITION: u8 = 0x01;
+const ALTITUDE: u8 = 0x02;
+const HEADING: u8 = 0x04;
+const SPEED: u8 = 0x08;
+const VERT_SPEED: u8 = 0x10;
+const ON_GROUND: u8 = 0x20;
+const CALLSIGN: u8 = 0x40;
+const SQUAWK: u8 = 0x80;
+
+/// Resolution of latitude and longitude
+const POSITION_SCALE: f64 = 100_000.0;
+
+/// Resolution of heading
+const HEADING_SCALE: f32 = 100.0;
+
+/// Resolution of speed
+const SPEED_SCALE: f32 = 10.0;
+
+/// Synced state of an aircraft, in the resolution of the protocol
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// (latitude, longitude) in 1e-5 degrees
+    position: Option<(i32, i32)>,
+    altitude: Option<u16>,
+    /// 0.01 degrees
+    heading: Option<u16>,
+    /// 0.1 kt
+    speed: Option<u16>,
+    vert_speed: Option<i16>,
+    on_ground: Option<bool>,
+    callsign: Option<String>,
+    squawk: Option<u16>,
+}
+
+impl Snapshot {
+    /// Quantize the current state of `icao`
+    fn new(airplanes: &Airplanes, icao: ICAO) -> Option<Self> {
+        let state = airplanes.get(icao)?;
+        let details = airplanes.aircraft_details(icao);
+        Some(Self {
+            position: details.as_ref().map(|details| {
+                (
+                    (details.position.latitude * POSITION_SCALE).round() as i32,
+                    (details.position.longitude * POSITION_SCALE).round() as i32,
+                )
+            }),
+            altitude: details.map(|details| details.altitude),
+            heading: state
+                .heading
+                .map(|heading| (heading * HEADING_SCALE).round() as u16),
+            speed: state
+                .speed
+                .map(|speed| (speed * SPEED_SCALE).round() as u16),
+            vert_speed: state.vert_speed,
+            on_ground: state.on_ground,
+            callsign: state.callsign.clone(),
+            squawk: state.squawk.map(|squawk| squawk.0),
+        })
+    }
+
+    /// (latitude, longitude) in degrees
+    #[must_use]
+    pub fn position(&self) -> Option<(f64, f64)> {
+        self.position.map(|(lat, long)| {
+            (
+                f64::from(lat) / POSITION_SCALE,
+                f64::from(long) / POSITION_SCALE,
+            )
+        })
+    }
+
+    /// Altitude in ft
+    #[must_use]
+    pub fn altitude(&self) -> Option<u16> {
+        self.altitude
+    }
+
+    /// Heading in degrees
+    #[must_use]
+    pub fn heading(&self) -> Option<f32> {
+        self.heading
+            .map(|heading| f32::from(heading) / HEADING_SCALE)
+    }
+
+    /// Ground speed in kt
+    #[must_use]
+    pub fn speed(&self) -> Option<f32> {
+        self.speed.map(|speed| f32::from(speed) / SPEED_SCALE)
+    }
+
+    /// Vertical speed in ft/min
+    #[must_use]
+    pub fn vert_speed(&self) -> Option<i16> {
+        self.vert_speed
+    }
+
+    #[must_use]
+    pub fn on_ground(&self) -> Option<bool> {
+        self.on_ground
+    }
+
+    #[must_use]
+    pub fn callsign(&self) -> Option<&str> {
+        self.callsign.as_deref()
+    }
+
+    #[must_use]
+    pub fn squawk(&self) -> Option<u16> {
+        self.squawk
+    }
+
+    /// Mask of the fields that are known in `self` and differ from `previous`
+    fn changed(&self, previous: &Self) -> u8 {
+        fn bit<T: PartialEq>(mask: u8, new: &Option<T>, previous: &Option<T>) -> u8 {
+            if new.is_some() && new != previous {
+                mask
+            } else {
+                0
+            }
+        }
+        bit(POSITION, &self.position, &previous.position)
+            | bit(ALTITUDE, &self.altitude, &previous.altitude)
+            | bit(HEADING, &self.heading, &previous.heading)
+            | bit(SPEED, &self.speed, &previous.speed)
+            | bit(VERT_SPEED, &self.vert_speed, &previous.vert_speed)
+            | bit(ON_GROUND, &self.on_ground, &previous.on_ground)
+            | bit(CALLSIGN, &self.callsign, &previous.callsign)
+            | bit(SQUAWK, &self.squawk, &previous.squawk)
+    }
+
+    /// Write the fields in `mask`, which must all be known
+    fn encode(&self, mask: u8, buf: &mut Vec<u8>) {
+        if let (true, Some((lat, long))) = (mask & POSITION != 0, self.position) {
+            buf.extend_from_slice(&lat.to_be_bytes());
+            buf.extend_from_slice(&long.to_be_bytes());
+        }
+        if let (true, Some(altitude)) = (mask & ALTITUDE != 0, self.altitude) {
+            buf.extend_from_slice(&altitude.to_be_bytes());
+        }
+        if let (true, Some(heading)) = (mask & HEADING != 0, self.heading) {
+            buf.extend_from_slice(&heading.to_be_bytes());
+        }
+        if let (true, Some(speed)) = (mask & SPEED != 0, self.speed) {
+            buf.extend_from_slice(&speed.to_be_bytes());
+        }
+        if let (true, Some(vert_speed)) = (mask & VERT_SPEED != 0, self.vert_speed) {
+            buf.extend_from_slice(&vert_speed.to_be_bytes());
+        }
+        if let (true, Some(on_ground)) = (mask & ON_GROUND != 0, self.on_ground) {
+            buf.push(u8::from(on_ground));
+        }
+        if let (true, Some(callsign)) = (mask & CALLSIGN != 0, &self.callsign) {
+            let callsign = &callsign.as_bytes()[..callsign.len().min(usize::from(u8::MAX))];
+            buf.push(callsign.len() as u8);
+            buf.extend_from_slice(callsign);
+        }
+        if let (true, Some(squawk)) = (mask & SQUAWK != 0, self.squawk) {
+            buf.extend_from_slice(&squawk.to_be_bytes());
+        }
+    }
+
+    /// Read the fields in `mask`, replacing the current values
+    fn decode(&mut self, mask: u8, buf: &mut &[u8]) -> Result<(), SyncError> {
+        if mask & POSITION != 0 {
+            let lat = i32::from_be_bytes(take(buf)?);
+            let long = i32::from_be_bytes(take(buf)?);
+            self.position = Some((lat, long));
+        }
+        if mask & ALTITUDE != 0 {
+            self.altitude = Some(u16::from_be_bytes(take(buf)?));
+        }
+        if mask & HEADING != 0 {
+            self.heading = Some(u16::from_be_bytes(take(buf)?));
+        }
+        if mask & SPEED != 0 {
+            self.speed = Some(u16::from_be_bytes(take(buf)?));
+        }
+        if mask & VERT_SPEED != 0 {
+            self.vert_speed = Some(i16::from_be_bytes(take(buf)?));
+        }
+        if mask & ON_GROUND != 0 {
+            let [on_ground] = take(buf)?;
+            self.on_ground = Some(on_ground != 0);
+        }
+        if mask & CALLSIGN != 0 {
+            let [len] = take(buf)?;
+            let callsign = take_slice(buf, usize::from(len))?;
+            let callsign = std::str::from_utf8(callsign).map_err(|_| SyncError::Malformed)?;
+            self.callsign = Some(callsign.to_string());
+        }
+        if mask & SQUAWK != 0 {
+            self.squawk = Some(u16::from_be_bytes(take(buf)?));
+        }
+        Ok(())
+    }
+}
+
+/// Error decoding a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncError {
+    /// Message ended before all of its fields were read
+    Truncated,
+    /// Unknown message kind or invalid field
+    Malformed,
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "truncated sync message"),
+            Self::Malformed => write!(f, "malformed sync message"),
+        }
+    }
+}
+
+impl std::error::Error for SyncError {}
+
+fn take_slice<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8], SyncError> {
+    if buf.len() < len {
+        return Err(SyncError::Truncated);
+    }
+    let (bytes, rest) = buf.split_at(len);
+    *buf = rest;
+    Ok(bytes)
+}
+
+fn take<const N: usize>(buf: &mut &[u8]) -> Result<[u8; N], SyncError> {
+    let mut bytes = [0; N];
+    bytes.copy_from_slice(take_slice(buf, N)?);
+    Ok(bytes)
+}
+
+/// Sending side, creating messages with the changes since the last call to [`Self::encode`]
+#[derive(Debug, Default)]
+pub struct Encoder {
+    synced: BTreeMap<ICAO, Snapshot>,
+    /// The next message contains the full state
+    full: bool,
+}
+
+impl Encoder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            synced: BTreeMap::new(),
+            full: true,
+        }
+    }
+
+    /// Message with all changes of `airplanes` since the last message
+    ///
+    /// The first message contains the full state. Returns `None` if nothing changed.
+    pub fn encode(&mut self, airplanes: &Airplanes) -> Option<Vec<u8>> {
+        let full = core::mem::take(&mut self.full);
+        let mut updates = vec![];
+        let mut current = BTreeMap::new();
+        for icao in airplanes.keys() {
+            let snapshot = match Snapshot::new(airplanes, *icao) {
+                Some(snapshot) => snapshot,
+                None => continue,
+            };
+            let previous = self.synced.get(icao).filter(|_| !full);
+            let mask = snapshot.changed(previous.unwrap_or(&Snapshot::default()));
+            // new aircraft are sent even without any known values
+            if mask != 0 || previous.is_none() {
+                updates.push((*icao, mask));
+            }
+            current.insert(*icao, snapshot);
+        }
+        let removals: Vec<ICAO> = self
+            .synced
+            .keys()
+            .filter(|icao| !current.contains_key(icao))
+            .copied()
+            .collect();
+
+        if !full && updates.is_empty() && removals.is_empty() {
+            return None;
+        }
+
+        let mut buf = vec![if full { KIND_FULL } else { KIND_DELTA }];
+        buf.extend_from_slice(&(updates.len() as u16).to_be_bytes());
+        for (icao, mask) in &updates {
+            buf.extend_from_slice(&icao.0);
+            buf.push(*mask);
+            current[icao].encode(*mask, &mut buf);
+        }
+        buf.extend_from_slice(&(removals.len() as u16).to_be_bytes());
+        for icao in &removals {
+            buf.extend_from_slice(&icao.0);
+        }
+
+        self.synced = current;
+        Some(buf)
+    }
+}
+
+/// Receiving side, applying messages from an [`Encoder`]
+#[derive(Debug, Default)]
+pub struct Decoder {
+    aircraft: BTreeMap<ICAO, Snapshot>,
+}
+
+impl Decoder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All currently synced aircraft
+    #[must_use]
+    pub fn aircraft(&self) -> &BTreeMap<ICAO, Snapshot> {
+        &self.aircraft
+    }
+
+    /// Apply a message created by [`Encoder::encode`]
+    ///
+    /// On error, the state is left partially updated and the sender should be reconnected to
+    /// receive the full state again.
+    pub fn decode(&mut self, mut buf: &[u8]) -> Result<(), SyncError> {
+        let buf = &mut buf;
+        let [kind] = take(buf)?;
+        match kind {
+            KIND_FULL => self.aircraft.clear(),
+            KIND_DELTA => (),
+            _ => return Err(SyncError::Malformed),
+        }
+        let updates = u16::from_be_bytes(take(buf)?);
+        for _ in 0..updates {
+            let icao = ICAO(take(buf)?);
+            let [mask] = take(buf)?;
+            self.aircraft.entry(icao).or_default().decode(mask, buf)?;
+        }
+        let removals = u16::from_be_bytes(take(buf)?);
+        for _ in 0..removals {
+            self.aircraft.remove(&ICAO(take(buf)?));
+        }
+        if buf.is_empty() {
+            Ok(())
+        } else {
+            Err(SyncError::Malformed)
+        }
+    }
+}
+
+/// Write `message` prefixed with its length
+pub fn write_message<W: Write>(writer: &mut W, message: &[u8]) -> io::Result<()> {
+    let len = u16::try_from(message.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "sync message too large"))?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(message)?;
+    writer.flush()
+}
+
+/// Read a message written by [`write_message`]
+pub fn read_message<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut len = [0; 2];
+    reader.read_exact(&mut len)?;
+    let mut message = vec![0; usize::from(u16::from_be_bytes(len))];
+    reader.read_exact(&mut message)?;
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use adsb_deku::deku::DekuContainerRead;
+    use adsb_deku::Frame;
+
+    use super::*;
+
+    const LAT_LONG: (f64, f64) = (52.25, 3.91);
+
+    fn action(airplanes: &mut Airplanes, frame: &str) {
+        let bytes = hex::decode(frame).unwrap();
+        let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+        airplanes.action(frame.validate().unwrap(), LAT_LONG);
+    }
+
+    #[test]
+    fn sync() {
+        let mut airplanes = Airplanes::new();
+        let mut encoder = Encoder::new();
+        let mut decoder = Decoder::new();
+        let klm = ICAO([0x48, 0x40, 0xd6]);
+        let other = ICAO([0x40, 0x62, 0x1d]);
+
+        // identification, airborne from the capability
+        action(&mut airplanes, "8d4840d6202cc371c32ce0576098");
+        let full = encoder.encode(&airplanes).unwrap();
+        assert_eq!(full.len(), 1 + 2 + 3 + 1 + 1 + 7 + 1 + 2);
+        decoder.decode(&full).unwrap();
+        assert_eq!(decoder.aircraft()[&klm].callsign(), Some("KLM1023"));
+        assert_eq!(encoder.encode(&airplanes), None);
+
+        // even and odd position of another aircraft, only its position, altitude and on_ground are
+        // sent
+        action(&mut airplanes, "8d40621d58c382d690c8ac2863a7");
+        action(&mut airplanes, "8d40621d58c386435cc412692ad6");
+        let delta = encoder.encode(&airplanes).unwrap();
+        assert_eq!(delta.len(), 1 + 2 + 3 + 1 + 8 + 2 + 1 + 2);
+        decoder.decode(&delta).unwrap();
+        let snapshot = &decoder.aircraft()[&other];
+        assert_eq!(snapshot.callsign(), None);
+        assert_eq!(snapshot.altitude(), Some(38000));
+        let (lat, long) = snapshot.position().unwrap();
+        let position = airplanes.aircraft_details(other).unwrap().position;
+        assert!((lat - position.latitude).abs() <= 0.000_005);
+        assert!((long - position.longitude).abs() <= 0.000_005);
+        assert_eq!(decoder.aircraft()[&klm].callsign(), Some("KLM1023"));
+
+        // a decoder is cleared by the full state
+        decoder.decode(&full).unwrap();
+        assert_eq!(decoder.aircraft().len(), 1);
+    }
+
+    #[test]
+    fn malformed() {
+        let mut decoder = Decoder::new();
+        assert_eq!(decoder.decode(&[]), Err(SyncError::Truncated));
+        assert_eq!(decoder.decode(&[2, 0, 0, 0, 0]), Err(SyncError::Malformed));
+        // position without its values
+        assert_eq!(
+            decoder.decode(&[1, 0, 1, 0x48, 0x40, 0xd6, POSITION, 0, 0]),
+            Err(SyncError::Truncated)
+        );
+        // trailing bytes
+        assert_eq!(
+            decoder.decode(&[1, 0, 0, 0, 0, 0]),
+            Err(SyncError::Malformed)
+        );
+    }
+
+    #[test]
+    fn stream() {
+        let mut stream = vec![];
+        write_message(&mut stream, &[KIND_DELTA, 0, 0, 0, 0]).unwrap();
+        assert_eq!(stream, [0, 5, KIND_DELTA, 0, 0, 0, 0]);
+        assert_eq!(
+            read_message(&mut stream.as_slice()).unwrap(),
+            [KIND_DELTA, 0, 0, 0, 0]
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use adsb_deku::deku::DekuContainerRead;
+use adsb_deku::Frame;
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use rsadsb_apps::source::{AvrReader, ReadEvent, StreamEnd};
+use rsadsb_apps::sync::{read_message, write_message, Decoder, Encoder};
+use rsadsb_common::Airplanes;
+
+/// Time a client has to accept a message before it's disconnected
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Messages waiting to be written to a client before it's disconnected for being too slow
+const MAX_QUEUED: usize = 8;
+
+#[derive(Debug, Parser)]
+#[clap(
+    version,
+    name = "sync",
+    author = "wcampbell0x2a",
+    about = "Sync tracked aircraft from a remote receiver over low-bandwidth links"
+)]
+struct Options {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Track aircraft from a demodulator and send the changes to all connected clients
+    Server {
+        /// ip address / hostname of ADS-B server / demodulator
+        #[clap(long, default_value = "127.0.0.1")]
+        host: String,
+        /// port of ADS-B server / demodulator
+        #[clap(long, default_value = "30002")]
+        port: u16,
+        /// Antenna location latitude, used for aircraft position algorithms
+        #[clap(long)]
+        lat: f64,
+        /// Antenna location longitude, used for aircraft position algorithms
+        #[clap(long)]
+        long: f64,
+        /// port clients connect to
+        #[clap(long, default_value = "30090")]
+        listen_port: u16,
+        /// Seconds between sending the changes
+        #[clap(long, default_value = "1")]
+        interval: u64,
+        /// Seconds since last message from airplane, triggers removal of airplane after time is
+        /// up
+        #[clap(long, default_value = "120")]
+        filter_time: u64,
+        /// Reconnect to the demodulator when the connection is closed or lost, instead of
+        /// exiting
+        #[clap(long)]
+        retry_tcp: bool,
+    },
+    /// Receive tracked aircraft from a server and print them after every update
+    Client {
+        /// ip address / hostname of the sync server
+        #[clap(long, default_value = "127.0.0.1")]
+        host: String,
+        /// port of the sync server
+        #[clap(long, default_value = "30090")]
+        port: u16,
+    },
+}
+
+/// Client of the server, with its messages written from its own thread, so that a stalled client
+/// doesn't stall decoding or the other clients
+struct Client {
+    addr: SocketAddr,
+    /// Every client has its own encoder, starting with the full state
+    encoder: Encoder,
+    messages: SyncSender<Vec<u8>>,
+}
+
+impl Client {
+    fn spawn(stream: TcpStream, addr: SocketAddr) -> Result<Self> {
+        stream.set_nonblocking(false)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let (messages, queued) = mpsc::sync_channel::<Vec<u8>>(MAX_QUEUED);
+        thread::spawn(move || {
+            let mut stream = stream;
+            for message in queued {
+                if let Err(e) = write_message(&mut stream, &message) {
+                    println!("client {addr} disconnected: {e}");
+                    return;
+                }
+            }
+        });
+        Ok(Self {
+            addr,
+            encoder: Encoder::new(),
+            messages,
+        })
+    }
+
+    /// Queue the changes of `airplanes`, returning `false` once the client is disconnected
+    ///
+    /// A client that doesn't keep up is disconnected, as the changes of a dropped message would
+    /// be missing from the following ones.
+    fn send(&mut self, airplanes: &Airplanes) -> bool {
+        let message = match self.encoder.encode(airplanes) {
+            Some(message) => message,
+            None => return true,
+        };
+        match self.messages.try_send(message) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                println!("client {} disconnected: too slow", self.addr);
+                false
+            },
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+}
+
+fn server(
+    host: &str,
+    port: u16,
+    lat_long: (f64, f64),
+    listen_port: u16,
+    interval: Duration,
+    filter_time: u64,
+    retry_tcp: bool,
+) -> Result<()> {
+    let mut reader = AvrReader::connect((host, port))
+        .with_context(|| format!("unable to connect to {host}:{port}"))?;
+
+    let listener = TcpListener::bind(("0.0.0.0", listen_port))
+        .with_context(|| format!("unable to listen on port {listen_port}"))?;
+    listener.set_nonblocking(true)?;
+
+    let mut clients: Vec<Client> = vec![];
+    let mut airplanes = Airplanes::new();
+    let mut last_sent = Instant::now();
+
+    loop {
+        match reader.poll_frame() {
+            ReadEvent::Frame(bytes) => {
+                if let Ok((_, frame)) = Frame::from_bytes((&bytes, 0)) {
+                    if let Ok(frame) = frame.validate() {
+                        airplanes.action(frame, lat_long);
+                    }
+                }
+            },
+            ReadEvent::Idle => (),
+            ReadEvent::End(end) if retry_tcp => {
+                println!("{host}:{port} {end}, reconnecting");
+                // keep serving clients the current state while reconnecting
+                if let Ok(new_reader) = AvrReader::connect((host, port)) {
+                    reader = new_reader;
+                } else {
+                    std::thread::sleep(Duration::from_secs(1));
+                }
+            },
+            ReadEvent::End(StreamEnd::Eof) => return Ok(()),
+            ReadEvent::End(end) => anyhow::bail!("{host}:{port} {end}"),
+        }
+
+        for event in airplanes.take_events() {
+            println!("{event}");
+        }
+
+        match listener.accept() {
+            Ok((stream, addr)) => {
+                println!("client connected: {addr}");
+                clients.push(Client::spawn(stream, addr)?);
+            },
+            Err(e) if e.kind() == ErrorKind::WouldBlock => (),
+            Err(e) => return Err(e.into()),
+        }
+
+        if last_sent.elapsed() >= interval {
+            last_sent = Instant::now();
+            airplanes.prune(filter_time);
+            let mut connected = vec![];
+            for mut client in clients.drain(..) {
+                if client.send(&airplanes) {
+                    connected.push(client);
+                }
+            }
+            clients = connected;
+        }
+    }
+}
+
+fn client(host: &str, port: u16) -> Result<()> {
+    let mut stream = TcpStream::connect((host, port))
+        .with_context(|| format!("unable to connect to {host}:{port}"))?;
+    let mut decoder = Decoder::new();
+
+    loop {
+        let message = read_message(&mut stream)?;
+        decoder.decode(&message)?;
+        println!(
+            "{} aircraft, {} byte update",
+            decoder.aircraft().len(),
+            message.len()
+        );
+        for (icao, snapshot) in decoder.aircraft() {
+            print!("  {icao} {:<8}", snapshot.callsign().unwrap_or(""));
+            if let Some((lat, long)) = snapshot.position() {
+                print!(" ({lat:.5}, {long:.5})");
+            }
+            if let Some(altitude) = snapshot.altitude() {
+                print!(" {altitude} ft");
+            }
+            if let Some(heading) = snapshot.heading() {
+                print!(" {heading:.1}");
+            }
+            if let Some(speed) = snapshot.speed() {
+                print!(" {speed:.1} kt");
+            }
+            println!();
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let options = Options::parse();
+    match options.command {
+        Command::Server {
+            host,
+            port,
+            lat,
+            long,
+            listen_port,
+            interval,
+            filter_time,
+            retry_tcp,
+        } => server(
+            &host,
+            port,
+            (lat, long),
+            listen_port,
+            Duration::from_secs(interval),
+            filter_time,
+            retry_tcp,
+        ),
+        Command::Client { host, port } => client(&host, port),
+    }
+}
@@ -0,0 +1,121 @@
+//! Limiting the amount of decoded frames on low-power devices
+//!
+//! During traffic spikes, decoding and tracking every frame can use more CPU than a small device
+//! such as a Raspberry Pi Zero has. A [`Throttle`] only lets every Nth frame through, and/or caps
+//! the amount of frames per second, counting all other frames as dropped.
+
+use std::time::{Duration, Instant};
+
+/// Length of the window `max_rate` is counted in
+const WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub struct Throttle {
+    /// Only every Nth frame is decoded
+    every: u32,
+    /// Max amount of frames decoded per second
+    max_rate: Option<u32>,
+    /// Frames seen since the last frame that was let through by `every`
+    skipped: u32,
+    /// Start of the current window of `max_rate`
+    window_start: Option<Instant>,
+    /// Frames let through within the current window
+    window_count: u32,
+    dropped: u64,
+}
+
+impl Throttle {
+    /// Decode every `every`th frame, and at most `max_rate` frames per second
+    ///
+    /// An `every` of 0 or 1 lets every frame through.
+    #[must_use]
+    pub fn new(every: u32, max_rate: Option<u32>) -> Self {
+        Self {
+            every: every.max(1),
+            max_rate,
+            skipped: 0,
+            window_start: None,
+            window_count: 0,
+            dropped: 0,
+        }
+    }
+
+    /// The next frame should be decoded, counting it as dropped if not
+    pub fn allow(&mut self) -> bool {
+        self.allow_at(Instant::now())
+    }
+
+    fn allow_at(&mut self, now: Instant) -> bool {
+        let allowed = self.allow_every() && self.allow_rate(now);
+        if !allowed {
+            self.dropped += 1;
+        }
+        allowed
+    }
+
+    fn allow_every(&mut self) -> bool {
+        if self.skipped == 0 {
+            self.skipped = self.every - 1;
+            true
+        } else {
+            self.skipped -= 1;
+            false
+        }
+    }
+
+    fn allow_rate(&mut self, now: Instant) -> bool {
+        let max_rate = match self.max_rate {
+            Some(max_rate) => max_rate,
+            None => return true,
+        };
+        match self.window_start {
+            Some(start) if now.duration_since(start) < WINDOW => (),
+            _ => {
+                self.window_start = Some(now);
+                self.window_count = 0;
+            },
+        }
+        if self.window_count < max_rate {
+            self.window_count += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Amount of frames dropped since the last call, resetting the count
+    pub fn take_dropped(&mut self) -> u64 {
+        core::mem::take(&mut self.dropped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every() {
+        let mut throttle = Throttle::new(3, None);
+        let now = Instant::now();
+        let allowed: Vec<bool> = (0..7).map(|_| throttle.allow_at(now)).collect();
+        assert_eq!(allowed, [true, false, false, true, false, false, true]);
+        assert_eq!(throttle.take_dropped(), 4);
+        assert_eq!(throttle.take_dropped(), 0);
+
+        let mut throttle = Throttle::new(0, None);
+        assert!((0..10).all(|_| throttle.allow_at(now)));
+    }
+
+    #[test]
+    fn max_rate() {
+        let mut throttle = Throttle::new(1, Some(2));
+        let now = Instant::now();
+        assert!(throttle.allow_at(now));
+        assert!(throttle.allow_at(now + Duration::from_millis(100)));
+        assert!(!throttle.allow_at(now + Duration::from_millis(200)));
+        assert!(!throttle.allow_at(now + Duration::from_millis(999)));
+        // next window
+        assert!(throttle.allow_at(now + Duration::from_millis(1000)));
+        assert_eq!(throttle.take_dropped(), 2);
+    }
+}
@@ -0,0 +1,126 @@
+//! Time zone aware formatting of timestamps
+//!
+//! All displayed times are converted to a configured [`Timezone`], which defaults to UTC. Times
+//! meant for machines (JSON/CSV) use ISO-8601 with an explicit offset, while times meant for
+//! humans use a short format which still includes the offset so they are never ambiguous.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use time::format_description::well_known::Rfc3339;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::{OffsetDateTime, UtcOffset};
+
+/// Short format for human readable output, such as the tui
+const SHORT: &[FormatItem<'_>] = format_description!(
+    "[month]/[day] [hour]:[minute]:[second] [offset_hour sign:mandatory]:[offset_minute]"
+);
+
+/// Time zone used for displaying times
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timezone {
+    Utc,
+    /// Local time zone of the system
+    Local,
+    /// Fixed offset from UTC, such as `+02:00`
+    Fixed(UtcOffset),
+}
+
+impl Default for Timezone {
+    fn default() -> Self {
+        Self::Utc
+    }
+}
+
+impl Timezone {
+    /// Offset from UTC of this time zone
+    ///
+    /// For [`Timezone::Local`] this calls `localtime_r`, so it should only be called while the
+    /// process is single-threaded. Returns `None` if the local offset can't be determined.
+    #[must_use]
+    pub fn offset(&self) -> Option<UtcOffset> {
+        match self {
+            Self::Utc => Some(UtcOffset::UTC),
+            Self::Local => UtcOffset::current_local_offset().ok(),
+            Self::Fixed(offset) => Some(*offset),
+        }
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "utc" | "z" => Ok(Self::Utc),
+            "local" => Ok(Self::Local),
+            _ => {
+                let format = format_description!("[offset_hour sign:mandatory]:[offset_minute]");
+                UtcOffset::parse(s, &format)
+                    .map(Self::Fixed)
+                    .map_err(|_| format!("invalid timezone: {s}, expected utc, local, or ±HH:MM"))
+            },
+        }
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Utc => write!(f, "UTC"),
+            Self::Local => write!(f, "local"),
+            Self::Fixed(offset) => write!(
+                f,
+                "{:+03}:{:02}",
+                offset.whole_hours(),
+                offset.minutes_past_hour().abs()
+            ),
+        }
+    }
+}
+
+/// Format `time` as ISO-8601 (RFC 3339) in `offset`, for JSON and CSV output
+#[must_use]
+pub fn iso8601(time: SystemTime, offset: UtcOffset) -> String {
+    OffsetDateTime::from(time)
+        .to_offset(offset)
+        .format(&Rfc3339)
+        .unwrap()
+}
+
+/// Format `time` in a short human readable form in `offset`, including the offset
+#[must_use]
+pub fn short(time: SystemTime, offset: UtcOffset) -> String {
+    OffsetDateTime::from(time)
+        .to_offset(offset)
+        .format(&SHORT)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn parse_timezone() {
+        assert_eq!(Timezone::from_str("UTC"), Ok(Timezone::Utc));
+        assert_eq!(Timezone::from_str("local"), Ok(Timezone::Local));
+        let offset = UtcOffset::from_hms(-5, -30, 0).unwrap();
+        assert_eq!(Timezone::from_str("-05:30"), Ok(Timezone::Fixed(offset)));
+        assert_eq!(Timezone::Fixed(offset).to_string(), "-05:30");
+        assert!(Timezone::from_str("mars").is_err());
+    }
+
+    #[test]
+    fn format_timestamps() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_650_000_000);
+        assert_eq!(iso8601(time, UtcOffset::UTC), "2022-04-15T05:20:00Z");
+        let offset = UtcOffset::from_hms(2, 0, 0).unwrap();
+        assert_eq!(iso8601(time, offset), "2022-04-15T07:20:00+02:00");
+        assert_eq!(short(time, offset), "04/15 07:20:00 +02:00");
+    }
+}
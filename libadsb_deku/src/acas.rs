@@ -0,0 +1,308 @@
+//! ACAS (TCAS) Resolution Advisory reports, contained in the MV field of [`DF::LongAirAir`]
+//!
+//! reference: ICAO 9871 (A.2.3.1.3), Annex 10 Vol IV (4.3.8.4.2.2)
+//!
+//! [`DF::LongAirAir`]: crate::DF::LongAirAir
+
+#[cfg(feature = "alloc")]
+use alloc::{format, vec::Vec};
+use core::fmt;
+#[cfg(feature = "alloc")]
+use core::{
+    clone::Clone,
+    cmp::{Eq, PartialEq},
+    fmt::Debug,
+    marker::Copy,
+    prelude::rust_2021::derive,
+    result::Result::Ok,
+    write, writeln,
+};
+
+use deku::prelude::*;
+
+use crate::{AC13Field, ICAO};
+
+/// MV: Message, ACAS
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+#[deku(type = "u8")]
+#[non_exhaustive]
+pub enum AcasMessage {
+    /// VDS 3,0: Resolution Advisory report
+    #[deku(id = "0x30")]
+    ResolutionAdvisory(ResolutionAdvisory),
+
+    #[deku(id_pat = "_")]
+    Unknown([u8; 7]),
+}
+
+impl fmt::Display for AcasMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ResolutionAdvisory(ra) => write!(f, "{ra}"),
+            Self::Unknown(_) => Ok(()),
+        }
+    }
+}
+
+/// Resolution Advisory report, after the 8 bit VDS
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct ResolutionAdvisory {
+    /// ARA: Active Resolution Advisories
+    pub ara: ActiveResolutionAdvisories,
+    /// RAC: Resolution Advisory Complements
+    pub rac: ResolutionAdvisoryComplements,
+    /// RAT: RA Terminated, the RA has been terminated within the last 18 seconds
+    #[deku(bits = "1")]
+    pub rat: bool,
+    /// MTE: Multiple Threat Encounter
+    #[deku(bits = "1")]
+    pub mte: bool,
+    /// TTI and TID: Threat Type Indicator and Threat Identity Data
+    pub threat: ThreatIdentity,
+}
+
+impl fmt::Display for ResolutionAdvisory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        match self.ara.multiple_threats(self.mte) {
+            Some(ara) => writeln!(f, "  RA:            {ara}")?,
+            None => writeln!(f, "  RA:            {}", self.ara)?,
+        }
+        writeln!(f, "  RA Complement: {}", self.rac)?;
+        writeln!(f, "  RA Terminated: {}", yes_no(self.rat))?;
+        writeln!(f, "  Multi-Threat:  {}", yes_no(self.mte))?;
+        writeln!(f, "  Threat:        {}", self.threat)
+    }
+}
+
+/// ARA: Active Resolution Advisories
+///
+/// The meaning of the bits after `active` is for a single threat, or multiple threats with the
+/// same sense (`active` set). For multiple threats with different senses (`active` clear and MTE
+/// set), the bits are: requires correction upwards, requires positive climb, requires correction
+/// downwards, requires positive descend, requires crossing, sense reversal, see
+/// [`ActiveResolutionAdvisories::multiple_threats`].
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct ActiveResolutionAdvisories {
+    /// At least one RA has been generated
+    #[deku(bits = "1")]
+    pub active: bool,
+    /// Corrective, otherwise preventive
+    #[deku(bits = "1")]
+    pub corrective: bool,
+    /// Downward sense, otherwise upward
+    #[deku(bits = "1")]
+    pub downward_sense: bool,
+    #[deku(bits = "1")]
+    pub increased_rate: bool,
+    #[deku(bits = "1")]
+    pub sense_reversal: bool,
+    #[deku(bits = "1")]
+    pub altitude_crossing: bool,
+    /// Positive, otherwise vertical speed limit
+    #[deku(bits = "1")]
+    pub positive: bool,
+    /// Reserved for ACAS III
+    #[deku(bits = "7")]
+    pub reserved: u8,
+}
+
+impl ActiveResolutionAdvisories {
+    /// Bits of multiple threats with different senses, `None` unless `active` is clear and `mte`
+    /// is set
+    #[must_use]
+    pub const fn multiple_threats(&self, mte: bool) -> Option<MultipleThreatAdvisories> {
+        if self.active || !mte {
+            return None;
+        }
+        Some(MultipleThreatAdvisories {
+            correction_upwards: self.corrective,
+            positive_climb: self.downward_sense,
+            correction_downwards: self.increased_rate,
+            positive_descend: self.sense_reversal,
+            crossing: self.altitude_crossing,
+            sense_reversal: self.positive,
+        })
+    }
+}
+
+impl fmt::Display for ActiveResolutionAdvisories {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.active {
+            return write!(f, "none");
+        }
+        let kind = if self.corrective {
+            "corrective"
+        } else {
+            "preventive"
+        };
+        let sense = if self.downward_sense {
+            "downward"
+        } else {
+            "upward"
+        };
+        let limit = if self.positive {
+            "positive"
+        } else {
+            "vertical speed limit"
+        };
+        write!(f, "{kind}, {sense} sense, {limit}")?;
+        if self.increased_rate {
+            write!(f, ", increased rate")?;
+        }
+        if self.sense_reversal {
+            write!(f, ", sense reversal")?;
+        }
+        if self.altitude_crossing {
+            write!(f, ", altitude crossing")?;
+        }
+        Ok(())
+    }
+}
+
+/// ARA of multiple threats with different senses, from
+/// [`ActiveResolutionAdvisories::multiple_threats`]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct MultipleThreatAdvisories {
+    pub correction_upwards: bool,
+    pub positive_climb: bool,
+    pub correction_downwards: bool,
+    pub positive_descend: bool,
+    pub crossing: bool,
+    pub sense_reversal: bool,
+}
+
+impl fmt::Display for MultipleThreatAdvisories {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let advisories = [
+            (self.correction_upwards, "requires correction upwards"),
+            (self.positive_climb, "requires positive climb"),
+            (self.correction_downwards, "requires correction downwards"),
+            (self.positive_descend, "requires positive descend"),
+            (self.crossing, "requires crossing"),
+            (self.sense_reversal, "sense reversal"),
+        ];
+        write!(f, "multiple threats")?;
+        for (_, name) in advisories.iter().filter(|(set, _)| *set) {
+            write!(f, ", {name}")?;
+        }
+        Ok(())
+    }
+}
+
+/// RAC: Resolution Advisory Complements, received from other ACAS aircraft
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct ResolutionAdvisoryComplements {
+    #[deku(bits = "1")]
+    pub do_not_pass_below: bool,
+    #[deku(bits = "1")]
+    pub do_not_pass_above: bool,
+    #[deku(bits = "1")]
+    pub do_not_turn_left: bool,
+    #[deku(bits = "1")]
+    pub do_not_turn_right: bool,
+}
+
+impl fmt::Display for ResolutionAdvisoryComplements {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let complements = [
+            (self.do_not_pass_below, "do not pass below"),
+            (self.do_not_pass_above, "do not pass above"),
+            (self.do_not_turn_left, "do not turn left"),
+            (self.do_not_turn_right, "do not turn right"),
+        ];
+        let mut first = true;
+        for (_, name) in complements.iter().filter(|(set, _)| *set) {
+            if !first {
+                write!(f, ", ")?;
+            }
+            write!(f, "{name}")?;
+            first = false;
+        }
+        if first {
+            write!(f, "none")?;
+        }
+        Ok(())
+    }
+}
+
+/// TTI: Threat Type Indicator, containing the TID: Threat Identity Data
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+#[deku(type = "u8", bits = "2")]
+pub enum ThreatIdentity {
+    /// No identity data
+    #[deku(id = "0")]
+    None(#[deku(bits = "26", endian = "big")] u32),
+    /// Mode S address of the threat
+    #[deku(id = "1")]
+    Address {
+        icao: ICAO,
+        #[deku(bits = "2")]
+        spare: u8,
+    },
+    /// Position of a threat without a Mode S transponder
+    #[deku(id = "2")]
+    Position {
+        /// TIDA: Altitude
+        altitude: AC13Field,
+        /// TIDR: Range, see [`ThreatIdentity::range`]
+        #[deku(bits = "7")]
+        range: u8,
+        /// TIDB: Bearing, see [`ThreatIdentity::bearing`]
+        #[deku(bits = "6")]
+        bearing: u8,
+    },
+    #[deku(id = "3")]
+    NotAssigned(#[deku(bits = "26", endian = "big")] u32),
+}
+
+impl ThreatIdentity {
+    /// Range of a [`ThreatIdentity::Position`] in NM, `None` if there is no estimate or it is
+    /// further than 12.55 NM
+    #[must_use]
+    pub fn range(&self) -> Option<f32> {
+        match self {
+            Self::Position { range, .. } => match range {
+                0 | 127 => None,
+                n => Some(f32::from(n - 1) / 10.0),
+            },
+            _ => None,
+        }
+    }
+
+    /// Bearing of a [`ThreatIdentity::Position`] relative to the heading of the reporting
+    /// aircraft in degrees, `None` if there is no estimate
+    #[must_use]
+    pub fn bearing(&self) -> Option<u16> {
+        match self {
+            Self::Position { bearing, .. } => match bearing {
+                1..=60 => Some(u16::from(bearing - 1) * 6),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ThreatIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address { icao, .. } => write!(f, "{icao}"),
+            Self::Position { altitude, .. } => {
+                match altitude.altitude() {
+                    Some(altitude) => write!(f, "{altitude} ft")?,
+                    None => write!(f, "unknown altitude")?,
+                }
+                match self.range() {
+                    Some(range) => write!(f, ", {range:.1} NM")?,
+                    None => write!(f, ", unknown range")?,
+                }
+                match self.bearing() {
+                    Some(bearing) => write!(f, ", {bearing} deg"),
+                    None => write!(f, ", unknown bearing"),
+                }
+            },
+            Self::None(_) | Self::NotAssigned(_) => write!(f, "unknown"),
+        }
+    }
+}
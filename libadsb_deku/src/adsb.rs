@@ -4,7 +4,8 @@
 //! [`DF::TisB`]: crate::DF::TisB
 
 #[cfg(feature = "alloc")]
-use alloc::{fmt, format, string::String};
+use alloc::{fmt, format, string::String, vec::Vec};
+use core::ops::Range;
 #[cfg(feature = "alloc")]
 use core::{
     clone::Clone,
@@ -23,14 +24,36 @@ use core::{
 #[cfg(not(feature = "alloc"))]
 use std::{fmt, i64};
 
-use deku::bitvec::{BitSlice, Msb0};
+use deku::bitvec::{BitSlice, BitVec, Msb0};
 use deku::prelude::*;
 
-use crate::mode_ac::decode_id13_field;
-use crate::{aircraft_identification_read, Altitude, CPRFormat, Capability, Sign, ICAO};
+use crate::acas::ResolutionAdvisory;
+use crate::{
+    aircraft_identification_read, aircraft_identification_write, Altitude, CPRFormat, Capability,
+    Peek, Sign, Squawk, SurveillanceStatus, ICAO,
+};
 
 /// [`crate::DF::ADSB`] || [`crate::DF::TisB`]
-#[derive(Debug, PartialEq, DekuRead, Clone)]
+///
+/// ```rust
+/// use adsb_deku::adsb::ME;
+/// use adsb_deku::deku::DekuContainerRead;
+/// use adsb_deku::{Frame, DF, ICAO};
+/// use hexlit::hex;
+///
+/// let bytes = hex!("8d4840d6202cc371c32ce0576098");
+/// let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+/// let adsb = match frame.df {
+///     DF::ADSB(adsb) => adsb,
+///     _ => unreachable!(),
+/// };
+/// assert_eq!(adsb.icao, ICAO([0x48, 0x40, 0xd6]));
+/// match adsb.me {
+///     ME::AircraftIdentification(identification) => assert_eq!(identification.cn, "KLM1023"),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct ADSB {
     /// Transponder Capability
     pub capability: Capability,
@@ -43,6 +66,43 @@ pub struct ADSB {
 }
 
 impl ADSB {
+    /// Geometric (GNSS) altitude in feet, from the barometric altitude of this
+    /// [`ME::AirbornePositionBaroAltitude`] and the GNSS delta of an [`ME::AirborneVelocity`] of
+    /// the same aircraft
+    ///
+    /// Returns `None` for other messages, without an altitude, or without a GNSS delta. A delta of
+    /// 0 ft is decoded the same as an unavailable delta, and also returns `None`.
+    ///
+    /// ```rust
+    /// use adsb_deku::adsb::ME;
+    /// use adsb_deku::deku::DekuContainerRead;
+    /// use adsb_deku::{Frame, DF};
+    /// use hexlit::hex;
+    ///
+    /// let position = hex!("8d40621d58c382d690c8ac2863a7");
+    /// let velocity = hex!("8d485020994409940838175b284f");
+    /// let position = Frame::from_bytes((&position, 0)).unwrap().1;
+    /// let velocity = Frame::from_bytes((&velocity, 0)).unwrap().1;
+    /// if let (DF::ADSB(position), DF::ADSB(velocity)) = (position.df, velocity.df) {
+    ///     if let ME::AirborneVelocity(velocity) = velocity.me {
+    ///         // 38000 ft barometric, GNSS altitude 550 ft above
+    ///         assert_eq!(position.geometric_altitude(&velocity), Some(38550));
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn geometric_altitude(&self, velocity: &AirborneVelocity) -> Option<i32> {
+        let altitude = match &self.me {
+            ME::AirbornePositionBaroAltitude(altitude) => altitude.alt?,
+            _ => return None,
+        };
+        if velocity.gnss_baro_diff == 0 {
+            return None;
+        }
+        let delta = i32::from(velocity.gnss_baro_diff) * i32::from(velocity.gnss_sign.value());
+        Some(i32::from(altitude) + delta)
+    }
+
     /// `to_string` with DF.id() input
     pub(crate) fn to_string(&self, address_type: &str) -> result::Result<String, Error> {
         let mut f = String::new();
@@ -59,11 +119,12 @@ impl ADSB {
 /// ADS-B Message, 5 first bits are known as Type Code (TC)
 ///
 /// reference: ICAO 9871 (A.2.3.1)
-#[derive(Debug, PartialEq, DekuRead, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 #[deku(type = "u8", bits = "5")]
+#[non_exhaustive]
 pub enum ME {
-    #[deku(id = "0")]
-    NoPosition([u8; 6]),
+    #[deku(id_pat = "0")]
+    NoPosition([u8; 7]),
 
     #[deku(id_pat = "1..=4")]
     AircraftIdentification(Identification),
@@ -81,28 +142,120 @@ pub enum ME {
     AirbornePositionGNSSAltitude(Altitude),
 
     #[deku(id = "23")]
-    Reserved0([u8; 6]),
+    TestMessage(TestMessage),
 
     #[deku(id_pat = "24")]
-    SurfaceSystemStatus([u8; 6]),
+    SurfaceSystemStatus([u8; 7]),
 
     #[deku(id_pat = "25..=27")]
-    Reserved1([u8; 6]),
+    Reserved1([u8; 7]),
 
     #[deku(id = "28")]
     AircraftStatus(AircraftStatus),
 
     #[deku(id = "29")]
-    TargetStateAndStatusInformation(TargetStateAndStatusInformation),
+    TargetStateAndStatusInformation(TargetStateAndStatus),
 
-    #[deku(id = "30")]
-    AircraftOperationalCoordination([u8; 6]),
+    #[deku(id_pat = "30")]
+    AircraftOperationalCoordination([u8; 7]),
 
     #[deku(id = "31")]
     AircraftOperationStatus(OperationStatus),
 }
 
+/// Allowlist of [`ME`] Type Codes to decode
+///
+/// Deployments that only need a subset of messages, such as positions and velocity, read frames
+/// with [`Frame::from_bytes_filtered`], which skips the decoding of all other messages.
+///
+/// ```rust
+/// use adsb_deku::adsb::TypeCodeFilter;
+/// use hexlit::hex;
+///
+/// let filter = TypeCodeFilter::AIRBORNE_POSITION.union(TypeCodeFilter::AIRBORNE_VELOCITY);
+/// // Airborne Position
+/// assert!(filter.allows(&hex!("8da2c1bd587ba2adb31799cb802b")));
+/// // Aircraft Identification
+/// assert!(!filter.allows(&hex!("8da8b84c213b6db111382029a2e2")));
+/// ```
+///
+/// [`Frame::from_bytes_filtered`]: crate::Frame::from_bytes_filtered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeCodeFilter(u32);
+
+impl TypeCodeFilter {
+    /// Decode all Type Codes
+    pub const ALL: Self = Self(u32::MAX);
+    /// Decode no Type Codes
+    pub const NONE: Self = Self(0);
+    /// [`ME::AircraftIdentification`]
+    pub const IDENTIFICATION: Self = Self(0b1_1110);
+    /// [`ME::SurfacePosition`]
+    pub const SURFACE_POSITION: Self = Self(0b1_1110_0000);
+    /// [`ME::AirbornePositionBaroAltitude`] and [`ME::AirbornePositionGNSSAltitude`]
+    pub const AIRBORNE_POSITION: Self = Self(0b0111_0111_1111_1110_0000_0000);
+    /// [`ME::AirborneVelocity`]
+    pub const AIRBORNE_VELOCITY: Self = Self(1 << 19);
+
+    /// Add Type Code `tc`
+    #[must_use]
+    pub const fn with_type_code(self, tc: u8) -> Self {
+        Self(self.0 | (1 << (tc & 0x1f)))
+    }
+
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Type Code `tc` is allowed to be decoded
+    #[must_use]
+    pub const fn contains(self, tc: u8) -> bool {
+        self.0 & (1 << (tc & 0x1f)) != 0
+    }
+
+    /// Check the Type Code of the raw bytes of a DF17/DF18 frame
+    ///
+    /// All other Downlink Formats, and frames too short to contain a Type Code, are allowed.
+    #[must_use]
+    pub fn allows(self, bytes: &[u8]) -> bool {
+        Peek::new(bytes)
+            .and_then(|peek| peek.tc)
+            .map_or(true, |tc| self.contains(tc))
+    }
+}
+
 impl ME {
+    /// Navigation Integrity Category of position messages, with NIC supplement-A and NIC
+    /// supplement-C of the [`ME::AircraftOperationStatus`] of the aircraft
+    ///
+    /// Pass 0 for supplements that are not known. Returns `None` for messages without a position
+    /// and for invalid combinations of the supplements, see [`Nic`].
+    ///
+    /// ```rust
+    /// use adsb_deku::adsb::{PositionQuality, ME};
+    /// use adsb_deku::deku::DekuContainerRead;
+    /// use adsb_deku::{Frame, DF};
+    /// use hexlit::hex;
+    ///
+    /// let bytes = hex!("8d40621d58c382d690c8ac2863a7");
+    /// let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    /// if let DF::ADSB(adsb) = frame.df {
+    ///     let nic = adsb.me.nic(0, 0).unwrap();
+    ///     assert_eq!(nic.rc, Some(185.2));
+    ///     assert_eq!(nic.quality(), PositionQuality::High);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn nic(&self, nic_supplement_a: u8, nic_supplement_c: u8) -> Option<Nic> {
+        match self {
+            ME::AirbornePositionBaroAltitude(altitude)
+            | ME::AirbornePositionGNSSAltitude(altitude) => altitude.nic(nic_supplement_a),
+            ME::SurfacePosition(surface) => surface.nic(nic_supplement_a, nic_supplement_c),
+            _ => None,
+        }
+    }
+
     /// `to_string` with DF.id() input
     pub(crate) fn to_string(
         &self,
@@ -126,7 +279,8 @@ impl ME {
                 writeln!(f, "  Address:       {icao} {address_type}")?;
                 writeln!(f, "  Air/Ground:    {capability}")?;
             },
-            ME::AircraftIdentification(Identification { tc, ca, cn }) => {
+            ME::AircraftIdentification(identification) => {
+                let Identification { tc, ca, cn } = identification;
                 writeln!(
                     f,
                     " Extended Squitter{}Aircraft identification and category",
@@ -135,11 +289,17 @@ impl ME {
                 writeln!(f, "  Address:       {icao} {address_type}")?;
                 writeln!(f, "  Air/Ground:    {capability}")?;
                 writeln!(f, "  Ident:         {cn}")?;
-                writeln!(f, "  Category:      {tc}{ca}")?;
+                writeln!(
+                    f,
+                    "  Category:      {tc}{ca} ({})",
+                    identification.emitter_category()
+                )?;
             },
-            ME::SurfacePosition(..) => {
+            ME::SurfacePosition(surface) => {
                 writeln!(f, " Extended Squitter{transponder}Surface position")?;
                 writeln!(f, "  Address:       {icao} {address_type}")?;
+                writeln!(f, "  Air/Ground:    {capability}")?;
+                write!(f, "{surface}")?;
             },
             ME::AirbornePositionBaroAltitude(altitude) => {
                 writeln!(
@@ -184,19 +344,38 @@ impl ME {
                     }
                 },
                 AirborneVelocitySubType::AirspeedDecoding(airspeed_decoding) => {
+                    let supersonic = airborne_velocity.st == 4;
                     writeln!(
                         f,
-                        " Extended Squitter{transponder}Airspeed and heading, subsonic",
+                        " Extended Squitter{transponder}Airspeed and heading, {}",
+                        if supersonic { "supersonic" } else { "subsonic" }
                     )?;
                     writeln!(f, "  Address:       {icao} {address_type}")?;
                     writeln!(f, "  Air/Ground:    {capability}")?;
-                    writeln!(f, "  IAS:           {} kt", airspeed_decoding.airspeed)?;
+                    if let Some(heading) = airspeed_decoding.heading() {
+                        writeln!(
+                            f,
+                            "  Heading:       {} magnetic",
+                            libm::ceil(heading as f64)
+                        )?;
+                    }
+                    let airspeed = if supersonic {
+                        airspeed_decoding.airspeed * 4
+                    } else {
+                        airspeed_decoding.airspeed
+                    };
+                    if airspeed_decoding.airspeed_type == 0 {
+                        writeln!(f, "  IAS:           {airspeed} kt")?;
+                    } else {
+                        writeln!(f, "  TAS:           {airspeed} kt")?;
+                    }
                     if airborne_velocity.vrate_value > 0 {
                         writeln!(
                             f,
-                            "  Baro rate:     {}{} ft/min",
+                            "  Vertical rate: {}{} ft/min {}",
                             airborne_velocity.vrate_sign,
-                            (airborne_velocity.vrate_value - 1) * 64
+                            (airborne_velocity.vrate_value - 1) * 64,
+                            airborne_velocity.vrate_src
                         )?;
                     }
                     writeln!(f, "  NACv:          {}", airborne_velocity.nac_v)?;
@@ -217,7 +396,20 @@ impl ME {
                 writeln!(f, "  Address:      {icao} {address_type}")?;
                 write!(f, "{}", altitude)?;
             },
-            ME::Reserved0(_) | ME::Reserved1(_) => {
+            ME::TestMessage(test_message) => {
+                writeln!(f, " Extended Squitter{transponder}Test message")?;
+                writeln!(f, "  Address:       {icao} {address_type}")?;
+                writeln!(f, "  Air/Ground:    {capability}")?;
+                match test_message {
+                    TestMessage::Squawk { squawk, .. } => {
+                        writeln!(f, "  Squawk:        {squawk}")?;
+                    },
+                    TestMessage::Unknown { subtype, .. } => {
+                        writeln!(f, "  Subtype:       {subtype}")?;
+                    },
+                }
+            },
+            ME::Reserved1(_) => {
                 writeln!(f, " Extended Squitter{transponder}Unknown")?;
                 writeln!(f, "  Address:       {icao} {address_type}")?;
                 writeln!(f, "  Air/Ground:    {capability}")?;
@@ -230,21 +422,43 @@ impl ME {
                 writeln!(f, "  Address:       {icao} {address_type}")?;
                 writeln!(f, "  Air/Ground:    {capability}")?;
             },
-            ME::AircraftStatus(AircraftStatus {
-                emergency_state,
-                squawk,
-                ..
-            }) => {
+            ME::AircraftStatus(AircraftStatus { sub_type, status }) => match status {
+                AircraftStatusData::EmergencyPriorityStatus {
+                    emergency_state,
+                    squawk,
+                } => {
+                    writeln!(
+                        f,
+                        " Extended Squitter{transponder}Emergency/priority status",
+                    )?;
+                    writeln!(f, "  Address:       {icao} {address_type}")?;
+                    writeln!(f, "  Air/Ground:    {capability}")?;
+                    writeln!(f, "  Squawk:        {squawk}")?;
+                    writeln!(f, "  Emergency/priority:    {emergency_state}")?;
+                },
+                AircraftStatusData::ACASRaBroadcast(ra) => {
+                    writeln!(f, " Extended Squitter{transponder}ACAS resolution advisory",)?;
+                    writeln!(f, "  Address:       {icao} {address_type}")?;
+                    writeln!(f, "  Air/Ground:    {capability}")?;
+                    write!(f, "{ra}")?;
+                },
+                AircraftStatusData::Unknown(_) => {
+                    writeln!(f, " Extended Squitter{transponder}Aircraft status")?;
+                    writeln!(f, "  Address:       {icao} {address_type}")?;
+                    writeln!(f, "  Air/Ground:    {capability}")?;
+                    writeln!(f, "  Subtype:       {sub_type:?}")?;
+                },
+            },
+            ME::TargetStateAndStatusInformation(TargetStateAndStatus::V1(target_info)) => {
                 writeln!(
                     f,
-                    " Extended Squitter{transponder}Emergency/priority status",
+                    " Extended Squitter{transponder}Target state and status (V1)",
                 )?;
                 writeln!(f, "  Address:       {icao} {address_type}")?;
                 writeln!(f, "  Air/Ground:    {capability}")?;
-                writeln!(f, "  Squawk:        {squawk:x?}")?;
-                writeln!(f, "  Emergency/priority:    {emergency_state}")?;
+                write!(f, "  Target State and Status:\n{target_info}")?;
             },
-            ME::TargetStateAndStatusInformation(target_info) => {
+            ME::TargetStateAndStatusInformation(TargetStateAndStatus::V2(target_info)) => {
                 writeln!(
                     f,
                     " Extended Squitter{transponder}Target state and status (V2)",
@@ -252,10 +466,17 @@ impl ME {
                 writeln!(f, "  Address:       {icao} {address_type}")?;
                 writeln!(f, "  Air/Ground:    {capability}")?;
                 writeln!(f, "  Target State and Status:")?;
-                writeln!(f, "    Target altitude:   MCP, {} ft", target_info.altitude)?;
+                writeln!(
+                    f,
+                    "    Target altitude:   {}, {} ft",
+                    target_info.altitude_source(),
+                    target_info.altitude
+                )?;
                 writeln!(f, "    Altimeter setting: {} millibars", target_info.qnh)?;
-                if target_info.is_heading {
-                    writeln!(f, "    Target heading:    {}", target_info.heading)?;
+                if let Some(heading) = target_info.selected_heading() {
+                    writeln!(f, "    Target heading:    {heading}")?;
+                } else {
+                    writeln!(f, "    Target heading:    not available")?;
                 }
                 if target_info.tcas {
                     write!(f, "    ACAS:              operational ")?;
@@ -280,6 +501,13 @@ impl ME {
                 writeln!(f, "    SIL:               {} (per sample)", target_info.sil)?;
                 writeln!(f, "    QNH:               {} millibars", target_info.qnh)?;
             },
+            ME::TargetStateAndStatusInformation(TargetStateAndStatus::Reserved { .. }) => {
+                writeln!(
+                    f,
+                    " Extended Squitter{transponder}Target state and status (reserved)",
+                )?;
+                writeln!(f, "  Address:       {icao} {address_type}")?;
+            },
             ME::AircraftOperationalCoordination(_) => {
                 writeln!(
                     f,
@@ -303,7 +531,7 @@ impl ME {
                 )?;
                 writeln!(f, "  Address:       {icao} {address_type}")?;
                 writeln!(f, "  Air/Ground:    {capability}")?;
-                write!(f, "  Aircraft Operational Status:\n {opstatus_surface}")?;
+                write!(f, "  Aircraft Operational Status:\n{opstatus_surface}")?;
             },
             ME::AircraftOperationStatus(OperationStatus::Reserved(..)) => {
                 writeln!(
@@ -318,7 +546,7 @@ impl ME {
 }
 
 /// [`ME::AirborneVelocity`] && [`AirborneVelocitySubType::GroundSpeedDecoding`]
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 pub struct GroundSpeedDecoding {
     pub ew_sign: Sign,
     #[deku(endian = "big", bits = "10")]
@@ -329,7 +557,7 @@ pub struct GroundSpeedDecoding {
 }
 
 /// [`ME::AirborneVelocity`] && [`AirborneVelocitySubType::AirspeedDecoding`]
-#[derive(Debug, PartialEq, Eq, DekuRead, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone)]
 pub struct AirspeedDecoding {
     #[deku(bits = "1")]
     pub status_heading: u8,
@@ -340,14 +568,24 @@ pub struct AirspeedDecoding {
     #[deku(
         endian = "big",
         bits = "10",
-        map = "|airspeed: u16| -> result::Result<_, DekuError> {Ok(if airspeed > 0 { airspeed - 1 } else { 0 })}"
+        map = "|airspeed: u16| -> result::Result<_, DekuError> {Ok(if airspeed > 0 { airspeed - 1 } else { 0 })}",
+        writer = "(airspeed + 1).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(10)))"
     )]
     pub airspeed: u16,
 }
 
+impl AirspeedDecoding {
+    /// Magnetic heading in degrees, if available
+    #[must_use]
+    pub fn heading(&self) -> Option<f32> {
+        (self.status_heading == 1).then(|| f32::from(self.mag_heading) * 360.0 / 1024.0)
+    }
+}
+
 /// Aircraft Operational Status Subtype
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "3")]
+#[non_exhaustive]
 pub enum OperationStatus {
     #[deku(id = "0")]
     Airborne(OperationStatusAirborne),
@@ -356,13 +594,13 @@ pub enum OperationStatus {
     Surface(OperationStatusSurface),
 
     #[deku(id_pat = "2..=7")]
-    Reserved(#[deku(bits = "5")] u8, [u8; 5]),
+    Reserved(#[deku(bits = "3")] u8, [u8; 6]),
 }
 
 /// [`ME::AircraftOperationStatus`] && [`OperationStatus`] == 0
 ///
 /// Version 2 support only
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 pub struct OperationStatusAirborne {
     /// CC (16 bits)
     pub capability_class: CapabilityClassAirborne,
@@ -432,58 +670,100 @@ impl fmt::Display for OperationStatusAirborne {
 }
 
 /// [`ME::AircraftOperationStatus`]
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+///
+/// Capability Class (CC) codes of airborne aircraft, DO-260B 2.2.3.2.7.2.3
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 pub struct CapabilityClassAirborne {
     #[deku(bits = "2", assert_eq = "0")]
     pub reserved0: u8,
 
     /// TCAS Operational
     #[deku(bits = "1")]
-    pub acas: u8,
+    pub acas: bool,
 
-    /// 1090ES IN
-    ///
-    /// bit 12
+    /// Aircraft has ADS-B 1090ES Receive Capability
     #[deku(bits = "1")]
-    pub cdti: u8,
+    pub es1090: bool,
 
     #[deku(bits = "2", assert_eq = "0")]
     pub reserved1: u8,
 
+    /// Air-Referenced Velocity report capability
     #[deku(bits = "1")]
-    pub arv: u8,
+    pub arv: bool,
+
+    /// Target State report capability
     #[deku(bits = "1")]
-    pub ts: u8,
-    #[deku(bits = "2")]
-    #[deku(pad_bits_after = "6")] //reserved
-    pub tc: u8,
+    pub ts: bool,
+
+    /// Trajectory Change report capability
+    pub tc: TrajectoryChangeCapability,
+
+    /// Aircraft has ADS-B UAT Receive Capability
+    #[deku(bits = "1")]
+    pub uat_in: bool,
+
+    #[deku(bits = "5")]
+    pub reserved2: u8,
 }
 
 impl fmt::Display for CapabilityClassAirborne {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.acas == 1 {
+        if self.acas {
             write!(f, " ACAS")?;
         }
-        if self.cdti == 1 {
-            write!(f, " CDTI")?;
+        if self.es1090 {
+            write!(f, " 1090IN")?;
         }
-        if self.arv == 1 {
+        if self.uat_in {
+            write!(f, " UATIN")?;
+        }
+        if self.arv {
             write!(f, " ARV")?;
         }
-        if self.ts == 1 {
+        if self.ts {
             write!(f, " TS")?;
         }
-        if self.tc == 1 {
-            write!(f, " TC")?;
+        match self.tc {
+            TrajectoryChangeCapability::NoCapability => (),
+            tc => write!(f, " {tc}")?,
         }
         Ok(())
     }
 }
 
+/// Trajectory Change report capability of [`CapabilityClassAirborne`]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+#[deku(type = "u8", bits = "2")]
+pub enum TrajectoryChangeCapability {
+    /// No capability for sending Trajectory Change Reports
+    #[deku(id = "0")]
+    NoCapability,
+    /// Capability of sending information for TC+0 report only
+    #[deku(id = "1")]
+    Single,
+    /// Capability of sending information for multiple TC reports
+    #[deku(id = "2")]
+    Multiple,
+    #[deku(id = "3")]
+    Reserved,
+}
+
+impl fmt::Display for TrajectoryChangeCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoCapability => write!(f, "no TC"),
+            Self::Single => write!(f, "TC+0"),
+            Self::Multiple => write!(f, "TC+n"),
+            Self::Reserved => write!(f, "TC=reserved"),
+        }
+    }
+}
+
 /// [`ME::AircraftOperationStatus`] && [`OperationStatus`] == 1
 ///
 /// Version 2 support only
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 pub struct OperationStatusSurface {
     /// CC (14 bits)
     pub capability_class: CapabilityClassSurface,
@@ -496,10 +776,7 @@ pub struct OperationStatusSurface {
     pub operational_mode: OperationalMode,
 
     /// OM last 8 bits (diff for airborne/surface)
-    // TODO: parse:
-    // http://www.anteni.net/adsb/Doc/1090-WP30-18-DRAFT_DO-260B-V42.pdf
-    // 2.2.3.2.7.2.4.7 “GPS Antenna Offset” OM Code Subfield in Aircraft Operational Status Messages
-    pub gps_antenna_offset: u8,
+    pub gps_antenna_offset: GpsAntennaOffset,
 
     pub version_number: ADSBVersion,
 
@@ -513,8 +790,9 @@ pub struct OperationStatusSurface {
     #[deku(bits = "2")]
     pub source_integrity_level: u8,
 
+    /// TRK/HDG: ground track angle is reported in [`SurfacePosition`], instead of the heading
     #[deku(bits = "1")]
-    pub barometric_altitude_integrity: u8,
+    pub track_angle_heading: u8,
 
     #[deku(bits = "1")]
     pub horizontal_reference_direction: u8,
@@ -526,32 +804,33 @@ pub struct OperationStatusSurface {
 
 impl fmt::Display for OperationStatusSurface {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "  Version:            {}", self.version_number)?;
+        writeln!(f, "   Version:            {}", self.version_number)?;
+        write!(f, "   Capability classes:{}", self.capability_class)?;
+        writeln!(f, " L/W={}", self.lw_codes)?;
+        writeln!(f, "   Operational modes: {}", self.operational_mode)?;
         writeln!(f, "   NIC-A:              {}", self.nic_supplement_a)?;
-        write!(f, "{}", self.capability_class)?;
-        write!(f, "   Capability classes:")?;
-        if self.lw_codes != 0 {
-            writeln!(f, " L/W={}", self.lw_codes)?;
-        } else {
-            writeln!(f)?;
-        }
-        write!(f, "   Operational modes: {}", self.operational_mode)?;
-        writeln!(f)?;
+        writeln!(
+            f,
+            "   NIC-C:              {}",
+            self.capability_class.nic_supplement_c
+        )?;
         writeln!(
             f,
             "   NACp:               {}",
             self.navigational_accuracy_category
         )?;
+        writeln!(f, "   NACv:               {}", self.capability_class.nac_v)?;
         writeln!(
             f,
             "   SIL:                {} (per hour)",
             self.source_integrity_level
         )?;
-        writeln!(
-            f,
-            "   NICbaro:            {}",
-            self.barometric_altitude_integrity
-        )?;
+        writeln!(f, "   GPS antenna offset: {}", self.gps_antenna_offset)?;
+        if self.track_angle_heading == 1 {
+            writeln!(f, "   Track/heading:      track angle")?;
+        } else {
+            writeln!(f, "   Track/heading:      heading")?;
+        }
         if self.horizontal_reference_direction == 1 {
             writeln!(f, "   Heading reference:  magnetic north")?;
         } else {
@@ -562,7 +841,7 @@ impl fmt::Display for OperationStatusSurface {
 }
 
 /// [`ME::AircraftOperationStatus`]
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 pub struct CapabilityClassSurface {
     /// 0, 0 in current version, reserved as id for later versions
     #[deku(bits = "2", assert_eq = "0")]
@@ -596,33 +875,47 @@ pub struct CapabilityClassSurface {
 
 impl fmt::Display for CapabilityClassSurface {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "   NIC-C:              {}", self.nic_supplement_c)?;
-        writeln!(f, "   NACv:               {}", self.nac_v)?;
+        if self.poe == 1 {
+            write!(f, " POA")?;
+        }
+        if self.es1090 == 1 {
+            write!(f, " 1090IN")?;
+        }
+        if self.uat_in == 1 {
+            write!(f, " UATIN")?;
+        }
+        if self.b2_low == 1 {
+            write!(f, " B2LOW")?;
+        }
         Ok(())
     }
 }
 
 /// `OperationMode` field not including the last 8 bits that are different for Surface/Airborne
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 pub struct OperationalMode {
     /// (0, 0) in Version 2, reserved for other values
     #[deku(bits = "2", assert_eq = "0")]
     reserved: u8,
 
+    /// TCAS/ACAS resolution advisory active
     #[deku(bits = "1")]
-    tcas_ra_active: bool,
+    pub tcas_ra_active: bool,
 
+    /// IDENT switch active
     #[deku(bits = "1")]
-    ident_switch_active: bool,
+    pub ident_switch_active: bool,
 
     #[deku(bits = "1")]
-    reserved_recv_atc_service: bool,
+    pub reserved_recv_atc_service: bool,
 
+    /// Aircraft has only a single antenna for transmitting
     #[deku(bits = "1")]
-    single_antenna_flag: bool,
+    pub single_antenna_flag: bool,
 
+    /// SDA: System Design Assurance
     #[deku(bits = "2")]
-    system_design_assurance: u8,
+    pub system_design_assurance: u8,
 }
 
 impl fmt::Display for OperationalMode {
@@ -646,11 +939,74 @@ impl fmt::Display for OperationalMode {
     }
 }
 
+/// [`OperationStatusSurface`] GPS antenna offset, last 8 bits of the `OperationMode` field
+///
+/// reference: DO-260B 2.2.3.2.7.2.4.7
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct GpsAntennaOffset {
+    /// Lateral axis encoding, left or right of the longitudinal axis of the aircraft
+    #[deku(bits = "3")]
+    pub lateral: u8,
+
+    /// Longitudinal axis encoding, aft of the nose of the aircraft
+    #[deku(bits = "5")]
+    pub longitudinal: u8,
+}
+
+impl GpsAntennaOffset {
+    /// Lateral distance in meters of the GPS antenna from the longitudinal axis, negative to the
+    /// left and positive to the right
+    ///
+    /// Returns `None` if no data is available.
+    #[must_use]
+    pub fn lateral_meters(&self) -> Option<i8> {
+        let distance = (self.lateral & 0b011) as i8 * 2;
+        if self.lateral & 0b100 == 0 {
+            (distance != 0).then(|| -distance)
+        } else {
+            Some(distance)
+        }
+    }
+
+    /// Longitudinal distance in meters of the GPS antenna aft of the nose of the aircraft
+    ///
+    /// Returns `None` if no data is available, or the position offset is applied by the sensor,
+    /// see [`GpsAntennaOffset::offset_applied_by_sensor`].
+    #[must_use]
+    pub fn longitudinal_meters(&self) -> Option<u8> {
+        (self.longitudinal > 1).then(|| (self.longitudinal - 1) * 2)
+    }
+
+    /// The GPS position is already offset to the ADS-B position reference point by the sensor
+    #[must_use]
+    pub const fn offset_applied_by_sensor(&self) -> bool {
+        self.longitudinal == 1
+    }
+}
+
+impl fmt::Display for GpsAntennaOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.lateral_meters() {
+            Some(distance) if distance < 0 => write!(f, "lateral {} m left", -distance)?,
+            Some(distance) => write!(f, "lateral {distance} m right")?,
+            None => write!(f, "lateral no data")?,
+        }
+        match self.longitudinal_meters() {
+            Some(distance) => write!(f, ", longitudinal {distance} m"),
+            None if self.offset_applied_by_sensor() => {
+                write!(f, ", longitudinal applied by sensor")
+            },
+            None => write!(f, ", longitudinal no data"),
+        }
+    }
+}
+
 /// ADS-B Defined from different ICAO documents
 ///
 /// reference: ICAO 9871 (5.3.2.3)
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "3")]
+#[non_exhaustive]
 pub enum ADSBVersion {
     #[deku(id = "0")]
     DOC9871AppendixA,
@@ -658,108 +1014,439 @@ pub enum ADSBVersion {
     DOC9871AppendixB,
     #[deku(id = "2")]
     DOC9871AppendixC,
+    #[deku(id_pat = "_")]
+    Unknown(#[deku(bits = "3")] u8),
 }
 
 impl fmt::Display for ADSBVersion {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.deku_id().unwrap())
+        let version = match self {
+            Self::DOC9871AppendixA => 0,
+            Self::DOC9871AppendixB => 1,
+            Self::DOC9871AppendixC => 2,
+            Self::Unknown(version) => *version,
+        };
+        write!(f, "{version}")
     }
 }
 
 /// Control Field (B.3) for [`crate::DF::TisB`]
 ///
 /// reference: ICAO 9871
-#[derive(Debug, PartialEq, DekuRead, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct ControlField {
-    t: ControlFieldType,
+    /// CF: Type of the message and the address in `aa`
+    pub t: ControlFieldType,
     /// AA: Address, Announced
+    ///
+    /// Management messages don't carry an address, see [`ControlField::management_message`]
     pub aa: ICAO,
     /// ME: message, extended quitter
-    pub me: ME,
+    #[deku(ctx = "*t")]
+    pub me: ControlFieldMessage,
+}
+
+impl ControlField {
+    /// All 80 bits after the CF field of a [`ControlFieldType::TISB_MANAGE`] message, including
+    /// the bits read as `aa`
+    #[must_use]
+    pub fn management_message(&self) -> Option<[u8; 10]> {
+        match &self.me {
+            ControlFieldMessage::Management(data) => {
+                let mut message = [0; 10];
+                message[..3].copy_from_slice(&self.aa.0);
+                message[3..].copy_from_slice(data);
+                Some(message)
+            },
+            _ => None,
+        }
+    }
+
+    /// ME field of the messages using the layout of [`crate::DF::ADSB`], including relayed
+    /// messages of [`ControlFieldType::TISB_ADSB_RELAY`]
+    #[must_use]
+    pub const fn me(&self) -> Option<&ME> {
+        match &self.me {
+            ControlFieldMessage::ME(me) | ControlFieldMessage::Relay(me) => Some(me),
+            _ => None,
+        }
+    }
+
+    /// Kind of address in `aa`, from the CF field and the IMF bit of the message if any
+    #[must_use]
+    pub const fn address_type(&self) -> AddressType {
+        match self.address_qualifier() {
+            Some(AddressQualifier::Icao) => AddressType::Icao,
+            Some(_) => AddressType::NonIcao,
+            None => AddressType::None,
+        }
+    }
+
+    /// Address space of `aa`, from the CF field and the IMF bit of the message if any
+    ///
+    /// Returns `None` for [`ControlFieldType::TISB_MANAGE`], without an address. Only
+    /// [`AddressQualifier::Icao`] addresses can be tracked along with DF17 frames of the same
+    /// address, the others are only unique among the targets of the same qualifier.
+    #[must_use]
+    pub const fn address_qualifier(&self) -> Option<AddressQualifier> {
+        let imf = match &self.me {
+            ControlFieldMessage::Coarse(coarse) => coarse.imf,
+            ControlFieldMessage::ME(
+                ME::AirbornePositionBaroAltitude(altitude)
+                | ME::AirbornePositionGNSSAltitude(altitude),
+            ) => altitude.saf_or_imf == 1,
+            _ => false,
+        };
+        let qualifier = match self.t {
+            ControlFieldType::ADSB_ES_NT => AddressQualifier::Icao,
+            ControlFieldType::ADSB_ES_NT_ALT => AddressQualifier::NonIcao,
+            ControlFieldType::TISB_FINE | ControlFieldType::TISB_COARSE if imf => {
+                AddressQualifier::TrackFile
+            },
+            ControlFieldType::TISB_ADSB if imf => AddressQualifier::NonIcao,
+            ControlFieldType::TISB_FINE
+            | ControlFieldType::TISB_COARSE
+            | ControlFieldType::TISB_ADSB => AddressQualifier::Icao,
+            ControlFieldType::TISB_MANAGE => return None,
+            ControlFieldType::TISB_ADSB_RELAY => AddressQualifier::Anonymous,
+            ControlFieldType::Reserved => AddressQualifier::Unknown,
+        };
+        Some(qualifier)
+    }
 }
 
 impl fmt::Display for ControlField {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.me.to_string(
-                self.aa,
-                &format!("{}", self.t),
-                Capability::AG_UNCERTAIN3,
-                false,
-            )?
-        )
+        match &self.me {
+            ControlFieldMessage::ME(me) | ControlFieldMessage::Relay(me) => write!(
+                f,
+                "{}",
+                me.to_string(
+                    self.aa,
+                    &format!("{}", self.t),
+                    Capability::AG_UNCERTAIN3,
+                    false,
+                )?
+            ),
+            ControlFieldMessage::Management(_) => {
+                writeln!(
+                    f,
+                    " Extended Squitter (Non-Transponder) TIS-B/ADS-R management"
+                )?;
+                write!(f, "  Data:          ")?;
+                for byte in self.management_message().unwrap_or_default() {
+                    write!(f, "{byte:02x}")?;
+                }
+                writeln!(f)
+            },
+            ControlFieldMessage::Coarse(coarse) => {
+                writeln!(
+                    f,
+                    " Extended Squitter (Non-Transponder) TIS-B coarse airborne position"
+                )?;
+                writeln!(f, "  Address:       {} {}", self.aa, self.t)?;
+                writeln!(f, "  Air/Ground:    {}", Capability::AG_UNCERTAIN3)?;
+                write!(f, "{coarse}")
+            },
+            ControlFieldMessage::Reserved(data) => {
+                writeln!(f, " Extended Squitter (Non-Transponder) reserved")?;
+                writeln!(f, "  Address:       {} {}", self.aa, self.t)?;
+                write!(f, "  Data:          ")?;
+                for byte in data {
+                    write!(f, "{byte:02x}")?;
+                }
+                writeln!(f)
+            },
+        }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Clone)]
-#[deku(type = "u8", bits = "3")]
-#[allow(non_camel_case_types)]
-pub enum ControlFieldType {
-    /// ADS-B Message from a non-transponder device
-    #[deku(id = "0")]
-    ADSB_ES_NT,
+/// ME field of [`ControlField`], selected by [`ControlField::t`]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
+#[deku(ctx = "t: ControlFieldType", id = "t")]
+pub enum ControlFieldMessage {
+    /// Code 3: TIS-B coarse airborne position
+    #[deku(id = "ControlFieldType::TISB_COARSE")]
+    Coarse(TisBCoarsePosition),
 
-    /// Reserved for ADS-B for ES/NT devices for alternate address space
-    #[deku(id = "1")]
-    ADSB_ES_NT_ALT,
+    /// Code 4: TIS-B/ADS-R service management, the last 56 bits of
+    /// [`ControlField::management_message`]
+    #[deku(id = "ControlFieldType::TISB_MANAGE")]
+    Management([u8; 7]),
 
-    /// Code 2, Fine Format TIS-B Message
-    #[deku(id = "2")]
-    TISB_FINE,
+    /// Code 5: ADS-B message relayed by TIS-B, with a non-ICAO address
+    #[deku(id = "ControlFieldType::TISB_ADSB_RELAY")]
+    Relay(ME),
 
-    /// Code 3, Coarse Format TIS-B Message
-    #[deku(id = "3")]
-    TISB_COARSE,
+    /// Code 7: Reserved, kept undecoded
+    #[deku(id = "ControlFieldType::Reserved")]
+    Reserved([u8; 7]),
 
-    /// Code 4, Coarse Format TIS-B Message
-    #[deku(id = "4")]
-    TISB_MANAGE,
+    /// All other codes use the ME field of [`crate::DF::ADSB`]
+    #[deku(id_pat = "_")]
+    ME(ME),
+}
 
-    /// Code 5, TIS-B Message for replay ADS-B Message
-    ///
-    /// Anonymous 24-bit addresses
-    #[deku(id = "5")]
-    TISB_ADSB_RELAY,
+/// Kind of address in [`ControlField::aa`], see [`ControlField::address_type`]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[non_exhaustive]
+pub enum AddressType {
+    /// 24-bit ICAO aircraft address
+    Icao,
+    /// Anonymous, surface vehicle or TIS-B track file address
+    NonIcao,
+    /// No address, such as [`ControlFieldType::TISB_MANAGE`]
+    None,
+}
 
-    /// Code 6, TIS-B Message, Same as DF=17
-    #[deku(id = "6")]
-    TISB_ADSB,
+impl fmt::Display for AddressType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Icao => "ICAO",
+            Self::NonIcao => "non-ICAO",
+            Self::None => "none",
+        };
+        write!(f, "{s}")
+    }
+}
 
-    /// Code 7, Reserved
-    #[deku(id = "7")]
-    Reserved,
+/// Address space of [`ControlField::aa`], see [`ControlField::address_qualifier`]
+///
+/// reference: ICAO 9871, CF field and IMF bit
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[non_exhaustive]
+pub enum AddressQualifier {
+    /// 24-bit ICAO aircraft address
+    Icao,
+    /// Address of an ADS-B target without an ICAO address, such as an anonymous aircraft, a
+    /// surface vehicle or a fixed obstruction, sent by the target (CF 1) or rebroadcast by ADS-R
+    /// with the IMF bit set (CF 6)
+    NonIcao,
+    /// Track file number assigned by the TIS-B ground station, with the IMF bit set (CF 2 and 3)
+    TrackFile,
+    /// Anonymous address of a relayed ADS-B message (CF 5)
+    Anonymous,
+    /// Reserved CF 7
+    Unknown,
 }
 
-impl fmt::Display for ControlFieldType {
+impl AddressQualifier {
+    /// The address is a 24-bit ICAO aircraft address
+    #[must_use]
+    pub const fn is_icao(&self) -> bool {
+        matches!(self, Self::Icao)
+    }
+}
+
+impl fmt::Display for AddressQualifier {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s_type = match self {
-            Self::ADSB_ES_NT | Self::ADSB_ES_NT_ALT => "(ADS-B)",
-            Self::TISB_COARSE | Self::TISB_ADSB_RELAY | Self::TISB_FINE => "(TIS-B)",
-            Self::TISB_MANAGE | Self::TISB_ADSB => "(ADS-R)",
-            Self::Reserved => "(unknown addressing scheme)",
+        let s = match self {
+            Self::Icao => "ICAO",
+            Self::NonIcao => "non-ICAO",
+            Self::TrackFile => "TIS-B track file",
+            Self::Anonymous => "anonymous",
+            Self::Unknown => "unknown",
         };
-        write!(f, "{s_type}")
+        write!(f, "{s}")
     }
 }
 
-/// Table: A-2-97
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
-pub struct AircraftStatus {
-    pub sub_type: AircraftStatusType,
-    pub emergency_state: EmergencyState,
+/// [`ControlFieldType::TISB_COARSE`] airborne position and velocity
+///
+/// Sent for TIS-B targets tracked by a ground radar with a lower resolution than the fine format,
+/// such as [`ME::AirbornePositionBaroAltitude`] and [`ME::AirborneVelocity`] in one message.
+///
+/// reference: DO-260B, TIS-B Coarse Airborne Position Message
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct TisBCoarsePosition {
+    /// IMF: [`ControlField::aa`] is not an ICAO address, but a TIS-B track file number
+    #[deku(bits = "1")]
+    pub imf: bool,
+
+    pub ss: SurveillanceStatus,
+
+    /// SVID: Service Volume ID of the TIS-B ground station
+    #[deku(bits = "4")]
+    pub svid: u8,
+
+    /// Barometric altitude in ft, encoded as in [`Altitude`]
     #[deku(
-        bits = "13",
-        endian = "big",
-        map = "|squawk: u32| -> Result<_, DekuError> {Ok(decode_id13_field(squawk))}"
+        reader = "Altitude::read(deku::rest)",
+        writer = "Altitude::write(deku::output, *alt)"
     )]
-    pub squawk: u32,
-}
+    pub alt: Option<u16>,
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
-#[deku(type = "u8", bits = "3")]
-pub enum AircraftStatusType {
+    /// Ground track status: `trk` is valid
+    #[deku(bits = "1")]
+    pub trk_valid: bool,
+
+    /// Ground track angle, in units of 360/32 degrees
+    #[deku(bits = "5")]
+    pub trk: u8,
+
+    /// Ground speed, in units of 16 kt
+    #[deku(bits = "6")]
+    pub gs: u8,
+
+    /// Odd or even
+    pub odd_flag: CPRFormat,
+
+    /// 12 bit CPR latitude
+    #[deku(bits = "12", endian = "big")]
+    pub lat_cpr: u32,
+
+    /// 12 bit CPR longitude
+    #[deku(bits = "12", endian = "big")]
+    pub lon_cpr: u32,
+}
+
+impl TisBCoarsePosition {
+    /// Ground track in degrees clockwise from true north, if valid
+    #[must_use]
+    pub fn ground_track(&self) -> Option<f32> {
+        self.trk_valid.then(|| f32::from(self.trk) * 360.0 / 32.0)
+    }
+
+    /// Ground speed in kt
+    #[must_use]
+    pub fn ground_speed(&self) -> u16 {
+        u16::from(self.gs) * 16
+    }
+}
+
+impl fmt::Display for TisBCoarsePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Service volume: {}", self.svid)?;
+        match self.alt {
+            Some(altitude) => writeln!(f, "  Altitude:      {altitude} ft barometric")?,
+            None => writeln!(f, "  Altitude:      None")?,
+        }
+        if let Some(track) = self.ground_track() {
+            writeln!(f, "  Ground track:  {track:.1}")?;
+        }
+        writeln!(f, "  Ground speed:  {} kt", self.ground_speed())?;
+        writeln!(f, "  CPR type:      Airborne (coarse)")?;
+        writeln!(f, "  CPR odd flag:  {}", self.odd_flag)?;
+        writeln!(f, "  CPR latitude:  ({})", self.lat_cpr)?;
+        writeln!(f, "  CPR longitude: ({})", self.lon_cpr)?;
+        Ok(())
+    }
+}
+
+/// CF: Control Field type (Table B-3-1)
+///
+/// reference: ICAO 9871
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+#[deku(type = "u8", bits = "3")]
+#[allow(non_camel_case_types)]
+#[non_exhaustive]
+pub enum ControlFieldType {
+    /// ADS-B Message from a non-transponder device
+    #[deku(id = "0")]
+    ADSB_ES_NT,
+
+    /// Reserved for ADS-B for ES/NT devices for alternate address space
+    #[deku(id = "1")]
+    ADSB_ES_NT_ALT,
+
+    /// Code 2, Fine Format TIS-B Message
+    #[deku(id = "2")]
+    TISB_FINE,
+
+    /// Code 3, Coarse Format TIS-B Message
+    #[deku(id = "3")]
+    TISB_COARSE,
+
+    /// Code 4, TIS-B and ADS-R Management Message, without an address
+    #[deku(id = "4")]
+    TISB_MANAGE,
+
+    /// Code 5, TIS-B Message for replay ADS-B Message
+    ///
+    /// Anonymous 24-bit addresses, see [`ControlFieldMessage::Relay`]
+    #[deku(id = "5")]
+    TISB_ADSB_RELAY,
+
+    /// Code 6, TIS-B Message, Same as DF=17
+    #[deku(id = "6")]
+    TISB_ADSB,
+
+    /// Code 7, Reserved
+    #[deku(id = "7")]
+    Reserved,
+}
+
+impl fmt::Display for ControlFieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s_type = match self {
+            Self::ADSB_ES_NT | Self::ADSB_ES_NT_ALT => "(ADS-B)",
+            Self::TISB_COARSE | Self::TISB_ADSB_RELAY | Self::TISB_FINE => "(TIS-B)",
+            Self::TISB_MANAGE => "(TIS-B/ADS-R management)",
+            Self::TISB_ADSB => "(ADS-R)",
+            Self::Reserved => "(unknown addressing scheme)",
+        };
+        write!(f, "{s_type}")
+    }
+}
+
+/// [`ME::TestMessage`], Type Code 23 reserved for test messages and national use
+///
+/// reference: DO-260B (2.2.3.2.7.7)
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+#[deku(type = "u8", bits = "3")]
+#[non_exhaustive]
+pub enum TestMessage {
+    /// Subtype 7: Mode A code of the aircraft
+    #[deku(id = "7")]
+    Squawk {
+        squawk: Squawk,
+        /// Reserved
+        #[deku(bits = "35", endian = "big")]
+        reserved: u64,
+    },
+    /// Subtypes without an assigned format, such as 0 for test messages without data
+    #[deku(id_pat = "_")]
+    Unknown {
+        #[deku(bits = "3")]
+        subtype: u8,
+        data: [u8; 6],
+    },
+}
+
+/// Table: A-2-97
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct AircraftStatus {
+    pub sub_type: AircraftStatusType,
+    #[deku(ctx = "*sub_type")]
+    pub status: AircraftStatusData,
+}
+
+/// Layout of [`AircraftStatus`], selected by [`AircraftStatus::sub_type`]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+#[deku(ctx = "sub_type: AircraftStatusType", id = "sub_type")]
+#[non_exhaustive]
+pub enum AircraftStatusData {
+    /// Subtype 1: Emergency/Priority Status and Mode A Code
+    #[deku(id = "AircraftStatusType::EmergencyPriorityStatus")]
+    EmergencyPriorityStatus {
+        emergency_state: EmergencyState,
+        #[deku(pad_bits_after = "32")] // reserved
+        squawk: Squawk,
+    },
+
+    /// Subtype 2: TCAS/ACAS Resolution Advisory, same layout as [`crate::bds::BDS`] 3,0
+    #[deku(id = "AircraftStatusType::ACASRaBroadcast")]
+    ACASRaBroadcast(ResolutionAdvisory),
+
+    /// No information or reserved subtype
+    #[deku(id_pat = "_")]
+    Unknown([u8; 6]),
+}
+
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+#[deku(type = "u8", bits = "3")]
+#[non_exhaustive]
+pub enum AircraftStatusType {
     #[deku(id = "0")]
     NoInformation,
     #[deku(id = "1")]
@@ -767,11 +1454,12 @@ pub enum AircraftStatusType {
     #[deku(id = "2")]
     ACASRaBroadcast,
     #[deku(id_pat = "_")]
-    Reserved,
+    Reserved(#[deku(bits = "3")] u8),
 }
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "3")]
+#[non_exhaustive]
 pub enum EmergencyState {
     None                 = 0,
     General              = 1,
@@ -800,7 +1488,7 @@ impl fmt::Display for EmergencyState {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 pub struct OperationCodeSurface {
     #[deku(bits = "1")]
     pub poe: u8,
@@ -813,16 +1501,27 @@ pub struct OperationCodeSurface {
     pub lw: u8,
 }
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone)]
 pub struct Identification {
     pub tc: TypeCoding,
     #[deku(bits = "3")]
     pub ca: u8,
-    #[deku(reader = "aircraft_identification_read(deku::rest)")]
+    #[deku(
+        reader = "aircraft_identification_read(deku::rest)",
+        writer = "aircraft_identification_write(deku::output, cn)"
+    )]
     pub cn: String,
 }
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+impl Identification {
+    /// Emitter category from the type code and `ca`
+    #[must_use]
+    pub const fn emitter_category(&self) -> EmitterCategory {
+        EmitterCategory::new(self.tc, self.ca)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "5")]
 pub enum TypeCoding {
     D = 1,
@@ -846,33 +1545,162 @@ impl fmt::Display for TypeCoding {
     }
 }
 
-/// Target State and Status (§2.2.3.2.7.1)
-#[derive(Copy, Clone, Debug, PartialEq, DekuRead)]
+/// [`Identification`] emitter category, from the [`TypeCoding`] set and `ca`
+///
+/// reference: DO-260B 2.2.3.2.5.2, ICAO 9871 (A.2.3.4)
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+#[non_exhaustive]
+pub enum EmitterCategory {
+    /// A0, B0, C0: No emitter category information
+    NoInformation,
+    /// A1: Light (< 15500 lbs)
+    Light,
+    /// A2: Small (15500 to 75000 lbs)
+    Small,
+    /// A3: Large (75000 to 300000 lbs)
+    Large,
+    /// A4: High vortex large, such as the B-757
+    HighVortexLarge,
+    /// A5: Heavy (> 300000 lbs)
+    Heavy,
+    /// A6: High performance (> 5g acceleration and > 400 kts)
+    HighPerformance,
+    /// A7: Rotorcraft
+    Rotorcraft,
+    /// B1: Glider or sailplane
+    Glider,
+    /// B2: Lighter than air
+    LighterThanAir,
+    /// B3: Parachutist or skydiver
+    Parachutist,
+    /// B4: Ultralight, hang glider or paraglider
+    Ultralight,
+    /// B6: Unmanned aerial vehicle
+    UnmannedAerialVehicle,
+    /// B7: Space or trans-atmospheric vehicle
+    SpaceVehicle,
+    /// C1: Surface vehicle, emergency vehicle
+    SurfaceEmergencyVehicle,
+    /// C2: Surface vehicle, service vehicle
+    SurfaceServiceVehicle,
+    /// C3: Point obstacle, including tethered balloons
+    PointObstacle,
+    /// C4: Cluster obstacle
+    ClusterObstacle,
+    /// C5: Line obstacle
+    LineObstacle,
+    /// B5, C6, C7 and all of set D
+    Reserved,
+}
+
+impl EmitterCategory {
+    /// Emitter category of `ca` in the set `tc`
+    #[must_use]
+    pub const fn new(tc: TypeCoding, ca: u8) -> Self {
+        match (tc, ca) {
+            (TypeCoding::A | TypeCoding::B | TypeCoding::C, 0) => Self::NoInformation,
+            (TypeCoding::A, 1) => Self::Light,
+            (TypeCoding::A, 2) => Self::Small,
+            (TypeCoding::A, 3) => Self::Large,
+            (TypeCoding::A, 4) => Self::HighVortexLarge,
+            (TypeCoding::A, 5) => Self::Heavy,
+            (TypeCoding::A, 6) => Self::HighPerformance,
+            (TypeCoding::A, 7) => Self::Rotorcraft,
+            (TypeCoding::B, 1) => Self::Glider,
+            (TypeCoding::B, 2) => Self::LighterThanAir,
+            (TypeCoding::B, 3) => Self::Parachutist,
+            (TypeCoding::B, 4) => Self::Ultralight,
+            (TypeCoding::B, 6) => Self::UnmannedAerialVehicle,
+            (TypeCoding::B, 7) => Self::SpaceVehicle,
+            (TypeCoding::C, 1) => Self::SurfaceEmergencyVehicle,
+            (TypeCoding::C, 2) => Self::SurfaceServiceVehicle,
+            (TypeCoding::C, 3) => Self::PointObstacle,
+            (TypeCoding::C, 4) => Self::ClusterObstacle,
+            (TypeCoding::C, 5) => Self::LineObstacle,
+            _ => Self::Reserved,
+        }
+    }
+}
+
+impl fmt::Display for EmitterCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::NoInformation => "no information",
+            Self::Light => "light",
+            Self::Small => "small",
+            Self::Large => "large",
+            Self::HighVortexLarge => "high vortex large",
+            Self::Heavy => "heavy",
+            Self::HighPerformance => "high performance",
+            Self::Rotorcraft => "rotorcraft",
+            Self::Glider => "glider",
+            Self::LighterThanAir => "lighter than air",
+            Self::Parachutist => "parachutist",
+            Self::Ultralight => "ultralight",
+            Self::UnmannedAerialVehicle => "unmanned aerial vehicle",
+            Self::SpaceVehicle => "space vehicle",
+            Self::SurfaceEmergencyVehicle => "surface emergency vehicle",
+            Self::SurfaceServiceVehicle => "surface service vehicle",
+            Self::PointObstacle => "point obstacle",
+            Self::ClusterObstacle => "cluster obstacle",
+            Self::LineObstacle => "line obstacle",
+            Self::Reserved => "reserved",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Target State and Status, selected by the 2 bit subtype
+#[derive(Copy, Clone, Debug, PartialEq, DekuRead, DekuWrite)]
+#[deku(type = "u8", bits = "2")]
+#[non_exhaustive]
+pub enum TargetStateAndStatus {
+    /// Subtype 0: ADS-B Version 1 (DO-260A)
+    #[deku(id_pat = "0")]
+    V1(TargetStateAndStatusV1),
+    /// Subtype 1: ADS-B Version 2 (DO-260B)
+    #[deku(id_pat = "1")]
+    V2(TargetStateAndStatusInformation),
+    #[deku(id_pat = "_")]
+    Reserved {
+        #[deku(bits = "2")]
+        subtype: u8,
+        #[deku(bits = "49", endian = "big")]
+        data: u64,
+    },
+}
+
+/// Target State and Status, ADS-B Version 2 (§2.2.3.2.7.1)
+#[derive(Copy, Clone, Debug, PartialEq, DekuRead, DekuWrite)]
 pub struct TargetStateAndStatusInformation {
-    // TODO Support Target State and Status defined in DO-260A, ADS-B Version=1
-    // TODO Support reserved 2..=3
     #[deku(bits = "2")]
     pub subtype: u8,
+    /// Selected altitude is from the FMS (Flight Management System), instead of the MCP/FCU
+    /// (Mode Control Panel/Flight Control Unit)
     #[deku(bits = "1")]
     pub is_fms: bool,
     #[deku(
         bits = "12",
         endian = "big",
-        map = "|altitude: u32| -> Result<_, DekuError> {Ok(if altitude > 1 {(altitude - 1) * 32} else {0} )}"
+        map = "|altitude: u32| -> Result<_, DekuError> {Ok(if altitude > 1 {(altitude - 1) * 32} else {0} )}",
+        writer = "(if *altitude > 0 { altitude / 32 + 1 } else { 0 }).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(12)))"
     )]
     pub altitude: u32,
     #[deku(
         bits = "9",
         endian = "big",
-        map = "|qnh: u32| -> Result<_, DekuError> {if qnh == 0 { Ok(0.0) } else { Ok(800.0 + ((qnh - 1) as f32) * 0.8)}}"
+        map = "|qnh: u32| -> Result<_, DekuError> {if qnh == 0 { Ok(0.0) } else { Ok(800.0 + ((qnh - 1) as f32) * 0.8)}}",
+        writer = "(if *qnh > 0.0 { libm::roundf((qnh - 800.0) / 0.8) as u32 + 1 } else { 0 }).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(9)))"
     )]
     pub qnh: f32,
+    /// `heading` contains a valid selected heading
     #[deku(bits = "1")]
     pub is_heading: bool,
     #[deku(
         bits = "9",
         endian = "big",
-        map = "|heading: u16| -> Result<_, DekuError> {Ok(heading as f32 * 180.0 / 256.0)}"
+        map = "|heading: u16| -> Result<_, DekuError> {Ok(heading as f32 * 180.0 / 256.0)}",
+        writer = "(libm::roundf(heading * 256.0 / 180.0) as u16).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(9)))"
     )]
     pub heading: f32,
     #[deku(bits = "4")]
@@ -900,12 +1728,269 @@ pub struct TargetStateAndStatusInformation {
     pub lnav: bool,
 }
 
+impl TargetStateAndStatusInformation {
+    /// Source of the selected `altitude`
+    #[must_use]
+    pub fn altitude_source(&self) -> SelectedAltitudeSource {
+        if self.is_fms {
+            SelectedAltitudeSource::Fms
+        } else {
+            SelectedAltitudeSource::Mcp
+        }
+    }
+
+    /// Selected heading in degrees, if valid
+    #[must_use]
+    pub fn selected_heading(&self) -> Option<f32> {
+        if self.is_heading {
+            Some(self.heading)
+        } else {
+            None
+        }
+    }
+}
+
+/// Target State and Status, ADS-B Version 1 (DO-260A §2.2.3.2.7.1)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead, DekuWrite)]
+pub struct TargetStateAndStatusV1 {
+    #[deku(bits = "2")]
+    pub subtype: u8,
+    pub vertical_source: VerticalDataSource,
+    pub altitude_type: TargetAltitudeType,
+    /// Always cleared, set by a later version of the message
+    #[deku(bits = "1")]
+    pub backward_compatibility: bool,
+    pub altitude_capability: TargetAltitudeCapability,
+    pub vertical_mode: TargetMode,
+    /// See [`Self::target_altitude`]
+    #[deku(bits = "10", endian = "big")]
+    pub altitude: u16,
+    pub horizontal_source: HorizontalDataSource,
+    /// See [`Self::target_heading`]
+    #[deku(bits = "9", endian = "big")]
+    pub heading: u16,
+    /// `heading` is a track angle, instead of a heading
+    #[deku(bits = "1")]
+    pub is_track: bool,
+    pub horizontal_mode: TargetMode,
+    #[deku(bits = "4")]
+    pub nacp: u8,
+    #[deku(bits = "1")]
+    pub nicbaro: u8,
+    #[deku(bits = "2")]
+    #[deku(pad_bits_after = "5")] // reserved
+    pub sil: u8,
+    #[deku(bits = "1")]
+    pub tcas_not_operational: bool,
+    /// TCAS/ACAS resolution advisory active
+    #[deku(bits = "1")]
+    pub tcas_ra: bool,
+    pub emergency_state: EmergencyState,
+}
+
+impl TargetStateAndStatusV1 {
+    /// Target altitude in feet, if available
+    #[must_use]
+    pub fn target_altitude(&self) -> Option<i32> {
+        if self.vertical_source == VerticalDataSource::NotAvailable || self.altitude > 1010 {
+            None
+        } else {
+            Some(i32::from(self.altitude) * 100 - 1000)
+        }
+    }
+
+    /// Target heading or track angle in degrees, if available
+    #[must_use]
+    pub fn target_heading(&self) -> Option<u16> {
+        if self.horizontal_source == HorizontalDataSource::NotAvailable || self.heading > 359 {
+            None
+        } else {
+            Some(self.heading)
+        }
+    }
+}
+
+impl fmt::Display for TargetStateAndStatusV1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(altitude) = self.target_altitude() {
+            writeln!(
+                f,
+                "    Target altitude:   {}, {altitude} ft {}",
+                self.vertical_source, self.altitude_type
+            )?;
+            writeln!(f, "    Vertical mode:     {}", self.vertical_mode)?;
+        } else {
+            writeln!(f, "    Target altitude:   not available")?;
+        }
+        if let Some(heading) = self.target_heading() {
+            if self.is_track {
+                writeln!(
+                    f,
+                    "    Target track:      {}, {heading}",
+                    self.horizontal_source
+                )?;
+            } else {
+                writeln!(
+                    f,
+                    "    Target heading:    {}, {heading}",
+                    self.horizontal_source
+                )?;
+            }
+            writeln!(f, "    Horizontal mode:   {}", self.horizontal_mode)?;
+        } else {
+            writeln!(f, "    Target heading:    not available")?;
+        }
+        if self.tcas_not_operational {
+            writeln!(f, "    ACAS:              NOT operational")?;
+        } else if self.tcas_ra {
+            writeln!(f, "    ACAS:              operational, RA active")?;
+        } else {
+            writeln!(f, "    ACAS:              operational")?;
+        }
+        writeln!(f, "    NACp:              {}", self.nacp)?;
+        writeln!(f, "    NICbaro:           {}", self.nicbaro)?;
+        writeln!(f, "    SIL:               {}", self.sil)?;
+        writeln!(f, "    Emergency/priority: {}", self.emergency_state)
+    }
+}
+
+/// Vertical Data Available / Source Indicator of [`TargetStateAndStatusV1`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead, DekuWrite)]
+#[deku(type = "u8", bits = "2")]
+pub enum VerticalDataSource {
+    NotAvailable    = 0,
+    /// Mode Control Panel/Flight Control Unit
+    Mcp             = 1,
+    HoldingAltitude = 2,
+    /// Flight Management System/RNAV
+    Fms             = 3,
+}
+
+impl fmt::Display for VerticalDataSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAvailable => write!(f, "not available"),
+            Self::Mcp => write!(f, "MCP"),
+            Self::HoldingAltitude => write!(f, "holding altitude"),
+            Self::Fms => write!(f, "FMS"),
+        }
+    }
+}
+
+/// Horizontal Data Available / Source Indicator of [`TargetStateAndStatusV1`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead, DekuWrite)]
+#[deku(type = "u8", bits = "2")]
+pub enum HorizontalDataSource {
+    NotAvailable   = 0,
+    /// Mode Control Panel/Flight Control Unit
+    Mcp            = 1,
+    /// Maintaining the current heading or track angle
+    HoldingHeading = 2,
+    /// Flight Management System/RNAV
+    Fms            = 3,
+}
+
+impl fmt::Display for HorizontalDataSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAvailable => write!(f, "not available"),
+            Self::Mcp => write!(f, "MCP"),
+            Self::HoldingHeading => write!(f, "holding heading"),
+            Self::Fms => write!(f, "FMS"),
+        }
+    }
+}
+
+/// Target Altitude Type of [`TargetStateAndStatusV1`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead, DekuWrite)]
+#[deku(type = "u8", bits = "1")]
+pub enum TargetAltitudeType {
+    /// Referenced to pressure altitude (flight level)
+    FlightLevel = 0,
+    /// Referenced to barometric corrected altitude (mean sea level)
+    Msl         = 1,
+}
+
+impl fmt::Display for TargetAltitudeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FlightLevel => write!(f, "FL"),
+            Self::Msl => write!(f, "MSL"),
+        }
+    }
+}
+
+/// Target Altitude Capability of [`TargetStateAndStatusV1`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead, DekuWrite)]
+#[deku(type = "u8", bits = "2")]
+pub enum TargetAltitudeCapability {
+    /// Holding altitude only
+    HoldingAltitude = 0,
+    /// Holding altitude or autopilot selected altitude
+    Autopilot       = 1,
+    /// Holding altitude, autopilot selected altitude, or FMS/RNAV level-off altitude
+    Fms             = 2,
+    Reserved        = 3,
+}
+
+/// Vertical or Horizontal Mode Indicator of [`TargetStateAndStatusV1`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead, DekuWrite)]
+#[deku(type = "u8", bits = "2")]
+pub enum TargetMode {
+    /// Unknown mode or information unavailable
+    Unknown     = 0,
+    /// Acquiring the target
+    Acquiring   = 1,
+    /// Capturing or maintaining the target
+    Maintaining = 2,
+    Reserved    = 3,
+}
+
+impl fmt::Display for TargetMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "unknown"),
+            Self::Acquiring => write!(f, "acquiring"),
+            Self::Maintaining => write!(f, "capturing or maintaining"),
+            Self::Reserved => write!(f, "reserved"),
+        }
+    }
+}
+
+/// Source of the selected altitude of [`TargetStateAndStatusInformation`]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SelectedAltitudeSource {
+    /// Mode Control Panel/Flight Control Unit
+    Mcp,
+    /// Flight Management System
+    Fms,
+}
+
+impl fmt::Display for SelectedAltitudeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mcp => write!(f, "MCP"),
+            Self::Fms => write!(f, "FMS"),
+        }
+    }
+}
+
 /// [`ME::AirborneVelocity`]
-#[derive(Debug, PartialEq, Eq, DekuRead, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone)]
 pub struct AirborneVelocity {
     #[deku(bits = "3")]
     pub st: u8,
-    #[deku(bits = "5")]
+    /// Intent Change Flag, the intent in the target state messages changed in the last 4
+    /// seconds. Reserved since ADS-B version 2
+    #[deku(bits = "1")]
+    pub intent_change: bool,
+    /// IFR Capability Flag, the aircraft is capable of ADS-B applications of Class A1 or above.
+    /// Reserved since ADS-B version 1
+    #[deku(bits = "1")]
+    pub ifr_capability: bool,
+    /// Navigation Accuracy Category for Velocity, or the Navigation Uncertainty Category for
+    /// Velocity of ADS-B version 0, see [`Self::velocity_accuracy`]
+    #[deku(bits = "3")]
     pub nac_v: u8,
     #[deku(ctx = "*st")]
     pub sub_type: AirborneVelocitySubType,
@@ -918,13 +2003,52 @@ pub struct AirborneVelocity {
     pub gnss_sign: Sign,
     #[deku(
         bits = "7",
-        map = "|gnss_baro_diff: u16| -> Result<_, DekuError> {Ok(if gnss_baro_diff > 1 {(gnss_baro_diff - 1)* 25} else { 0 })}"
+        map = "|gnss_baro_diff: u16| -> Result<_, DekuError> {Ok(if gnss_baro_diff > 1 {(gnss_baro_diff - 1)* 25} else { 0 })}",
+        writer = "(if *gnss_baro_diff > 0 { gnss_baro_diff / 25 + 1 } else { 0 }).write(deku::output, deku::ctx::Size::Bits(7))"
     )]
     pub gnss_baro_diff: u16,
 }
 
 impl AirborneVelocity {
+    /// 95% accuracy bound of the horizontal velocity in m/s from `nac_v`, `None` if unknown or
+    /// reserved
+    ///
+    /// The NUCr of version 0 and the NACv of later versions share the same encoding.
+    ///
+    /// reference: DO-260B (Table 2-22)
+    #[must_use]
+    pub const fn velocity_accuracy(&self) -> Option<f32> {
+        match self.nac_v {
+            1 => Some(10.0),
+            2 => Some(3.0),
+            3 => Some(1.0),
+            4 => Some(0.3),
+            _ => None,
+        }
+    }
+
     /// Return effective (`heading`, `ground_speed`, `vertical_rate`) for groundspeed
+    ///
+    /// ```rust
+    /// use adsb_deku::adsb::ME;
+    /// use adsb_deku::deku::DekuContainerRead;
+    /// use adsb_deku::{Frame, DF};
+    /// use hexlit::hex;
+    ///
+    /// let bytes = hex!("8d485020994409940838175b284f");
+    /// let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    /// let velocity = match frame.df {
+    ///     DF::ADSB(adsb) => match adsb.me {
+    ///         ME::AirborneVelocity(velocity) => velocity,
+    ///         _ => unreachable!(),
+    ///     },
+    ///     _ => unreachable!(),
+    /// };
+    /// let (heading, ground_speed, vertical_rate) = velocity.calculate().unwrap();
+    /// assert!((heading - 182.88).abs() < 0.01);
+    /// assert!((ground_speed - 159.20).abs() < 0.01);
+    /// assert_eq!(vertical_rate, -832);
+    /// ```
     #[must_use]
     pub fn calculate(&self) -> Option<(f32, f64, i16)> {
         if let AirborneVelocitySubType::GroundSpeedDecoding(ground_speed) = &self.sub_type {
@@ -948,8 +2072,9 @@ impl AirborneVelocity {
 }
 
 /// Airborne Velocity Message “Subtype” Code Field Encoding
-#[derive(Debug, PartialEq, Eq, DekuRead, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone)]
 #[deku(ctx = "st: u8", id = "st")]
+#[non_exhaustive]
 pub enum AirborneVelocitySubType {
     #[deku(id = "0")]
     Reserved0(#[deku(bits = "22")] u32),
@@ -964,21 +2089,27 @@ pub enum AirborneVelocitySubType {
     Reserved1(#[deku(bits = "22")] u32),
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead, DekuWrite)]
 #[deku(type = "u8", bits = "3")]
 pub enum AirborneVelocityType {
     Subsonic   = 1,
     Supersonic = 3,
 }
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(ctx = "t: AirborneVelocityType")]
 pub struct AirborneVelocitySubFields {
     pub dew: DirectionEW,
-    #[deku(reader = "Self::read_v(deku::rest, t)")]
+    #[deku(
+        reader = "Self::read_v(deku::rest, t)",
+        writer = "Self::write_v(deku::output, *vew, t)"
+    )]
     pub vew: u16,
     pub dns: DirectionNS,
-    #[deku(reader = "Self::read_v(deku::rest, t)")]
+    #[deku(
+        reader = "Self::read_v(deku::rest, t)",
+        writer = "Self::write_v(deku::output, *vns, t)"
+    )]
     pub vns: u16,
 }
 
@@ -998,48 +2129,61 @@ impl AirborneVelocitySubFields {
             },
         }
     }
+
+    fn write_v(
+        output: &mut BitVec<Msb0, u8>,
+        v: u16,
+        t: AirborneVelocityType,
+    ) -> result::Result<(), DekuError> {
+        let value = match t {
+            AirborneVelocityType::Subsonic => v + 1,
+            AirborneVelocityType::Supersonic => v / 4 + 1,
+        };
+        value.write(output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(10)))
+    }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead, DekuWrite)]
 #[deku(type = "u8", bits = "1")]
 pub enum DirectionEW {
     WestToEast = 0,
     EastToWest = 1,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead, DekuWrite)]
 #[deku(type = "u8", bits = "1")]
 pub enum DirectionNS {
     SouthToNorth = 0,
     NorthToSouth = 1,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead, DekuWrite)]
 #[deku(type = "u8", bits = "1")]
 pub enum SourceBitVerticalRate {
     GNSS      = 0,
     Barometer = 1,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead, DekuWrite)]
 #[deku(type = "u8", bits = "1")]
 pub enum SignBitVerticalRate {
     Up   = 0,
     Down = 1,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DekuRead, DekuWrite)]
 #[deku(type = "u8", bits = "1")]
 pub enum SignBitGNSSBaroAltitudesDiff {
     Above = 0,
     Below = 1,
 }
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+/// VrSrc: Source of the vertical rate of [`AirborneVelocity`]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "1")]
 pub enum VerticalRateSource {
-    BarometricPressureAltitude = 0,
-    GeometricAltitude          = 1,
+    GeometricAltitude          = 0,
+    BarometricPressureAltitude = 1,
 }
 
 impl fmt::Display for VerticalRateSource {
@@ -1055,8 +2199,11 @@ impl fmt::Display for VerticalRateSource {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 pub struct SurfacePosition {
+    /// Type Code, 5..=8
+    #[deku(bits = "5")]
+    pub tc: u8,
     #[deku(bits = "7")]
     pub mov: u8,
     pub s: StatusForGroundTrack,
@@ -1071,7 +2218,419 @@ pub struct SurfacePosition {
     pub lon_cpr: u32,
 }
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+impl SurfacePosition {
+    /// Ground speed in knots from the `mov` field, the lower bound of its quantization step
+    ///
+    /// Returns `None` if there is no information or the value is reserved.
+    ///
+    /// reference: ICAO 9871 (Table A-2-6)
+    #[must_use]
+    pub fn ground_speed(&self) -> Option<f32> {
+        self.ground_speed_range().map(|range| range.start)
+    }
+
+    /// Range of ground speeds in knots the `mov` field was quantized from
+    ///
+    /// The lower bound is inclusive and the upper bound exclusive. Stopped aircraft are
+    /// `0.0..0.125`, and the highest code is `175.0..f32::INFINITY`. Returns `None` if there is
+    /// no information or the value is reserved.
+    ///
+    /// reference: ICAO 9871 (Table A-2-6)
+    #[must_use]
+    pub fn ground_speed_range(&self) -> Option<Range<f32>> {
+        // (first code, knots of first code, knots per step)
+        let (first, base, step) = match self.mov {
+            1 => return Some(0.0..0.125),
+            2..=8 => (2, 0.125, 0.125),
+            9..=12 => (9, 1.0, 0.25),
+            13..=38 => (13, 2.0, 0.5),
+            39..=93 => (39, 15.0, 1.0),
+            94..=108 => (94, 70.0, 2.0),
+            109..=123 => (109, 100.0, 5.0),
+            124 => return Some(175.0..f32::INFINITY),
+            _ => return None,
+        };
+        let start = base + f32::from(self.mov - first) * step;
+        Some(start..start + step)
+    }
+
+    /// Ground track in degrees clockwise from true north, if the track is valid
+    #[must_use]
+    pub fn ground_track(&self) -> Option<f32> {
+        match self.s {
+            StatusForGroundTrack::Valid => Some(f32::from(self.trk) * 360.0 / 128.0),
+            StatusForGroundTrack::Invalid => None,
+        }
+    }
+
+    /// Navigation Integrity Category, with NIC supplement-A and NIC supplement-C of the surface
+    /// [`OperationStatusSurface`]
+    #[must_use]
+    pub fn nic(&self, nic_supplement_a: u8, nic_supplement_c: u8) -> Option<Nic> {
+        Nic::surface(self.tc, nic_supplement_a == 1, nic_supplement_c == 1)
+    }
+}
+
+impl fmt::Display for SurfacePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.ground_speed_range() {
+            None if self.mov == 0 => writeln!(f, "  Movement:      not available")?,
+            None => writeln!(f, "  Movement:      reserved ({})", self.mov)?,
+            Some(range) if self.mov == 1 => {
+                writeln!(f, "  Movement:      stopped (< {} kt)", range.end)?
+            },
+            Some(range) if range.end.is_infinite() => {
+                writeln!(f, "  Movement:      >= {} kt", range.start)?;
+            },
+            Some(range) => writeln!(f, "  Movement:      {}-{} kt", range.start, range.end)?,
+        }
+        match self.ground_track() {
+            Some(track) => writeln!(f, "  Ground track:  {track}")?,
+            None => writeln!(f, "  Ground track:  invalid")?,
+        }
+        writeln!(f, "  CPR type:      Surface")?;
+        writeln!(f, "  CPR odd flag:  {}", self.f)?;
+        writeln!(f, "  CPR latitude:  ({})", self.lat_cpr)?;
+        writeln!(f, "  CPR longitude: ({})", self.lon_cpr)?;
+        Ok(())
+    }
+}
+
+/// Navigation Integrity Category of a position message
+///
+/// The type code of a position message only narrows down the NIC, the NIC supplement bits sent
+/// in the position message and the [`ME::AircraftOperationStatus`] of the aircraft select the
+/// value.
+///
+/// reference: RTCA DO-260B (Table 2-14, Table 2-15)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Nic {
+    /// NIC, 0..=11
+    pub nic: u8,
+    /// Horizontal containment radius limit (Rc) in meters, `None` if unknown
+    pub rc: Option<f32>,
+}
+
+impl Nic {
+    const fn new(nic: u8, rc: f32) -> Self {
+        Self { nic, rc: Some(rc) }
+    }
+
+    const UNKNOWN: Self = Self { nic: 0, rc: None };
+
+    /// NIC of an airborne position message with type code `tc`, from NIC supplement-A of the
+    /// [`OperationStatusAirborne`] and NIC supplement-B of the position message
+    ///
+    /// Returns `None` for other type codes and for combinations of the supplements not used with
+    /// `tc`.
+    ///
+    /// ```rust
+    /// use adsb_deku::adsb::Nic;
+    ///
+    /// let nic = Nic::airborne(11, false, false).unwrap();
+    /// assert_eq!(nic.nic, 8);
+    /// assert_eq!(nic.rc, Some(185.2));
+    /// assert_eq!(Nic::airborne(11, true, false), None);
+    /// ```
+    #[must_use]
+    pub fn airborne(tc: u8, nic_supplement_a: bool, nic_supplement_b: bool) -> Option<Self> {
+        let nic = match (tc, nic_supplement_a, nic_supplement_b) {
+            (9 | 20, false, false) => Self::new(11, 7.5),
+            (10 | 21, false, false) => Self::new(10, 25.0),
+            (11, true, true) => Self::new(9, 75.0),
+            (11, false, false) => Self::new(8, 185.2), // 0.1 NM
+            (12, false, false) => Self::new(7, 370.4), // 0.2 NM
+            (13, false, true) => Self::new(6, 555.6),  // 0.3 NM
+            (13, false, false) => Self::new(6, 926.0), // 0.5 NM
+            (13, true, true) => Self::new(6, 1111.2),  // 0.6 NM
+            (14, false, false) => Self::new(5, 1852.0), // 1 NM
+            (15, false, false) => Self::new(4, 3704.0), // 2 NM
+            (16, true, true) => Self::new(3, 7408.0),  // 4 NM
+            (16, false, false) => Self::new(2, 14816.0), // 8 NM
+            (17, false, false) => Self::new(1, 37040.0), // 20 NM
+            (18 | 22, false, false) => Self::UNKNOWN,
+            _ => return None,
+        };
+        Some(nic)
+    }
+
+    /// Coarse grade of the position integrity, from the containment radius
+    #[must_use]
+    pub fn quality(&self) -> PositionQuality {
+        match self.rc {
+            None => PositionQuality::Unknown,
+            Some(rc) if rc <= 370.4 => PositionQuality::High,
+            Some(rc) if rc <= 1852.0 => PositionQuality::Medium,
+            Some(_) => PositionQuality::Low,
+        }
+    }
+
+    /// NIC of a surface position message with type code `tc`, from NIC supplement-A and NIC
+    /// supplement-C of the [`OperationStatusSurface`]
+    ///
+    /// Returns `None` for other type codes and for combinations of the supplements not used with
+    /// `tc`.
+    #[must_use]
+    pub fn surface(tc: u8, nic_supplement_a: bool, nic_supplement_c: bool) -> Option<Self> {
+        let nic = match (tc, nic_supplement_a, nic_supplement_c) {
+            (5, false, false) => Self::new(11, 7.5),
+            (6, false, false) => Self::new(10, 25.0),
+            (7, true, false) => Self::new(9, 75.0),
+            (7, false, false) => Self::new(8, 185.2), // 0.1 NM
+            (8, true, true) => Self::new(7, 370.4),   // 0.2 NM
+            (8, true, false) => Self::new(6, 555.6),  // 0.3 NM
+            (8, false, true) => Self::new(6, 1111.2), // 0.6 NM
+            (8, false, false) => Self::UNKNOWN,
+            _ => return None,
+        };
+        Some(nic)
+    }
+}
+
+/// Coarse grade of the integrity of a position, see [`Nic::quality`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PositionQuality {
+    /// Containment radius is unknown, the position shouldn't be relied on
+    Unknown,
+    /// Containment radius above 1 NM (NIC 0..=4)
+    Low,
+    /// Containment radius of 0.3 NM to 1 NM (NIC 5..=6)
+    Medium,
+    /// Containment radius of at most 0.2 NM (NIC 7..=11), as required for ADS-B Out in the
+    /// airspace of the FAA
+    High,
+}
+
+impl fmt::Display for PositionQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Unknown => "unknown",
+                Self::Low => "low",
+                Self::Medium => "medium",
+                Self::High => "high",
+            }
+        )
+    }
+}
+
+/// Accuracy and integrity fields of an aircraft, interpreted for its [`ADSBVersion`]
+///
+/// Version 0 transponders (DO-260) encode the Navigation Uncertainty Category (NUCp) in the type
+/// code of position messages. Version 1 (DO-260A) and 2 (DO-260B) transponders encode the
+/// Navigation Integrity Category (NIC) there instead, with NIC supplements of different meanings,
+/// and send the NACp and SIL in [`ME::AircraftOperationStatus`]. This maps the raw fields of
+/// every version to the same physical values.
+///
+/// Aircraft are version 0 until their operation status is received, see
+/// [`AccuracyContext::VERSION_0`].
+///
+/// ```rust
+/// use adsb_deku::adsb::{ADSBVersion, AccuracyContext};
+/// use adsb_deku::deku::DekuContainerRead;
+/// use adsb_deku::{Frame, DF};
+/// use hexlit::hex;
+///
+/// // type code 11
+/// let bytes = hex!("8d40621d58c382d690c8ac2863a7");
+/// let me = match Frame::from_bytes((&bytes, 0)).unwrap().1.df {
+///     DF::ADSB(adsb) => adsb.me,
+///     _ => unreachable!(),
+/// };
+///
+/// // NUCp 7
+/// let accuracy = AccuracyContext::VERSION_0.position(&me).unwrap();
+/// assert_eq!(accuracy.containment, Some(185.2));
+/// assert_eq!(accuracy.accuracy, Some(92.6));
+///
+/// // NIC 9 with NIC supplement-A, NACp 10
+/// let context = AccuracyContext {
+///     version: ADSBVersion::DOC9871AppendixB,
+///     nic_supplement_a: true,
+///     nac_p: 10,
+///     ..AccuracyContext::VERSION_0
+/// };
+/// let accuracy = context.position(&me).unwrap();
+/// assert_eq!(accuracy.containment, Some(75.0));
+/// assert_eq!(accuracy.accuracy, Some(10.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccuracyContext {
+    pub version: ADSBVersion,
+    /// NIC supplement-A of the operation status
+    pub nic_supplement_a: bool,
+    /// NIC supplement-C of the surface operation status
+    pub nic_supplement_c: bool,
+    /// NACp of the operation status, 0..=11
+    pub nac_p: u8,
+    /// SIL of the operation status, 0..=3
+    pub sil: u8,
+}
+
+impl AccuracyContext {
+    /// Aircraft without a received operation status
+    pub const VERSION_0: Self = Self {
+        version: ADSBVersion::DOC9871AppendixA,
+        nic_supplement_a: false,
+        nic_supplement_c: false,
+        nac_p: 0,
+        sil: 0,
+    };
+
+    /// Context of the aircraft sending `status`, `None` for reserved subtypes
+    #[must_use]
+    pub const fn new(status: &OperationStatus) -> Option<Self> {
+        match status {
+            OperationStatus::Airborne(status) => Some(Self {
+                version: status.version_number,
+                nic_supplement_a: status.nic_supplement_a == 1,
+                nic_supplement_c: false,
+                nac_p: status.navigational_accuracy_category,
+                sil: status.source_integrity_level,
+            }),
+            OperationStatus::Surface(status) => Some(Self {
+                version: status.version_number,
+                nic_supplement_a: status.nic_supplement_a == 1,
+                nic_supplement_c: status.capability_class.nic_supplement_c == 1,
+                nac_p: status.navigational_accuracy_category,
+                sil: status.source_integrity_level,
+            }),
+            OperationStatus::Reserved(..) => None,
+        }
+    }
+
+    /// Containment radius and accuracy of the position message `me`, `None` for other messages
+    ///
+    /// Versions above 2 are interpreted as version 2.
+    #[must_use]
+    pub fn position(&self, me: &ME) -> Option<PositionAccuracy> {
+        let (tc, nic_supplement_b) = match me {
+            ME::AirbornePositionBaroAltitude(altitude)
+            | ME::AirbornePositionGNSSAltitude(altitude) => (altitude.tc, altitude.saf_or_imf == 1),
+            ME::SurfacePosition(surface) => (surface.tc, false),
+            _ => return None,
+        };
+        let surface = matches!(me, ME::SurfacePosition(_));
+        match self.version {
+            ADSBVersion::DOC9871AppendixA => {
+                let (containment, accuracy) = nuc_p(tc)?;
+                Some(PositionAccuracy {
+                    containment,
+                    accuracy,
+                })
+            },
+            ADSBVersion::DOC9871AppendixB => {
+                let containment = nic_v1(tc, surface, self.nic_supplement_a)?;
+                Some(PositionAccuracy {
+                    containment,
+                    accuracy: nac_p(self.nac_p),
+                })
+            },
+            _ => {
+                let nic = if surface {
+                    Nic::surface(tc, self.nic_supplement_a, self.nic_supplement_c)?
+                } else {
+                    Nic::airborne(tc, self.nic_supplement_a, nic_supplement_b)?
+                };
+                Some(PositionAccuracy {
+                    containment: nic.rc,
+                    accuracy: nac_p(self.nac_p),
+                })
+            },
+        }
+    }
+
+    /// Probability of exceeding the containment radius without an alert, from the SIL
+    ///
+    /// `None` for version 0, without a SIL, and for an unknown SIL.
+    #[must_use]
+    pub const fn integrity_probability(&self) -> Option<f64> {
+        match (self.version, self.sil) {
+            (ADSBVersion::DOC9871AppendixA, _) => None,
+            (_, 1) => Some(1e-3),
+            (_, 2) => Some(1e-5),
+            (_, 3) => Some(1e-7),
+            _ => None,
+        }
+    }
+}
+
+/// Physical accuracy and integrity of a position, see [`AccuracyContext::position`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionAccuracy {
+    /// Horizontal containment radius in meters (HPL for version 0, Rc), `None` if unknown
+    pub containment: Option<f32>,
+    /// 95% bound of the horizontal position error in meters (EPU), `None` if unknown
+    pub accuracy: Option<f32>,
+}
+
+/// HPL and 95% accuracy in meters of the NUCp of a version 0 position with type code `tc`
+///
+/// reference: DO-260 (Table 2-14)
+const fn nuc_p(tc: u8) -> Option<(Option<f32>, Option<f32>)> {
+    let nuc = match tc {
+        5 | 9 | 20 => (7.5, 3.0),
+        6 | 10 | 21 => (25.0, 10.0),
+        7 | 11 => (185.2, 92.6),
+        8 | 12 => (370.4, 185.2),
+        13 => (926.0, 463.0),
+        14 => (1852.0, 926.0),
+        15 => (3704.0, 1852.0),
+        16 => (18520.0, 9260.0),
+        17 => (37040.0, 18520.0),
+        18 | 22 => return Some((None, None)),
+        _ => return None,
+    };
+    Some((Some(nuc.0), Some(nuc.1)))
+}
+
+/// Containment radius in meters of the NIC of a version 1 position with type code `tc`, from the
+/// single NIC supplement of the operation status
+///
+/// reference: DO-260A (Table 2-14)
+const fn nic_v1(tc: u8, surface: bool, nic_supplement: bool) -> Option<Option<f32>> {
+    let rc = match (tc, surface, nic_supplement) {
+        (5, true, _) | (9 | 20, false, _) => 7.5,
+        (6, true, _) | (10 | 21, false, _) => 25.0,
+        (7, true, true) | (11, false, true) => 75.0,
+        (7, true, false) | (11, false, false) => 185.2,
+        (12, false, _) => 370.4,
+        (13, false, true) => 926.0,
+        (13, false, false) => 1111.2,
+        (14, false, _) => 1852.0,
+        (15, false, _) => 3704.0,
+        (16, false, true) => 7408.0,
+        (16, false, false) => 14816.0,
+        (17, false, _) => 37040.0,
+        (8, true, _) | (18 | 22, false, _) => return Some(None),
+        _ => return None,
+    };
+    Some(Some(rc))
+}
+
+/// 95% accuracy in meters of a NACp
+///
+/// reference: DO-260B (Table 2-70)
+const fn nac_p(nac_p: u8) -> Option<f32> {
+    match nac_p {
+        1 => Some(18520.0),
+        2 => Some(7408.0),
+        3 => Some(3704.0),
+        4 => Some(1852.0),
+        5 => Some(926.0),
+        6 => Some(555.6),
+        7 => Some(185.2),
+        8 => Some(92.6),
+        9 => Some(30.0),
+        10 => Some(10.0),
+        11 => Some(3.0),
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "1")]
 pub enum StatusForGroundTrack {
     Invalid = 0,
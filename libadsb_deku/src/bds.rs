@@ -1,19 +1,41 @@
 //! B-Definition Subfield for Comm-B Messages
 
-use alloc::format;
 use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
 #[cfg(feature = "alloc")]
 use core::{
     clone::Clone, cmp::PartialEq, fmt, fmt::Debug, prelude::rust_2021::derive, result::Result,
-    result::Result::Ok, writeln,
+    result::Result::Ok, write, writeln,
 };
 
 use deku::prelude::*;
 
-use crate::aircraft_identification_read;
+use crate::acas::ResolutionAdvisory;
+use crate::{aircraft_identification_read, aircraft_identification_write};
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Clone)]
+/// MB: Message, Comm-B
+///
+/// The 56 bit payload of a Comm-B reply, containing the BDS register requested by the
+/// interrogator. Only `mb` is written back, `bds` is decoded from it.
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone)]
+pub struct CommB {
+    /// Raw MB field
+    pub mb: [u8; 7],
+    /// Register decoded from `mb`
+    #[deku(skip, default = "BDS::from_bytes((mb.as_ref(), 0))?.1")]
+    pub bds: BDS,
+}
+
+impl fmt::Display for CommB {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.bds)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone)]
 #[deku(type = "u8", bits = "8")]
+#[non_exhaustive]
 pub enum BDS {
     /// (1, 0) Table A-2-16
     #[deku(id = "0x00")]
@@ -25,10 +47,14 @@ pub enum BDS {
 
     /// (2, 0) Table A-2-32
     #[deku(id = "0x20")]
-    AircraftIdentification(#[deku(reader = "aircraft_identification_read(deku::rest)")] String),
+    AircraftIdentification(AircraftIdentification),
+
+    /// (3, 0) Table A-2-48
+    #[deku(id = "0x30")]
+    AcasResolutionAdvisory(ResolutionAdvisory),
 
     #[deku(id_pat = "_")]
-    Unknown([u8; 6]),
+    Unknown([u8; 7]),
 }
 
 impl fmt::Display for BDS {
@@ -37,12 +63,17 @@ impl fmt::Display for BDS {
             Self::Empty(_) => {
                 writeln!(f, "Comm-B format: empty response")?;
             },
-            Self::AircraftIdentification(s) => {
+            Self::AircraftIdentification(identification) => {
                 writeln!(f, "Comm-B format: BDS2,0 Aircraft identification")?;
-                writeln!(f, "  Ident:         {}", s)?;
+                writeln!(f, "  Ident:         {}", identification.callsign)?;
             },
-            Self::DataLinkCapability(_) => {
+            Self::DataLinkCapability(capability) => {
                 writeln!(f, "Comm-B format: BDS1,0 Datalink capabilities")?;
+                write!(f, "{capability}")?;
+            },
+            Self::AcasResolutionAdvisory(ra) => {
+                writeln!(f, "Comm-B format: BDS3,0 ACAS active resolution advisory")?;
+                write!(f, "{ra}")?;
             },
             Self::Unknown(_) => {
                 writeln!(f, "Comm-B format: unknown format")?;
@@ -52,35 +83,1328 @@ impl fmt::Display for BDS {
     }
 }
 
+/// To report the aircraft identification (callsign)
+///
+/// Encoded with the same 6 bit characters as [`crate::adsb::Identification`]. Spaces are removed.
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone)]
+pub struct AircraftIdentification {
+    #[deku(
+        reader = "aircraft_identification_read(deku::rest)",
+        writer = "aircraft_identification_write(deku::output, callsign)"
+    )]
+    pub callsign: String,
+}
+
 /// To report the data link capability of the Mode S transponder/data link installation
-#[derive(Debug, PartialEq, Eq, DekuRead, Clone)]
+///
+/// Table A-2-16
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone)]
 pub struct DataLinkCapability {
+    /// Another capability report follows in BDS 1,1
     #[deku(bits = "1")]
     #[deku(pad_bits_after = "5")] // reserved
     pub continuation_flag: bool,
+    /// Overlay Command capability, only set with a `mode_s_subnetwork_version_number` of at
+    /// least 5
     #[deku(bits = "1")]
     pub overlay_command_capability: bool,
+    /// ACAS is operating, instead of failed or on standby
     #[deku(bits = "1")]
     pub acas: bool,
+    /// Version of the Mode S subnetwork, 0 if not available
     #[deku(bits = "7")]
     pub mode_s_subnetwork_version_number: u8,
+    /// Level 5 transponder, instead of level 2 to 4
     #[deku(bits = "1")]
     pub transponder_enhanced_protocol_indicator: bool,
+    /// At least one Mode S specific service, other than GICB, is supported
     #[deku(bits = "1")]
     pub mode_s_specific_services_capability: bool,
+    /// Average uplink ELM throughput, 0 for no capability
     #[deku(bits = "3")]
     pub uplink_elm_average_throughput_capability: u8,
+    /// Downlink ELM throughput, 0 for no capability
     #[deku(bits = "4")]
     pub downlink_elm: u8,
+    /// Aircraft identification can be reported in BDS 2,0
     #[deku(bits = "1")]
     pub aircraft_identification_capability: bool,
+    /// Extended squitter registers BDS 0,5 and 0,6 are updated
     #[deku(bits = "1")]
     pub squitter_capability_subfield: bool,
+    /// Surveillance Identifier codes are supported
     #[deku(bits = "1")]
     pub surveillance_identifier_code: bool,
+    /// BDS 1,7 has changed since last reported
     #[deku(bits = "1")]
     pub common_usage_gicb_capability_report: bool,
-    #[deku(bits = "4")]
-    pub reserved_acas: u8,
+    /// ACAS hybrid surveillance is supported
+    #[deku(bits = "1")]
+    pub acas_hybrid_surveillance: bool,
+    /// ACAS generates both Traffic and Resolution Advisories, instead of only Traffic Advisories
+    #[deku(bits = "1")]
+    pub acas_resolution_advisories: bool,
+    pub acas_version: AcasVersion,
+    /// Data link capability bit array, the status of the DTE sub-addresses 0 to 15
     pub bit_array: u16,
 }
+
+impl fmt::Display for DataLinkCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "  Subnetwork:    version {}",
+            self.mode_s_subnetwork_version_number
+        )?;
+        if self.acas {
+            let advisories = if self.acas_resolution_advisories {
+                "TA/RA"
+            } else {
+                "TA only"
+            };
+            writeln!(
+                f,
+                "  ACAS:          operating, {advisories}, {}",
+                self.acas_version
+            )?;
+        } else {
+            writeln!(f, "  ACAS:          failed or on standby")?;
+        }
+        Ok(())
+    }
+}
+
+/// Version of the ACAS MOPS (Minimum Operational Performance Standards) in use
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone, Copy)]
+#[deku(type = "u8", bits = "2")]
+pub enum AcasVersion {
+    /// RTCA DO-185
+    #[deku(id = "0")]
+    Do185,
+    /// RTCA DO-185A
+    #[deku(id = "1")]
+    Do185A,
+    /// RTCA DO-185B or EUROCAE ED-143
+    #[deku(id = "2")]
+    Do185B,
+    #[deku(id = "3")]
+    Reserved,
+}
+
+impl fmt::Display for AcasVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Do185 => write!(f, "RTCA DO-185"),
+            Self::Do185A => write!(f, "RTCA DO-185A"),
+            Self::Do185B => write!(f, "RTCA DO-185B/EUROCAE ED-143"),
+            Self::Reserved => write!(f, "reserved"),
+        }
+    }
+}
+
+/// To indicate which GICB (Ground Initiated Comm-B) registers are serviced by the transponder
+///
+/// Table A-2-23. Unlike BDS 1,0, this register doesn't start with its register number, and is
+/// only found in [`CommB::common_usage_gicb_capability`].
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone, Copy)]
+pub struct CommonUsageGicbCapability {
+    /// (0, 5) Extended squitter airborne position
+    #[deku(bits = "1")]
+    pub es_airborne_position: bool,
+    /// (0, 6) Extended squitter surface position
+    #[deku(bits = "1")]
+    pub es_surface_position: bool,
+    /// (0, 7) Extended squitter status
+    #[deku(bits = "1")]
+    pub es_status: bool,
+    /// (0, 8) Extended squitter identification and category
+    #[deku(bits = "1")]
+    pub es_identification: bool,
+    /// (0, 9) Extended squitter airborne velocity
+    #[deku(bits = "1")]
+    pub es_airborne_velocity: bool,
+    /// (0, A) Extended squitter event-driven information
+    #[deku(bits = "1")]
+    pub es_event_driven: bool,
+    /// (2, 0) Aircraft identification
+    #[deku(bits = "1")]
+    pub aircraft_identification: bool,
+    /// (2, 1) Aircraft registration number
+    #[deku(bits = "1")]
+    pub aircraft_registration: bool,
+    /// (4, 0) Selected vertical intention
+    #[deku(bits = "1")]
+    pub selected_vertical_intention: bool,
+    /// (4, 1) Next waypoint identifier
+    #[deku(bits = "1")]
+    pub next_waypoint_identifier: bool,
+    /// (4, 2) Next waypoint position
+    #[deku(bits = "1")]
+    pub next_waypoint_position: bool,
+    /// (4, 3) Next waypoint information
+    #[deku(bits = "1")]
+    pub next_waypoint_information: bool,
+    /// (4, 4) Meteorological routine air report
+    #[deku(bits = "1")]
+    pub meteorological_routine: bool,
+    /// (4, 5) Meteorological hazard report
+    #[deku(bits = "1")]
+    pub meteorological_hazard: bool,
+    /// (4, 8) VHF channel report
+    #[deku(bits = "1")]
+    pub vhf_channel: bool,
+    /// (5, 0) Track and turn report
+    #[deku(bits = "1")]
+    pub track_and_turn: bool,
+    /// (5, 1) Position report coarse
+    #[deku(bits = "1")]
+    pub position_coarse: bool,
+    /// (5, 2) Position report fine
+    #[deku(bits = "1")]
+    pub position_fine: bool,
+    /// (5, 3) Air-referenced state vector
+    #[deku(bits = "1")]
+    pub air_referenced_state_vector: bool,
+    /// (5, 4) Waypoint 1
+    #[deku(bits = "1")]
+    pub waypoint_1: bool,
+    /// (5, 5) Waypoint 2
+    #[deku(bits = "1")]
+    pub waypoint_2: bool,
+    /// (5, 6) Waypoint 3
+    #[deku(bits = "1")]
+    pub waypoint_3: bool,
+    /// (5, F) Quasi-static parameter monitoring
+    #[deku(bits = "1")]
+    pub quasi_static_parameter_monitoring: bool,
+    /// (6, 0) Heading and speed report
+    #[deku(bits = "1")]
+    pub heading_and_speed: bool,
+    /// Reserved for aircraft capability
+    #[deku(bits = "4")]
+    pub reserved0: u8,
+    #[deku(bits = "28", endian = "big")]
+    pub reserved1: u32,
+}
+
+impl fmt::Display for CommonUsageGicbCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let registers = [
+            (self.es_airborne_position, "0,5"),
+            (self.es_surface_position, "0,6"),
+            (self.es_status, "0,7"),
+            (self.es_identification, "0,8"),
+            (self.es_airborne_velocity, "0,9"),
+            (self.es_event_driven, "0,A"),
+            (self.aircraft_identification, "2,0"),
+            (self.aircraft_registration, "2,1"),
+            (self.selected_vertical_intention, "4,0"),
+            (self.next_waypoint_identifier, "4,1"),
+            (self.next_waypoint_position, "4,2"),
+            (self.next_waypoint_information, "4,3"),
+            (self.meteorological_routine, "4,4"),
+            (self.meteorological_hazard, "4,5"),
+            (self.vhf_channel, "4,8"),
+            (self.track_and_turn, "5,0"),
+            (self.position_coarse, "5,1"),
+            (self.position_fine, "5,2"),
+            (self.air_referenced_state_vector, "5,3"),
+            (self.waypoint_1, "5,4"),
+            (self.waypoint_2, "5,5"),
+            (self.waypoint_3, "5,6"),
+            (self.quasi_static_parameter_monitoring, "5,F"),
+            (self.heading_and_speed, "6,0"),
+        ];
+        let mut first = true;
+        for (_, register) in registers.iter().filter(|(serviced, _)| *serviced) {
+            if !first {
+                write!(f, " ")?;
+            }
+            write!(f, "{register}")?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+/// Selected vertical intention
+///
+/// Table A-2-64. Like [`CommonUsageGicbCapability`], this register doesn't start with its register
+/// number, and is only found in [`CommB::selected_vertical_intention`]. Every value is only valid
+/// when its status bit is set, use the methods to only get the valid values.
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone, Copy)]
+pub struct SelectedVerticalIntention {
+    /// `mcp_altitude` is valid
+    #[deku(bits = "1")]
+    pub mcp_status: bool,
+    /// MCP/FCU (Mode Control Panel/Flight Control Unit) selected altitude in ft
+    #[deku(
+        bits = "12",
+        endian = "big",
+        map = "|altitude: u32| -> Result<_, DekuError> {Ok(altitude * 16)}",
+        writer = "(mcp_altitude / 16).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(12)))"
+    )]
+    pub mcp_altitude: u32,
+    /// `fms_altitude` is valid
+    #[deku(bits = "1")]
+    pub fms_status: bool,
+    /// FMS (Flight Management System) selected altitude in ft
+    #[deku(
+        bits = "12",
+        endian = "big",
+        map = "|altitude: u32| -> Result<_, DekuError> {Ok(altitude * 16)}",
+        writer = "(fms_altitude / 16).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(12)))"
+    )]
+    pub fms_altitude: u32,
+    /// `qnh` is valid
+    #[deku(bits = "1")]
+    pub qnh_status: bool,
+    /// Barometric pressure setting minus 800 millibars
+    #[deku(
+        bits = "12",
+        endian = "big",
+        map = "|qnh: u32| -> Result<_, DekuError> {Ok(800.0 + qnh as f32 * 0.1)}",
+        writer = "(libm::roundf((qnh - 800.0) * 10.0) as u32).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(12)))"
+    )]
+    pub qnh: f32,
+    #[deku(bits = "8")]
+    pub reserved0: u8,
+    /// `vnav`, `alt_hold` and `approach` are valid
+    #[deku(bits = "1")]
+    pub mode_status: bool,
+    #[deku(bits = "1")]
+    pub vnav: bool,
+    #[deku(bits = "1")]
+    pub alt_hold: bool,
+    #[deku(bits = "1")]
+    pub approach: bool,
+    #[deku(bits = "2")]
+    pub reserved1: u8,
+    /// `source` is valid
+    #[deku(bits = "1")]
+    pub source_status: bool,
+    pub source: TargetAltitudeSource,
+}
+
+impl SelectedVerticalIntention {
+    /// MCP/FCU selected altitude in ft, if valid
+    #[must_use]
+    pub fn mcp_selected_altitude(&self) -> Option<u32> {
+        self.mcp_status.then(|| self.mcp_altitude)
+    }
+
+    /// FMS selected altitude in ft, if valid
+    #[must_use]
+    pub fn fms_selected_altitude(&self) -> Option<u32> {
+        self.fms_status.then(|| self.fms_altitude)
+    }
+
+    /// Barometric pressure setting in millibars, if valid
+    #[must_use]
+    pub fn barometric_pressure_setting(&self) -> Option<f32> {
+        self.qnh_status.then(|| self.qnh)
+    }
+
+    /// Source of the target altitude, if valid
+    #[must_use]
+    pub fn altitude_source(&self) -> Option<TargetAltitudeSource> {
+        self.source_status.then(|| self.source)
+    }
+}
+
+impl fmt::Display for SelectedVerticalIntention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(altitude) = self.mcp_selected_altitude() {
+            writeln!(f, "  MCP altitude:  {altitude} ft")?;
+        }
+        if let Some(altitude) = self.fms_selected_altitude() {
+            writeln!(f, "  FMS altitude:  {altitude} ft")?;
+        }
+        if let Some(qnh) = self.barometric_pressure_setting() {
+            writeln!(f, "  QNH:           {qnh:.1} millibars")?;
+        }
+        if self.mode_status {
+            write!(f, "  Modes:        ")?;
+            if self.vnav {
+                write!(f, " vnav")?;
+            }
+            if self.alt_hold {
+                write!(f, " altitude-hold")?;
+            }
+            if self.approach {
+                write!(f, " approach")?;
+            }
+            writeln!(f)?;
+        }
+        if let Some(source) = self.altitude_source() {
+            writeln!(f, "  Target source: {source}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Source of the target altitude of [`SelectedVerticalIntention`]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+#[deku(type = "u8", bits = "2")]
+pub enum TargetAltitudeSource {
+    #[deku(id = "0")]
+    Unknown,
+    /// Current altitude of the aircraft
+    #[deku(id = "1")]
+    Aircraft,
+    /// MCP/FCU selected altitude
+    #[deku(id = "2")]
+    Mcp,
+    /// FMS selected altitude
+    #[deku(id = "3")]
+    Fms,
+}
+
+impl fmt::Display for TargetAltitudeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "unknown"),
+            Self::Aircraft => write!(f, "aircraft altitude"),
+            Self::Mcp => write!(f, "MCP"),
+            Self::Fms => write!(f, "FMS"),
+        }
+    }
+}
+
+/// Meteorological routine air report
+///
+/// Table A-2-68. Only found in [`CommB::meteorological_routine_air_report`], this register doesn't
+/// start with its register number. Every value except the temperature is only valid when its
+/// status bit is set, use the methods to only get the valid values.
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone, Copy)]
+pub struct MeteorologicalRoutineAirReport {
+    /// Figure of merit, the source of the position used for the wind
+    pub source: MeteorologicalSource,
+    /// `wind_speed` and `wind_direction` are valid
+    #[deku(bits = "1")]
+    pub wind_status: bool,
+    /// Wind speed in kt
+    #[deku(bits = "9", endian = "big")]
+    pub wind_speed: u16,
+    /// Wind direction in degrees, clockwise from true north
+    #[deku(
+        bits = "9",
+        endian = "big",
+        map = "|direction: u16| -> Result<_, DekuError> {Ok(direction as f32 * 180.0 / 256.0)}",
+        writer = "(libm::roundf(wind_direction * 256.0 / 180.0) as u16).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(9)))"
+    )]
+    pub wind_direction: f32,
+    /// Static air temperature in degrees Celsius
+    #[deku(
+        bits = "11",
+        endian = "big",
+        map = "|temperature: u16| -> Result<_, DekuError> {Ok(if temperature >= 1024 { f32::from(temperature) - 2048.0 } else { f32::from(temperature) } * 0.25)}",
+        writer = "((libm::roundf(temperature * 4.0) as i16 as u16) & 0x7ff).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(11)))"
+    )]
+    pub temperature: f32,
+    /// `pressure` is valid
+    #[deku(bits = "1")]
+    pub pressure_status: bool,
+    /// Average static pressure in hPa
+    #[deku(bits = "11", endian = "big")]
+    pub pressure: u16,
+    /// `turbulence` is valid
+    #[deku(bits = "1")]
+    pub turbulence_status: bool,
+    pub turbulence: Hazard,
+    /// `humidity` is valid
+    #[deku(bits = "1")]
+    pub humidity_status: bool,
+    /// Humidity in percent
+    #[deku(
+        bits = "6",
+        map = "|humidity: u8| -> Result<_, DekuError> {Ok(f32::from(humidity) * 100.0 / 64.0)}",
+        writer = "(libm::roundf(humidity * 64.0 / 100.0) as u8).write(deku::output, deku::ctx::Size::Bits(6))"
+    )]
+    pub humidity: f32,
+}
+
+impl MeteorologicalRoutineAirReport {
+    /// Wind speed in kt and direction in degrees, if valid
+    #[must_use]
+    pub fn wind(&self) -> Option<(u16, f32)> {
+        self.wind_status
+            .then(|| (self.wind_speed, self.wind_direction))
+    }
+
+    /// Average static pressure in hPa, if valid
+    #[must_use]
+    pub fn static_pressure(&self) -> Option<u16> {
+        self.pressure_status.then(|| self.pressure)
+    }
+
+    /// Turbulence, if valid
+    #[must_use]
+    pub fn turbulence(&self) -> Option<Hazard> {
+        self.turbulence_status.then(|| self.turbulence)
+    }
+
+    /// Humidity in percent, if valid
+    #[must_use]
+    pub fn humidity(&self) -> Option<f32> {
+        self.humidity_status.then(|| self.humidity)
+    }
+}
+
+impl fmt::Display for MeteorologicalRoutineAirReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Source:        {}", self.source)?;
+        if let Some((speed, direction)) = self.wind() {
+            writeln!(f, "  Wind:          {speed} kt, {direction:.1}")?;
+        }
+        writeln!(f, "  Temperature:   {:.2} C", self.temperature)?;
+        if let Some(pressure) = self.static_pressure() {
+            writeln!(f, "  Pressure:      {pressure} hPa")?;
+        }
+        if let Some(turbulence) = self.turbulence() {
+            writeln!(f, "  Turbulence:    {turbulence}")?;
+        }
+        if let Some(humidity) = self.humidity() {
+            writeln!(f, "  Humidity:      {humidity:.1}%")?;
+        }
+        Ok(())
+    }
+}
+
+/// Meteorological hazard report
+///
+/// Table A-2-69. Only found in [`CommB::meteorological_hazard_report`], this register doesn't
+/// start with its register number. Every value is only valid when its status bit is set, use the
+/// methods to only get the valid values.
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone, Copy)]
+pub struct MeteorologicalHazardReport {
+    /// `turbulence` is valid
+    #[deku(bits = "1")]
+    pub turbulence_status: bool,
+    pub turbulence: Hazard,
+    /// `wind_shear` is valid
+    #[deku(bits = "1")]
+    pub wind_shear_status: bool,
+    pub wind_shear: Hazard,
+    /// `microburst` is valid
+    #[deku(bits = "1")]
+    pub microburst_status: bool,
+    pub microburst: Hazard,
+    /// `icing` is valid
+    #[deku(bits = "1")]
+    pub icing_status: bool,
+    pub icing: Hazard,
+    /// `wake_vortex` is valid
+    #[deku(bits = "1")]
+    pub wake_vortex_status: bool,
+    pub wake_vortex: Hazard,
+    /// `temperature` is valid
+    #[deku(bits = "1")]
+    pub temperature_status: bool,
+    /// Static air temperature in degrees Celsius
+    #[deku(
+        bits = "10",
+        endian = "big",
+        map = "|temperature: u16| -> Result<_, DekuError> {Ok(if temperature >= 512 { f32::from(temperature) - 1024.0 } else { f32::from(temperature) } * 0.25)}",
+        writer = "((libm::roundf(temperature * 4.0) as i16 as u16) & 0x3ff).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(10)))"
+    )]
+    pub temperature: f32,
+    /// `pressure` is valid
+    #[deku(bits = "1")]
+    pub pressure_status: bool,
+    /// Average static pressure in hPa
+    #[deku(bits = "11", endian = "big")]
+    pub pressure: u16,
+    /// `radio_height` is valid
+    #[deku(bits = "1")]
+    pub radio_height_status: bool,
+    /// Radio height in ft
+    #[deku(
+        bits = "12",
+        endian = "big",
+        map = "|height: u16| -> Result<_, DekuError> {Ok(height * 16)}",
+        writer = "(radio_height / 16).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(12)))"
+    )]
+    pub radio_height: u16,
+    #[deku(bits = "5")]
+    pub reserved: u8,
+}
+
+impl MeteorologicalHazardReport {
+    /// Turbulence, if valid
+    #[must_use]
+    pub fn turbulence(&self) -> Option<Hazard> {
+        self.turbulence_status.then(|| self.turbulence)
+    }
+
+    /// Wind shear, if valid
+    #[must_use]
+    pub fn wind_shear(&self) -> Option<Hazard> {
+        self.wind_shear_status.then(|| self.wind_shear)
+    }
+
+    /// Microburst, if valid
+    #[must_use]
+    pub fn microburst(&self) -> Option<Hazard> {
+        self.microburst_status.then(|| self.microburst)
+    }
+
+    /// Icing, if valid
+    #[must_use]
+    pub fn icing(&self) -> Option<Hazard> {
+        self.icing_status.then(|| self.icing)
+    }
+
+    /// Wake vortex, if valid
+    #[must_use]
+    pub fn wake_vortex(&self) -> Option<Hazard> {
+        self.wake_vortex_status.then(|| self.wake_vortex)
+    }
+
+    /// Static air temperature in degrees Celsius, if valid
+    #[must_use]
+    pub fn static_air_temperature(&self) -> Option<f32> {
+        self.temperature_status.then(|| self.temperature)
+    }
+
+    /// Average static pressure in hPa, if valid
+    #[must_use]
+    pub fn static_pressure(&self) -> Option<u16> {
+        self.pressure_status.then(|| self.pressure)
+    }
+
+    /// Radio height in ft, if valid
+    #[must_use]
+    pub fn radio_height(&self) -> Option<u16> {
+        self.radio_height_status.then(|| self.radio_height)
+    }
+}
+
+impl fmt::Display for MeteorologicalHazardReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hazards = [
+            ("Turbulence:   ", self.turbulence()),
+            ("Wind shear:   ", self.wind_shear()),
+            ("Microburst:   ", self.microburst()),
+            ("Icing:        ", self.icing()),
+            ("Wake vortex:  ", self.wake_vortex()),
+        ];
+        for (name, hazard) in hazards {
+            if let Some(hazard) = hazard {
+                writeln!(f, "  {name} {hazard}")?;
+            }
+        }
+        if let Some(temperature) = self.static_air_temperature() {
+            writeln!(f, "  Temperature:   {temperature:.2} C")?;
+        }
+        if let Some(pressure) = self.static_pressure() {
+            writeln!(f, "  Pressure:      {pressure} hPa")?;
+        }
+        if let Some(height) = self.radio_height() {
+            writeln!(f, "  Radio height:  {height} ft")?;
+        }
+        Ok(())
+    }
+}
+
+/// Track and turn report
+///
+/// Table A-2-80. Only found in [`CommB::track_and_turn_report`], this register doesn't start with
+/// its register number. Every value is only valid when its status bit is set, use the methods to
+/// only get the valid values.
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone, Copy)]
+pub struct TrackAndTurnReport {
+    /// `roll` is valid
+    #[deku(bits = "1")]
+    pub roll_status: bool,
+    /// Roll angle in degrees, negative is left wing down
+    #[deku(
+        bits = "10",
+        endian = "big",
+        map = "|roll: u16| -> Result<_, DekuError> {Ok(if roll >= 512 { f32::from(roll) - 1024.0 } else { f32::from(roll) } * 45.0 / 256.0)}",
+        writer = "((libm::roundf(roll * 256.0 / 45.0) as i16 as u16) & 0x3ff).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(10)))"
+    )]
+    pub roll: f32,
+    /// `track` is valid
+    #[deku(bits = "1")]
+    pub track_status: bool,
+    /// True track angle in degrees, negative west of south, see [`Self::true_track`]
+    #[deku(
+        bits = "11",
+        endian = "big",
+        map = "|track: u16| -> Result<_, DekuError> {Ok(if track >= 1024 { f32::from(track) - 2048.0 } else { f32::from(track) } * 90.0 / 512.0)}",
+        writer = "((libm::roundf(track * 512.0 / 90.0) as i16 as u16) & 0x7ff).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(11)))"
+    )]
+    pub track: f32,
+    /// `ground_speed` is valid
+    #[deku(bits = "1")]
+    pub ground_speed_status: bool,
+    /// Ground speed in kt
+    #[deku(
+        bits = "10",
+        endian = "big",
+        map = "|speed: u16| -> Result<_, DekuError> {Ok(speed * 2)}",
+        writer = "(ground_speed / 2).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(10)))"
+    )]
+    pub ground_speed: u16,
+    /// `track_rate` is valid
+    #[deku(bits = "1")]
+    pub track_rate_status: bool,
+    /// Track angle rate in degrees per second, negative is turning left
+    #[deku(
+        bits = "10",
+        endian = "big",
+        map = "|rate: u16| -> Result<_, DekuError> {Ok(if rate >= 512 { f32::from(rate) - 1024.0 } else { f32::from(rate) } * 8.0 / 256.0)}",
+        writer = "((libm::roundf(track_rate * 256.0 / 8.0) as i16 as u16) & 0x3ff).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(10)))"
+    )]
+    pub track_rate: f32,
+    /// `true_airspeed` is valid
+    #[deku(bits = "1")]
+    pub airspeed_status: bool,
+    /// True airspeed in kt
+    #[deku(
+        bits = "10",
+        endian = "big",
+        map = "|speed: u16| -> Result<_, DekuError> {Ok(speed * 2)}",
+        writer = "(true_airspeed / 2).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(10)))"
+    )]
+    pub true_airspeed: u16,
+}
+
+impl TrackAndTurnReport {
+    /// Roll angle in degrees, if valid
+    #[must_use]
+    pub fn roll_angle(&self) -> Option<f32> {
+        self.roll_status.then(|| self.roll)
+    }
+
+    /// True track angle in degrees, clockwise from true north in `0.0..360.0`, if valid
+    #[must_use]
+    pub fn true_track(&self) -> Option<f32> {
+        self.track_status.then(|| {
+            if self.track < 0.0 {
+                self.track + 360.0
+            } else {
+                self.track
+            }
+        })
+    }
+
+    /// Ground speed in kt, if valid
+    #[must_use]
+    pub fn ground_speed(&self) -> Option<u16> {
+        self.ground_speed_status.then(|| self.ground_speed)
+    }
+
+    /// Track angle rate in degrees per second, if valid
+    #[must_use]
+    pub fn track_angle_rate(&self) -> Option<f32> {
+        self.track_rate_status.then(|| self.track_rate)
+    }
+
+    /// True airspeed in kt, if valid
+    #[must_use]
+    pub fn true_airspeed(&self) -> Option<u16> {
+        self.airspeed_status.then(|| self.true_airspeed)
+    }
+}
+
+impl fmt::Display for TrackAndTurnReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(roll) = self.roll_angle() {
+            writeln!(f, "  Roll angle:    {roll:.1}")?;
+        }
+        if let Some(track) = self.true_track() {
+            writeln!(f, "  True track:    {track:.1}")?;
+        }
+        if let Some(speed) = self.ground_speed() {
+            writeln!(f, "  Ground speed:  {speed} kt")?;
+        }
+        if let Some(rate) = self.track_angle_rate() {
+            writeln!(f, "  Track rate:    {rate:.3} deg/s")?;
+        }
+        if let Some(speed) = self.true_airspeed() {
+            writeln!(f, "  TAS:           {speed} kt")?;
+        }
+        Ok(())
+    }
+}
+
+/// Heading and speed report
+///
+/// Table A-2-96. Only found in [`CommB::heading_and_speed_report`], this register doesn't start
+/// with its register number. Every value is only valid when its status bit is set, use the
+/// methods to only get the valid values.
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone, Copy)]
+pub struct HeadingAndSpeedReport {
+    /// `heading` is valid
+    #[deku(bits = "1")]
+    pub heading_status: bool,
+    /// Magnetic heading in degrees, negative west of south, see [`Self::magnetic_heading`]
+    #[deku(
+        bits = "11",
+        endian = "big",
+        map = "|heading: u16| -> Result<_, DekuError> {Ok(if heading >= 1024 { f32::from(heading) - 2048.0 } else { f32::from(heading) } * 90.0 / 512.0)}",
+        writer = "((libm::roundf(heading * 512.0 / 90.0) as i16 as u16) & 0x7ff).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(11)))"
+    )]
+    pub heading: f32,
+    /// `airspeed` is valid
+    #[deku(bits = "1")]
+    pub airspeed_status: bool,
+    /// Indicated airspeed in kt
+    #[deku(bits = "10", endian = "big")]
+    pub airspeed: u16,
+    /// `mach` is valid
+    #[deku(bits = "1")]
+    pub mach_status: bool,
+    /// Mach number, in steps of 2.048 / 512
+    #[deku(
+        bits = "10",
+        endian = "big",
+        map = "|mach: u16| -> Result<_, DekuError> {Ok(f32::from(mach) / 250.0)}",
+        writer = "(libm::roundf(mach * 250.0) as u16).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(10)))"
+    )]
+    pub mach: f32,
+    /// `baro_vertical_rate` is valid
+    #[deku(bits = "1")]
+    pub baro_vertical_rate_status: bool,
+    /// Barometric altitude rate in ft/min
+    #[deku(
+        bits = "10",
+        endian = "big",
+        map = "|rate: u16| -> Result<_, DekuError> {Ok(if rate >= 512 { rate as i16 - 1024 } else { rate as i16 } * 32)}",
+        writer = "((baro_vertical_rate / 32) as u16 & 0x3ff).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(10)))"
+    )]
+    pub baro_vertical_rate: i16,
+    /// `inertial_vertical_rate` is valid
+    #[deku(bits = "1")]
+    pub inertial_vertical_rate_status: bool,
+    /// Inertial vertical velocity in ft/min
+    #[deku(
+        bits = "10",
+        endian = "big",
+        map = "|rate: u16| -> Result<_, DekuError> {Ok(if rate >= 512 { rate as i16 - 1024 } else { rate as i16 } * 32)}",
+        writer = "((inertial_vertical_rate / 32) as u16 & 0x3ff).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(10)))"
+    )]
+    pub inertial_vertical_rate: i16,
+}
+
+impl HeadingAndSpeedReport {
+    /// Magnetic heading in degrees, clockwise from magnetic north in `0.0..360.0`, if valid
+    #[must_use]
+    pub fn magnetic_heading(&self) -> Option<f32> {
+        self.heading_status.then(|| {
+            if self.heading < 0.0 {
+                self.heading + 360.0
+            } else {
+                self.heading
+            }
+        })
+    }
+
+    /// Indicated airspeed in kt, if valid
+    #[must_use]
+    pub fn indicated_airspeed(&self) -> Option<u16> {
+        self.airspeed_status.then(|| self.airspeed)
+    }
+
+    /// Mach number, if valid
+    #[must_use]
+    pub fn mach_number(&self) -> Option<f32> {
+        self.mach_status.then(|| self.mach)
+    }
+
+    /// Barometric altitude rate in ft/min, if valid
+    #[must_use]
+    pub fn baro_altitude_rate(&self) -> Option<i16> {
+        self.baro_vertical_rate_status
+            .then(|| self.baro_vertical_rate)
+    }
+
+    /// Inertial vertical velocity in ft/min, if valid
+    #[must_use]
+    pub fn inertial_vertical_velocity(&self) -> Option<i16> {
+        self.inertial_vertical_rate_status
+            .then(|| self.inertial_vertical_rate)
+    }
+}
+
+impl fmt::Display for HeadingAndSpeedReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(heading) = self.magnetic_heading() {
+            writeln!(f, "  Mag heading:   {heading:.1}")?;
+        }
+        if let Some(speed) = self.indicated_airspeed() {
+            writeln!(f, "  IAS:           {speed} kt")?;
+        }
+        if let Some(mach) = self.mach_number() {
+            writeln!(f, "  Mach:          {mach:.3}")?;
+        }
+        if let Some(rate) = self.baro_altitude_rate() {
+            writeln!(f, "  Baro rate:     {rate} ft/min")?;
+        }
+        if let Some(rate) = self.inertial_vertical_velocity() {
+            writeln!(f, "  Inertial rate: {rate} ft/min")?;
+        }
+        Ok(())
+    }
+}
+
+/// Source of the position used by [`MeteorologicalRoutineAirReport`]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone, Copy)]
+#[deku(type = "u8", bits = "4")]
+pub enum MeteorologicalSource {
+    #[deku(id = "0")]
+    Invalid,
+    /// Inertial Navigation System
+    #[deku(id = "1")]
+    Ins,
+    /// Global Navigation Satellite System
+    #[deku(id = "2")]
+    Gnss,
+    /// Distance Measuring Equipment, from more than one station
+    #[deku(id = "3")]
+    DmeDme,
+    /// VHF Omnidirectional Range and Distance Measuring Equipment
+    #[deku(id = "4")]
+    VorDme,
+    #[deku(id_pat = "_")]
+    Reserved(#[deku(bits = "4")] u8),
+}
+
+impl fmt::Display for MeteorologicalSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid => write!(f, "invalid"),
+            Self::Ins => write!(f, "INS"),
+            Self::Gnss => write!(f, "GNSS"),
+            Self::DmeDme => write!(f, "DME/DME"),
+            Self::VorDme => write!(f, "VOR/DME"),
+            Self::Reserved(source) => write!(f, "reserved {source}"),
+        }
+    }
+}
+
+/// Severity of a hazard of [`MeteorologicalRoutineAirReport`] and [`MeteorologicalHazardReport`]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone, Copy)]
+#[deku(type = "u8", bits = "2")]
+pub enum Hazard {
+    #[deku(id = "0")]
+    Nil,
+    #[deku(id = "1")]
+    Light,
+    #[deku(id = "2")]
+    Moderate,
+    #[deku(id = "3")]
+    Severe,
+}
+
+impl fmt::Display for Hazard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nil => write!(f, "nil"),
+            Self::Light => write!(f, "light"),
+            Self::Moderate => write!(f, "moderate"),
+            Self::Severe => write!(f, "severe"),
+        }
+    }
+}
+
+/// BDS register identified by [`infer`]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[non_exhaustive]
+pub enum Register {
+    /// All zeros, no information
+    Empty,
+    /// (1, 0) Data link capability report
+    DataLinkCapability,
+    /// (1, 7) Common usage GICB capability report
+    CommonUsageGicbCapability,
+    /// (2, 0) Aircraft identification
+    AircraftIdentification,
+    /// (3, 0) ACAS active resolution advisory
+    AcasResolutionAdvisory,
+    /// (4, 0) Selected vertical intention
+    SelectedVerticalIntention,
+    /// (4, 4) Meteorological routine air report
+    MeteorologicalRoutine,
+    /// (4, 5) Meteorological hazard report
+    MeteorologicalHazard,
+    /// (5, 0) Track and turn report
+    TrackAndTurn,
+    /// (6, 0) Heading and speed report
+    HeadingAndSpeed,
+    /// None, or more than one, of the registers are plausible
+    Unknown,
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Empty => "empty",
+            Self::DataLinkCapability => "BDS1,0",
+            Self::CommonUsageGicbCapability => "BDS1,7",
+            Self::AircraftIdentification => "BDS2,0",
+            Self::AcasResolutionAdvisory => "BDS3,0",
+            Self::SelectedVerticalIntention => "BDS4,0",
+            Self::MeteorologicalRoutine => "BDS4,4",
+            Self::MeteorologicalHazard => "BDS4,5",
+            Self::TrackAndTurn => "BDS5,0",
+            Self::HeadingAndSpeed => "BDS6,0",
+            Self::Unknown => "unknown",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl CommB {
+    /// Register contained in `mb`, see [`infer`]
+    #[must_use]
+    pub fn infer(&self, mrar: bool) -> Register {
+        infer(&self.mb, mrar)
+    }
+
+    /// Callsign of BDS 2,0, if `mb` contains it and all characters are valid
+    #[must_use]
+    pub fn callsign(&self) -> Option<&str> {
+        match &self.bds {
+            BDS::AircraftIdentification(identification)
+                if MbBits::new(&self.mb).is_20() && !identification.callsign.is_empty() =>
+            {
+                Some(&identification.callsign)
+            },
+            _ => None,
+        }
+    }
+
+    /// Decode `mb` as BDS 1,7, if it is plausible for that register
+    #[must_use]
+    pub fn common_usage_gicb_capability(&self) -> Option<CommonUsageGicbCapability> {
+        if !MbBits::new(&self.mb).is_17() {
+            return None;
+        }
+        CommonUsageGicbCapability::from_bytes((self.mb.as_ref(), 0))
+            .ok()
+            .map(|(_, capability)| capability)
+    }
+
+    /// Decode `mb` as BDS 4,0, if it is plausible for that register
+    #[must_use]
+    pub fn selected_vertical_intention(&self) -> Option<SelectedVerticalIntention> {
+        if !MbBits::new(&self.mb).is_40() {
+            return None;
+        }
+        SelectedVerticalIntention::from_bytes((self.mb.as_ref(), 0))
+            .ok()
+            .map(|(_, intention)| intention)
+    }
+
+    /// Decode `mb` as BDS 4,4, if it is plausible for that register
+    #[must_use]
+    pub fn meteorological_routine_air_report(&self) -> Option<MeteorologicalRoutineAirReport> {
+        if !MbBits::new(&self.mb).is_44() {
+            return None;
+        }
+        MeteorologicalRoutineAirReport::from_bytes((self.mb.as_ref(), 0))
+            .ok()
+            .map(|(_, report)| report)
+    }
+
+    /// Decode `mb` as BDS 4,5, if it is plausible for that register
+    #[must_use]
+    pub fn meteorological_hazard_report(&self) -> Option<MeteorologicalHazardReport> {
+        if !MbBits::new(&self.mb).is_45() {
+            return None;
+        }
+        MeteorologicalHazardReport::from_bytes((self.mb.as_ref(), 0))
+            .ok()
+            .map(|(_, report)| report)
+    }
+
+    /// Decode `mb` as BDS 5,0, if it is plausible for that register
+    ///
+    /// BDS 5,0 and 6,0 are often both plausible, use [`Self::infer`] to only accept an
+    /// unambiguous BDS 5,0.
+    #[must_use]
+    pub fn track_and_turn_report(&self) -> Option<TrackAndTurnReport> {
+        if !MbBits::new(&self.mb).is_50() {
+            return None;
+        }
+        TrackAndTurnReport::from_bytes((self.mb.as_ref(), 0))
+            .ok()
+            .map(|(_, report)| report)
+    }
+
+    /// Decode `mb` as BDS 6,0, if it is plausible for that register
+    ///
+    /// BDS 5,0 and 6,0 are often both plausible, use [`Self::infer`] to only accept an
+    /// unambiguous BDS 6,0.
+    #[must_use]
+    pub fn heading_and_speed_report(&self) -> Option<HeadingAndSpeedReport> {
+        if !MbBits::new(&self.mb).is_60() {
+            return None;
+        }
+        HeadingAndSpeedReport::from_bytes((self.mb.as_ref(), 0))
+            .ok()
+            .map(|(_, report)| report)
+    }
+}
+
+/// Identify the BDS register contained in the MB field of a DF20/DF21 reply
+///
+/// The MB field doesn't say which register it contains, only the interrogator knows. Only BDS
+/// 1,0, 2,0 and 3,0 start with their register number, so all registers are checked for reserved
+/// bits, status bits without a value, and values that are physically implausible. Returns
+/// [`Register::Unknown`] if no register, or more than one, is plausible. See [`candidates`] for
+/// all plausible registers.
+///
+/// `mrar`: also consider the meteorological reports (BDS 4,4 and 4,5). These are rarely
+/// downlinked, and with few status bits are plausible for a lot of other registers.
+#[must_use]
+pub fn infer(mb: &[u8; 7], mrar: bool) -> Register {
+    match candidates(mb, mrar).as_slice() {
+        [register] => *register,
+        _ => Register::Unknown,
+    }
+}
+
+/// All plausible BDS registers for the MB field of a DF20/DF21 reply, see [`infer`]
+#[must_use]
+pub fn candidates(mb: &[u8; 7], mrar: bool) -> Vec<Register> {
+    let mb = MbBits::new(mb);
+    if mb.0 == 0 {
+        return vec![Register::Empty];
+    }
+
+    [
+        Register::DataLinkCapability,
+        Register::CommonUsageGicbCapability,
+        Register::AircraftIdentification,
+        Register::AcasResolutionAdvisory,
+        Register::SelectedVerticalIntention,
+        Register::MeteorologicalRoutine,
+        Register::MeteorologicalHazard,
+        Register::TrackAndTurn,
+        Register::HeadingAndSpeed,
+    ]
+    .into_iter()
+    .filter(|register| {
+        mrar || !matches!(
+            register,
+            Register::MeteorologicalRoutine | Register::MeteorologicalHazard
+        )
+    })
+    .filter(|register| mb.plausible(*register))
+    .collect()
+}
+
+/// 56 bits of the MB field, with bits numbered 1..=56 as in the specification
+struct MbBits(u64);
+
+impl MbBits {
+    fn new(mb: &[u8; 7]) -> Self {
+        let [a, b, c, d, e, f, g] = *mb;
+        Self(u64::from_be_bytes([0, a, b, c, d, e, f, g]))
+    }
+
+    /// Value of bits `first..=last`
+    fn bits(&self, first: u32, last: u32) -> u64 {
+        (self.0 >> (56 - last)) & ((1 << (last - first + 1)) - 1)
+    }
+
+    fn bit(&self, n: u32) -> bool {
+        self.bits(n, n) == 1
+    }
+
+    /// Value of bits `first..=last` if the `status` bit is set
+    fn status_value(&self, status: u32, first: u32, last: u32) -> Option<u64> {
+        self.bit(status).then(|| self.bits(first, last))
+    }
+
+    /// Two's complement value of bits `sign..=last` if the `status` bit is set
+    fn signed_value(&self, status: u32, sign: u32, last: u32) -> Option<i64> {
+        let value = self.status_value(status, sign + 1, last)? as i64;
+        Some(if self.bit(sign) {
+            value - (1 << (last - sign))
+        } else {
+            value
+        })
+    }
+
+    /// The `status` bit is clear, but bits `first..=last` contain a value
+    fn wrong_status(&self, status: u32, first: u32, last: u32) -> bool {
+        !self.bit(status) && self.bits(first, last) != 0
+    }
+
+    fn any_wrong_status(&self, fields: &[(u32, u32, u32)]) -> bool {
+        fields
+            .iter()
+            .any(|(status, first, last)| self.wrong_status(*status, *first, *last))
+    }
+
+    /// Contents are plausible for `register`
+    fn plausible(&self, register: Register) -> bool {
+        match register {
+            Register::DataLinkCapability => self.is_10(),
+            Register::CommonUsageGicbCapability => self.is_17(),
+            Register::AircraftIdentification => self.is_20(),
+            Register::AcasResolutionAdvisory => self.is_30(),
+            Register::SelectedVerticalIntention => self.is_40(),
+            Register::MeteorologicalRoutine => self.is_44(),
+            Register::MeteorologicalHazard => self.is_45(),
+            Register::TrackAndTurn => self.is_50(),
+            Register::HeadingAndSpeed => self.is_60(),
+            Register::Empty => self.0 == 0,
+            Register::Unknown => false,
+        }
+    }
+
+    fn is_10(&self) -> bool {
+        // reserved
+        if self.bits(1, 8) != 0x10 || self.bits(10, 14) != 0 {
+            return false;
+        }
+        // overlay command capability requires a Mode S subnetwork version of at least 5
+        let version = self.bits(17, 23);
+        self.bit(15) == (version >= 5)
+    }
+
+    fn is_17(&self) -> bool {
+        // reserved, and BDS 2,0 is always supported
+        self.bits(29, 56) == 0 && self.bit(7)
+    }
+
+    fn is_20(&self) -> bool {
+        if self.bits(1, 8) != 0x20 {
+            return false;
+        }
+        (0..8).all(|i| {
+            let c = self.bits(9 + i * 6, 14 + i * 6) as usize;
+            crate::CHAR_LOOKUP[c] != b'#'
+        })
+    }
+
+    fn is_30(&self) -> bool {
+        // threat type 3 is not assigned, and ACAS III is not in use
+        self.bits(1, 8) == 0x30 && self.bits(29, 30) != 3 && self.bits(16, 22) < 48
+    }
+
+    fn is_40(&self) -> bool {
+        !self.any_wrong_status(&[(1, 2, 13), (14, 15, 26), (27, 28, 39), (48, 49, 51), (54, 55, 56)])
+            // reserved
+            && self.bits(40, 47) == 0
+            && self.bits(52, 53) == 0
+    }
+
+    fn is_44(&self) -> bool {
+        if self.any_wrong_status(&[(5, 6, 23), (35, 36, 46), (47, 48, 49), (50, 51, 56)]) {
+            return false;
+        }
+        // source values above 4 are reserved
+        if self.bits(1, 4) > 4 {
+            return false;
+        }
+        // wind speed in kt
+        if self
+            .status_value(5, 6, 14)
+            .map_or(false, |speed| speed > 250)
+        {
+            return false;
+        }
+        // static air temperature in 0.25 C
+        let temperature = self.bits(25, 34) as i64;
+        let temperature = if self.bit(24) {
+            temperature - 1024
+        } else {
+            temperature
+        };
+        (-80 * 4..=60 * 4).contains(&temperature)
+    }
+
+    fn is_45(&self) -> bool {
+        if self.any_wrong_status(&[
+            (1, 2, 3),
+            (4, 5, 6),
+            (7, 8, 9),
+            (10, 11, 12),
+            (13, 14, 15),
+            (16, 17, 26),
+            (27, 28, 38),
+            (39, 40, 51),
+        ]) {
+            return false;
+        }
+        // reserved
+        if self.bits(52, 56) != 0 {
+            return false;
+        }
+        // static air temperature in 0.25 C
+        self.signed_value(16, 17, 26).map_or(true, |temperature| {
+            (-80 * 4..=60 * 4).contains(&temperature)
+        })
+    }
+
+    fn is_50(&self) -> bool {
+        if self.any_wrong_status(&[
+            (1, 3, 11),
+            (12, 13, 23),
+            (24, 25, 34),
+            (35, 36, 45),
+            (46, 47, 56),
+        ]) {
+            return false;
+        }
+        // roll angle in 45/256 degrees
+        if self
+            .signed_value(1, 2, 11)
+            .map_or(false, |roll| roll.abs() * 45 > 50 * 256)
+        {
+            return false;
+        }
+        // ground speed and true airspeed in 2 kt
+        let ground_speed = self.status_value(24, 25, 34).map(|speed| speed * 2);
+        let airspeed = self.status_value(46, 47, 56).map(|speed| speed * 2);
+        if ground_speed.map_or(false, |speed| speed > 600) {
+            return false;
+        }
+        if airspeed.map_or(false, |speed| speed > 500) {
+            return false;
+        }
+        match (ground_speed, airspeed) {
+            (Some(ground_speed), Some(airspeed)) => {
+                ground_speed.max(airspeed) - ground_speed.min(airspeed) <= 200
+            },
+            _ => true,
+        }
+    }
+
+    fn is_60(&self) -> bool {
+        if self.any_wrong_status(&[
+            (1, 2, 12),
+            (13, 14, 23),
+            (24, 25, 34),
+            (35, 36, 45),
+            (46, 47, 56),
+        ]) {
+            return false;
+        }
+        // indicated airspeed in kt
+        if self
+            .status_value(13, 14, 23)
+            .map_or(false, |speed| speed > 500)
+        {
+            return false;
+        }
+        // mach in 2.048/512
+        if self
+            .status_value(24, 25, 34)
+            .map_or(false, |mach| mach * 2048 > 512 * 1000)
+        {
+            return false;
+        }
+        // barometric and inertial vertical rate in 32 ft/min
+        let too_fast = |rate: i64| (rate * 32).abs() > 6000;
+        !(self.signed_value(35, 36, 45).map_or(false, too_fast)
+            || self.signed_value(46, 47, 56).map_or(false, too_fast))
+    }
+}
@@ -227,10 +227,115 @@ pub(crate) fn cpr_nl(lat: f64) -> u64 {
 /// Using both an Odd and Even `Altitude`, calculate the latitude/longitude
 ///
 /// reference: ICAO 9871 (D.2.4.7.7)
+///
+/// ```rust
+/// use adsb_deku::adsb::ME;
+/// use adsb_deku::deku::DekuContainerRead;
+/// use adsb_deku::{cpr, Altitude, Frame, DF};
+/// use hexlit::hex;
+///
+/// fn altitude(bytes: &[u8]) -> Altitude {
+///     let frame = Frame::from_bytes((bytes, 0)).unwrap().1;
+///     match frame.df {
+///         DF::ADSB(adsb) => match adsb.me {
+///             ME::AirbornePositionBaroAltitude(altitude) => altitude,
+///             _ => unreachable!(),
+///         },
+///         _ => unreachable!(),
+///     }
+/// }
+///
+/// let odd = altitude(&hex!("8d40621d58c386435cc412692ad6"));
+/// let even = altitude(&hex!("8d40621d58c382d690c8ac2863a7"));
+///
+/// // the even frame was received last
+/// let position = cpr::get_position((&odd, &even)).unwrap();
+/// assert!((position.latitude - 52.2572).abs() < 0.0001);
+/// assert!((position.longitude - 3.9194).abs() < 0.0001);
+/// ```
 #[must_use]
 pub fn get_position(cpr_frames: (&Altitude, &Altitude)) -> Option<Position> {
     let latest_frame = cpr_frames.1;
-    let (even_frame, odd_frame) = match cpr_frames {
+    let (even_frame, odd_frame) = even_odd(cpr_frames)?;
+
+    let cpr_lon_even = f64::from(even_frame.lon_cpr) / CPR_MAX;
+    let cpr_lon_odd = f64::from(odd_frame.lon_cpr) / CPR_MAX;
+
+    let (_, lat_even, lat_odd) = latitudes(even_frame, odd_frame);
+
+    let lat = if latest_frame == even_frame {
+        lat_even
+    } else {
+        lat_odd
+    };
+
+    let (lat, lon) = get_lat_lon(lat, cpr_lon_even, cpr_lon_odd, &latest_frame.odd_flag);
+
+    Some(Position {
+        latitude: lat,
+        longitude: lon,
+    })
+}
+
+/// Zones computed by [`get_position`] from an Odd and Even `Altitude`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Zones {
+    /// Latitude zone index (j)
+    pub lat_index: f64,
+    /// Latitude decoded from the even frame
+    pub lat_even: f64,
+    /// Latitude decoded from the odd frame
+    pub lat_odd: f64,
+    /// Number of longitude zones (NL) at `lat_even`
+    pub nl_even: u64,
+    /// Number of longitude zones (NL) at `lat_odd`
+    pub nl_odd: u64,
+}
+
+/// Using both an Odd and Even `Altitude`, calculate the zones used by [`get_position`]
+///
+/// For debugging wrong positions: if `nl_even` and `nl_odd` differ, the aircraft crossed into
+/// another longitude zone between the two frames.
+///
+/// ```rust
+/// use adsb_deku::adsb::ME;
+/// use adsb_deku::deku::DekuContainerRead;
+/// use adsb_deku::{cpr, Altitude, Frame, DF};
+/// use hexlit::hex;
+///
+/// fn altitude(bytes: &[u8]) -> Altitude {
+///     let frame = Frame::from_bytes((bytes, 0)).unwrap().1;
+///     match frame.df {
+///         DF::ADSB(adsb) => match adsb.me {
+///             ME::AirbornePositionBaroAltitude(altitude) => altitude,
+///             _ => unreachable!(),
+///         },
+///         _ => unreachable!(),
+///     }
+/// }
+///
+/// let odd = altitude(&hex!("8d40621d58c386435cc412692ad6"));
+/// let even = altitude(&hex!("8d40621d58c382d690c8ac2863a7"));
+///
+/// let zones = cpr::zones((&odd, &even)).unwrap();
+/// assert_eq!(zones.nl_even, zones.nl_odd);
+/// ```
+#[must_use]
+pub fn zones(cpr_frames: (&Altitude, &Altitude)) -> Option<Zones> {
+    let (even_frame, odd_frame) = even_odd(cpr_frames)?;
+    let (lat_index, lat_even, lat_odd) = latitudes(even_frame, odd_frame);
+    Some(Zones {
+        lat_index,
+        lat_even,
+        lat_odd,
+        nl_even: cpr_nl(lat_even),
+        nl_odd: cpr_nl(lat_odd),
+    })
+}
+
+/// Order `cpr_frames` as (even, odd), if there is one of each
+fn even_odd<'a>(cpr_frames: (&'a Altitude, &'a Altitude)) -> Option<(&'a Altitude, &'a Altitude)> {
+    match cpr_frames {
         (
             even @ Altitude {
                 odd_flag: CPRFormat::Even,
@@ -250,14 +355,15 @@ pub fn get_position(cpr_frames: (&Altitude, &Altitude)) -> Option<Position> {
                 odd_flag: CPRFormat::Even,
                 ..
             },
-        ) => (even, odd),
-        _ => return None,
-    };
+        ) => Some((even, odd)),
+        _ => None,
+    }
+}
 
+/// Latitude zone index, and the latitudes of the even and odd frame
+fn latitudes(even_frame: &Altitude, odd_frame: &Altitude) -> (f64, f64, f64) {
     let cpr_lat_even = f64::from(even_frame.lat_cpr) / CPR_MAX;
-    let cpr_lon_even = f64::from(even_frame.lon_cpr) / CPR_MAX;
     let cpr_lat_odd = f64::from(odd_frame.lat_cpr) / CPR_MAX;
-    let cpr_lon_odd = f64::from(odd_frame.lon_cpr) / CPR_MAX;
 
     let j = libm::floor(59.0 * cpr_lat_even - 60.0 * cpr_lat_odd + 0.5);
 
@@ -272,18 +378,7 @@ pub fn get_position(cpr_frames: (&Altitude, &Altitude)) -> Option<Position> {
         lat_odd -= 360.0;
     }
 
-    let lat = if latest_frame == even_frame {
-        lat_even
-    } else {
-        lat_odd
-    };
-
-    let (lat, lon) = get_lat_lon(lat, cpr_lon_even, cpr_lon_odd, &latest_frame.odd_flag);
-
-    Some(Position {
-        latitude: lat,
-        longitude: lon,
-    })
+    (j, lat_even, lat_odd)
 }
 
 fn get_lat_lon(
@@ -345,6 +440,31 @@ mod tests {
         assert!((position.longitude - 3.919_372_558_593_75).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn cpr_zones() {
+        let odd = Altitude {
+            odd_flag: CPRFormat::Odd,
+            lat_cpr: 74158,
+            lon_cpr: 50194,
+            ..Altitude::default()
+        };
+        let even = Altitude {
+            odd_flag: CPRFormat::Even,
+            lat_cpr: 93000,
+            lon_cpr: 51372,
+            ..Altitude::default()
+        };
+
+        let pair = zones((&odd, &even)).unwrap();
+        assert!((pair.lat_index - 8.0).abs() < f64::EPSILON);
+        assert!((pair.lat_even - 52.257_202_148_437_5).abs() < f64::EPSILON);
+        assert_eq!(pair.nl_even, 36);
+        assert_eq!(pair.nl_odd, 36);
+
+        // two frames of the same format can't be decoded
+        assert_eq!(zones((&even, &even)), None);
+    }
+
     #[test]
     fn cpr_calculate_position_high_lat() {
         let even = Altitude {
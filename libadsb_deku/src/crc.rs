@@ -1,13 +1,25 @@
+//! Mode S CRC-24 parity
+//!
+//! The last 24 bits of every Mode S message is the PI (Parity/Interrogator) or AP (Address/Parity)
+//! field, containing the CRC-24 of the preceding bits using [`GENERATOR_POLYNOMIAL`]. For DF11 the
+//! lower 7 bits are overlaid by the interrogator code, and for the Address/Parity formats the
+//! parity is overlaid by the ICAO address.
+
 //This module includes functionality translated from mode_s.c
 #[cfg(feature = "alloc")]
 use core::{
     convert::From,
+    option::Option::{self, None, Some},
     result,
     result::Result::{Err, Ok},
 };
 
 use deku::prelude::*;
 
+/// Mode S CRC-24 generator polynomial
+pub const GENERATOR_POLYNOMIAL: u32 = 0x00ff_f409;
+
+/// Lookup table of [`GENERATOR_POLYNOMIAL`] for each byte value
 pub const CRC_TABLE: [u32; 256] = [
     0x0000_0000,
     0x00ff_f409,
@@ -267,19 +279,31 @@ pub const CRC_TABLE: [u32; 256] = [
     0x00fa_0480,
 ];
 
-pub fn modes_checksum(message: &[u8], bits: usize) -> result::Result<u32, DekuError> {
+/// Calculate the CRC-24 parity of `data`, which doesn't include the PI/AP field
+///
+/// This is the value to be placed in the PI field of a DF17/DF18 message.
+#[must_use]
+pub fn crc24(data: &[u8]) -> u32 {
     let mut rem: u32 = 0;
+    for byte in data {
+        rem = (rem << 8) ^ CRC_TABLE[(u32::from(*byte) ^ ((rem & 0x00ff_0000) >> 16)) as usize];
+        rem &= 0x00ff_ffff;
+    }
+    rem
+}
+
+/// Calculate the CRC-24 remainder of the first `bits` of `message`, including the PI/AP field
+///
+/// This results in zero for a valid DF17/DF18 message, the interrogator code for DF11, and the
+/// ICAO address for the Address/Parity formats.
+pub fn modes_checksum(message: &[u8], bits: usize) -> result::Result<u32, DekuError> {
     let n = bits / 8;
 
     if (n < 3) || (message.len() < n) {
         return Err(DekuError::Incomplete(NeedSize::new(4)));
     }
 
-    for i in 0..(n - 3) {
-        rem =
-            (rem << 8) ^ CRC_TABLE[(u32::from(message[i]) ^ ((rem & 0x00ff_0000) >> 16)) as usize];
-        rem &= 0x00ff_ffff;
-    }
+    let mut rem = crc24(&message[..n - 3]);
 
     let msg_1 = u32::from(message[n - 3]) << 16;
     let msg_2 = u32::from(message[n - 2]) << 8;
@@ -290,3 +314,144 @@ pub fn modes_checksum(message: &[u8], bits: usize) -> result::Result<u32, DekuEr
 
     Ok(rem)
 }
+
+/// Maximum amount of bit errors [`fix_bit_errors`] attempts to fix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCorrection {
+    /// Fix single bit errors
+    SingleBit,
+    /// Fix single and two bit errors
+    ///
+    /// This greatly increases the chance of "fixing" a garbage message into a valid looking
+    /// one, and should only be used for weak signals.
+    TwoBit,
+}
+
+/// Bit indexes flipped by [`fix_bit_errors`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitFix {
+    One(usize),
+    Two(usize, usize),
+}
+
+/// Attempt to fix bit errors in the first `bits` of `message`, which has a non-zero CRC-24
+/// remainder
+///
+/// Only useful for DF17/DF18, where the remainder of a valid message is zero. The first 5 bits
+/// (Downlink Format) are never changed, as that would change how the message is decoded. On
+/// success `message` is repaired and the indexes of the flipped bits are returned.
+pub fn fix_bit_errors(
+    message: &mut [u8],
+    bits: usize,
+    correction: ErrorCorrection,
+) -> Option<BitFix> {
+    const MAX_BITS: usize = 112;
+
+    let rem = modes_checksum(message, bits).ok()?;
+    if rem == 0 || bits > MAX_BITS {
+        return None;
+    }
+
+    // The CRC is linear, so the remainder of a message with flipped bits is the remainder of
+    // the original message xor the remainder of each flipped bit on its own
+    let mut syndromes = [0; MAX_BITS];
+    let mut zeros = [0; MAX_BITS / 8];
+    for (bit, syndrome) in syndromes.iter_mut().enumerate().take(bits) {
+        zeros[bit / 8] = 0x80 >> (bit % 8);
+        *syndrome = modes_checksum(&zeros, bits).ok()?;
+        zeros[bit / 8] = 0;
+    }
+
+    let fix = if let Some(bit) = (5..bits).find(|&bit| syndromes[bit] == rem) {
+        BitFix::One(bit)
+    } else if correction == ErrorCorrection::TwoBit {
+        let (first, second) = (5..bits)
+            .flat_map(|first| (first + 1..bits).map(move |second| (first, second)))
+            .find(|&(first, second)| syndromes[first] ^ syndromes[second] == rem)?;
+        BitFix::Two(first, second)
+    } else {
+        return None;
+    };
+
+    match fix {
+        BitFix::One(bit) => flip_bit(message, bit),
+        BitFix::Two(first, second) => {
+            flip_bit(message, first);
+            flip_bit(message, second);
+        },
+    }
+
+    Some(fix)
+}
+
+fn flip_bit(message: &mut [u8], bit: usize) {
+    message[bit / 8] ^= 0x80 >> (bit % 8);
+}
+
+#[cfg(test)]
+mod tests {
+    use hexlit::hex;
+
+    use super::*;
+
+    #[test]
+    fn crc24_table() {
+        // CRC_TABLE is the bitwise division of each byte by the polynomial
+        for (byte, entry) in CRC_TABLE.iter().enumerate() {
+            let mut rem = (byte as u32) << 16;
+            for _ in 0..8 {
+                rem <<= 1;
+                if rem & 0x0100_0000 != 0 {
+                    rem ^= 0x0100_0000 | GENERATOR_POLYNOMIAL;
+                }
+            }
+            assert_eq!(rem, *entry);
+        }
+    }
+
+    #[test]
+    fn crc24_adsb() {
+        let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+        assert_eq!(crc24(&bytes[..11]), 0x00cb_802b);
+        assert_eq!(modes_checksum(&bytes, 112).unwrap(), 0);
+    }
+
+    #[test]
+    fn fix_single_bit() {
+        let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+        for bit in 5..112 {
+            let mut corrupted = bytes;
+            flip_bit(&mut corrupted, bit);
+            assert_eq!(
+                fix_bit_errors(&mut corrupted, 112, ErrorCorrection::SingleBit),
+                Some(BitFix::One(bit))
+            );
+            assert_eq!(corrupted, bytes);
+        }
+
+        // DF bits are never fixed
+        let mut corrupted = bytes;
+        flip_bit(&mut corrupted, 0);
+        assert_eq!(
+            fix_bit_errors(&mut corrupted, 112, ErrorCorrection::TwoBit),
+            None
+        );
+    }
+
+    #[test]
+    fn fix_two_bits() {
+        let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+        let mut corrupted = bytes;
+        flip_bit(&mut corrupted, 40);
+        flip_bit(&mut corrupted, 90);
+        assert_eq!(
+            fix_bit_errors(&mut corrupted.clone(), 112, ErrorCorrection::SingleBit),
+            None
+        );
+        assert_eq!(
+            fix_bit_errors(&mut corrupted, 112, ErrorCorrection::TwoBit),
+            Some(BitFix::Two(40, 90))
+        );
+        assert_eq!(corrupted, bytes);
+    }
+}
@@ -27,6 +27,7 @@ See [`rsadsb.github.io`] for more details.
 | (0,0)     | [`Empty`]                               |             |
 | (1,0)     | [`Data Link Capability`]                | A-2-16      |
 | (2,0)     | [`Aircraft Identification`]             | A-2-32      |
+| (3,0)     | [`ACAS Resolution Advisory`]            | A-2-48      |
 
 # [`Extended Squitter(ADS-B)`] and [`Extended Squitter(TIS-B)`] Type Code Support
 
@@ -38,7 +39,7 @@ See [`rsadsb.github.io`] for more details.
 | 9..=18              | [`ME::AirbornePositionBaroAltitude`]   |
 | 19                  | [`ME::AirborneVelocity`]               |
 | 20..=22             | [`ME::AirbornePositionGNSSAltitude`]   |
-| 23                  | [`ME::Reserved0`]                      |
+| 23                  | [`ME::TestMessage`]                    |
 | 24                  | [`ME::SurfaceSystemStatus`]            |
 | 25..=27             | [`ME::Reserved1`]                      |
 | 28                  | [`ME::AircraftStatus`]                 |
@@ -46,6 +47,19 @@ See [`rsadsb.github.io`] for more details.
 | 30                  | [`ME::AircraftOperationalCoordination`]|
 | 31                  | [`ME::AircraftOperationStatus`]        |
 
+# Uplink Format Support
+Interrogations received on 1030 MHz are decoded with [`UplinkFrame`].
+
+|  [`UF`]  |  Name                               |
+| -------- | ----------------------------------- |
+| 0        | [`UF::ShortAirAirSurveillance`]     |
+| 4        | [`UF::SurveillanceAltitudeRequest`] |
+| 5        | [`UF::SurveillanceIdentityRequest`] |
+| 11       | [`UF::AllCall`]                     |
+| 16       | [`UF::LongAirAir`]                  |
+| 20       | [`UF::CommAAltitudeRequest`]        |
+| 21       | [`UF::CommAIdentityRequest`]        |
+
 # Example
 To begin using `adsb_deku`, import the [`Frame`] struct as well as the trait [`deku::DekuContainerRead`].
 This trait is re-exported for your convenience. [`Frame::from_bytes()`] provides the interface for decoding bytes
@@ -60,7 +74,7 @@ let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
 let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
 assert_eq!(
         r#" Extended Squitter Airborne position (barometric altitude)
-  Address:       a2c1bd (Mode S / ADS-B)
+  Address:       A2C1BD (Mode S / ADS-B)
   Air/Ground:    airborne
   Altitude:      23650 ft barometric
   CPR type:      Airborne
@@ -72,6 +86,19 @@ assert_eq!(
 );
 ```
 
+All types also implement [`deku::DekuWrite`], so a [`Frame`] can be written back into bytes with
+[`deku::DekuContainerWrite::to_bytes()`].
+
+```rust
+use hexlit::hex;
+use adsb_deku::Frame;
+use adsb_deku::deku::{DekuContainerRead, DekuContainerWrite};
+
+let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+assert_eq!(frame.to_bytes().unwrap(), bytes);
+```
+
 # Apps
 The [`apps/`] directory of the project repository contains programs `radar` and `1090` for showcasing
 different `adsb_deku` uses. See the [`README.md`] for examples of use.
@@ -86,7 +113,7 @@ different `adsb_deku` uses. See the [`README.md`] for examples of use.
 [`Long Air-Air Surveillance`]: crate::DF::LongAirAir
 [`Extended Squitter(ADS-B)`]: crate::DF::ADSB
 [`Extended Squitter(TIS-B)`]: crate::DF::TisB
-[`Extended Squitter(Military)`]: crate::DF::ExtendedQuitterMilitaryApplication
+[`Extended Squitter(Military)`]: crate::DF::ExtendedSquitterMilitary
 [`Comm-B Altitude Reply`]: crate::DF::CommBAltitudeReply
 [`Comm-B Identity Reply`]: crate::DF::CommBIdentityReply
 [`Comm-D`]: crate::DF::CommDExtendedLengthMessage
@@ -94,13 +121,23 @@ different `adsb_deku` uses. See the [`README.md`] for examples of use.
 [`Empty`]: crate::bds::BDS::Empty
 [`Data Link Capability`]: crate::bds::BDS::DataLinkCapability
 [`Aircraft Identification`]: crate::bds::BDS::AircraftIdentification
+[`ACAS Resolution Advisory`]: crate::bds::BDS::AcasResolutionAdvisory
+[`UplinkFrame`]: crate::uplink::UplinkFrame
+[`UF`]: crate::uplink::UF
+[`UF::ShortAirAirSurveillance`]: crate::uplink::UF::ShortAirAirSurveillance
+[`UF::SurveillanceAltitudeRequest`]: crate::uplink::UF::SurveillanceAltitudeRequest
+[`UF::SurveillanceIdentityRequest`]: crate::uplink::UF::SurveillanceIdentityRequest
+[`UF::AllCall`]: crate::uplink::UF::AllCall
+[`UF::LongAirAir`]: crate::uplink::UF::LongAirAir
+[`UF::CommAAltitudeRequest`]: crate::uplink::UF::CommAAltitudeRequest
+[`UF::CommAIdentityRequest`]: crate::uplink::UF::CommAIdentityRequest
 [`ME::NoPosition`]: crate::adsb::ME::NoPosition
 [`ME::AircraftIdentification`]: crate::adsb::ME::AircraftIdentification
 [`ME::SurfacePosition`]: crate::adsb::ME::SurfacePosition
 [`ME::AirbornePositionBaroAltitude`]: crate::adsb::ME::AirbornePositionBaroAltitude
 [`ME::AirborneVelocity`]: crate::adsb::ME::AirborneVelocity
 [`ME::AirbornePositionGNSSAltitude`]: crate::adsb::ME::AirbornePositionGNSSAltitude
-[`ME::Reserved0`]: crate::adsb::ME::Reserved0
+[`ME::TestMessage`]: crate::adsb::ME::TestMessage
 [`ME::SurfaceSystemStatus`]: crate::adsb::ME::SurfaceSystemStatus
 [`ME::Reserved1`]: crate::adsb::ME::Reserved1
 [`ME::AircraftStatus`]: crate::adsb::ME::AircraftStatus
@@ -126,6 +163,7 @@ extern crate alloc;
 
 #[cfg(feature = "alloc")]
 use alloc::{fmt, format, string::String, string::ToString, vec, vec::Vec};
+use core::str::FromStr;
 #[cfg(feature = "alloc")]
 use core::{
     clone::Clone,
@@ -142,34 +180,91 @@ use core::{
     write, writeln,
 };
 
+pub mod acas;
 pub mod adsb;
 pub mod bds;
+#[cfg(feature = "icao_flags")]
+pub mod country;
 pub mod cpr;
-mod crc;
-mod mode_ac;
+pub mod crc;
+pub mod mode_ac;
+pub mod registration;
+pub mod uplink;
 
 #[doc = include_str!("../README.md")]
 mod readme_test {}
 
-use adsb::{ControlField, ADSB};
-use bds::BDS;
-use deku::bitvec::{BitSlice, Msb0};
+use acas::AcasMessage;
+use adsb::{ControlField, ControlFieldType, ADSB};
+use bds::CommB;
+use deku::bitvec::{BitSlice, BitVec, Msb0};
 use deku::prelude::*;
 
 /// Downlink ADS-B Packet
-#[derive(Debug, PartialEq, DekuRead, Clone)]
+///
+/// ```rust
+/// use adsb_deku::deku::DekuContainerRead;
+/// use adsb_deku::{Frame, DF, ICAO};
+/// use hexlit::hex;
+///
+/// let bytes = hex!("8d4840d6202cc371c32ce0576098");
+/// let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+/// assert!(matches!(frame.df, DF::ADSB(_)));
+/// assert!(frame.is_valid());
+/// assert_eq!(frame.icao(), Some(ICAO([0x48, 0x40, 0xd6])));
+///
+/// // a corrupted bit fails the parity check
+/// let mut bytes = bytes;
+/// bytes[5] ^= 0x01;
+/// let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+/// assert!(!frame.is_valid());
+/// ```
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct Frame {
     /// Starting with 5 bit identifier, decode packet
     pub df: DF,
     /// Calculated from all bits, used as ICAO for Response packets
-    #[deku(reader = "Self::read_crc(df, deku::input_bits)")]
+    ///
+    /// Not written, the parity is contained within the fields of `df`
+    #[deku(
+        reader = "Self::read_crc(df, deku::input_bits)",
+        writer = "Ok::<_, DekuError>(())"
+    )]
     pub crc: u32,
 }
 
+/// [`Frame`] with a checked Parity/Interrogator field
+///
+/// Created with [`Frame::validate`], allowing consumers to require at compile time that garbage
+/// frames have been filtered out.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValidFrame(Frame);
+
+impl ValidFrame {
+    /// Wrap `frame` without checking the Parity/Interrogator field
+    #[must_use]
+    pub const fn new_unchecked(frame: Frame) -> Self {
+        Self(frame)
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> Frame {
+        self.0
+    }
+}
+
+impl core::ops::Deref for ValidFrame {
+    type Target = Frame;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 impl Frame {
     /// Read rest as CRC bits
-    fn read_crc<'a, 'b>(
-        df: &'a DF,
+    fn read_crc<'b>(
+        df: &DF,
         rest: &'b BitSlice<Msb0, u8>,
     ) -> result::Result<(&'b BitSlice<Msb0, u8>, u32), DekuError> {
         const MODES_LONG_MSG_BYTES: usize = 14;
@@ -181,46 +276,205 @@ impl Frame {
             } else {
                 MODES_SHORT_MSG_BYTES * 8
             }
+        } else if let DF::UnknownShort { .. } = df {
+            MODES_SHORT_MSG_BYTES * 8
         } else {
-            // In this case, it's the DF::CommD, which has multiple ids
+            // In this case, it's the DF::CommD or DF::UnknownLong, which have multiple ids
             MODES_LONG_MSG_BYTES * 8
         };
 
         let crc = crc::modes_checksum(rest.as_raw_slice(), bit_len)?;
         Ok((rest, crc))
     }
+
+    /// Read a `Frame` from `bytes`, attempting to fix bit errors in DF17/DF18 frames
+    ///
+    /// Other formats are read as with [`Frame::from_bytes`]. Returns the indexes of the bits that
+    /// were flipped if a fix was applied. A frame that can't be fixed is still returned, use
+    /// [`Frame::is_valid`] to check it.
+    pub fn from_bytes_with_fix(
+        bytes: &[u8],
+        correction: crc::ErrorCorrection,
+    ) -> result::Result<(Self, Option<crc::BitFix>), DekuError> {
+        const MODES_LONG_MSG_BYTES: usize = 14;
+
+        let df = bytes.first().map(|b| b >> 3);
+        if matches!(df, Some(17 | 18)) && bytes.len() >= MODES_LONG_MSG_BYTES {
+            let mut fixed = [0; MODES_LONG_MSG_BYTES];
+            fixed.copy_from_slice(&bytes[..MODES_LONG_MSG_BYTES]);
+            let fix = crc::fix_bit_errors(&mut fixed, MODES_LONG_MSG_BYTES * 8, correction);
+            if fix.is_some() {
+                let (_, frame) = Self::from_bytes((&fixed, 0))?;
+                return Ok((frame, fix));
+            }
+        }
+
+        let (_, frame) = Self::from_bytes((bytes, 0))?;
+        Ok((frame, None))
+    }
+
+    /// Read a `Frame` from `bytes`, if its Type Code is allowed by `filter`
+    ///
+    /// DF17/DF18 frames with a Type Code not in `filter` are skipped without decoding them,
+    /// returning `None`. Other formats are read as with [`Frame::from_bytes`].
+    pub fn from_bytes_filtered(
+        bytes: &[u8],
+        filter: adsb::TypeCodeFilter,
+    ) -> result::Result<Option<Self>, DekuError> {
+        if !filter.allows(bytes) {
+            return Ok(None);
+        }
+        let (_, frame) = Self::from_bytes((bytes, 0))?;
+        Ok(Some(frame))
+    }
+
+    /// CRC-24 remainder of all bits of the frame, including the PI/AP field
+    ///
+    /// See [`crc::modes_checksum`] for the meaning of this value for each DF.
+    #[must_use]
+    pub const fn crc(&self) -> u32 {
+        self.crc
+    }
+
+    /// Parity/Interrogator field of the frame checks out
+    ///
+    /// Only DF11, DF17 and DF18 contain a PI field that can be checked without knowing the
+    /// ICAO address of the aircraft. DF11 is valid if the remainder is a valid interrogator code.
+    /// Formats not assigned by the specification are never valid. All other formats overlay the
+    /// parity with the address, and always return `true`.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        match self.df {
+            DF::ADSB(_) | DF::TisB { .. } => self.crc == 0,
+            // CL(3 bits) and IC(4 bits), with CL values 5..=7 unassigned
+            DF::AllCallReply { .. } => self.crc <= 0x7f && (self.crc >> 4) <= 4,
+            DF::UnknownShort { .. } | DF::UnknownLong { .. } => false,
+            _ => true,
+        }
+    }
+
+    /// Check the Parity/Interrogator field, see [`Frame::is_valid`]
+    ///
+    /// Returns the `Frame` as is if it isn't valid.
+    pub fn validate(self) -> result::Result<ValidFrame, Self> {
+        if self.is_valid() {
+            Ok(ValidFrame(self))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Code of the interrogator a DF11 All Call Reply is answering, recovered from the PI field
+    ///
+    /// Returns `None` for all other formats, or if the PI field doesn't contain a valid code.
+    #[must_use]
+    pub fn interrogator_code(&self) -> Option<InterrogatorCode> {
+        match self.df {
+            DF::AllCallReply { .. } => InterrogatorCode::from_pi(self.crc),
+            _ => None,
+        }
+    }
+
+    /// Reason this decoded frame can't be used, see [`ErrorKind`]
+    ///
+    /// Returns `None` if the frame [`is_valid`](Frame::is_valid).
+    #[must_use]
+    pub fn error_kind(&self) -> Option<ErrorKind> {
+        match self.df {
+            DF::UnknownShort { .. } | DF::UnknownLong { .. } => Some(ErrorKind::Unsupported),
+            _ if !self.is_valid() => Some(ErrorKind::Crc),
+            _ => None,
+        }
+    }
+
+    /// Transponder capability (CA field) of DF11 and DF17 frames
+    #[must_use]
+    pub const fn capability(&self) -> Option<Capability> {
+        match &self.df {
+            DF::ADSB(adsb::ADSB { capability, .. }) | DF::AllCallReply { capability, .. } => {
+                Some(*capability)
+            },
+            _ => None,
+        }
+    }
+
+    /// Address space of [`Frame::icao`]
+    ///
+    /// DF18 frames may carry a non-ICAO address, see [`adsb::ControlField::address_qualifier`],
+    /// which must not be tracked as the aircraft of the same ICAO address. All other frames with
+    /// an address carry an ICAO address.
+    #[must_use]
+    pub fn address_qualifier(&self) -> Option<adsb::AddressQualifier> {
+        match &self.df {
+            DF::TisB { cf, .. } => cf.address_qualifier(),
+            _ => self.icao().map(|_| adsb::AddressQualifier::Icao),
+        }
+    }
+
+    /// ICAO address of the aircraft
+    ///
+    /// For the Address/Parity formats (DF0, DF4, DF5, DF16, DF20, DF21, DF24) the address is
+    /// recovered from the CRC remainder, which is only correct if the frame isn't corrupted.
+    /// Returns `None` for DF19 and TIS-B/ADS-R management messages, which don't carry an address,
+    /// and for formats not assigned by the specification.
+    #[must_use]
+    pub fn icao(&self) -> Option<ICAO> {
+        match &self.df {
+            DF::ADSB(adsb) => Some(adsb.icao),
+            DF::TisB { cf, .. } => (cf.t != ControlFieldType::TISB_MANAGE).then(|| cf.aa),
+            DF::AllCallReply { icao, .. } => Some(*icao),
+            DF::ExtendedSquitterMilitary { .. }
+            | DF::UnknownShort { .. }
+            | DF::UnknownLong { .. } => None,
+            DF::ShortAirAirSurveillance { .. }
+            | DF::SurveillanceAltitudeReply(_)
+            | DF::SurveillanceIdentityReply(_)
+            | DF::LongAirAir { .. }
+            | DF::CommBAltitudeReply(_)
+            | DF::CommBIdentityReply(_)
+            | DF::CommDExtendedLengthMessage(_) => Some(ICAO::from_u32(self.crc)),
+        }
+    }
 }
 
 impl fmt::Display for Frame {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let crc = self.crc;
+        // address of the Address/Parity formats
+        let crc = ICAO::from_u32(self.crc);
         match &self.df {
-            DF::ShortAirAirSurveillance { altitude, .. } => {
+            DF::ShortAirAirSurveillance {
+                vs,
+                cc,
+                sl,
+                ri,
+                altitude,
+                ..
+            } => {
                 writeln!(f, " Short Air-Air Surveillance")?;
-                writeln!(f, "  ICAO Address:  {crc:06x} (Mode S / ADS-B)")?;
+                writeln!(f, "  ICAO Address:  {crc} (Mode S / ADS-B)")?;
+                writeln!(f, "  Air/Ground:    {vs}")?;
                 if altitude.0 > 0 {
                     let altitude = altitude.0;
-                    writeln!(f, "  Air/Ground:    airborne?")?;
                     writeln!(f, "  Altitude:      {altitude} ft barometric")?;
-                } else {
-                    writeln!(f, "  Air/Ground:    ground")?;
                 }
+                let cc = if *cc { "supported" } else { "not supported" };
+                writeln!(f, "  Cross-link:    {cc}")?;
+                writeln!(f, "  Sensitivity:   {sl}")?;
+                writeln!(f, "  Reply Info:    {ri}")?;
             },
-            DF::SurveillanceAltitudeReply { fs, ac, .. } => {
+            DF::SurveillanceAltitudeReply(reply) => {
                 writeln!(f, " Surveillance, Altitude Reply")?;
-                writeln!(f, "  ICAO Address:  {crc:06x} (Mode S / ADS-B)")?;
-                writeln!(f, "  Air/Ground:    {fs}")?;
-                if ac.0 > 0 {
-                    let altitude = ac.0;
+                writeln!(f, "  ICAO Address:  {crc} (Mode S / ADS-B)")?;
+                writeln!(f, "  Air/Ground:    {}", reply.fs)?;
+                if let Some(altitude) = reply.altitude() {
                     writeln!(f, "  Altitude:      {altitude} ft barometric")?;
                 }
             },
-            DF::SurveillanceIdentityReply { fs, id, .. } => {
-                let identity = id.0;
+            DF::SurveillanceIdentityReply(reply) => {
                 writeln!(f, " Surveillance, Identity Reply")?;
-                writeln!(f, "  ICAO Address:  {crc:06x} (Mode S / ADS-B)")?;
-                writeln!(f, "  Air/Ground:    {fs}")?;
-                writeln!(f, "  Identity:      {identity:04x}")?;
+                writeln!(f, "  ICAO Address:  {crc} (Mode S / ADS-B)")?;
+                writeln!(f, "  Air/Ground:    {}", reply.fs)?;
+                writeln!(f, "  Identity:      {}", reply.squawk())?;
             },
             DF::AllCallReply {
                 capability, icao, ..
@@ -228,18 +482,27 @@ impl fmt::Display for Frame {
                 writeln!(f, " All Call Reply")?;
                 writeln!(f, "  ICAO Address:  {icao} (Mode S / ADS-B)")?;
                 writeln!(f, "  Air/Ground:    {capability}")?;
+                if let Some(ic) = self.interrogator_code() {
+                    writeln!(f, "  Interrogator:  {ic}")?;
+                }
             },
-            DF::LongAirAir { altitude, .. } => {
+            DF::LongAirAir {
+                vs,
+                sl,
+                ri,
+                altitude,
+                mv,
+                ..
+            } => {
                 writeln!(f, " Long Air-Air ACAS")?;
-                writeln!(f, "  ICAO Address:  {crc:06x} (Mode S / ADS-B)")?;
-                // TODO the airborne? should't be static
-                if altitude.0 > 0 {
-                    let altitude = altitude.0;
-                    writeln!(f, "  Air/Ground:    airborne?")?;
+                writeln!(f, "  ICAO Address:  {crc} (Mode S / ADS-B)")?;
+                writeln!(f, "  Air/Ground:    {vs}")?;
+                if let Some(altitude) = altitude.altitude() {
                     writeln!(f, "  Baro altitude: {altitude} ft")?;
-                } else {
-                    writeln!(f, "  Air/Ground:    ground")?;
                 }
+                writeln!(f, "  Sensitivity:   {sl}")?;
+                writeln!(f, "  Reply Info:    {ri}")?;
+                write!(f, "{mv}")?;
             },
             DF::ADSB(adsb) => {
                 write!(f, "{}", adsb.to_string("(Mode S / ADS-B)")?)?;
@@ -247,24 +510,39 @@ impl fmt::Display for Frame {
             DF::TisB { cf, .. } => {
                 write!(f, "{}", cf)?;
             },
-            // TODO
-            DF::ExtendedQuitterMilitaryApplication { .. } => {},
-            DF::CommBAltitudeReply { bds, alt, .. } => {
+            DF::ExtendedSquitterMilitary { af, payload } => {
+                writeln!(f, " Extended Squitter Military Application")?;
+                writeln!(f, "  Application:   {af}")?;
+                if cfg!(feature = "military") {
+                    write!(f, "  Data:          ")?;
+                    for byte in payload {
+                        write!(f, "{byte:02x}")?;
+                    }
+                    writeln!(f)?;
+                }
+            },
+            DF::CommBAltitudeReply(reply) => {
                 writeln!(f, " Comm-B, Altitude Reply")?;
-                writeln!(f, "  ICAO Address:  {crc:x?} (Mode S / ADS-B)")?;
-                let altitude = alt.0;
-                writeln!(f, "  Altitude:      {altitude} ft")?;
-                write!(f, "  {}", bds)?;
+                writeln!(f, "  ICAO Address:  {crc} (Mode S / ADS-B)")?;
+                if let Some(altitude) = reply.altitude() {
+                    writeln!(f, "  Altitude:      {altitude} ft")?;
+                }
+                write!(f, "  {}", reply.mb)?;
             },
-            DF::CommBIdentityReply { id, bds, .. } => {
+            DF::CommBIdentityReply(reply) => {
                 writeln!(f, " Comm-B, Identity Reply")?;
-                writeln!(f, "    ICAO Address:  {crc:x?} (Mode S / ADS-B)")?;
-                writeln!(f, "    Squawk:        {id:x?}")?;
-                write!(f, "    {}", bds)?;
+                writeln!(f, "    ICAO Address:  {crc} (Mode S / ADS-B)")?;
+                writeln!(f, "    Squawk:        {}", reply.squawk())?;
+                write!(f, "    {}", reply.mb)?;
             },
-            DF::CommDExtendedLengthMessage { .. } => {
+            DF::UnknownShort { df, .. } | DF::UnknownLong { df, .. } => {
+                writeln!(f, " Unknown Downlink Format {df}")?;
+            },
+            DF::CommDExtendedLengthMessage(message) => {
                 writeln!(f, " Comm-D Extended Length Message")?;
-                writeln!(f, "    ICAO Address:     {crc:x?} (Mode S / ADS-B)")?;
+                writeln!(f, "    ICAO Address:     {crc} (Mode S / ADS-B)")?;
+                writeln!(f, "    Control:          {}", message.ke)?;
+                writeln!(f, "    Segment:          {}", message.nd)?;
             },
         }
         Ok(())
@@ -274,30 +552,29 @@ impl fmt::Display for Frame {
 /// Downlink Format (3.1.2.3.2.1.2)
 ///
 /// Starting with 5 bits, decode the rest of the message as the correct data packets
-#[derive(Debug, PartialEq, DekuRead, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 #[deku(type = "u8", bits = "5")]
+#[non_exhaustive]
 pub enum DF {
     /// 0: (Mode S) Short Air-Air Surveillance, Downlink Format 0 (3.1.2.8.2)
     #[deku(id = "0")]
     ShortAirAirSurveillance {
         /// VS: Vertical Status
+        vs: VerticalStatus,
+        /// CC: Cross-link Capability, supports replying with DF16 to a `UF0` with `DS` set
         #[deku(bits = "1")]
-        vs: u8,
-        /// CC:
-        #[deku(bits = "1")]
-        cc: u8,
+        cc: bool,
         /// Spare
         #[deku(bits = "1")]
         unused: u8,
-        /// SL: Sensitivity level, ACAS
+        /// SL: Sensitivity level, ACAS. 0 if ACAS is inoperative
         #[deku(bits = "3")]
         sl: u8,
         /// Spare
         #[deku(bits = "2")]
         unused1: u8,
         /// RI: Reply Information
-        #[deku(bits = "4")]
-        ri: u8,
+        ri: ReplyInformation,
         /// Spare
         #[deku(bits = "2")]
         unused2: u8,
@@ -309,33 +586,11 @@ pub enum DF {
 
     /// 4: (Mode S) Surveillance Altitude Reply, Downlink Format 4 (3.1.2.6.5)
     #[deku(id = "4")]
-    SurveillanceAltitudeReply {
-        /// FS: Flight Status
-        fs: FlightStatus,
-        /// DR: DownlinkRequest
-        dr: DownlinkRequest,
-        /// UM: Utility Message
-        um: UtilityMessage,
-        /// AC: AltitudeCode
-        ac: AC13Field,
-        /// AP: Address/Parity
-        ap: ICAO,
-    },
+    SurveillanceAltitudeReply(SurveillanceAltitudeReply),
 
     /// 5: (Mode S) Surveillance Identity Reply (3.1.2.6.7)
     #[deku(id = "5")]
-    SurveillanceIdentityReply {
-        /// FS: Flight Status
-        fs: FlightStatus,
-        /// DR: Downlink Request
-        dr: DownlinkRequest,
-        /// UM: UtilityMessage
-        um: UtilityMessage,
-        /// ID: Identity
-        id: IdentityCode,
-        /// AP: Address/Parity
-        ap: ICAO,
-    },
+    SurveillanceIdentityReply(SurveillanceIdentityReply),
 
     /// 11: (Mode S) All-call reply, Downlink format 11 (2.1.2.5.2.2)
     #[deku(id = "11")]
@@ -351,23 +606,23 @@ pub enum DF {
     /// 16: (Mode S) Long Air-Air Surveillance Downlink Format 16 (3.1.2.8.3)
     #[deku(id = "16")]
     LongAirAir {
-        #[deku(bits = "1")]
-        vs: u8,
+        /// VS: Vertical Status
+        vs: VerticalStatus,
         #[deku(bits = "2")]
         spare1: u8,
+        /// SL: Sensitivity level, ACAS. 0 if ACAS is inoperative
         #[deku(bits = "3")]
         sl: u8,
         #[deku(bits = "2")]
         spare2: u8,
-        #[deku(bits = "4")]
-        ri: u8,
+        /// RI: Reply Information
+        ri: ReplyInformation,
         #[deku(bits = "2")]
         spare3: u8,
         /// AC: altitude code
         altitude: AC13Field,
         /// MV: message, acas
-        #[deku(count = "7")]
-        mv: Vec<u8>,
+        mv: AcasMessage,
         /// AP: address, parity
         parity: ICAO,
     },
@@ -391,80 +646,71 @@ pub enum DF {
     },
 
     /// 19: Extended Squitter Military Application, Downlink Format 19 (3.1.2.8.8)
+    ///
+    /// Without feature `military`, only the AF field is read, `payload` is all zeros and the rest
+    /// of the frame is left over.
     #[deku(id = "19")]
-    ExtendedQuitterMilitaryApplication {
-        /// Reserved
+    ExtendedSquitterMilitary {
+        /// AF: Application Field
         #[deku(bits = "3")]
         af: u8,
+        /// Bits after the AF field, not decoded
+        #[cfg_attr(not(feature = "military"), deku(skip, default = "[0; 13]"))]
+        payload: [u8; 13],
     },
 
     /// 20: COMM-B Altitude Reply (3.1.2.6.6)
     #[deku(id = "20")]
-    CommBAltitudeReply {
-        /// FS: Flight Status
-        flight_status: FlightStatus,
-        /// DR: Downlink Request
-        dr: DownlinkRequest,
-        /// UM: Utility Message
-        um: UtilityMessage,
-        /// AC: Altitude Code
-        alt: AC13Field,
-        /// MB Message, Comm-B
-        bds: BDS,
-        /// AP: address/parity
-        parity: ICAO,
-    },
+    CommBAltitudeReply(CommBAltitudeReply),
 
     /// 21: COMM-B Reply, Downlink Format 21 (3.1.2.6.8)
     #[deku(id = "21")]
-    CommBIdentityReply {
-        /// FS: Flight Status
-        fs: FlightStatus,
-        /// DR: Downlink Request
-        dr: DownlinkRequest,
-        /// UM: Utility Message
-        um: UtilityMessage,
-        /// ID: Identity
-        #[deku(
-            bits = "13",
-            endian = "big",
-            map = "|squawk: u32| -> Result<_, DekuError> {Ok(mode_ac::decode_id13_field(squawk))}"
-        )]
-        id: u32,
-        /// MB Message, Comm-B
-        bds: BDS,
-        /// AP address/parity
-        parity: ICAO,
+    CommBIdentityReply(CommBIdentityReply),
+
+    /// 1..=3, 6..=10, 12..=15: Short format not assigned by the specification
+    #[deku(id_pat = "1..=3 | 6..=10 | 12..=15")]
+    UnknownShort {
+        /// DF: Downlink Format
+        #[deku(bits = "5")]
+        df: u8,
+        /// Bits between the DF and AP fields
+        #[deku(bits = "27", endian = "big")]
+        data: u32,
+        /// AP: address/parity
+        ap: ICAO,
     },
 
-    /// 24..=31: Comm-D(ELM), Downlink Format 24 (3.1.2.7.3)
-    #[deku(id_pat = "24..=31")]
-    CommDExtendedLengthMessage {
-        /// Spare - 1 bit
-        #[deku(bits = "1")]
-        spare: u8,
-        /// KE: control, ELM
-        ke: KE,
-        /// ND: number of D-segment
-        #[deku(bits = "4")]
-        nd: u8,
-        /// MD: message, Comm-D, 80 bits
-        #[deku(count = "10")]
-        md: Vec<u8>,
+    /// 22..=23: Long format not assigned by the specification
+    #[deku(id_pat = "22..=23")]
+    UnknownLong {
+        /// DF: Downlink Format
+        #[deku(bits = "5")]
+        df: u8,
+        /// Bits between the DF and AP fields
+        #[deku(bits = "83", endian = "big")]
+        data: u128,
         /// AP: address/parity
-        parity: ICAO,
+        ap: ICAO,
     },
+
+    /// 24..=31: Comm-D(ELM), Downlink Format 24 (3.1.2.7.3)
+    #[deku(id_pat = "24..=31")]
+    CommDExtendedLengthMessage(CommDExtendedLengthMessage),
 }
 
 /// Latitude, Longitude and Altitude information
-#[derive(Debug, PartialEq, Eq, DekuRead, Default, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Default, Copy, Clone)]
 pub struct Altitude {
     #[deku(bits = "5")]
     pub tc: u8,
     pub ss: SurveillanceStatus,
     #[deku(bits = "1")]
     pub saf_or_imf: u8,
-    #[deku(reader = "Self::read(deku::rest)")]
+    /// Altitude in ft, barometric for TC 9..=18 and GNSS height (HAE) for TC 20..=22
+    #[deku(
+        reader = "Self::read_tc(deku::rest, *tc)",
+        writer = "Self::write_tc(deku::output, *tc, *alt)"
+    )]
     pub alt: Option<u16>,
     /// UTC sync or not
     #[deku(bits = "1")]
@@ -479,11 +725,20 @@ pub struct Altitude {
 
 impl fmt::Display for Altitude {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = if self.is_gnss() {
+            "GNSS height (HAE)"
+        } else {
+            "barometric"
+        };
         let altitude = self.alt.map_or_else(
             || "None".to_string(),
-            |altitude| format!("{} ft barometric", altitude),
+            |altitude| format!("{} ft {}", altitude, source),
         );
         writeln!(f, "  Altitude:      {}", altitude)?;
+        // alerts and ident, the usual no condition is left out
+        if self.ss != SurveillanceStatus::NoCondition {
+            writeln!(f, "  Surveillance:  {}", self.ss)?;
+        }
         writeln!(f, "  CPR type:      Airborne")?;
         writeln!(f, "  CPR odd flag:  {}", self.odd_flag)?;
         writeln!(f, "  CPR latitude:  ({})", self.lat_cpr)?;
@@ -493,8 +748,61 @@ impl fmt::Display for Altitude {
 }
 
 impl Altitude {
+    /// Navigation Integrity Category, with NIC supplement-A of the airborne
+    /// [`adsb::OperationStatusAirborne`] and the NIC supplement-B of this message
+    ///
+    /// `saf_or_imf` is only NIC supplement-B in ADS-B version 2 messages of [`DF::ADSB`].
+    #[must_use]
+    pub fn nic(&self, nic_supplement_a: u8) -> Option<adsb::Nic> {
+        adsb::Nic::airborne(self.tc, nic_supplement_a == 1, self.saf_or_imf == 1)
+    }
+
+    /// Feet in a meter
+    const FEET_PER_METER: f64 = 3.28084;
+
+    /// `alt` is the GNSS height (HAE) of a TC 20..=22 message, instead of a barometric altitude
+    #[must_use]
+    pub const fn is_gnss(&self) -> bool {
+        matches!(self.tc, 20..=22)
+    }
+
+    /// Read the altitude of `tc`: an AC12 field, or for TC 20..=22 the GNSS height as a 12 bit
+    /// number of meters, where 0 is no altitude
+    fn read_tc(
+        rest: &BitSlice<Msb0, u8>,
+        tc: u8,
+    ) -> result::Result<(&BitSlice<Msb0, u8>, Option<u16>), DekuError> {
+        if !matches!(tc, 20..=22) {
+            return Self::read(rest);
+        }
+        let (rest, meters) = u16::read(rest, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(12)))?;
+        let feet =
+            (meters != 0).then(|| libm::round(f64::from(meters) * Self::FEET_PER_METER) as u16);
+        Ok((rest, feet))
+    }
+
+    /// Write the altitude of `tc`, see [`Self::read_tc`]
+    fn write_tc(
+        output: &mut BitVec<Msb0, u8>,
+        tc: u8,
+        alt: Option<u16>,
+    ) -> result::Result<(), DekuError> {
+        if !matches!(tc, 20..=22) {
+            return Self::write(output, alt);
+        }
+        let meters = alt.map_or(0, |feet| {
+            libm::round(f64::from(feet) / Self::FEET_PER_METER) as u16
+        });
+        if meters > 0xfff {
+            return Err(DekuError::InvalidParam(format!(
+                "GNSS height not encodable: {meters} m"
+            )));
+        }
+        meters.write(output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(12)))
+    }
+
     /// `decodeAC12Field`
-    fn read(
+    pub(crate) fn read(
         rest: &BitSlice<Msb0, u8>,
     ) -> result::Result<(&BitSlice<Msb0, u8>, Option<u16>), DekuError> {
         let (rest, num) = u32::read(rest, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(12)))?;
@@ -520,18 +828,241 @@ impl Altitude {
             }
         }
     }
+
+    /// `encodeAC12Field`, using 25 ft increments (Q bit set) when possible
+    pub(crate) fn write(
+        output: &mut BitVec<Msb0, u8>,
+        alt: Option<u16>,
+    ) -> result::Result<(), DekuError> {
+        let num = match alt {
+            None => 0,
+            Some(alt) => {
+                let alt = u32::from(alt);
+                let n = (alt + 1000) / 25;
+                if (alt + 1000) % 25 == 0 && n <= 0x7ff {
+                    ((n & 0x07f0) << 1) | 0x0010 | (n & 0x000f)
+                } else {
+                    let n = (alt % 100 == 0)
+                        .then(|| mode_ac::mode_c_to_id13_field(alt / 100))
+                        .flatten()
+                        .ok_or_else(|| {
+                            DekuError::InvalidParam(format!("altitude not encodable: {alt}"))
+                        })?;
+                    ((n & 0x1f80) >> 1) | (n & 0x003f)
+                }
+            },
+        };
+        num.write(output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(12)))
+    }
+}
+
+/// Category of a frame that can't be used
+///
+/// Frames with [`ErrorKind::Framing`] and [`ErrorKind::Crc`] errors are usually noise on the RF
+/// input, while the other kinds point to messages the decoder doesn't handle (yet). Decode errors
+/// are categorized with [`ErrorKind::from_deku`], and decoded frames with [`Frame::error_kind`].
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Not a whole Mode S frame, such as a frame shorter than its DF
+    Framing,
+    /// Parity/Interrogator field doesn't check out, see [`Frame::is_valid`]
+    Crc,
+    /// Field could not be parsed
+    Parse,
+    /// Field contains a value outside of its valid range, or a reserved field isn't empty
+    Range,
+    /// Format or value not assigned by the specification, or not decoded by this crate
+    Unsupported,
 }
 
-/// SPI Condition
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+impl ErrorKind {
+    /// All kinds, in order
+    pub const ALL: [Self; 5] = [
+        Self::Framing,
+        Self::Crc,
+        Self::Parse,
+        Self::Range,
+        Self::Unsupported,
+    ];
+
+    /// Category of an error returned by [`Frame::from_bytes`] for `bytes`
+    ///
+    /// Other errors than framing and range errors are unsupported if the DF or TC peeked from
+    /// `bytes` isn't decoded, see [`Peek::is_supported`].
+    #[must_use]
+    pub fn from_deku(bytes: &[u8], e: &DekuError) -> Self {
+        match e {
+            DekuError::Incomplete(_) => Self::Framing,
+            DekuError::Assertion(_) | DekuError::InvalidParam(_) => Self::Range,
+            DekuError::IdVariantNotFound => Self::Unsupported,
+            _ => match Peek::new(bytes) {
+                Some(peek) if !peek.is_supported() => Self::Unsupported,
+                _ => Self::Parse,
+            },
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Framing => "framing",
+                Self::Crc => "CRC",
+                Self::Parse => "parse",
+                Self::Range => "range",
+                Self::Unsupported => "unsupported",
+            }
+        )
+    }
+}
+
+/// Downlink format and ADS-B type code of a frame, read from its header without decoding it
+///
+/// This is much cheaper than [`Frame::from_bytes`], for classifying every received frame, such
+/// as for logging the raw frames. The parity isn't checked, so a corrupted frame is classified
+/// from its corrupted header.
+///
+/// ```rust
+/// use adsb_deku::Peek;
+/// use hexlit::hex;
+///
+/// let peek = Peek::new(&hex!("8d40621d58c382d690c8ac2863a7")).unwrap();
+/// assert_eq!((peek.df, peek.tc), (17, Some(11)));
+/// assert_eq!(peek.to_string(), "DF17 TC11 pos");
+/// ```
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Peek {
+    /// Downlink format, 24 for all Comm-D formats
+    pub df: u8,
+    /// Type code of DF17 and DF18 frames
+    pub tc: Option<u8>,
+}
+
+impl Peek {
+    /// Header of the frame in `bytes`, `None` if `bytes` is empty
+    #[must_use]
+    pub fn new(bytes: &[u8]) -> Option<Self> {
+        // the Comm-D formats only use the first two bits
+        let df = (bytes.first()? >> 3).min(24);
+        let tc = match df {
+            17 | 18 => bytes.get(4).map(|byte| byte >> 3),
+            _ => None,
+        };
+        Some(Self { df, tc })
+    }
+
+    /// Short description of the message, such as `"pos"` for an airborne position
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match (self.df, self.tc) {
+            (0 | 16, _) => "acas",
+            (4, _) => "alt",
+            (5, _) => "id",
+            (11, _) => "all-call",
+            (17 | 18, Some(1..=4)) => "ident",
+            (17 | 18, Some(5..=8)) => "surf",
+            (17 | 18, Some(0 | 9..=18 | 20..=22)) => "pos",
+            (17 | 18, Some(19)) => "vel",
+            (17 | 18, Some(23)) => "test",
+            (17 | 18, Some(28)) => "status",
+            (17 | 18, Some(29)) => "target",
+            (17 | 18, Some(31)) => "op status",
+            (17 | 18, _) => "reserved",
+            (19, _) => "military",
+            (20, _) => "comm-b alt",
+            (21, _) => "comm-b id",
+            (24, _) => "comm-d",
+            _ => "unknown",
+        }
+    }
+
+    /// Whether the format is decoded by this crate, `false` for unassigned formats and reserved
+    /// type codes
+    #[must_use]
+    pub fn is_supported(&self) -> bool {
+        !matches!(self.kind(), "reserved" | "unknown")
+    }
+}
+
+impl fmt::Display for Peek {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DF{}", self.df)?;
+        if let Some(tc) = self.tc {
+            write!(f, " TC{tc}")?;
+        }
+        write!(f, " {}", self.kind())
+    }
+}
+
+/// Interrogator Identifier, see [`Frame::interrogator_code`]
+///
+/// reference: ICAO 9871 (3.1.2.5.2.1.2)
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub enum InterrogatorCode {
+    /// Interrogator Identifier (CL = 0), 0..=15
+    II(u8),
+    /// Surveillance Identifier (CL = 1..=4), 1..=63
+    SI(u8),
+}
+
+impl InterrogatorCode {
+    /// From the remainder of the PI field, containing `(CL << 4) | IC`
+    #[must_use]
+    pub fn from_pi(pi: u32) -> Option<Self> {
+        let cl = (pi >> 4) as u8;
+        let ic = (pi & 0xf) as u8;
+        match (pi > 0x7f, cl) {
+            (false, 0) => Some(Self::II(ic)),
+            (false, 1..=4) => Some(Self::SI(((cl - 1) << 4) | ic)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InterrogatorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::II(code) => write!(f, "II {code}"),
+            Self::SI(code) => write!(f, "SI {code}"),
+        }
+    }
+}
+
+/// Surveillance Status of an airborne position
+///
+/// reference: ICAO 9871 (A.2.3.2.6)
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "2")]
+#[non_exhaustive]
 pub enum SurveillanceStatus {
     NoCondition    = 0,
+    /// Emergency squawk (7500, 7600 or 7700)
     PermanentAlert = 1,
+    /// Change of Mode A squawk, other than to an emergency code
     TemporaryAlert = 2,
+    /// Special Position Identification (ident) activated by the pilot
     SPICondition   = 3,
 }
 
+impl fmt::Display for SurveillanceStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::NoCondition => "no condition",
+                Self::PermanentAlert => "permanent alert",
+                Self::TemporaryAlert => "temporary alert",
+                Self::SPICondition => "SPI condition",
+            }
+        )
+    }
+}
+
 impl Default for SurveillanceStatus {
     fn default() -> Self {
         Self::NoCondition
@@ -539,7 +1070,7 @@ impl Default for SurveillanceStatus {
 }
 
 /// Even / Odd
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "1")]
 pub enum CPRFormat {
     Even = 0,
@@ -566,7 +1097,7 @@ impl fmt::Display for CPRFormat {
 }
 
 /// Positive / Negative
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "1")]
 pub enum Sign {
     Positive = 0,
@@ -596,71 +1127,316 @@ impl fmt::Display for Sign {
     }
 }
 
-/// 13 bit identity code
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
-pub struct IdentityCode(#[deku(reader = "Self::read(deku::rest)")] pub u16);
+/// Mode A code (squawk), with each octal digit in a nibble (7700 is `Squawk(0x7700)`)
+///
+/// Decoded from the 13 bit identity code, and displayed as its 4 octal digits.
+///
+/// ```rust
+/// use adsb_deku::Squawk;
+///
+/// let squawk = Squawk(0x7600);
+/// assert!(squawk.is_radio_failure());
+/// assert!(squawk.is_alert());
+/// assert_eq!(squawk.to_string(), "7600");
+/// assert!(!Squawk(0x1200).is_alert());
+/// ```
+#[derive(
+    Debug, PartialEq, Eq, PartialOrd, Ord, Hash, DekuRead, DekuWrite, Copy, Clone, Default,
+)]
+pub struct Squawk(
+    #[deku(
+        bits = "13",
+        endian = "big",
+        map = "|id: u32| -> Result<_, DekuError> {Ok(mode_ac::decode_id13_field(id) as u16)}",
+        writer = "mode_ac::encode_id13_field(u32::from(*field_0)).write(deku::output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(13)))"
+    )]
+    pub u16,
+);
 
-impl IdentityCode {
-    fn read(rest: &BitSlice<Msb0, u8>) -> result::Result<(&BitSlice<Msb0, u8>, u16), DekuError> {
-        let (rest, num) = u32::read(rest, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(13)))?;
+impl Squawk {
+    /// 7500: Unlawful interference (hijack)
+    pub const HIJACK: Self = Self(0x7500);
+    /// 7600: Radio failure
+    pub const RADIO_FAILURE: Self = Self(0x7600);
+    /// 7700: General emergency
+    pub const EMERGENCY: Self = Self(0x7700);
 
-        let c1 = (num & 0b1_0000_0000_0000) >> 12;
-        let a1 = (num & 0b0_1000_0000_0000) >> 11;
-        let c2 = (num & 0b0_0100_0000_0000) >> 10;
-        let a2 = (num & 0b0_0010_0000_0000) >> 9;
-        let c4 = (num & 0b0_0001_0000_0000) >> 8;
-        let a4 = (num & 0b0_0000_1000_0000) >> 7;
-        let b1 = (num & 0b0_0000_0010_0000) >> 5;
-        let d1 = (num & 0b0_0000_0001_0000) >> 4;
-        let b2 = (num & 0b0_0000_0000_1000) >> 3;
-        let d2 = (num & 0b0_0000_0000_0100) >> 2;
-        let b4 = (num & 0b0_0000_0000_0010) >> 1;
-        let d4 = num & 0b0_0000_0000_0001;
+    /// Squawk is [`Self::HIJACK`]
+    #[must_use]
+    pub const fn is_hijack(self) -> bool {
+        self.0 == Self::HIJACK.0
+    }
 
-        let a = a4 << 2 | a2 << 1 | a1;
-        let b = b4 << 2 | b2 << 1 | b1;
-        let c = c4 << 2 | c2 << 1 | c1;
-        let d = d4 << 2 | d2 << 1 | d1;
+    /// Squawk is [`Self::RADIO_FAILURE`]
+    #[must_use]
+    pub const fn is_radio_failure(self) -> bool {
+        self.0 == Self::RADIO_FAILURE.0
+    }
+
+    /// Squawk is [`Self::EMERGENCY`]
+    #[must_use]
+    pub const fn is_emergency(self) -> bool {
+        self.0 == Self::EMERGENCY.0
+    }
 
-        let num: u16 = (a << 12 | b << 8 | c << 4 | d) as u16;
-        Ok((rest, num))
+    /// Squawk is any of 7500, 7600 or 7700, reported as [`SurveillanceStatus::PermanentAlert`]
+    #[must_use]
+    pub const fn is_alert(self) -> bool {
+        self.is_hijack() || self.is_radio_failure() || self.is_emergency()
+    }
+}
+
+impl fmt::Display for Squawk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04x}", self.0)
     }
 }
 
 /// ICAO Address; Mode S transponder code
-#[derive(Debug, PartialEq, Eq, PartialOrd, DekuRead, Hash, Copy, Clone, Ord)]
+///
+/// Displayed as 6 uppercase hex digits, the same as `{:X}`. Use `{:x}` for lowercase hex digits.
+/// Parsed from 6 hex digits of either case.
+///
+/// ```rust
+/// use adsb_deku::ICAO;
+///
+/// let icao: ICAO = "a0001f".parse().unwrap();
+/// assert_eq!(icao, ICAO([0xa0, 0x00, 0x1f]));
+/// assert_eq!(icao.to_string(), "A0001F");
+/// assert_eq!(format!("{:x}", ICAO([0x00, 0x12, 0x34])), "001234");
+/// assert!("a0001".parse::<ICAO>().is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, DekuRead, DekuWrite, Hash, Copy, Clone, Ord)]
 pub struct ICAO(pub [u8; 3]);
 
+impl ICAO {
+    /// Country the address is allocated to, see [`country`]
+    #[cfg(feature = "icao_flags")]
+    #[must_use]
+    pub fn country(&self) -> Option<&'static str> {
+        country::country(*self)
+    }
+
+    /// Address from the lower 24 bits of `value`, such as the CRC remainder of an Address/Parity
+    /// format
+    const fn from_u32(value: u32) -> Self {
+        let [_, a, b, c] = value.to_be_bytes();
+        Self([a, b, c])
+    }
+}
+
 impl fmt::Display for ICAO {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:02x}", self.0[0])?;
-        write!(f, "{:02x}", self.0[1])?;
-        write!(f, "{:02x}", self.0[2])?;
-        Ok(())
+        fmt::UpperHex::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for ICAO {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c] = self.0;
+        write!(f, "{a:02x}{b:02x}{c:02x}")
+    }
+}
+
+impl fmt::UpperHex for ICAO {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c] = self.0;
+        write!(f, "{a:02X}{b:02X}{c:02X}")
+    }
+}
+
+/// Error of parsing an [`ICAO`] address that isn't 6 hex digits
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct ParseIcaoError;
+
+impl fmt::Display for ParseIcaoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ICAO address must be 6 hex digits")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseIcaoError {}
+
+impl FromStr for ICAO {
+    type Err = ParseIcaoError;
+
+    fn from_str(s: &str) -> result::Result<Self, Self::Err> {
+        if s.len() != 6 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseIcaoError);
+        }
+        let value = u32::from_str_radix(s, 16).map_err(|_| ParseIcaoError)?;
+        Ok(Self::from_u32(value))
+    }
+}
+
+/// [`DF::SurveillanceAltitudeReply`]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct SurveillanceAltitudeReply {
+    /// FS: Flight Status
+    pub fs: FlightStatus,
+    /// DR: DownlinkRequest
+    pub dr: DownlinkRequest,
+    /// UM: Utility Message
+    pub um: UtilityMessage,
+    /// AC: AltitudeCode
+    pub ac: AC13Field,
+    /// AP: Address/Parity
+    pub ap: ICAO,
+}
+
+impl SurveillanceAltitudeReply {
+    /// Barometric altitude in feet, see [`AC13Field::altitude`]
+    #[must_use]
+    pub const fn altitude(&self) -> Option<u16> {
+        self.ac.altitude()
+    }
+}
+
+/// [`DF::CommBAltitudeReply`]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone)]
+pub struct CommBAltitudeReply {
+    /// FS: Flight Status
+    pub fs: FlightStatus,
+    /// DR: Downlink Request
+    pub dr: DownlinkRequest,
+    /// UM: Utility Message
+    pub um: UtilityMessage,
+    /// AC: Altitude Code
+    pub ac: AC13Field,
+    /// MB: Message, Comm-B
+    pub mb: CommB,
+    /// AP: Address/Parity
+    pub ap: ICAO,
+}
+
+impl CommBAltitudeReply {
+    /// Barometric altitude in feet, see [`AC13Field::altitude`]
+    #[must_use]
+    pub const fn altitude(&self) -> Option<u16> {
+        self.ac.altitude()
+    }
+}
+
+/// [`DF::SurveillanceIdentityReply`]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct SurveillanceIdentityReply {
+    /// FS: Flight Status
+    pub fs: FlightStatus,
+    /// DR: Downlink Request
+    pub dr: DownlinkRequest,
+    /// UM: UtilityMessage
+    pub um: UtilityMessage,
+    /// ID: Identity
+    pub id: Squawk,
+    /// AP: Address/Parity
+    pub ap: ICAO,
+}
+
+impl SurveillanceIdentityReply {
+    /// Mode A squawk
+    #[must_use]
+    pub const fn squawk(&self) -> Squawk {
+        self.id
     }
 }
 
+/// [`DF::CommBIdentityReply`]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone)]
+pub struct CommBIdentityReply {
+    /// FS: Flight Status
+    pub fs: FlightStatus,
+    /// DR: Downlink Request
+    pub dr: DownlinkRequest,
+    /// UM: Utility Message
+    pub um: UtilityMessage,
+    /// ID: Identity
+    pub id: Squawk,
+    /// MB: Message, Comm-B
+    pub mb: CommB,
+    /// AP: Address/Parity
+    pub ap: ICAO,
+}
+
+impl CommBIdentityReply {
+    /// Mode A squawk
+    #[must_use]
+    pub const fn squawk(&self) -> Squawk {
+        self.id
+    }
+}
+
+/// [`DF::CommDExtendedLengthMessage`]
+///
+/// One 80 bit segment of a downlink ELM (Extended Length Message), see
+/// [`DownlinkRequest::elm_segments`] for the amount of segments of the whole message.
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct CommDExtendedLengthMessage {
+    /// First 2 bits of the DF, always `0b11`
+    #[deku(bits = "2")]
+    pub format: u8,
+    /// Spare - 1 bit
+    #[deku(bits = "1")]
+    pub spare: u8,
+    /// KE: control, ELM
+    pub ke: KE,
+    /// ND: number of D-segment, starting at 0
+    #[deku(bits = "4")]
+    pub nd: u8,
+    /// MD: message, Comm-D
+    pub md: [u8; 10],
+    /// AP: address/parity
+    pub ap: ICAO,
+}
+
 /// Type of `DownlinkRequest`
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "5")]
+#[non_exhaustive]
 pub enum DownlinkRequest {
-    None               = 0b00000,
-    RequestSendCommB   = 0b00001,
-    CommBBroadcastMsg1 = 0b00100,
-    CommBBroadcastMsg2 = 0b00101,
+    #[deku(id = "0b00000")]
+    None,
+    #[deku(id = "0b00001")]
+    RequestSendCommB,
+    #[deku(id = "0b00100")]
+    CommBBroadcastMsg1,
+    #[deku(id = "0b00101")]
+    CommBBroadcastMsg2,
     #[deku(id_pat = "_")]
-    Unknown,
+    Unknown(#[deku(bits = "5")] u8),
+}
+
+impl DownlinkRequest {
+    /// Amount of segments of the downlink ELM the aircraft is requesting to send
+    ///
+    /// DR values 16..=31 announce an ELM of 1..=16 segments, see [`CommDExtendedLengthMessage`].
+    #[must_use]
+    pub const fn elm_segments(&self) -> Option<u8> {
+        match self {
+            Self::Unknown(dr @ 16..=31) => Some(*dr - 15),
+            _ => None,
+        }
+    }
 }
 
 /// Uplink / Downlink
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "1")]
 pub enum KE {
     DownlinkELMTx = 0,
     UplinkELMAck  = 1,
 }
 
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+impl fmt::Display for KE {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DownlinkELMTx => write!(f, "downlink ELM"),
+            Self::UplinkELMAck => write!(f, "uplink ELM acknowledgement"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 pub struct UtilityMessage {
     #[deku(bits = "4")]
     pub iis: u8,
@@ -668,7 +1444,7 @@ pub struct UtilityMessage {
 }
 
 /// Message Type
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "2")]
 pub enum UtilityMessageType {
     NoInformation = 0b00,
@@ -677,9 +1453,80 @@ pub enum UtilityMessageType {
     CommD         = 0b11,
 }
 
+/// Airborne / Ground, from the VS field
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+#[deku(type = "u8", bits = "1")]
+pub enum VerticalStatus {
+    Airborne = 0,
+    Ground   = 1,
+}
+
+impl fmt::Display for VerticalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Airborne => "airborne",
+                Self::Ground => "ground",
+            }
+        )
+    }
+}
+
+/// ACAS capability or maximum airspeed, from the RI field
+///
+/// reference: ICAO 9871 (3.1.2.8.2.2)
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+#[deku(type = "u8", bits = "4")]
+#[non_exhaustive]
+pub enum ReplyInformation {
+    /// No operating ACAS
+    #[deku(id = "0")]
+    NoAcas,
+    /// ACAS with resolution capability inhibited
+    #[deku(id = "2")]
+    AcasResolutionInhibited,
+    /// ACAS with vertical-only resolution capability
+    #[deku(id = "3")]
+    AcasVerticalOnly,
+    /// ACAS with vertical and horizontal resolution capability
+    #[deku(id = "4")]
+    AcasVerticalAndHorizontal,
+    /// Reply to an acquisition request, containing the maximum airspeed code (8..=15)
+    #[deku(id_pat = "8..=15")]
+    MaxAirspeed(#[deku(bits = "4")] u8),
+    #[deku(id_pat = "_")]
+    NotAssigned(#[deku(bits = "4")] u8),
+}
+
+impl fmt::Display for ReplyInformation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoAcas => write!(f, "no operating ACAS"),
+            Self::AcasResolutionInhibited => write!(f, "ACAS, resolution inhibited"),
+            Self::AcasVerticalOnly => write!(f, "ACAS, vertical-only resolution"),
+            Self::AcasVerticalAndHorizontal => {
+                write!(f, "ACAS, vertical and horizontal resolution")
+            },
+            Self::MaxAirspeed(code) => match code {
+                9 => write!(f, "max airspeed <= 75 kt"),
+                10 => write!(f, "max airspeed 75 - 150 kt"),
+                11 => write!(f, "max airspeed 150 - 300 kt"),
+                12 => write!(f, "max airspeed 300 - 600 kt"),
+                13 => write!(f, "max airspeed 600 - 1200 kt"),
+                14 => write!(f, "max airspeed > 1200 kt"),
+                _ => write!(f, "max airspeed unavailable"),
+            },
+            Self::NotAssigned(code) => write!(f, "not assigned ({code})"),
+        }
+    }
+}
+
 /// Airborne / Ground and SPI
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "3")]
+#[non_exhaustive]
 pub enum FlightStatus {
     NoAlertNoSPIAirborne     = 0b000,
     NoAlertNoSPIOnGround     = 0b001,
@@ -709,69 +1556,155 @@ impl fmt::Display for FlightStatus {
     }
 }
 
-/// 13 bit encoded altitude
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
-pub struct AC13Field(#[deku(reader = "Self::read(deku::rest)")] pub u16);
+/// 13 bit encoded altitude, in feet
+///
+/// Decoded from either 25 ft increments (Q bit set) or 100 ft gillham code (Q bit clear). With
+/// the M bit set, the other 12 bits are the altitude in meters, converted to feet. `0` if the
+/// altitude is unavailable or invalid.
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct AC13Field(
+    #[deku(
+        reader = "Self::read(deku::rest)",
+        writer = "Self::write(deku::output, *field_0)"
+    )]
+    pub u16,
+);
 
 impl AC13Field {
-    // TODO Add unit
+    /// Altitude in feet, `None` if unavailable
+    #[must_use]
+    pub const fn altitude(&self) -> Option<u16> {
+        if self.0 > 0 {
+            Some(self.0)
+        } else {
+            None
+        }
+    }
+
     fn read(rest: &BitSlice<Msb0, u8>) -> result::Result<(&BitSlice<Msb0, u8>, u16), DekuError> {
         let (rest, num) = u32::read(rest, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(13)))?;
 
         let m_bit = num & 0x0040;
         let q_bit = num & 0x0010;
 
-        if m_bit != 0 {
-            // TODO: this might be wrong?
-            Ok((rest, 0))
+        let altitude = if m_bit != 0 {
+            // 12 bit altitude in meters
+            let meters = ((num & 0x1f80) >> 1) | (num & 0x003f);
+            Some((meters * 328_084 + 50_000) / 100_000)
         } else if q_bit != 0 {
+            // 11 bit altitude in 25 ft increments, with an offset of -1000 ft
             let n = ((num & 0x1f80) >> 2) | ((num & 0x0020) >> 1) | (num & 0x000f);
-            let n = n as u32 * 25;
-            if n > 1000 {
-                Ok((rest, (n - 1000) as u16))
-            } else {
-                // TODO: add error
-                Ok((rest, 0))
-            }
+            (n * 25).checked_sub(1000)
         } else {
-            // TODO 11 bit gillham coded altitude
-            if let Ok(n) = mode_ac::mode_a_to_mode_c(mode_ac::decode_id13_field(num)) {
-                Ok((rest, (100 * n) as u16))
-            } else {
-                Ok((rest, 0))
-            }
-        }
+            // gillham coded altitude in 100 ft increments
+            mode_ac::mode_a_to_mode_c(mode_ac::decode_id13_field(num))
+                .ok()
+                .map(|n| n * 100)
+        };
+
+        Ok((
+            rest,
+            altitude.and_then(|n| u16::try_from(n).ok()).unwrap_or(0),
+        ))
+    }
+
+    /// Encode with the M bit cleared, using 25 ft increments (Q bit set) when possible
+    fn write(output: &mut BitVec<Msb0, u8>, altitude: u16) -> result::Result<(), DekuError> {
+        let altitude = u32::from(altitude);
+        let n = (altitude + 1000) / 25;
+        let num = if altitude == 0 {
+            0
+        } else if (altitude + 1000) % 25 == 0 && n <= 0x7ff {
+            ((n & 0x07e0) << 2) | ((n & 0x0010) << 1) | 0x0010 | (n & 0x000f)
+        } else {
+            (altitude % 100 == 0)
+                .then(|| mode_ac::mode_c_to_id13_field(altitude / 100))
+                .flatten()
+                .ok_or_else(|| {
+                    DekuError::InvalidParam(format!("altitude not encodable: {altitude}"))
+                })?
+        };
+        num.write(output, (deku::ctx::Endian::Big, deku::ctx::Size::Bits(13)))
     }
 }
 
 /// Transponder level and additional information (3.1.2.5.2.2.1)
-#[derive(Debug, PartialEq, Eq, DekuRead, Copy, Clone)]
+///
+/// Only [`Capability::AG_GROUND`] and [`Capability::AG_AIRBORNE`] tell whether the aircraft is on
+/// the ground, see [`Capability::is_on_ground`] and [`Capability::is_airborne`].
+///
+/// `{}` displays the short air/ground state as printed by dump1090, and `{:#}` the meaning from
+/// ICAO 9871.
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
 #[deku(type = "u8", bits = "3")]
 #[allow(non_camel_case_types)]
+#[non_exhaustive]
 pub enum Capability {
     /// Level 1 transponder (surveillance only), and either airborne or on the ground
-    AG_UNCERTAIN  = 0x00,
+    #[deku(id = "0x00")]
+    AG_UNCERTAIN,
+    /// Reserved values 1..=3, only sent by non-compliant transponders
+    ///
+    /// Frames with these are still decoded, see [`Capability::is_reserved`].
     #[deku(id_pat = "0x01..=0x03")]
-    Reserved,
+    Reserved(#[deku(bits = "3")] u8),
     /// Level 2 or above transponder, on ground
-    AG_GROUND     = 0x04,
+    #[deku(id = "0x04")]
+    AG_GROUND,
     /// Level 2 or above transponder, airborne
-    AG_AIRBORNE   = 0x05,
+    #[deku(id = "0x05")]
+    AG_AIRBORNE,
     /// Level 2 or above transponder, either airborne or on ground
-    AG_UNCERTAIN2 = 0x06,
-    /// DR field is not equal to 0, or fs field equal 2, 3, 4, or 5, and either airborne or on
-    /// ground
-    AG_UNCERTAIN3 = 0x07,
+    #[deku(id = "0x06")]
+    AG_UNCERTAIN2,
+    /// Level 2 or above transponder, with the DR field not equal to 0 or the FS field equal to
+    /// 2, 3, 4 or 5 (alert or SPI), and either airborne or on ground
+    #[deku(id = "0x07")]
+    AG_UNCERTAIN3,
+}
+
+impl Capability {
+    /// Reserved value, sent by a non-compliant transponder
+    #[must_use]
+    pub const fn is_reserved(&self) -> bool {
+        matches!(self, Self::Reserved(_))
+    }
+
+    /// The transponder reports being airborne
+    ///
+    /// `false` doesn't mean on the ground, most values don't tell either way.
+    #[must_use]
+    pub const fn is_airborne(&self) -> bool {
+        matches!(self, Self::AG_AIRBORNE)
+    }
+
+    /// The transponder reports being on the ground
+    ///
+    /// `false` doesn't mean airborne, most values don't tell either way.
+    #[must_use]
+    pub const fn is_on_ground(&self) -> bool {
+        matches!(self, Self::AG_GROUND)
+    }
 }
 
 impl fmt::Display for Capability {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return match self {
+                Self::AG_UNCERTAIN => write!(f, "Level 1, airborne or on ground"),
+                Self::Reserved(ca) => write!(f, "Reserved ({ca})"),
+                Self::AG_GROUND => write!(f, "Level 2+, on ground"),
+                Self::AG_AIRBORNE => write!(f, "Level 2+, airborne"),
+                Self::AG_UNCERTAIN2 => write!(f, "Level 2+, airborne or on ground"),
+                Self::AG_UNCERTAIN3 => write!(f, "Level 2+, DR/FS alert, airborne or on ground"),
+            };
+        }
         write!(
             f,
             "{}",
             match self {
                 Self::AG_UNCERTAIN => "uncertain1",
-                Self::Reserved => "reserved",
+                Self::Reserved(_) => "reserved",
                 Self::AG_GROUND => "ground",
                 Self::AG_AIRBORNE => "airborne",
                 Self::AG_UNCERTAIN2 => "uncertain2",
@@ -781,7 +1714,8 @@ impl fmt::Display for Capability {
     }
 }
 
-const CHAR_LOOKUP: &[u8; 64] = b"#ABCDEFGHIJKLMNOPQRSTUVWXYZ##### ###############0123456789######";
+pub(crate) const CHAR_LOOKUP: &[u8; 64] =
+    b"#ABCDEFGHIJKLMNOPQRSTUVWXYZ##### ###############0123456789######";
 
 pub(crate) fn aircraft_identification_read(
     rest: &BitSlice<Msb0, u8>,
@@ -789,7 +1723,7 @@ pub(crate) fn aircraft_identification_read(
     let mut inside_rest = rest;
 
     let mut chars = vec![];
-    for _ in 0..=6 {
+    for _ in 0..8 {
         let (for_rest, c) = <u8>::read(inside_rest, deku::ctx::Size::Bits(6))?;
         if c != 32 {
             chars.push(c);
@@ -803,3 +1737,26 @@ pub(crate) fn aircraft_identification_read(
 
     Ok((inside_rest, encoded))
 }
+
+/// Write `cn` as 8 characters, padded with spaces
+pub(crate) fn aircraft_identification_write(
+    output: &mut BitVec<Msb0, u8>,
+    cn: &str,
+) -> result::Result<(), DekuError> {
+    if cn.len() > 8 {
+        return Err(DekuError::InvalidParam(format!(
+            "aircraft identification longer than 8 characters: {cn}"
+        )));
+    }
+    for c in cn.bytes().chain(core::iter::repeat(b' ')).take(8) {
+        let n = CHAR_LOOKUP
+            .iter()
+            .position(|l| *l == c && c != b'#')
+            .ok_or_else(|| {
+                DekuError::InvalidParam(format!("invalid aircraft identification character: {c}"))
+            })?;
+        (n as u8).write(output, deku::ctx::Size::Bits(6))?;
+    }
+
+    Ok(())
+}
@@ -1,11 +1,92 @@
-//! Mode AC Conversion methods
+//! Mode AC Conversion methods, and parsing of Mode A/C replies
+//!
+//! Receivers with Mode A/C enabled, such as in the Beast format, output the 2 byte code of the
+//! legacy SSR replies received on 1090 MHz, see [`parse_reply`].
 
 #[cfg(feature = "alloc")]
 use core::{
+    iter::Iterator,
+    option::Option,
     result,
     result::Result::{Err, Ok},
 };
 
+use crate::Squawk;
+
+/// Ident bit of a Mode A/C code
+const SPI: u16 = 0x0080;
+
+/// Mode A/C reply, see [`parse_reply`]
+///
+/// The interrogation decides if a Mode A or Mode C reply is sent, which isn't part of the reply,
+/// so both readings of the code are given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reply {
+    /// Mode A reading of the code
+    pub squawk: Squawk,
+    /// Mode C reading of the code, barometric altitude in feet
+    ///
+    /// `None` if the code isn't a valid gillham coded altitude, or with `ident`, which is only
+    /// sent in Mode A replies.
+    pub altitude: Option<u16>,
+    /// SPI: Special Position Identification, the pilot pressed ident
+    pub ident: bool,
+}
+
+/// Parse the 2 byte code of a Mode A/C reply
+///
+/// The code is the A, B, C and D digits of the reply in the bits `0x7000`, `0x0700`, `0x0070`
+/// and `0x0007`, with the ident bit as `0x0080`.
+///
+/// ```rust
+/// use adsb_deku::mode_ac::{parse_reply, Reply};
+/// use adsb_deku::Squawk;
+///
+/// assert_eq!(
+///     parse_reply(&[0x77, 0x00]),
+///     Ok(Reply {
+///         squawk: Squawk::EMERGENCY,
+///         altitude: None,
+///         ident: false
+///     })
+/// );
+/// assert_eq!(
+///     parse_reply(&[0x12, 0x80]),
+///     Ok(Reply {
+///         squawk: Squawk(0x1200),
+///         altitude: None,
+///         ident: true
+///     })
+/// );
+/// assert_eq!(
+///     parse_reply(&[0x01, 0x30]),
+///     Ok(Reply {
+///         squawk: Squawk(0x0130),
+///         altitude: Some(2400),
+///         ident: false
+///     })
+/// );
+/// ```
+pub fn parse_reply(bytes: &[u8]) -> result::Result<Reply, &'static str> {
+    let code = match bytes {
+        [high, low] => u16::from_be_bytes([*high, *low]),
+        _ => return Err("Mode A/C reply is not 2 bytes"),
+    };
+    let ident = code & SPI != 0;
+    let altitude = if ident {
+        None
+    } else {
+        mode_a_to_mode_c(u32::from(code))
+            .ok()
+            .and_then(|n| u16::try_from(n * 100).ok())
+    };
+    Ok(Reply {
+        squawk: Squawk(code & 0x7777),
+        altitude,
+        ident,
+    })
+}
+
 pub(crate) fn decode_id13_field(id13_field: u32) -> u32 {
     let mut hex_gillham: u32 = 0;
 
@@ -50,6 +131,58 @@ pub(crate) fn decode_id13_field(id13_field: u32) -> u32 {
     hex_gillham
 }
 
+/// Inverse of [`decode_id13_field`]
+pub(crate) fn encode_id13_field(hex_gillham: u32) -> u32 {
+    let mut id13_field: u32 = 0;
+
+    if hex_gillham & 0x0010 != 0 {
+        id13_field |= 0x1000;
+    } // C1
+    if hex_gillham & 0x1000 != 0 {
+        id13_field |= 0x0800;
+    } // A1
+    if hex_gillham & 0x0020 != 0 {
+        id13_field |= 0x0400;
+    } // C2
+    if hex_gillham & 0x2000 != 0 {
+        id13_field |= 0x0200;
+    } // A2
+    if hex_gillham & 0x0040 != 0 {
+        id13_field |= 0x0100;
+    } // C4
+    if hex_gillham & 0x4000 != 0 {
+        id13_field |= 0x0080;
+    } // A4
+    if hex_gillham & 0x0100 != 0 {
+        id13_field |= 0x0020;
+    } // B1
+    if hex_gillham & 0x0001 != 0 {
+        id13_field |= 0x0010;
+    } // D1
+    if hex_gillham & 0x0200 != 0 {
+        id13_field |= 0x0008;
+    } // B2
+    if hex_gillham & 0x0002 != 0 {
+        id13_field |= 0x0004;
+    } // D2
+    if hex_gillham & 0x0400 != 0 {
+        id13_field |= 0x0002;
+    } // B4
+    if hex_gillham & 0x0004 != 0 {
+        id13_field |= 0x0001;
+    } // D4
+
+    id13_field
+}
+
+/// Search for the 13 bit field (with the M bit cleared) that decodes to `mode_c`, as returned by
+/// [`mode_a_to_mode_c`]
+pub(crate) fn mode_c_to_id13_field(mode_c: u32) -> Option<u32> {
+    (0..0x2000)
+        .filter(|id13_field| id13_field & 0x0040 == 0)
+        .find(|id13_field| mode_a_to_mode_c(decode_id13_field(*id13_field)) == Ok(mode_c))
+}
+
 pub(crate) fn mode_a_to_mode_c(mode_a: u32) -> result::Result<u32, &'static str> {
     let mut five_hundreds: u32 = 0;
     let mut one_hundreds: u32 = 0;
@@ -0,0 +1,145 @@
+//! Aircraft registrations derived from the ICAO address
+//!
+//! The United States assigns the ICAO addresses `A00001..=ADF7C7` in order of the sorted
+//! N-numbers `N1..=N99999`, so the registration of a US aircraft can be computed from its address
+//! and back without a registry database.
+//!
+//! ```rust
+//! use adsb_deku::registration::{icao_to_n_number, n_number_to_icao};
+//! use adsb_deku::ICAO;
+//!
+//! assert_eq!(
+//!     icao_to_n_number(ICAO([0xa0, 0x00, 0x01])).as_deref(),
+//!     Some("N1")
+//! );
+//! assert_eq!(n_number_to_icao("N99999"), Some(ICAO([0xad, 0xf7, 0xc7])));
+//! ```
+
+use alloc::string::String;
+
+use crate::ICAO;
+
+/// First ICAO address assigned to a N-number, `N1`
+const FIRST: u32 = 0x00a0_0001;
+
+/// Letters of N-numbers, without `I` and `O`
+const LETTERS: &[u8; 24] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+/// Addresses of the letter suffixes after a digit: none, one letter, or two letters
+const SUFFIX_SIZE: u32 = 1 + 24 * 25;
+
+/// Addresses after the 4th digit: none, one letter, or a 5th digit
+const BUCKET4_SIZE: u32 = 1 + 24 + 10;
+
+/// Addresses after each digit of a N-number, indexed by the position of the digit
+const BUCKET_SIZES: [u32; 4] = [
+    SUFFIX_SIZE + 10 * (SUFFIX_SIZE + 10 * (SUFFIX_SIZE + 10 * BUCKET4_SIZE)),
+    SUFFIX_SIZE + 10 * (SUFFIX_SIZE + 10 * BUCKET4_SIZE),
+    SUFFIX_SIZE + 10 * BUCKET4_SIZE,
+    BUCKET4_SIZE,
+];
+
+/// US N-number of `icao`, such as `N12345` or `N1AB`
+///
+/// Returns `None` if `icao` is not within the US range `A00001..=ADF7C7`.
+#[must_use]
+pub fn icao_to_n_number(icao: ICAO) -> Option<String> {
+    let [a, b, c] = icao.0;
+    let value = u32::from_be_bytes([0, a, b, c]);
+    let mut offset = value.checked_sub(FIRST)?;
+    if offset >= 9 * BUCKET_SIZES[0] {
+        return None;
+    }
+
+    let mut n_number = String::from("N");
+    push_digit(&mut n_number, offset / BUCKET_SIZES[0] + 1);
+    offset %= BUCKET_SIZES[0];
+
+    for bucket_size in &BUCKET_SIZES[1..] {
+        if offset < SUFFIX_SIZE {
+            push_suffix(&mut n_number, offset);
+            return Some(n_number);
+        }
+        offset -= SUFFIX_SIZE;
+        push_digit(&mut n_number, offset / bucket_size);
+        offset %= bucket_size;
+    }
+
+    // last character is either a letter or a digit
+    match offset {
+        0 => (),
+        1..=24 => n_number.push(char::from(LETTERS[offset as usize - 1])),
+        _ => push_digit(&mut n_number, offset - 25),
+    }
+    Some(n_number)
+}
+
+/// ICAO address of the US N-number `n_number`, such as `N12345` or `n1ab`
+///
+/// Returns `None` if `n_number` is not a valid N-number: the letter `N`, followed by up to 5
+/// digits and letters, starting with a non-zero digit and ending with at most two letters.
+#[must_use]
+pub fn n_number_to_icao(n_number: &str) -> Option<ICAO> {
+    let (n, rest) = n_number.as_bytes().split_first()?;
+    if !n.eq_ignore_ascii_case(&b'N') || rest.is_empty() || rest.len() > 5 {
+        return None;
+    }
+
+    let first = digit(rest[0]).filter(|&d| d != 0)?;
+    let mut offset = (first - 1) * BUCKET_SIZES[0];
+
+    for (i, &c) in rest.iter().enumerate().skip(1) {
+        if i == BUCKET_SIZES.len() {
+            // last character is either a letter or a digit
+            offset += match digit(c) {
+                Some(d) => 25 + d,
+                None => 1 + letter(c)?,
+            };
+            break;
+        }
+        match digit(c) {
+            Some(d) => offset += SUFFIX_SIZE + d * BUCKET_SIZES[i],
+            None => {
+                offset += suffix_offset(&rest[i..])?;
+                break;
+            },
+        }
+    }
+
+    let [_, a, b, c] = (FIRST + offset).to_be_bytes();
+    Some(ICAO([a, b, c]))
+}
+
+fn push_digit(n_number: &mut String, d: u32) {
+    n_number.push(char::from(b'0' + d as u8));
+}
+
+/// Push the letters of the suffix `offset` within [`SUFFIX_SIZE`]
+fn push_suffix(n_number: &mut String, offset: u32) {
+    if offset == 0 {
+        return;
+    }
+    let offset = offset as usize - 1;
+    n_number.push(char::from(LETTERS[offset / 25]));
+    if offset % 25 != 0 {
+        n_number.push(char::from(LETTERS[offset % 25 - 1]));
+    }
+}
+
+/// Offset within [`SUFFIX_SIZE`] of one or two trailing letters
+fn suffix_offset(letters: &[u8]) -> Option<u32> {
+    match *letters {
+        [a] => Some(1 + letter(a)? * 25),
+        [a, b] => Some(1 + letter(a)? * 25 + 1 + letter(b)?),
+        _ => None,
+    }
+}
+
+fn digit(c: u8) -> Option<u32> {
+    c.is_ascii_digit().then(|| u32::from(c - b'0'))
+}
+
+fn letter(c: u8) -> Option<u32> {
+    let c = c.to_ascii_uppercase();
+    LETTERS.iter().position(|&l| l == c).map(|i| i as u32)
+}
@@ -0,0 +1,554 @@
+//! Mode S uplink (1030 MHz) interrogations
+//!
+//! [`UplinkFrame`] decodes the interrogations sent by ground stations and ACAS, which are answered
+//! by the downlink [`Frame`]. The aircraft address returned by [`UplinkFrame::icao`] matches
+//! [`Frame::icao`] of the reply, allowing interrogations and replies captured by the same receiver
+//! to be analyzed together.
+//!
+//! ```rust
+//! use adsb_deku::deku::DekuContainerRead;
+//! use adsb_deku::uplink::{UplinkFrame, UF};
+//! use adsb_deku::ICAO;
+//! use hexlit::hex;
+//!
+//! let bytes = hex!("200000004fbbb1");
+//! let frame = UplinkFrame::from_bytes((&bytes, 0)).unwrap().1;
+//! assert!(matches!(frame.uf, UF::SurveillanceAltitudeRequest(_)));
+//! assert_eq!(frame.icao(), Some(ICAO([0xa8, 0x35, 0xaf])));
+//! ```
+//!
+//! reference: ICAO Annex 10 Vol IV (3.1.2.3.2.1.1, 3.1.2.3.3.2)
+//!
+//! [`Frame`]: crate::Frame
+//! [`Frame::icao`]: crate::Frame::icao
+
+#[cfg(feature = "alloc")]
+use alloc::{format, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::{
+    clone::Clone,
+    cmp::{Eq, PartialEq},
+    fmt::Debug,
+    marker::Copy,
+    prelude::rust_2021::derive,
+    result::Result::Ok,
+    write, writeln,
+};
+use core::{fmt, result};
+
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+
+use crate::crc::{self, GENERATOR_POLYNOMIAL};
+use crate::{InterrogatorCode, ICAO};
+
+/// Address of Mode S only all-call interrogations, see [`UF::AllCall`]
+pub const ALL_CALL_ADDRESS: ICAO = ICAO([0xff, 0xff, 0xff]);
+
+/// Uplink Mode S Packet
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone)]
+pub struct UplinkFrame {
+    /// Starting with 5 bit identifier, decode packet
+    pub uf: UF,
+    /// Calculated from all bits, containing the address parity of the interrogated aircraft
+    ///
+    /// Not written, the parity is contained within the fields of `uf`
+    #[deku(
+        reader = "Self::read_crc(uf, deku::input_bits)",
+        writer = "Ok::<_, DekuError>(())"
+    )]
+    pub crc: u32,
+}
+
+impl UplinkFrame {
+    /// Read rest as CRC bits
+    fn read_crc<'b>(
+        uf: &UF,
+        rest: &'b BitSlice<Msb0, u8>,
+    ) -> result::Result<(&'b BitSlice<Msb0, u8>, u32), DekuError> {
+        const MODES_LONG_MSG_BYTES: usize = 14;
+        const MODES_SHORT_MSG_BYTES: usize = 7;
+
+        let bit_len = if let Ok(id) = uf.deku_id() {
+            if id & 0x10 != 0 {
+                MODES_LONG_MSG_BYTES * 8
+            } else {
+                MODES_SHORT_MSG_BYTES * 8
+            }
+        } else if let UF::UnknownShort { .. } = uf {
+            MODES_SHORT_MSG_BYTES * 8
+        } else {
+            MODES_LONG_MSG_BYTES * 8
+        };
+
+        let crc = crc::modes_checksum(rest.as_raw_slice(), bit_len)?;
+        Ok((rest, crc))
+    }
+
+    /// CRC-24 remainder of all bits of the frame, including the AP field
+    ///
+    /// Unlike the downlink, this isn't the address itself but the address parity, see
+    /// [`UplinkFrame::icao`].
+    #[must_use]
+    pub const fn crc(&self) -> u32 {
+        self.crc
+    }
+
+    /// Address of the interrogated aircraft, recovered from the address parity of the AP field
+    ///
+    /// The AP field of an interrogation is the parity overlaid with the 24 highest-order
+    /// coefficients of `A(x) * G(x)`, where `A(x)` is the address and `G(x)` the generator
+    /// polynomial, so the address is the quotient of the CRC remainder shifted by 24 bits and
+    /// `G(x)`. This is only correct if the frame isn't corrupted. [`UF::AllCall`] is addressed
+    /// to [`ALL_CALL_ADDRESS`]. Returns `None` for formats not assigned by the specification.
+    #[must_use]
+    pub fn icao(&self) -> Option<ICAO> {
+        match self.uf {
+            UF::UnknownShort { .. } | UF::UnknownLong { .. } => None,
+            _ => {
+                let [_, a, b, c] = address_from_parity(self.crc).to_be_bytes();
+                Some(ICAO([a, b, c]))
+            },
+        }
+    }
+
+    /// The address parity of the frame checks out
+    ///
+    /// Only [`UF::AllCall`] is addressed to a known address and can be checked. Formats not
+    /// assigned by the specification are never valid. All other formats overlay the parity with
+    /// the address of the interrogated aircraft, and always return `true`.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        match self.uf {
+            UF::AllCall { .. } => self.icao() == Some(ALL_CALL_ADDRESS),
+            UF::UnknownShort { .. } | UF::UnknownLong { .. } => false,
+            _ => true,
+        }
+    }
+}
+
+/// Quotient of `parity * x^24` and the generator polynomial
+fn address_from_parity(parity: u32) -> u32 {
+    let generator = u64::from((1 << 24) | GENERATOR_POLYNOMIAL);
+    let mut rem = u64::from(parity & 0x00ff_ffff) << 24;
+    let mut address = 0;
+    for bit in (0..24).rev() {
+        if rem & (1 << (bit + 24)) != 0 {
+            rem ^= generator << bit;
+            address |= 1 << bit;
+        }
+    }
+    address
+}
+
+impl fmt::Display for UplinkFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let address = self.icao();
+        match &self.uf {
+            UF::ShortAirAirSurveillance { rl, aq, ds, .. } => {
+                writeln!(f, " Short Air-Air Surveillance Interrogation")?;
+                write_air_air(f, address, *rl, *aq)?;
+                writeln!(f, "  Data selector: {ds:02x}")?;
+            },
+            UF::LongAirAir { rl, aq, mu, .. } => {
+                writeln!(f, " Long Air-Air Surveillance Interrogation")?;
+                write_air_air(f, address, *rl, *aq)?;
+                writeln!(f, "  MU:            {}", Hex(mu))?;
+            },
+            UF::SurveillanceAltitudeRequest(request) | UF::SurveillanceIdentityRequest(request) => {
+                if let UF::SurveillanceAltitudeRequest(_) = self.uf {
+                    writeln!(f, " Surveillance, Altitude Request")?;
+                } else {
+                    writeln!(f, " Surveillance, Identity Request")?;
+                }
+                if let Some(address) = address {
+                    writeln!(f, "  ICAO Address:  {address} (Mode S / ADS-B)")?;
+                }
+                write_request(f, request.pc, request.rr, request.di, request.sd)?;
+            },
+            UF::AllCall { .. } => {
+                writeln!(f, " Mode S Only All Call")?;
+                if let Some(ic) = self.uf.interrogator_code() {
+                    writeln!(f, "  Interrogator:  {ic}")?;
+                }
+                if let Some(probability) = self.uf.reply_probability() {
+                    writeln!(f, "  Probability:   {probability}")?;
+                }
+            },
+            UF::CommAAltitudeRequest(request) | UF::CommAIdentityRequest(request) => {
+                if let UF::CommAAltitudeRequest(_) = self.uf {
+                    writeln!(f, " Comm-A, Altitude Request")?;
+                } else {
+                    writeln!(f, " Comm-A, Identity Request")?;
+                }
+                if let Some(address) = address {
+                    writeln!(f, "  ICAO Address:  {address} (Mode S / ADS-B)")?;
+                }
+                write_request(f, request.pc, request.rr, request.di, request.sd)?;
+                writeln!(f, "  MA:            {}", Hex(&request.ma))?;
+            },
+            UF::UnknownShort { uf, .. } | UF::UnknownLong { uf, .. } => {
+                writeln!(f, " Unknown Uplink Format {uf}")?;
+            },
+        }
+        Ok(())
+    }
+}
+
+fn write_air_air(
+    f: &mut fmt::Formatter<'_>,
+    address: Option<ICAO>,
+    rl: bool,
+    aq: bool,
+) -> fmt::Result {
+    if let Some(address) = address {
+        writeln!(f, "  ICAO Address:  {address} (Mode S / ADS-B)")?;
+    }
+    let reply = if rl { "long (DF16)" } else { "short (DF0)" };
+    writeln!(f, "  Reply:         {reply}")?;
+    let aq = if aq { "yes" } else { "no" };
+    writeln!(f, "  Acquisition:   {aq}")?;
+    Ok(())
+}
+
+fn write_request(
+    f: &mut fmt::Formatter<'_>,
+    pc: ProtocolCode,
+    rr: u8,
+    di: u8,
+    sd: u16,
+) -> fmt::Result {
+    writeln!(f, "  Protocol:      {pc}")?;
+    match requested_register(rr, di, sd) {
+        Some(register) => writeln!(f, "  Reply:         Comm-B, BDS {register:02x}")?,
+        None => writeln!(f, "  Reply:         surveillance")?,
+    }
+    if let Some(code) = interrogator_identifier(di, sd) {
+        writeln!(f, "  Interrogator:  {code}")?;
+    }
+    Ok(())
+}
+
+/// Bytes as lowercase hex
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Uplink Format (3.1.2.3.2.1.1)
+///
+/// Starting with 5 bits, decode the rest of the message as the correct data packets
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Clone)]
+#[deku(type = "u8", bits = "5")]
+#[non_exhaustive]
+pub enum UF {
+    /// 0: Short Air-Air Surveillance (ACAS), answered with [`DF::ShortAirAirSurveillance`] or
+    /// [`DF::LongAirAir`]
+    ///
+    /// [`DF::ShortAirAirSurveillance`]: crate::DF::ShortAirAirSurveillance
+    /// [`DF::LongAirAir`]: crate::DF::LongAirAir
+    #[deku(id = "0")]
+    ShortAirAirSurveillance {
+        /// Spare
+        #[deku(bits = "3")]
+        spare1: u8,
+        /// RL: Reply Length, reply with DF16 instead of DF0
+        #[deku(bits = "1")]
+        rl: bool,
+        /// Spare
+        #[deku(bits = "4")]
+        spare2: u8,
+        /// AQ: Acquisition, set by ACAS when acquiring the aircraft
+        #[deku(bits = "1")]
+        aq: bool,
+        /// DS: Data Selector, the BDS register requested in the MV field of a DF16 reply
+        ds: u8,
+        /// Spare
+        #[deku(bits = "10", endian = "big")]
+        spare3: u16,
+        /// AP: address/parity
+        ap: ICAO,
+    },
+
+    /// 4: Surveillance, Altitude Request, answered with [`DF::SurveillanceAltitudeReply`] or
+    /// [`DF::CommBAltitudeReply`]
+    ///
+    /// [`DF::SurveillanceAltitudeReply`]: crate::DF::SurveillanceAltitudeReply
+    /// [`DF::CommBAltitudeReply`]: crate::DF::CommBAltitudeReply
+    #[deku(id = "4")]
+    SurveillanceAltitudeRequest(SurveillanceRequest),
+
+    /// 5: Surveillance, Identity Request, answered with [`DF::SurveillanceIdentityReply`] or
+    /// [`DF::CommBIdentityReply`]
+    ///
+    /// [`DF::SurveillanceIdentityReply`]: crate::DF::SurveillanceIdentityReply
+    /// [`DF::CommBIdentityReply`]: crate::DF::CommBIdentityReply
+    #[deku(id = "5")]
+    SurveillanceIdentityRequest(SurveillanceRequest),
+
+    /// 11: Mode S Only All-Call, answered with [`DF::AllCallReply`]
+    ///
+    /// [`DF::AllCallReply`]: crate::DF::AllCallReply
+    #[deku(id = "11")]
+    AllCall {
+        /// PR: Probability of Reply
+        #[deku(bits = "4")]
+        pr: u8,
+        /// IC: Interrogator Code
+        #[deku(bits = "4")]
+        ic: u8,
+        /// CL: Code Label
+        #[deku(bits = "3")]
+        cl: u8,
+        /// Spare
+        #[deku(bits = "16", endian = "big")]
+        spare: u16,
+        /// AP: address/parity, with the address [`ALL_CALL_ADDRESS`]
+        ap: ICAO,
+    },
+
+    /// 16: Long Air-Air Surveillance (ACAS), answered with [`DF::LongAirAir`]
+    ///
+    /// [`DF::LongAirAir`]: crate::DF::LongAirAir
+    #[deku(id = "16")]
+    LongAirAir {
+        /// Spare
+        #[deku(bits = "3")]
+        spare1: u8,
+        /// RL: Reply Length, reply with DF16 instead of DF0
+        #[deku(bits = "1")]
+        rl: bool,
+        /// Spare
+        #[deku(bits = "4")]
+        spare2: u8,
+        /// AQ: Acquisition, set by ACAS when acquiring the aircraft
+        #[deku(bits = "1")]
+        aq: bool,
+        /// Spare
+        #[deku(bits = "18", endian = "big")]
+        spare3: u32,
+        /// MU: Message, ACAS
+        mu: [u8; 7],
+        /// AP: address/parity
+        ap: ICAO,
+    },
+
+    /// 20: Comm-A, Altitude Request, answered with [`DF::SurveillanceAltitudeReply`] or
+    /// [`DF::CommBAltitudeReply`]
+    ///
+    /// [`DF::SurveillanceAltitudeReply`]: crate::DF::SurveillanceAltitudeReply
+    /// [`DF::CommBAltitudeReply`]: crate::DF::CommBAltitudeReply
+    #[deku(id = "20")]
+    CommAAltitudeRequest(CommARequest),
+
+    /// 21: Comm-A, Identity Request, answered with [`DF::SurveillanceIdentityReply`] or
+    /// [`DF::CommBIdentityReply`]
+    ///
+    /// [`DF::SurveillanceIdentityReply`]: crate::DF::SurveillanceIdentityReply
+    /// [`DF::CommBIdentityReply`]: crate::DF::CommBIdentityReply
+    #[deku(id = "21")]
+    CommAIdentityRequest(CommARequest),
+
+    /// 1..=3, 6..=10, 12..=15: Short format not assigned by the specification
+    #[deku(id_pat = "1..=3 | 6..=10 | 12..=15")]
+    UnknownShort {
+        /// UF: Uplink Format
+        #[deku(bits = "5")]
+        uf: u8,
+        /// Bits between the UF and AP fields
+        #[deku(bits = "27", endian = "big")]
+        data: u32,
+        /// AP: address/parity
+        ap: ICAO,
+    },
+
+    /// 17..=19, 22..=31: Long format not decoded, including the Comm-C ELM of UF24
+    #[deku(id_pat = "17..=19 | 22..=31")]
+    UnknownLong {
+        /// UF: Uplink Format
+        #[deku(bits = "5")]
+        uf: u8,
+        /// Bits between the UF and AP fields
+        #[deku(bits = "83", endian = "big")]
+        data: u128,
+        /// AP: address/parity
+        ap: ICAO,
+    },
+}
+
+impl UF {
+    /// Code of the interrogator sending a [`UF::AllCall`], from the IC and CL fields
+    ///
+    /// This is the code returned by [`Frame::interrogator_code`] of the [`DF::AllCallReply`]
+    /// answering it. Returns `None` for all other formats, or if CL is not assigned.
+    ///
+    /// [`Frame::interrogator_code`]: crate::Frame::interrogator_code
+    /// [`DF::AllCallReply`]: crate::DF::AllCallReply
+    #[must_use]
+    pub fn interrogator_code(&self) -> Option<InterrogatorCode> {
+        match self {
+            Self::AllCall { ic, cl, .. } => {
+                InterrogatorCode::from_pi((u32::from(*cl) << 4) | u32::from(*ic))
+            },
+            _ => None,
+        }
+    }
+
+    /// Probability of an aircraft replying to a [`UF::AllCall`], from the PR field
+    ///
+    /// PR values 8..=12 have the same probability as 0..=4, while disregarding the lockout.
+    /// Returns `None` for all other formats, or if PR is not assigned.
+    #[must_use]
+    pub fn reply_probability(&self) -> Option<f32> {
+        match self {
+            Self::AllCall { pr, .. } => match pr & 0b0111 {
+                n @ 0..=4 => Some(1.0 / f32::from(1_u8 << n)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// [`UF::SurveillanceAltitudeRequest`] and [`UF::SurveillanceIdentityRequest`]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct SurveillanceRequest {
+    /// PC: Protocol
+    pub pc: ProtocolCode,
+    /// RR: Reply Request
+    #[deku(bits = "5")]
+    pub rr: u8,
+    /// DI: Designator Identification, the contents of `sd`
+    #[deku(bits = "3")]
+    pub di: u8,
+    /// SD: Special Designator
+    #[deku(endian = "big")]
+    pub sd: u16,
+    /// AP: address/parity
+    pub ap: ICAO,
+}
+
+impl SurveillanceRequest {
+    /// BDS register requested in the Comm-B reply, see [`requested_register`]
+    #[must_use]
+    pub fn requested_register(&self) -> Option<u8> {
+        requested_register(self.rr, self.di, self.sd)
+    }
+
+    /// Interrogator sending the request, see [`interrogator_identifier`]
+    #[must_use]
+    pub fn interrogator_identifier(&self) -> Option<InterrogatorCode> {
+        interrogator_identifier(self.di, self.sd)
+    }
+}
+
+/// [`UF::CommAAltitudeRequest`] and [`UF::CommAIdentityRequest`]
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+pub struct CommARequest {
+    /// PC: Protocol
+    pub pc: ProtocolCode,
+    /// RR: Reply Request
+    #[deku(bits = "5")]
+    pub rr: u8,
+    /// DI: Designator Identification, the contents of `sd`
+    #[deku(bits = "3")]
+    pub di: u8,
+    /// SD: Special Designator
+    #[deku(endian = "big")]
+    pub sd: u16,
+    /// MA: Message, Comm-A
+    pub ma: [u8; 7],
+    /// AP: address/parity
+    pub ap: ICAO,
+}
+
+impl CommARequest {
+    /// BDS register requested in the Comm-B reply, see [`requested_register`]
+    #[must_use]
+    pub fn requested_register(&self) -> Option<u8> {
+        requested_register(self.rr, self.di, self.sd)
+    }
+
+    /// Interrogator sending the request, see [`interrogator_identifier`]
+    #[must_use]
+    pub fn interrogator_identifier(&self) -> Option<InterrogatorCode> {
+        interrogator_identifier(self.di, self.sd)
+    }
+}
+
+/// BDS register requested in the Comm-B reply, such as `0x20` for the aircraft identification
+///
+/// RR values 16..=31 request a Comm-B reply with the first digit `RR - 16`. The second digit is
+/// the RRS subfield of `sd` if `di` is 7, and 0 otherwise. Returns `None` if a surveillance reply
+/// is requested.
+///
+/// reference: ICAO Annex 10 Vol IV (3.1.2.6.1.2, 3.1.2.6.11.3.2.1)
+#[must_use]
+pub fn requested_register(rr: u8, di: u8, sd: u16) -> Option<u8> {
+    if rr < 16 {
+        return None;
+    }
+    let bds2 = if di == 7 { (sd >> 8) as u8 & 0xf } else { 0 };
+    Some(((rr - 16) << 4) | bds2)
+}
+
+/// Interrogator sending the request, from the IIS or SIS subfield of `sd`
+///
+/// `di` values 0, 1 and 7 contain the IIS (Interrogator Identifier), and 3 the SIS (Surveillance
+/// Identifier). Returns `None` for all other values of `di`.
+///
+/// reference: ICAO Annex 10 Vol IV (3.1.2.6.1.3, 3.1.2.6.1.4)
+#[must_use]
+pub fn interrogator_identifier(di: u8, sd: u16) -> Option<InterrogatorCode> {
+    match di {
+        0 | 1 | 7 => Some(InterrogatorCode::II((sd >> 12) as u8)),
+        3 => Some(InterrogatorCode::SI((sd >> 10) as u8)),
+        _ => None,
+    }
+}
+
+/// PC: Protocol, operating commands for the transponder
+///
+/// reference: ICAO Annex 10 Vol IV (3.1.2.6.2.1)
+#[derive(Debug, PartialEq, Eq, DekuRead, DekuWrite, Copy, Clone)]
+#[deku(type = "u8", bits = "3")]
+#[non_exhaustive]
+pub enum ProtocolCode {
+    /// No changes in transponder state
+    #[deku(id = "0")]
+    NoChanges,
+    /// Non-selective all-call lockout
+    #[deku(id = "1")]
+    NonSelectiveLockout,
+    /// Cancel Comm-B broadcast or reservation
+    #[deku(id = "4")]
+    CancelCommB,
+    /// Cancel Comm-C reservation
+    #[deku(id = "5")]
+    CancelCommC,
+    /// Cancel Comm-D reservation
+    #[deku(id = "6")]
+    CancelCommD,
+    /// Not assigned
+    #[deku(id_pat = "_")]
+    Unassigned(#[deku(bits = "3")] u8),
+}
+
+impl fmt::Display for ProtocolCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoChanges => write!(f, "no changes"),
+            Self::NonSelectiveLockout => write!(f, "non-selective all-call lockout"),
+            Self::CancelCommB => write!(f, "cancel Comm-B"),
+            Self::CancelCommC => write!(f, "cancel Comm-C"),
+            Self::CancelCommD => write!(f, "cancel Comm-D"),
+            Self::Unassigned(pc) => write!(f, "unassigned ({pc})"),
+        }
+    }
+}
@@ -1,6 +1,25 @@
-use adsb_deku::adsb::{VerticalRateSource, ME};
+use adsb_deku::acas::ThreatIdentity;
+use adsb_deku::adsb::{
+    ADSBVersion, AccuracyContext, AddressQualifier, AddressType, AirborneVelocitySubType,
+    AircraftStatusData, AircraftStatusType, CapabilityClassAirborne, ControlFieldMessage,
+    ControlFieldType, EmergencyState, EmitterCategory, GpsAntennaOffset, Nic, OperationStatus,
+    PositionQuality, SelectedAltitudeSource, StatusForGroundTrack, SurfacePosition, TargetMode,
+    TargetStateAndStatus, TestMessage, TrajectoryChangeCapability, TypeCoding, VerticalRateSource,
+    ME,
+};
+use adsb_deku::bds::{
+    self, AcasVersion, AircraftIdentification, DataLinkCapability, Hazard, MeteorologicalSource,
+    Register, TargetAltitudeSource, BDS,
+};
+use adsb_deku::crc::{BitFix, ErrorCorrection};
 use adsb_deku::deku::prelude::*;
-use adsb_deku::{CPRFormat, Capability, Frame, DF};
+use adsb_deku::mode_ac::{parse_reply, Reply};
+use adsb_deku::registration::{icao_to_n_number, n_number_to_icao};
+use adsb_deku::uplink::{ProtocolCode, UplinkFrame, ALL_CALL_ADDRESS, UF};
+use adsb_deku::{
+    AC13Field, Altitude, CPRFormat, Capability, DownlinkRequest, FlightStatus, Frame, Peek, Sign,
+    Squawk, SurveillanceStatus, DF, ICAO,
+};
 use assert_hex::assert_eq_hex;
 use hexlit::hex;
 
@@ -32,7 +51,7 @@ fn testing02() {
             assert!((heading - 322.197_2).abs() < f32::EPSILON);
             assert!((ground_speed - 417.655_360_315_176_6).abs() < f64::EPSILON);
             assert_eq!(vertical_rate, 0);
-            assert_eq!(me.vrate_src, VerticalRateSource::GeometricAltitude);
+            assert_eq!(me.vrate_src, VerticalRateSource::BarometricPressureAltitude);
             return;
         }
     }
@@ -60,7 +79,7 @@ fn testing03() {
     let bytes = hex!("8da08f94ea1b785e8f3c088ab467");
     let frame = Frame::from_bytes((&bytes, 0));
     if let DF::ADSB(adsb) = frame.unwrap().1.df {
-        if let ME::TargetStateAndStatusInformation(me) = adsb.me {
+        if let ME::TargetStateAndStatusInformation(TargetStateAndStatus::V2(me)) = adsb.me {
             assert_eq!(me.subtype, 1);
             assert!(!me.is_fms);
             assert_eq!(me.altitude, 14016);
@@ -156,7 +175,7 @@ fn testing06() {
     let bytes = hex!("8dab3d17ea486860015f4870b796");
     let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
     if let DF::ADSB(adsb) = frame.df {
-        if let ME::TargetStateAndStatusInformation(me) = adsb.me {
+        if let ME::TargetStateAndStatusInformation(TargetStateAndStatus::V2(me)) = adsb.me {
             assert_eq!(me.subtype, 1);
             assert!(!me.is_fms);
             assert_eq!(me.altitude, 37024);
@@ -214,9 +233,12 @@ fn testing_df_shortairairsurveillance() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Short Air-Air Surveillance
-  ICAO Address:  0d097e (Mode S / ADS-B)
-  Air/Ground:    airborne?
+  ICAO Address:  0D097E (Mode S / ADS-B)
+  Air/Ground:    airborne
   Altitude:      45000 ft barometric
+  Cross-link:    supported
+  Sensitivity:   7
+  Reply Info:    ACAS, vertical-only resolution
 "#,
         resulting_string
     );
@@ -280,7 +302,7 @@ fn testing_df_extendedsquitteraircraftopstatus() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Aircraft operational status (airborne)
-  Address:       0d097e (Mode S / ADS-B)
+  Address:       0D097E (Mode S / ADS-B)
   Air/Ground:    airborne
   Aircraft Operational Status:
    Version:            2
@@ -301,7 +323,7 @@ fn testing_df_extendedsquitteraircraftopstatus() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Aircraft operational status (airborne)
-  Address:       a1a8da (Mode S / ADS-B)
+  Address:       A1A8DA (Mode S / ADS-B)
   Air/Ground:    airborne
   Aircraft Operational Status:
    Version:            2
@@ -318,6 +340,40 @@ fn testing_df_extendedsquitteraircraftopstatus() {
     );
 }
 
+#[test]
+fn testing_df_extendedsquitteraircraftopstatus_capability_class() {
+    let bytes = hex!("8d0d097ef833a007005ab8e279c9");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(frame.is_valid());
+    let capability_class = match frame.df {
+        DF::ADSB(adsb) => match adsb.me {
+            ME::AircraftOperationStatus(OperationStatus::Airborne(opstatus)) => {
+                opstatus.capability_class
+            },
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+    assert_eq!(
+        capability_class,
+        CapabilityClassAirborne {
+            reserved0: 0,
+            acas: true,
+            es1090: true,
+            reserved1: 0,
+            arv: true,
+            ts: true,
+            tc: TrajectoryChangeCapability::Multiple,
+            uat_in: true,
+            reserved2: 0,
+        }
+    );
+    assert_eq!(
+        capability_class.to_string(),
+        " ACAS 1090IN UATIN ARV TS TC+n"
+    );
+}
+
 #[test]
 fn testing_allcall_reply() {
     let bytes = hex!("5da58fd4561b39");
@@ -325,8 +381,9 @@ fn testing_allcall_reply() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" All Call Reply
-  ICAO Address:  a58fd4 (Mode S / ADS-B)
+  ICAO Address:  A58FD4 (Mode S / ADS-B)
   Air/Ground:    airborne
+  Interrogator:  II 0
 "#,
         resulting_string
     );
@@ -339,7 +396,7 @@ fn testing_airbornepositionbaroaltitude() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Airborne position (barometric altitude)
-  Address:       a2c1bd (Mode S / ADS-B)
+  Address:       A2C1BD (Mode S / ADS-B)
   Air/Ground:    airborne
   Altitude:      23650 ft barometric
   CPR type:      Airborne
@@ -351,6 +408,66 @@ fn testing_airbornepositionbaroaltitude() {
     );
 }
 
+#[test]
+fn testing_airbornepositionbaroaltitude_spi() {
+    let bytes = hex!("8d40621d5ec382d690c8ace1618e");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::ADSB(adsb) = &frame.df {
+        if let ME::AirbornePositionBaroAltitude(altitude) = &adsb.me {
+            assert_eq!(altitude.ss, SurveillanceStatus::SPICondition);
+        } else {
+            unreachable!();
+        }
+    } else {
+        unreachable!();
+    }
+    let resulting_string = format!("{}", frame);
+    assert_eq!(
+        r#" Extended Squitter Airborne position (barometric altitude)
+  Address:       40621D (Mode S / ADS-B)
+  Air/Ground:    airborne
+  Altitude:      38000 ft barometric
+  Surveillance:  SPI condition
+  CPR type:      Airborne
+  CPR odd flag:  even
+  CPR latitude:  (93000)
+  CPR longitude: (51372)
+"#,
+        resulting_string
+    );
+}
+
+#[test]
+fn testing_airbornepositiongnssaltitude() {
+    let bytes = hex!("8d40621da0bb86435cc412f58305");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::ADSB(adsb) = &frame.df {
+        if let ME::AirbornePositionGNSSAltitude(altitude) = &adsb.me {
+            assert_eq!(altitude.tc, 20);
+            assert!(altitude.is_gnss());
+            // 3000 m
+            assert_eq!(altitude.alt, Some(9843));
+        } else {
+            unreachable!();
+        }
+    } else {
+        unreachable!();
+    }
+    assert_eq!(frame.to_bytes().unwrap(), bytes);
+    let resulting_string = format!("{}", frame);
+    assert_eq!(
+        r#" Extended Squitter Airborne position (GNSS altitude)
+  Address:      40621D (Mode S / ADS-B)
+  Altitude:      9843 ft GNSS height (HAE)
+  CPR type:      Airborne
+  CPR odd flag:  odd
+  CPR latitude:  (74158)
+  CPR longitude: (50194)
+"#,
+        resulting_string
+    );
+}
+
 #[test]
 fn testing_surveillancealtitudereply() {
     let bytes = hex!("200012b0d96e39");
@@ -358,7 +475,7 @@ fn testing_surveillancealtitudereply() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Surveillance, Altitude Reply
-  ICAO Address:  a3ecce (Mode S / ADS-B)
+  ICAO Address:  A3ECCE (Mode S / ADS-B)
   Air/Ground:    airborne?
   Altitude:      29000 ft barometric
 "#,
@@ -366,6 +483,442 @@ fn testing_surveillancealtitudereply() {
     );
 }
 
+#[test]
+fn testing_surveillancealtitudereply_fields() {
+    let bytes = hex!("200012b0d96e39");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::SurveillanceAltitudeReply(reply) = frame.df {
+        assert_eq!(reply.fs, FlightStatus::NoAlertNoSPIAirborne);
+        assert_eq!(reply.dr, DownlinkRequest::None);
+        assert_eq!(reply.um.iis, 0);
+        assert_eq!(reply.altitude(), Some(29000));
+    } else {
+        unreachable!();
+    }
+
+    // M bit set, metric altitude of 2416 m
+    let bytes = hex!("200012f0d96e39");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::SurveillanceAltitudeReply(reply) = frame.df {
+        assert_eq!(reply.altitude(), Some(7927));
+    } else {
+        unreachable!();
+    }
+}
+
+#[test]
+fn testing_ac13field_gillham() {
+    // above the 50175 ft of 25 ft increments, gillham coded in 100 ft increments
+    for altitude in [29000, 50100, 50200, 60000, 65000] {
+        let bytes = AC13Field(altitude).to_bytes().unwrap();
+        // Q bit
+        assert_eq!(bytes[1] & 0x80 != 0, altitude <= 50175);
+        let (_, field) = AC13Field::from_bytes((&bytes, 0)).unwrap();
+        assert_eq!(field.altitude(), Some(altitude));
+
+        // 12 bit altitude of airborne positions, without the M bit
+        let position = Altitude {
+            tc: 11,
+            alt: Some(altitude),
+            ..Altitude::default()
+        };
+        let bytes = position.to_bytes().unwrap();
+        assert_eq!(Altitude::from_bytes((&bytes, 0)).unwrap().1, position);
+    }
+}
+
+#[test]
+fn testing_mode_ac_reply() {
+    assert_eq!(
+        parse_reply(&[0x45, 0x20]),
+        Ok(Reply {
+            squawk: Squawk(0x4520),
+            altitude: Some(3500),
+            ident: false
+        })
+    );
+    // same code with ident is only sent as a Mode A reply
+    assert_eq!(
+        parse_reply(&[0x45, 0xa0]),
+        Ok(Reply {
+            squawk: Squawk(0x4520),
+            altitude: None,
+            ident: true
+        })
+    );
+    // without C digit, not a valid altitude
+    assert_eq!(
+        parse_reply(&[0x12, 0x00]),
+        Ok(Reply {
+            squawk: Squawk(0x1200),
+            altitude: None,
+            ident: false
+        })
+    );
+    // a squawk that is also a valid altitude
+    let reply = parse_reply(&[0x71, 0x20]).unwrap();
+    assert_eq!(reply.squawk, Squawk(0x7120));
+    assert_eq!(reply.altitude, Some(19000));
+    assert!(parse_reply(&[0x12]).is_err());
+    assert!(parse_reply(&hex!("5da039b46d7d81")).is_err());
+}
+
+#[test]
+fn testing_commbaltitudereply_fields() {
+    let bytes = hex!("a0001910204d7075d35820c25c0c");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::CommBAltitudeReply(reply) = frame.df {
+        assert_eq!(reply.fs, FlightStatus::NoAlertNoSPIAirborne);
+        assert_eq!(reply.altitude(), Some(39000));
+        assert_eq!(reply.mb.mb, hex!("204d7075d35820"));
+        assert_eq!(
+            reply.mb.bds,
+            BDS::AircraftIdentification(AircraftIdentification {
+                callsign: "SWA545".to_string()
+            })
+        );
+        assert_eq!(reply.mb.callsign(), Some("SWA545"));
+        return;
+    }
+    unreachable!();
+}
+
+#[test]
+fn testing_commbidentityreply_fields() {
+    let bytes = hex!("AE24238D15EE315463718B1AF755");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::CommBIdentityReply(reply) = frame.df {
+        assert_eq!(reply.fs, FlightStatus::Reserved);
+        assert_eq!(reply.squawk(), Squawk(0x6246));
+        assert_eq!(reply.mb.mb, hex!("15EE315463718B"));
+        assert_eq!(reply.mb.bds, BDS::Unknown(hex!("15EE315463718B")));
+        return;
+    }
+    unreachable!();
+}
+
+#[test]
+fn testing_bds_10() {
+    let bytes = hex!("A800178D10010080F50000D5893C");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let mb = match frame.df {
+        DF::CommBIdentityReply(reply) => reply.mb,
+        _ => unreachable!(),
+    };
+    assert_eq!(
+        mb.bds,
+        BDS::DataLinkCapability(DataLinkCapability {
+            continuation_flag: false,
+            overlay_command_capability: false,
+            acas: true,
+            mode_s_subnetwork_version_number: 0,
+            transponder_enhanced_protocol_indicator: false,
+            mode_s_specific_services_capability: true,
+            uplink_elm_average_throughput_capability: 0,
+            downlink_elm: 0,
+            aircraft_identification_capability: true,
+            squitter_capability_subfield: true,
+            surveillance_identifier_code: true,
+            common_usage_gicb_capability_report: true,
+            acas_hybrid_surveillance: false,
+            acas_resolution_advisories: true,
+            acas_version: AcasVersion::Do185A,
+            bit_array: 0,
+        })
+    );
+    assert_eq!(
+        mb.to_string(),
+        "Comm-B format: BDS1,0 Datalink capabilities
+  Subnetwork:    version 0
+  ACAS:          operating, TA/RA, RTCA DO-185A
+"
+    );
+}
+
+#[test]
+fn testing_bds_17() {
+    let bytes = hex!("A0000638FA81C10000000081A92F");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let mb = match frame.df {
+        DF::CommBAltitudeReply(reply) => reply.mb,
+        _ => unreachable!(),
+    };
+    let capability = mb.common_usage_gicb_capability().unwrap();
+    assert!(capability.es_airborne_position);
+    assert!(capability.es_airborne_velocity);
+    assert!(!capability.es_event_driven);
+    assert!(capability.aircraft_identification);
+    assert!(!capability.aircraft_registration);
+    assert!(capability.selected_vertical_intention);
+    assert!(!capability.meteorological_routine);
+    assert!(capability.track_and_turn);
+    assert!(capability.heading_and_speed);
+    assert_eq!(
+        capability.to_string(),
+        "0,5 0,6 0,7 0,8 0,9 2,0 4,0 5,0 5,1 5,2 6,0"
+    );
+
+    // BDS 2,0 is not a capability report
+    let bytes = hex!("A0001838201584F23468207CDFA5");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let mb = match frame.df {
+        DF::CommBAltitudeReply(reply) => reply.mb,
+        _ => unreachable!(),
+    };
+    assert_eq!(mb.common_usage_gicb_capability(), None);
+}
+
+#[test]
+fn testing_bds_40() {
+    let mb = |bytes: &[u8]| match Frame::from_bytes((bytes, 0)).unwrap().1.df {
+        DF::CommBAltitudeReply(reply) => reply.mb,
+        _ => unreachable!(),
+    };
+
+    let intention = mb(&hex!("A000029C85E42F313000007047D3"))
+        .selected_vertical_intention()
+        .unwrap();
+    assert_eq!(intention.mcp_selected_altitude(), Some(3008));
+    assert_eq!(intention.fms_selected_altitude(), Some(3008));
+    assert_eq!(intention.barometric_pressure_setting(), Some(1020.0));
+    assert!(!intention.mode_status);
+    assert_eq!(intention.altitude_source(), None);
+
+    let commb = mb(&hex!("a0001839ca3800315801a67ca604"));
+    let intention = commb.selected_vertical_intention().unwrap();
+    assert_eq!(intention.to_bytes().unwrap(), commb.mb);
+    assert_eq!(intention.mcp_selected_altitude(), Some(38000));
+    assert_eq!(intention.fms_selected_altitude(), None);
+    assert_eq!(intention.barometric_pressure_setting(), Some(1022.0));
+    assert!(intention.mode_status);
+    assert!(intention.vnav);
+    assert!(!intention.alt_hold);
+    assert!(intention.approach);
+    assert_eq!(intention.altitude_source(), Some(TargetAltitudeSource::Mcp));
+    assert_eq!(
+        intention.to_string(),
+        r#"  MCP altitude:  38000 ft
+  QNH:           1022.0 millibars
+  Modes:         vnav approach
+  Target source: MCP
+"#
+    );
+
+    // BDS 2,0 is not a selected vertical intention
+    assert_eq!(
+        mb(&hex!("A0001838201584F23468207CDFA5")).selected_vertical_intention(),
+        None
+    );
+}
+
+#[test]
+fn testing_bds_44() {
+    let mb = |bytes: &[u8]| match Frame::from_bytes((bytes, 0)).unwrap().1.df {
+        DF::CommBAltitudeReply(reply) => reply.mb,
+        _ => unreachable!(),
+    };
+
+    let report = mb(&hex!("A0001692185BD5CF400000DFC696"))
+        .meteorological_routine_air_report()
+        .unwrap();
+    assert_eq!(report.source, MeteorologicalSource::Ins);
+    assert_eq!(report.wind(), Some((22, 344.53125)));
+    assert_eq!(report.temperature, -48.75);
+    assert_eq!(report.static_pressure(), None);
+    assert_eq!(report.turbulence(), None);
+    assert_eq!(report.humidity(), None);
+
+    let commb = mb(&hex!("a0001692185bd5cf63eae05e2953"));
+    let report = commb.meteorological_routine_air_report().unwrap();
+    assert_eq!(report.to_bytes().unwrap(), commb.mb);
+    assert_eq!(report.static_pressure(), Some(250));
+    assert_eq!(report.turbulence(), Some(Hazard::Light));
+    assert_eq!(report.humidity(), Some(50.0));
+    assert_eq!(
+        report.to_string(),
+        r#"  Source:        INS
+  Wind:          22 kt, 344.5
+  Temperature:   -48.75 C
+  Pressure:      250 hPa
+  Turbulence:    light
+  Humidity:      50.0%
+"#
+    );
+
+    // BDS 2,0 is not a meteorological report
+    assert_eq!(
+        mb(&hex!("A0001838201584F23468207CDFA5")).meteorological_routine_air_report(),
+        None
+    );
+}
+
+#[test]
+fn testing_bds_45() {
+    let bytes = hex!("a0001692b06feba4b20c801d5343");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let mb = match frame.df {
+        DF::CommBAltitudeReply(reply) => reply.mb,
+        _ => unreachable!(),
+    };
+    let report = mb.meteorological_hazard_report().unwrap();
+    assert_eq!(report.to_bytes().unwrap(), mb.mb);
+    assert_eq!(report.turbulence(), Some(Hazard::Light));
+    assert_eq!(report.wind_shear(), Some(Hazard::Nil));
+    assert_eq!(report.microburst(), None);
+    assert_eq!(report.icing(), Some(Hazard::Moderate));
+    assert_eq!(report.wake_vortex(), Some(Hazard::Severe));
+    assert_eq!(report.static_air_temperature(), Some(-20.5));
+    assert_eq!(report.static_pressure(), Some(300));
+    assert_eq!(report.radio_height(), Some(1600));
+    assert_eq!(
+        report.to_string(),
+        r#"  Turbulence:    light
+  Wind shear:    nil
+  Icing:         moderate
+  Wake vortex:   severe
+  Temperature:   -20.50 C
+  Pressure:      300 hPa
+  Radio height:  1600 ft
+"#
+    );
+
+    // BDS 2,0 is not a meteorological report
+    let bytes = hex!("A0001838201584F23468207CDFA5");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let mb = match frame.df {
+        DF::CommBAltitudeReply(reply) => reply.mb,
+        _ => unreachable!(),
+    };
+    assert_eq!(mb.meteorological_hazard_report(), None);
+}
+
+#[test]
+fn testing_bds_50() {
+    let bytes = hex!("A000139381951536E024D4CCF6B5");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let mb = match frame.df {
+        DF::CommBAltitudeReply(reply) => reply.mb,
+        _ => unreachable!(),
+    };
+    let report = mb.track_and_turn_report().unwrap();
+    assert_eq!(report.to_bytes().unwrap(), mb.mb);
+    assert_eq!(report.roll_angle(), Some(2.109375));
+    assert_eq!(report.true_track(), Some(114.257_81));
+    assert_eq!(report.ground_speed(), Some(438));
+    assert_eq!(report.track_angle_rate(), Some(0.125));
+    assert_eq!(report.true_airspeed(), Some(424));
+    assert_eq!(
+        report.to_string(),
+        r#"  Roll angle:    2.1
+  True track:    114.3
+  Ground speed:  438 kt
+  Track rate:    0.125 deg/s
+  TAS:           424 kt
+"#
+    );
+
+    // negative track, west of south
+    let mb = bds::CommB {
+        mb: hex!("001c0000000000"),
+        bds: BDS::Empty([0; 6]),
+    };
+    let report = mb.track_and_turn_report().unwrap();
+    assert_eq!(report.true_track(), Some(270.0));
+    assert_eq!(report.roll_angle(), None);
+    assert_eq!(report.ground_speed(), None);
+}
+
+#[test]
+fn testing_bds_60() {
+    let bytes = hex!("A00004128F39F91A7E27C46ADC21");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let mb = match frame.df {
+        DF::CommBAltitudeReply(reply) => reply.mb,
+        _ => unreachable!(),
+    };
+    let report = mb.heading_and_speed_report().unwrap();
+    assert_eq!(report.to_bytes().unwrap(), mb.mb);
+    assert_eq!(report.magnetic_heading(), Some(42.714_844));
+    assert_eq!(report.indicated_airspeed(), Some(252));
+    assert_eq!(report.mach_number(), Some(0.42));
+    assert_eq!(report.baro_altitude_rate(), Some(-1920));
+    assert_eq!(report.inertial_vertical_velocity(), Some(-1920));
+    assert_eq!(
+        report.to_string(),
+        r#"  Mag heading:   42.7
+  IAS:           252 kt
+  Mach:          0.420
+  Baro rate:     -1920 ft/min
+  Inertial rate: -1920 ft/min
+"#
+    );
+
+    // BDS 2,0 is not a heading and speed report
+    let bytes = hex!("A0001838201584F23468207CDFA5");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let mb = match frame.df {
+        DF::CommBAltitudeReply(reply) => reply.mb,
+        _ => unreachable!(),
+    };
+    assert_eq!(mb.heading_and_speed_report(), None);
+}
+
+#[test]
+fn testing_bds_infer() {
+    let infer = |bytes: &[u8]| {
+        let frame = Frame::from_bytes((bytes, 0)).unwrap().1;
+        match frame.df {
+            DF::CommBAltitudeReply(reply) => reply.mb.infer(false),
+            DF::CommBIdentityReply(reply) => reply.mb.infer(false),
+            _ => unreachable!(),
+        }
+    };
+    assert_eq!(
+        infer(&hex!("A800178D10010080F50000D5893C")),
+        Register::DataLinkCapability
+    );
+    assert_eq!(
+        infer(&hex!("A0000638FA81C10000000081A92F")),
+        Register::CommonUsageGicbCapability
+    );
+    assert_eq!(
+        infer(&hex!("A0001838201584F23468207CDFA5")),
+        Register::AircraftIdentification
+    );
+    assert_eq!(
+        infer(&hex!("A0001839CA3800315800007448D9")),
+        Register::SelectedVerticalIntention
+    );
+    assert_eq!(
+        infer(&hex!("A000139381951536E024D4CCF6B5")),
+        Register::TrackAndTurn
+    );
+    assert_eq!(
+        infer(&hex!("A00004128F39F91A7E27C46ADC21")),
+        Register::HeadingAndSpeed
+    );
+    assert_eq!(
+        infer(&hex!("a000179f0000000000000019a524")),
+        Register::Empty
+    );
+
+    let frame = Frame::from_bytes((&hex!("A0001692185BD5CF400000DFC696"), 0))
+        .unwrap()
+        .1;
+    if let DF::CommBAltitudeReply(reply) = frame.df {
+        assert_eq!(reply.mb.infer(false), Register::Unknown);
+        assert_eq!(reply.mb.infer(true), Register::MeteorologicalRoutine);
+    }
+
+    // ground speed or mach
+    let mb = hex!("00000100400000");
+    assert_eq!(
+        bds::candidates(&mb, false),
+        vec![Register::TrackAndTurn, Register::HeadingAndSpeed]
+    );
+    assert_eq!(bds::infer(&mb, false), Register::Unknown);
+}
+
 #[test]
 fn testing_surveillanceidentityreply_err() {
     let bytes = hex!("245093892a1bfd");
@@ -373,9 +926,8 @@ fn testing_surveillanceidentityreply_err() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Surveillance, Altitude Reply
-  ICAO Address:  a168ad (Mode S / ADS-B)
+  ICAO Address:  A168AD (Mode S / ADS-B)
   Air/Ground:    airborne?
-  Altitude:      6500 ft barometric
 "#,
         resulting_string
     );
@@ -390,7 +942,7 @@ fn testing_surveillanceidentityreply() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Surveillance, Identity Reply
-  ICAO Address:  510af9 (Mode S / ADS-B)
+  ICAO Address:  510AF9 (Mode S / ADS-B)
   Air/Ground:    airborne
   Identity:      0356
 "#,
@@ -398,6 +950,19 @@ fn testing_surveillanceidentityreply() {
     );
 }
 
+#[test]
+fn testing_surveillanceidentityreply_fields() {
+    let bytes = hex!("2A00516D492B80");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::SurveillanceIdentityReply(reply) = frame.df {
+        assert_eq!(reply.fs, FlightStatus::AlertNoSPIAirborne);
+        assert_eq!(reply.dr, DownlinkRequest::None);
+        assert_eq!(reply.squawk(), Squawk(0x0356));
+        return;
+    }
+    unreachable!();
+}
+
 #[test]
 fn testing_airbornevelocity() {
     let bytes = hex!("8dac8e1a9924263950043944cf32");
@@ -405,12 +970,12 @@ fn testing_airbornevelocity() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Airborne velocity over ground, subsonic
-  Address:       ac8e1a (Mode S / ADS-B)
+  Address:       AC8E1A (Mode S / ADS-B)
   Air/Ground:    airborne
   GNSS delta:    1400 ft
   Heading:       356
   Speed:         458 kt groundspeed
-  Vertical rate: 0 ft/min GNSS
+  Vertical rate: 0 ft/min barometric
 "#,
         resulting_string
     );
@@ -420,12 +985,12 @@ fn testing_airbornevelocity() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Airborne velocity over ground, subsonic
-  Address:       a3f9cb (Mode S / ADS-B)
+  Address:       A3F9CB (Mode S / ADS-B)
   Air/Ground:    airborne
   GNSS delta:    -100 ft
   Heading:       8
   Speed:         109 kt groundspeed
-  Vertical rate: -256 ft/min barometric
+  Vertical rate: -256 ft/min GNSS
 "#,
         resulting_string
     );
@@ -438,11 +1003,12 @@ fn testing_targetstateandstatusinformation() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Target state and status (V2)
-  Address:       a97753 (Mode S / ADS-B)
+  Address:       A97753 (Mode S / ADS-B)
   Air/Ground:    airborne
   Target State and Status:
     Target altitude:   MCP, 23008 ft
     Altimeter setting: 1012.8 millibars
+    Target heading:    not available
     ACAS:              NOT operational
     NACp:              10
     NICbaro:           1
@@ -460,13 +1026,125 @@ fn testing_aircraftidentificationandcategory() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Aircraft identification and category
-  Address:       a3f9cb (Mode S / ADS-B)
+  Address:       A3F9CB (Mode S / ADS-B)
   Air/Ground:    airborne
   Ident:         N3550U
-  Category:      A1
+  Category:      A1 (light)
+"#,
+        resulting_string
+    );
+}
+
+#[test]
+fn testing_emitter_category() {
+    let bytes = hex!("8da3f9cb213b3d75c1582080f4d9");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::ADSB(adsb) = frame.df {
+        if let ME::AircraftIdentification(identification) = adsb.me {
+            assert_eq!(identification.emitter_category(), EmitterCategory::Light);
+            return;
+        }
+    }
+    unreachable!();
+}
+
+#[test]
+fn testing_emitter_category_sets() {
+    assert_eq!(
+        EmitterCategory::new(TypeCoding::A, 0),
+        EmitterCategory::NoInformation
+    );
+    assert_eq!(
+        EmitterCategory::new(TypeCoding::A, 5),
+        EmitterCategory::Heavy
+    );
+    assert_eq!(
+        EmitterCategory::new(TypeCoding::A, 7),
+        EmitterCategory::Rotorcraft
+    );
+    assert_eq!(
+        EmitterCategory::new(TypeCoding::B, 1),
+        EmitterCategory::Glider
+    );
+    assert_eq!(
+        EmitterCategory::new(TypeCoding::B, 5),
+        EmitterCategory::Reserved
+    );
+    assert_eq!(
+        EmitterCategory::new(TypeCoding::B, 6),
+        EmitterCategory::UnmannedAerialVehicle
+    );
+    assert_eq!(
+        EmitterCategory::new(TypeCoding::C, 2),
+        EmitterCategory::SurfaceServiceVehicle
+    );
+    assert_eq!(
+        EmitterCategory::new(TypeCoding::C, 6),
+        EmitterCategory::Reserved
+    );
+    assert_eq!(
+        EmitterCategory::new(TypeCoding::D, 0),
+        EmitterCategory::Reserved
+    );
+    assert_eq!(
+        EmitterCategory::HighVortexLarge.to_string(),
+        "high vortex large"
+    );
+}
+
+#[test]
+fn testing_targetstateandstatusinformation_fms() {
+    let bytes = hex!("8dad50a9eb466867811c0877c055");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(frame.is_valid());
+    if let DF::ADSB(adsb) = &frame.df {
+        if let ME::TargetStateAndStatusInformation(TargetStateAndStatus::V2(target_info)) = &adsb.me
+        {
+            assert_eq!(target_info.altitude_source(), SelectedAltitudeSource::Fms);
+            assert_eq!(target_info.selected_heading(), Some(315.0));
+            assert!(frame
+                .to_string()
+                .contains("    Target altitude:   FMS, 36000 ft\n"));
+            return;
+        }
+    }
+    unreachable!();
+}
+
+#[test]
+fn testing_targetstateandstatus_v1() {
+    let bytes = hex!("8da835afe88cb430eb38003824e7");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(frame.is_valid());
+    let resulting_string = format!("{}", frame);
+    assert_eq!(
+        r#" Extended Squitter Target state and status (V1)
+  Address:       A835AF (Mode S / ADS-B)
+  Air/Ground:    airborne
+  Target State and Status:
+    Target altitude:   MCP, 35000 ft FL
+    Vertical mode:     capturing or maintaining
+    Target track:      MCP, 270
+    Horizontal mode:   acquiring
+    ACAS:              operational
+    NACp:              9
+    NICbaro:           1
+    SIL:               2
+    Emergency/priority: no emergency
 "#,
         resulting_string
     );
+    if let DF::ADSB(adsb) = frame.df {
+        if let ME::TargetStateAndStatusInformation(TargetStateAndStatus::V1(me)) = adsb.me {
+            assert_eq!(me.target_altitude(), Some(35000));
+            assert_eq!(me.target_heading(), Some(270));
+            assert!(me.is_track);
+            assert_eq!(me.vertical_mode, TargetMode::Maintaining);
+            assert_eq!(me.horizontal_mode, TargetMode::Acquiring);
+            return;
+        }
+    }
+    unreachable!();
 }
 
 #[test]
@@ -476,7 +1154,7 @@ fn testing_issue_01() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Target state and status (V2)
-  Address:       ad50a9 (Mode S / ADS-B)
+  Address:       AD50A9 (Mode S / ADS-B)
   Air/Ground:    airborne
   Target State and Status:
     Target altitude:   MCP, 36000 ft
@@ -499,65 +1177,417 @@ fn testing_issue_03() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Long Air-Air ACAS
-  ICAO Address:  ac049e (Mode S / ADS-B)
-  Air/Ground:    airborne?
+  ICAO Address:  AC049E (Mode S / ADS-B)
+  Air/Ground:    airborne
   Baro altitude: 35000 ft
+  Sensitivity:   7
+  Reply Info:    ACAS, vertical-only resolution
 "#,
         resulting_string
     );
 }
 
 #[test]
-fn testing_issue_04() {
-    let bytes = hex!("0621776e99b6ad");
+fn testing_df16_resolution_advisory() {
+    let bytes = hex!("80e1969030c2010686cb0c000000");
     let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
     let resulting_string = format!("{}", frame);
     assert_eq!(
-        r#" Short Air-Air Surveillance
-  ICAO Address:  a33325 (Mode S / ADS-B)
-  Air/Ground:    ground
+        r#" Long Air-Air ACAS
+  ICAO Address:  30931C (Mode S / ADS-B)
+  Air/Ground:    airborne
+  Baro altitude: 35000 ft
+  Sensitivity:   7
+  Reply Info:    ACAS, vertical-only resolution
+  RA:            corrective, upward sense, positive
+  RA Complement: do not pass above
+  RA Terminated: no
+  Multi-Threat:  no
+  Threat:        A1B2C3
 "#,
         resulting_string
     );
 }
 
 #[test]
-fn testing_df_21() {
-    let bytes = hex!("AE24238D15EE315463718B1AF755");
+fn testing_df16_resolution_advisory_multiple_threats() {
+    let bytes = hex!("80e196903044011686cb0c10c623");
     let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
     let resulting_string = format!("{}", frame);
     assert_eq!(
-        r#" Comm-B, Identity Reply
-    ICAO Address:  a95fdc (Mode S / ADS-B)
-    Squawk:        6246
-    Comm-B format: unknown format
+        r#" Long Air-Air ACAS
+  ICAO Address:  30931C (Mode S / ADS-B)
+  Air/Ground:    airborne
+  Baro altitude: 35000 ft
+  Sensitivity:   7
+  Reply Info:    ACAS, vertical-only resolution
+  RA:            multiple threats, requires correction upwards, requires crossing
+  RA Complement: do not pass above
+  RA Terminated: no
+  Multi-Threat:  yes
+  Threat:        A1B2C3
 "#,
         resulting_string
     );
 }
 
 #[test]
-fn testing_df_24() {
-    let bytes = hex!("daca7f82613c2db14a49c535a3a2");
+fn testing_bds_30() {
+    let bytes = hex!("a000191030c2010686cb0c5f1bc6");
     let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
     let resulting_string = format!("{}", frame);
     assert_eq!(
-        r#" Comm-D Extended Length Message
-    ICAO Address:     a01f73 (Mode S / ADS-B)
+        r#" Comm-B, Altitude Reply
+  ICAO Address:  4840D6 (Mode S / ADS-B)
+  Altitude:      39000 ft
+  Comm-B format: BDS3,0 ACAS active resolution advisory
+  RA:            corrective, upward sense, positive
+  RA Complement: do not pass above
+  RA Terminated: no
+  Multi-Threat:  no
+  Threat:        A1B2C3
 "#,
         resulting_string
     );
+    if let DF::CommBAltitudeReply(reply) = frame.df {
+        assert_eq!(reply.mb.infer(false), Register::AcasResolutionAdvisory);
+        if let BDS::AcasResolutionAdvisory(ra) = reply.mb.bds {
+            assert!(ra.ara.corrective);
+            assert!(ra.rac.do_not_pass_above);
+            return;
+        }
+    }
+    unreachable!();
 }
 
 #[test]
-fn testing_df_18() {
-    // test github issue #2 (with sample output from dump1090_fa as control)
+fn testing_issue_04() {
+    let bytes = hex!("0621776e99b6ad");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let resulting_string = format!("{}", frame);
+    assert_eq!(
+        r#" Short Air-Air Surveillance
+  ICAO Address:  A33325 (Mode S / ADS-B)
+  Air/Ground:    ground
+  Altitude:      9810 ft barometric
+  Cross-link:    supported
+  Sensitivity:   1
+  Reply Info:    ACAS, resolution inhibited
+"#,
+        resulting_string
+    );
+}
+
+#[test]
+fn testing_df_21() {
+    let bytes = hex!("AE24238D15EE315463718B1AF755");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let resulting_string = format!("{}", frame);
+    assert_eq!(
+        r#" Comm-B, Identity Reply
+    ICAO Address:  A95FDC (Mode S / ADS-B)
+    Squawk:        6246
+    Comm-B format: unknown format
+"#,
+        resulting_string
+    );
+}
+
+#[test]
+fn testing_df_24() {
+    let bytes = hex!("daca7f82613c2db14a49c535a3a2");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let resulting_string = format!("{}", frame);
+    assert_eq!(
+        r#" Comm-D Extended Length Message
+    ICAO Address:     A01F73 (Mode S / ADS-B)
+    Control:          uplink ELM acknowledgement
+    Segment:          10
+"#,
+        resulting_string
+    );
+}
+
+#[test]
+fn testing_df_unknown() {
+    let bytes = hex!("0800000b78f9a3");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(matches!(frame.df, DF::UnknownShort { df: 1, .. }));
+    assert!(!frame.is_valid());
+    assert_eq!(frame.icao(), None);
+    assert_eq!(format!("{}", frame), " Unknown Downlink Format 1\n");
+
+    let bytes = hex!("b0a185b8582f86648c5bfe059abd");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(matches!(frame.df, DF::UnknownLong { df: 22, .. }));
+    assert!(!frame.is_valid());
+}
+
+#[test]
+fn testing_surface_position_velocity() {
+    let bytes = hex!("8C4841753A9A153237AEF0F275BE");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(
+        r#" Extended Squitter Surface position
+  Address:       484175 (Mode S / ADS-B)
+  Air/Ground:    ground
+  Movement:      17-18 kt
+  Ground track:  92.8125
+  CPR type:      Surface
+  CPR odd flag:  odd
+  CPR latitude:  (39195)
+  CPR longitude: (110320)
+"#,
+        frame.to_string()
+    );
+    if let DF::ADSB(adsb) = frame.df {
+        if let ME::SurfacePosition(surface) = adsb.me {
+            assert_eq!(surface.ground_speed(), Some(17.0));
+            assert_eq!(surface.ground_speed_range(), Some(17.0..18.0));
+            assert_eq!(surface.ground_track(), Some(92.8125));
+            return;
+        }
+    }
+    unreachable!();
+}
+
+#[test]
+fn testing_surface_position_ground_speed_range() {
+    let mut surface = SurfacePosition {
+        tc: 7,
+        mov: 0,
+        s: StatusForGroundTrack::Invalid,
+        trk: 0,
+        t: false,
+        f: CPRFormat::Even,
+        lat_cpr: 0,
+        lon_cpr: 0,
+    };
+    assert_eq!(surface.ground_speed_range(), None);
+    assert_eq!(surface.ground_track(), None);
+
+    let ranges = [
+        (1, 0.0..0.125),
+        (2, 0.125..0.25),
+        (8, 0.875..1.0),
+        (9, 1.0..1.25),
+        (12, 1.75..2.0),
+        (13, 2.0..2.5),
+        (38, 14.5..15.0),
+        (39, 15.0..16.0),
+        (93, 69.0..70.0),
+        (94, 70.0..72.0),
+        (108, 98.0..100.0),
+        (109, 100.0..105.0),
+        (123, 170.0..175.0),
+        (124, 175.0..f32::INFINITY),
+    ];
+    for (mov, range) in ranges {
+        surface.mov = mov;
+        assert_eq!(
+            surface.ground_speed_range(),
+            Some(range.clone()),
+            "mov {mov}"
+        );
+        assert_eq!(surface.ground_speed(), Some(range.start));
+    }
+    for mov in 125..=127 {
+        surface.mov = mov;
+        assert_eq!(surface.ground_speed_range(), None);
+    }
+
+    surface.s = StatusForGroundTrack::Valid;
+    surface.trk = 64;
+    assert_eq!(surface.ground_track(), Some(180.0));
+    surface.trk = 127;
+    assert_eq!(surface.ground_track(), Some(357.1875));
+}
+
+#[test]
+fn testing_df_18_control_field() {
+    let bytes = hex!("95298FCA680946499671468C7ACA");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::TisB { cf, .. } = frame.df {
+        assert_eq!(cf.t, ControlFieldType::TISB_ADSB_RELAY);
+        assert_eq!(cf.aa, ICAO([0x29, 0x8f, 0xca]));
+        assert_eq!(cf.address_type(), AddressType::NonIcao);
+        assert_eq!(cf.address_qualifier(), Some(AddressQualifier::Anonymous));
+        assert!(matches!(cf.me(), Some(ME::AirbornePositionBaroAltitude(_))));
+        if let ControlFieldMessage::Relay(ME::AirbornePositionBaroAltitude(me)) = cf.me {
+            assert_eq!(me.alt, Some(700));
+            return;
+        }
+    }
+    unreachable!();
+}
+
+#[test]
+fn testing_df_18_tisb_coarse() {
+    let bytes = hex!("93abcdef0a3e141f1234563bf4eb");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(frame.is_valid());
+    if let DF::TisB { cf, .. } = &frame.df {
+        assert_eq!(cf.t, ControlFieldType::TISB_COARSE);
+        assert_eq!(cf.aa, ICAO([0xab, 0xcd, 0xef]));
+        assert_eq!(cf.address_type(), AddressType::Icao);
+        assert_eq!(cf.address_qualifier(), Some(AddressQualifier::Icao));
+        if let ControlFieldMessage::Coarse(coarse) = cf.me {
+            assert!(!coarse.imf);
+            assert_eq!(coarse.svid, 5);
+            assert_eq!(coarse.alt, Some(5000));
+            assert_eq!(coarse.ground_track(), Some(90.0));
+            assert_eq!(coarse.ground_speed(), 240);
+            assert_eq!(coarse.odd_flag, CPRFormat::Odd);
+            assert_eq!(coarse.lat_cpr, 0x123);
+            assert_eq!(coarse.lon_cpr, 0x456);
+        } else {
+            unreachable!();
+        }
+    } else {
+        unreachable!();
+    }
+    assert_eq!(
+        r#" Extended Squitter (Non-Transponder) TIS-B coarse airborne position
+  Address:       ABCDEF (TIS-B)
+  Air/Ground:    airborne?
+  Service volume: 5
+  Altitude:      5000 ft barometric
+  Ground track:  90.0
+  Ground speed:  240 kt
+  CPR type:      Airborne (coarse)
+  CPR odd flag:  odd
+  CPR latitude:  (291)
+  CPR longitude: (1110)
+"#,
+        format!("{frame}")
+    );
+    assert_eq!(frame.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn testing_df_18_address_qualifier() {
+    let bytes = hex!("93abcdef0a3e141f1234563bf4eb");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.address_qualifier(), Some(AddressQualifier::Icao));
+    if let DF::TisB { mut cf, .. } = frame.df {
+        if let ControlFieldMessage::Coarse(coarse) = &mut cf.me {
+            coarse.imf = true;
+        }
+        assert_eq!(cf.address_qualifier(), Some(AddressQualifier::TrackFile));
+        assert_eq!(cf.address_type(), AddressType::NonIcao);
+        cf.t = ControlFieldType::ADSB_ES_NT_ALT;
+        assert_eq!(cf.address_qualifier(), Some(AddressQualifier::NonIcao));
+        assert!(!AddressQualifier::TrackFile.is_icao());
+        assert_eq!(AddressQualifier::TrackFile.to_string(), "TIS-B track file");
+    } else {
+        unreachable!();
+    }
+
+    let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.address_qualifier(), Some(AddressQualifier::Icao));
+    let bytes = hex!("94c0ffeef9fffffffffffff0655d");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.address_qualifier(), None);
+}
+
+#[test]
+fn testing_df_18_tisb_management() {
+    let bytes = hex!("94c0ffeef9fffffffffffff0655d");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(frame.is_valid());
+    assert_eq!(frame.icao(), None);
+    if let DF::TisB { cf, .. } = &frame.df {
+        assert_eq!(cf.t, ControlFieldType::TISB_MANAGE);
+        assert_eq!(cf.address_type(), AddressType::None);
+        assert_eq!(cf.address_qualifier(), None);
+        assert_eq!(
+            cf.me,
+            ControlFieldMessage::Management(hex!("f9ffffffffffff"))
+        );
+        assert_eq!(cf.management_message(), Some(hex!("c0ffeef9ffffffffffff")));
+    } else {
+        unreachable!();
+    }
+    assert_eq!(
+        r#" Extended Squitter (Non-Transponder) TIS-B/ADS-R management
+  Data:          c0ffeef9ffffffffffff
+"#,
+        format!("{frame}")
+    );
+    assert_eq!(frame.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn testing_df_18_reserved() {
+    let bytes = hex!("97123456aabbccddeeff00514497");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(frame.is_valid());
+    if let DF::TisB { cf, .. } = &frame.df {
+        assert_eq!(cf.t, ControlFieldType::Reserved);
+        assert_eq!(cf.address_type(), AddressType::NonIcao);
+        assert_eq!(cf.me, ControlFieldMessage::Reserved(hex!("aabbccddeeff00")));
+        assert_eq!(cf.me(), None);
+    } else {
+        unreachable!();
+    }
+    assert_eq!(
+        r#" Extended Squitter (Non-Transponder) reserved
+  Address:       123456 (unknown addressing scheme)
+  Data:          aabbccddeeff00
+"#,
+        format!("{frame}")
+    );
+    assert_eq!(frame.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn testing_df_19() {
+    let bytes = hex!("98a1b2c3d4e5f60718293a6a0694");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.icao(), None);
+    #[cfg(not(feature = "military"))]
+    {
+        assert_eq!(
+            frame.df,
+            DF::ExtendedSquitterMilitary {
+                af: 0,
+                payload: [0; 13]
+            }
+        );
+        // only the AF field is read
+        assert_eq!(frame.to_bytes().unwrap(), [0x98]);
+    }
+    #[cfg(feature = "military")]
+    {
+        assert!(frame.is_valid());
+        assert_eq!(
+            frame.df,
+            DF::ExtendedSquitterMilitary {
+                af: 0,
+                payload: hex!("a1b2c3d4e5f60718293a6a0694"),
+            }
+        );
+        assert_eq!(
+            r#" Extended Squitter Military Application
+  Application:   0
+  Data:          a1b2c3d4e5f60718293a6a0694
+"#,
+            format!("{frame}")
+        );
+        assert_eq!(frame.to_bytes().unwrap(), bytes);
+    }
+}
+
+#[test]
+fn testing_df_18() {
+    // test github issue #2 (with sample output from dump1090_fa as control)
     let bytes = hex!("95298FCA680946499671468C7ACA");
     let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter (Non-Transponder) Airborne position (barometric altitude)
-  Address:       298fca (TIS-B)
+  Address:       298FCA (TIS-B)
   Air/Ground:    airborne?
   Altitude:      700 ft barometric
   CPR type:      Airborne
@@ -574,10 +1604,10 @@ fn testing_df_18() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter (Non-Transponder) Aircraft identification and category
-  Address:       a082fb (ADS-R)
+  Address:       A082FB (ADS-R)
   Air/Ground:    airborne?
   Ident:         N132DS
-  Category:      A1
+  Category:      A1 (light)
 "#,
         resulting_string
     );
@@ -588,12 +1618,12 @@ fn testing_df_18() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter (Non-Transponder) Airborne velocity over ground, subsonic
-  Address:       a6c246 (ADS-R)
+  Address:       A6C246 (ADS-R)
   Air/Ground:    airborne?
   GNSS delta:    150 ft
   Heading:       346
   Speed:         118 kt groundspeed
-  Vertical rate: 320 ft/min barometric
+  Vertical rate: 320 ft/min GNSS
 "#,
         resulting_string
     );
@@ -604,12 +1634,12 @@ fn testing_df_18() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter (Non-Transponder) Airborne velocity over ground, subsonic
-  Address:       a24528 (TIS-B)
+  Address:       A24528 (TIS-B)
   Air/Ground:    airborne?
   GNSS delta:    775 ft
   Heading:       206
   Speed:         78 kt groundspeed
-  Vertical rate: 0 ft/min barometric
+  Vertical rate: 0 ft/min GNSS
 "#,
         resulting_string
     );
@@ -620,7 +1650,7 @@ fn testing_df_18() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter (Non-Transponder) Airborne position (barometric altitude)
-  Address:       130d9d (ADS-R)
+  Address:       130D9D (ADS-R)
   Air/Ground:    airborne?
   Altitude:      2000 ft barometric
   CPR type:      Airborne
@@ -637,7 +1667,7 @@ fn testing_df_18() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter (Non-Transponder) Reserved for surface system status
-  Address:       adf9ce (ADS-B)
+  Address:       ADF9CE (ADS-B)
   Air/Ground:    airborne?
 "#,
         resulting_string
@@ -647,8 +1677,9 @@ fn testing_df_18() {
     let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
     let resulting_string = format!("{}", frame);
     assert_eq!(
-        r#" Extended Squitter (Non-Transponder) Surface position
-  Address:       caeef7 (unknown addressing scheme)
+        r#" Extended Squitter (Non-Transponder) reserved
+  Address:       CAEEF7 (unknown addressing scheme)
+  Data:          37fb1341bf58df
 "#,
         resulting_string
     );
@@ -659,23 +1690,79 @@ fn testing_df_18() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter (Non-Transponder) Aircraft operational status (surface)
-  Address:       a4d01f (ADS-R)
+  Address:       A4D01F (ADS-R)
   Air/Ground:    airborne?
   Aircraft Operational Status:
    Version:            2
+   Capability classes: L/W=1
+   Operational modes:  SAF SDA=2
    NIC-A:              0
    NIC-C:              0
+   NACp:               9
    NACv:               1
-   Capability classes: L/W=1
+   SIL:                3 (per hour)
+   GPS antenna offset: lateral no data, longitudinal no data
+   Track/heading:      heading
+   Heading reference:  true north
+"#,
+        resulting_string
+    );
+}
+#[test]
+fn testing_operation_status_surface() {
+    let bytes = hex!("8ca835aff9304506004938bcb510");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(frame.is_valid());
+    let resulting_string = format!("{}", frame);
+    assert_eq!(
+        r#" Extended Squitter Aircraft operational status (surface)
+  Address:       A835AF (Mode S / ADS-B)
+  Air/Ground:    ground
+  Aircraft Operational Status:
+   Version:            2
+   Capability classes: POA 1090IN L/W=5
    Operational modes:  SAF SDA=2
+   NIC-A:              0
+   NIC-C:              0
    NACp:               9
+   NACv:               2
    SIL:                3 (per hour)
-   NICbaro:            0
+   GPS antenna offset: lateral no data, longitudinal no data
+   Track/heading:      track angle
    Heading reference:  true north
 "#,
         resulting_string
     );
 }
+
+#[test]
+fn testing_gps_antenna_offset() {
+    let offset = GpsAntennaOffset {
+        lateral: 0b000,
+        longitudinal: 0b00000,
+    };
+    assert_eq!(offset.lateral_meters(), None);
+    assert_eq!(offset.longitudinal_meters(), None);
+    assert!(!offset.offset_applied_by_sensor());
+    assert_eq!(offset.to_string(), "lateral no data, longitudinal no data");
+
+    let offset = GpsAntennaOffset {
+        lateral: 0b010,
+        longitudinal: 0b00011,
+    };
+    assert_eq!(offset.lateral_meters(), Some(-4));
+    assert_eq!(offset.longitudinal_meters(), Some(4));
+    assert_eq!(offset.to_string(), "lateral 4 m left, longitudinal 4 m");
+
+    let offset = GpsAntennaOffset {
+        lateral: 0b100,
+        longitudinal: 0b11111,
+    };
+    assert_eq!(offset.lateral_meters(), Some(0));
+    assert_eq!(offset.longitudinal_meters(), Some(60));
+    assert_eq!(offset.to_string(), "lateral 0 m right, longitudinal 60 m");
+}
+
 #[test]
 fn test_emergency() {
     let bytes = hex!("8dc06800e1108500000000baa81f");
@@ -683,7 +1770,7 @@ fn test_emergency() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Emergency/priority status
-  Address:       c06800 (Mode S / ADS-B)
+  Address:       C06800 (Mode S / ADS-B)
   Air/Ground:    airborne
   Squawk:        4016
   Emergency/priority:    no emergency
@@ -692,6 +1779,81 @@ fn test_emergency() {
     );
 }
 
+#[test]
+fn test_emergency_general() {
+    let bytes = hex!("8dc06800e12aaa00000000807ced");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(frame.is_valid());
+    let resulting_string = format!("{}", frame);
+    assert_eq!(
+        r#" Extended Squitter Emergency/priority status
+  Address:       C06800 (Mode S / ADS-B)
+  Air/Ground:    airborne
+  Squawk:        7700
+  Emergency/priority:    general
+"#,
+        resulting_string
+    );
+
+    // emergency cleared, still squawking 7700
+    let bytes = hex!("8dc06800e10aaa00000000240a34");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(frame.is_valid());
+    if let DF::ADSB(adsb) = frame.df {
+        if let ME::AircraftStatus(status) = adsb.me {
+            assert_eq!(
+                status.status,
+                AircraftStatusData::EmergencyPriorityStatus {
+                    emergency_state: EmergencyState::None,
+                    squawk: Squawk::EMERGENCY,
+                }
+            );
+            return;
+        }
+    }
+    unreachable!();
+}
+
+#[test]
+fn test_aircraft_status_acas_ra() {
+    let bytes = hex!("8da835afe2c201068fc70859ed00");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.crc, 0);
+    let resulting_string = format!("{}", frame);
+    assert_eq!(
+        r#" Extended Squitter ACAS resolution advisory
+  Address:       A835AF (Mode S / ADS-B)
+  Air/Ground:    airborne
+  RA:            corrective, upward sense, positive
+  RA Complement: do not pass above
+  RA Terminated: no
+  Multi-Threat:  no
+  Threat:        A3F1C2
+"#,
+        resulting_string
+    );
+    if let DF::ADSB(adsb) = frame.df {
+        if let ME::AircraftStatus(status) = adsb.me {
+            assert_eq!(status.sub_type, AircraftStatusType::ACASRaBroadcast);
+            if let AircraftStatusData::ACASRaBroadcast(ra) = status.status {
+                assert!(ra.ara.active);
+                assert!(ra.ara.corrective);
+                assert!(ra.rac.do_not_pass_above);
+                assert!(!ra.rat);
+                assert_eq!(
+                    ra.threat,
+                    ThreatIdentity::Address {
+                        icao: ICAO([0xa3, 0xf1, 0xc2]),
+                        spare: 0
+                    }
+                );
+                return;
+            }
+        }
+    }
+    unreachable!();
+}
+
 #[test]
 fn issue_10() {
     let bytes = hex!("8DA35EBC9B000024B00C0004E897");
@@ -699,10 +1861,41 @@ fn issue_10() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Airspeed and heading, subsonic
-  Address:       a35ebc (Mode S / ADS-B)
+  Address:       A35EBC (Mode S / ADS-B)
   Air/Ground:    airborne
   IAS:           292 kt
-  Baro rate:     128 ft/min
+  Vertical rate: 128 ft/min barometric
+  NACv:          0
+"#,
+        resulting_string
+    );
+}
+
+#[test]
+fn testing_airspeed_supersonic() {
+    let bytes = hex!("8dabcdef9c060092f82c00e52d70");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::ADSB(adsb) = &frame.df {
+        if let ME::AirborneVelocity(velocity) = &adsb.me {
+            if let AirborneVelocitySubType::AirspeedDecoding(airspeed) = &velocity.sub_type {
+                assert_eq!(airspeed.heading(), Some(180.0));
+            } else {
+                unreachable!();
+            }
+        } else {
+            unreachable!();
+        }
+    } else {
+        unreachable!();
+    }
+    let resulting_string = format!("{}", frame);
+    assert_eq!(
+        r#" Extended Squitter Airspeed and heading, supersonic
+  Address:       ABCDEF (Mode S / ADS-B)
+  Air/Ground:    airborne
+  Heading:       180 magnetic
+  TAS:           600 kt
+  Vertical rate: -640 ft/min barometric
   NACv:          0
 "#,
         resulting_string
@@ -716,7 +1909,7 @@ fn issue_11_12() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter No position information
-  Address:       a90a6e (Mode S / ADS-B)
+  Address:       A90A6E (Mode S / ADS-B)
   Air/Ground:    airborne
 "#,
         resulting_string
@@ -727,7 +1920,7 @@ fn issue_11_12() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter (Non-Transponder) No position information
-  Address:       ef92b3 (TIS-B)
+  Address:       EF92B3 (TIS-B)
   Air/Ground:    airborne?
 "#,
         resulting_string
@@ -740,9 +1933,10 @@ fn fix_issue_unknown() {
     let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
     let resulting_string = format!("{}", frame);
     assert_eq!(
-        r#" Extended Squitter Unknown
-  Address:       85d792 (Mode S / ADS-B)
+        r#" Extended Squitter Test message
+  Address:       85D792 (Mode S / ADS-B)
   Air/Ground:    airborne
+  Subtype:       6
 "#,
         resulting_string
     );
@@ -751,9 +1945,9 @@ fn fix_issue_unknown() {
     let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
     let resulting_string = format!("{}", frame);
     assert_eq!(
-        r#" Extended Squitter (Non-Transponder) Unknown
-  Address:       2ae8d6 (unknown addressing scheme)
-  Air/Ground:    airborne?
+        r#" Extended Squitter (Non-Transponder) reserved
+  Address:       2AE8D6 (unknown addressing scheme)
+  Data:          d73e298fcaa6be
 "#,
         resulting_string
     );
@@ -767,7 +1961,7 @@ fn fix_issue_13() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Airborne position (barometric altitude)
-  Address:       ab92a2 (Mode S / ADS-B)
+  Address:       AB92A2 (Mode S / ADS-B)
   Air/Ground:    airborne
   Altitude:      10600 ft barometric
   CPR type:      Airborne
@@ -784,12 +1978,12 @@ fn fix_issue_13() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Airborne velocity over ground, subsonic
-  Address:       ab92a2 (Mode S / ADS-B)
+  Address:       AB92A2 (Mode S / ADS-B)
   Air/Ground:    airborne
   GNSS delta:    -125 ft
   Heading:       149
   Speed:         177 kt groundspeed
-  Vertical rate: 256 ft/min barometric
+  Vertical rate: 256 ft/min GNSS
 "#,
         resulting_string
     );
@@ -800,9 +1994,12 @@ fn fix_issue_13() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Short Air-Air Surveillance
-  ICAO Address:  ab92a2 (Mode S / ADS-B)
-  Air/Ground:    airborne?
+  ICAO Address:  AB92A2 (Mode S / ADS-B)
+  Air/Ground:    airborne
   Altitude:      10600 ft barometric
+  Cross-link:    supported
+  Sensitivity:   0
+  Reply Info:    no operating ACAS
 "#,
         resulting_string
     );
@@ -813,8 +2010,9 @@ fn fix_issue_13() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" All Call Reply
-  ICAO Address:  ab92a2 (Mode S / ADS-B)
+  ICAO Address:  AB92A2 (Mode S / ADS-B)
   Air/Ground:    airborne
+  Interrogator:  II 0
 "#,
         resulting_string
     );
@@ -825,7 +2023,7 @@ fn fix_issue_13() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Airborne position (barometric altitude)
-  Address:       ab92a2 (Mode S / ADS-B)
+  Address:       AB92A2 (Mode S / ADS-B)
   Air/Ground:    airborne
   Altitude:      10600 ft barometric
   CPR type:      Airborne
@@ -844,7 +2042,7 @@ fn test_issue_14() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Comm-B, Altitude Reply
-  ICAO Address:  aa6f80 (Mode S / ADS-B)
+  ICAO Address:  AA6F80 (Mode S / ADS-B)
   Altitude:      39000 ft
   Comm-B format: BDS2,0 Aircraft identification
   Ident:         SWA545
@@ -857,9 +2055,11 @@ fn test_issue_14() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Comm-B, Altitude Reply
-  ICAO Address:  aacb19 (Mode S / ADS-B)
+  ICAO Address:  AACB19 (Mode S / ADS-B)
   Altitude:      36000 ft
   Comm-B format: BDS1,0 Datalink capabilities
+  Subnetwork:    version 5
+  ACAS:          operating, TA/RA, RTCA DO-185B/EUROCAE ED-143
 "#,
         resulting_string
     );
@@ -872,9 +2072,11 @@ fn test_issue_09() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Comm-B, Altitude Reply
-  ICAO Address:  a6c756 (Mode S / ADS-B)
+  ICAO Address:  A6C756 (Mode S / ADS-B)
   Altitude:      37000 ft
   Comm-B format: BDS1,0 Datalink capabilities
+  Subnetwork:    version 5
+  ACAS:          operating, TA/RA, RTCA DO-185B/EUROCAE ED-143
 "#,
         resulting_string
     );
@@ -884,7 +2086,7 @@ fn test_issue_09() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Comm-B, Altitude Reply
-  ICAO Address:  a6c756 (Mode S / ADS-B)
+  ICAO Address:  A6C756 (Mode S / ADS-B)
   Altitude:      36975 ft
   Comm-B format: empty response
 "#,
@@ -899,7 +2101,7 @@ fn test_issue_16() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Comm-B, Altitude Reply
-  ICAO Address:  abef98 (Mode S / ADS-B)
+  ICAO Address:  ABEF98 (Mode S / ADS-B)
   Altitude:      20300 ft
   Comm-B format: unknown format
 "#,
@@ -914,7 +2116,7 @@ fn test_operational_coordination() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter (Non-Transponder) Aircraft Operational Coordination
-  Address:       43e8ee (ADS-B)
+  Address:       43E8EE (ADS-B)
 "#,
         resulting_string
     );
@@ -940,7 +2142,7 @@ fn test_issue_22() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter (Non-Transponder) Airborne Velocity status (reserved)
-  Address:       1c059d (ADS-B)
+  Address:       1C059D (ADS-B)
 "#,
         resulting_string
     );
@@ -953,11 +2155,12 @@ fn test_df17_error() {
     let resulting_string = format!("{}", frame);
     assert_eq!(
         r#" Extended Squitter Target state and status (V2)
-  Address:       a04e60 (Mode S / ADS-B)
+  Address:       A04E60 (Mode S / ADS-B)
   Air/Ground:    airborne
   Target State and Status:
     Target altitude:   MCP, 30016 ft
     Altimeter setting: 1013.6 millibars
+    Target heading:    not available
     ACAS:              operational autopilot vnav 
     NACp:              10
     NICbaro:           1
@@ -967,3 +2170,737 @@ fn test_df17_error() {
         resulting_string
     );
 }
+
+#[test]
+fn testing_crc_is_valid() {
+    let mut bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.crc(), 0);
+    assert!(frame.is_valid());
+
+    // single bit error in the ME field
+    bytes[5] ^= 0x10;
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_ne!(frame.crc(), 0);
+    assert!(!frame.is_valid());
+
+    // DF11 with interrogator code
+    let bytes = hex!("5da58fd4561b39");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(frame.is_valid());
+}
+
+#[test]
+fn testing_icao() {
+    // DF17
+    let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.icao(), Some(ICAO([0xa2, 0xc1, 0xbd])));
+
+    // DF11
+    let bytes = hex!("5da58fd4561b39");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.icao(), Some(ICAO([0xa5, 0x8f, 0xd4])));
+
+    // DF4, recovered from the Address/Parity field
+    let bytes = hex!("200012b0d96e39");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.icao(), Some(ICAO([0xa3, 0xec, 0xce])));
+
+    let icao = ICAO([0x0a, 0xbc, 0xde]);
+    assert_eq!(icao.to_string(), "0ABCDE");
+    assert_eq!(format!("{icao:x}"), "0abcde");
+    assert_eq!("0ABCDE".parse(), Ok(icao));
+    assert_eq!("0abcde".parse(), Ok(icao));
+
+    // addresses recovered from the parity are displayed the same way
+    let bytes = hex!("a0001838ca3e51f0a8000047a11b");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let address = frame.icao().unwrap().to_string();
+    assert!(frame.to_string().contains(&address));
+}
+
+#[test]
+fn testing_from_bytes_with_fix() {
+    let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+    let (frame, fixed) = Frame::from_bytes_with_fix(&bytes, ErrorCorrection::SingleBit).unwrap();
+    assert!(frame.is_valid());
+    assert_eq!(fixed, None);
+
+    // flip a bit of the altitude
+    let mut corrupted = bytes;
+    corrupted[6] ^= 0x04;
+    let (fixed_frame, fixed) =
+        Frame::from_bytes_with_fix(&corrupted, ErrorCorrection::SingleBit).unwrap();
+    assert_eq!(fixed, Some(BitFix::One(53)));
+    assert_eq!(fixed_frame, frame);
+
+    // two bit errors are only fixed if requested
+    corrupted[9] ^= 0x01;
+    let (unfixed_frame, fixed) =
+        Frame::from_bytes_with_fix(&corrupted, ErrorCorrection::SingleBit).unwrap();
+    assert!(!unfixed_frame.is_valid());
+    assert_eq!(fixed, None);
+
+    let (fixed_frame, fixed) =
+        Frame::from_bytes_with_fix(&corrupted, ErrorCorrection::TwoBit).unwrap();
+    assert_eq!(fixed, Some(BitFix::Two(53, 79)));
+    assert_eq!(fixed_frame, frame);
+}
+
+#[test]
+fn testing_validate() {
+    let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let valid = frame.clone().validate().unwrap();
+    assert_eq!(valid.icao(), Some(ICAO([0xa2, 0xc1, 0xbd])));
+    assert_eq!(valid.into_inner(), frame);
+
+    let mut corrupted = bytes;
+    corrupted[13] ^= 0x01;
+    let frame = Frame::from_bytes((&corrupted, 0)).unwrap().1;
+    assert_eq!(frame.clone().validate(), Err(frame));
+}
+
+#[test]
+fn testing_interrogator_code() {
+    use adsb_deku::InterrogatorCode;
+
+    let bytes = hex!("5da58fd4561b39");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.interrogator_code(), Some(InterrogatorCode::II(0)));
+
+    assert_eq!(
+        InterrogatorCode::from_pi(0x0f),
+        Some(InterrogatorCode::II(15))
+    );
+    assert_eq!(
+        InterrogatorCode::from_pi(0x11),
+        Some(InterrogatorCode::SI(1))
+    );
+    assert_eq!(
+        InterrogatorCode::from_pi(0x4f),
+        Some(InterrogatorCode::SI(63))
+    );
+    assert_eq!(InterrogatorCode::from_pi(0x50), None);
+    assert_eq!(InterrogatorCode::from_pi(0xa58f), None);
+
+    // only DF11 contains an interrogator code
+    let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.interrogator_code(), None);
+}
+
+#[test]
+fn testing_n_number() {
+    let known = [
+        ("N1", ICAO([0xa0, 0x00, 0x01])),
+        ("N1A", ICAO([0xa0, 0x00, 0x02])),
+        ("N1AA", ICAO([0xa0, 0x00, 0x03])),
+        ("N1AZ", ICAO([0xa0, 0x00, 0x1a])),
+        ("N10", ICAO([0xa0, 0x02, 0x5a])),
+        ("N12345", ICAO([0xa0, 0x61, 0xd9])),
+        ("N628TS", ICAO([0xa8, 0x35, 0xaf])),
+        ("N99999", ICAO([0xad, 0xf7, 0xc7])),
+    ];
+    for (n_number, icao) in known {
+        assert_eq!(n_number_to_icao(n_number), Some(icao), "{n_number}");
+        assert_eq!(icao_to_n_number(icao).as_deref(), Some(n_number));
+    }
+    assert_eq!(n_number_to_icao("n628ts"), Some(ICAO([0xa8, 0x35, 0xaf])));
+
+    // outside of the US range
+    assert_eq!(icao_to_n_number(ICAO([0xa0, 0x00, 0x00])), None);
+    assert_eq!(icao_to_n_number(ICAO([0xad, 0xf7, 0xc8])), None);
+    assert_eq!(icao_to_n_number(ICAO([0x48, 0x40, 0xd6])), None);
+
+    for invalid in [
+        "", "N", "1234", "N0", "N01", "NA", "N1I", "N1O", "N1ABC", "N1A1", "N123456", "N1234AB",
+        "G-ABCD",
+    ] {
+        assert_eq!(n_number_to_icao(invalid), None, "{invalid}");
+    }
+
+    // every address of the range round trips
+    for value in 0x00a0_0001..=0x00ad_f7c7_u32 {
+        let [_, a, b, c] = value.to_be_bytes();
+        let icao = ICAO([a, b, c]);
+        let n_number = icao_to_n_number(icao).unwrap();
+        assert_eq!(n_number_to_icao(&n_number), Some(icao), "{n_number}");
+    }
+}
+
+#[test]
+fn testing_uplink() {
+    use adsb_deku::InterrogatorCode;
+
+    let bytes = hex!("200000004fbbb1");
+    let frame = UplinkFrame::from_bytes((&bytes, 0)).unwrap().1;
+    let request = match frame.uf {
+        UF::SurveillanceAltitudeRequest(request) => request,
+        _ => unreachable!(),
+    };
+    assert_eq!(request.pc, ProtocolCode::NoChanges);
+    assert_eq!(request.requested_register(), None);
+    assert_eq!(
+        request.interrogator_identifier(),
+        Some(InterrogatorCode::II(0))
+    );
+    assert_eq!(frame.icao(), Some(ICAO([0xa8, 0x35, 0xaf])));
+    assert!(frame.is_valid());
+    assert_eq!(frame.to_bytes().unwrap(), bytes);
+
+    // Comm-B request for BDS 5,0 by II 3
+    let bytes = hex!("28af30003664a3");
+    let frame = UplinkFrame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.icao(), Some(ICAO([0x3c, 0x4d, 0xd2])));
+    assert_eq!(
+        r#" Surveillance, Identity Request
+  ICAO Address:  3C4DD2 (Mode S / ADS-B)
+  Protocol:      no changes
+  Reply:         Comm-B, BDS 50
+  Interrogator:  II 3
+"#,
+        frame.to_string()
+    );
+    assert_eq!(frame.to_bytes().unwrap(), bytes);
+
+    // Comm-A with SI 20
+    let bytes = hex!("a4035000020304050607087984f5");
+    let frame = UplinkFrame::from_bytes((&bytes, 0)).unwrap().1;
+    if let UF::CommAAltitudeRequest(request) = frame.uf {
+        assert_eq!(request.pc, ProtocolCode::CancelCommB);
+        assert_eq!(request.ma, [0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]);
+        assert_eq!(
+            request.interrogator_identifier(),
+            Some(InterrogatorCode::SI(20))
+        );
+    } else {
+        unreachable!();
+    }
+    assert_eq!(frame.icao(), Some(ICAO([0xa8, 0x35, 0xaf])));
+    assert_eq!(
+        r#" Comm-A, Altitude Request
+  ICAO Address:  A835AF (Mode S / ADS-B)
+  Protocol:      cancel Comm-B
+  Reply:         surveillance
+  Interrogator:  SI 20
+  MA:            02030405060708
+"#,
+        frame.to_string()
+    );
+    assert_eq!(frame.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn testing_uplink_all_call() {
+    use adsb_deku::InterrogatorCode;
+
+    let bytes = hex!("58a800008b9986");
+    let frame = UplinkFrame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.uf.interrogator_code(), Some(InterrogatorCode::II(5)));
+    assert_eq!(frame.uf.reply_probability(), Some(0.5));
+    assert_eq!(frame.icao(), Some(ALL_CALL_ADDRESS));
+    assert!(frame.is_valid());
+    assert_eq!(
+        r#" Mode S Only All Call
+  Interrogator:  II 5
+  Probability:   0.5
+"#,
+        frame.to_string()
+    );
+    assert_eq!(frame.to_bytes().unwrap(), bytes);
+
+    let bytes = hex!("581200008caf98");
+    let frame = UplinkFrame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.uf.interrogator_code(), Some(InterrogatorCode::SI(18)));
+    assert_eq!(frame.uf.reply_probability(), Some(1.0));
+    assert!(frame.is_valid());
+
+    // all-call with the parity of an aircraft address
+    let bytes = hex!("580000009092c9");
+    let frame = UplinkFrame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(matches!(frame.uf, UF::AllCall { .. }));
+    assert!(!frame.is_valid());
+}
+
+#[test]
+fn testing_uplink_air_air() {
+    let bytes = hex!("0084c00028b05b");
+    let frame = UplinkFrame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(matches!(
+        frame.uf,
+        UF::ShortAirAirSurveillance {
+            rl: true,
+            aq: true,
+            ds: 0x30,
+            ..
+        }
+    ));
+    assert_eq!(frame.icao(), Some(ICAO([0x48, 0x40, 0xd6])));
+    assert_eq!(
+        r#" Short Air-Air Surveillance Interrogation
+  ICAO Address:  4840D6 (Mode S / ADS-B)
+  Reply:         long (DF16)
+  Acquisition:   yes
+  Data selector: 30
+"#,
+        frame.to_string()
+    );
+    assert_eq!(frame.to_bytes().unwrap(), bytes);
+
+    let bytes = hex!("8000000030123456789abc5396d5");
+    let frame = UplinkFrame::from_bytes((&bytes, 0)).unwrap().1;
+    if let UF::LongAirAir { rl, aq, mu, .. } = frame.uf {
+        assert!(!rl);
+        assert!(!aq);
+        assert_eq!(mu, [0x30, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc]);
+    } else {
+        unreachable!();
+    }
+    assert_eq!(frame.icao(), Some(ICAO([0x48, 0x40, 0xd6])));
+    assert_eq!(frame.to_bytes().unwrap(), bytes);
+
+    // UF24 Comm-C is not decoded
+    let bytes = hex!("c000000000000000000000000000");
+    let frame = UplinkFrame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(matches!(frame.uf, UF::UnknownLong { uf: 24, .. }));
+    assert_eq!(frame.icao(), None);
+    assert!(!frame.is_valid());
+}
+
+#[test]
+fn testing_test_message() {
+    let bytes = hex!("8d4840d6bf551000000000d8dec3");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(frame.is_valid());
+    assert_eq!(
+        r#" Extended Squitter Test message
+  Address:       4840D6 (Mode S / ADS-B)
+  Air/Ground:    airborne
+  Squawk:        7500
+"#,
+        frame.to_string()
+    );
+    assert_eq!(frame.to_bytes().unwrap(), bytes);
+    if let DF::ADSB(adsb) = frame.df {
+        assert_eq!(
+            adsb.me,
+            ME::TestMessage(TestMessage::Squawk {
+                squawk: Squawk::HIJACK,
+                reserved: 0
+            })
+        );
+    } else {
+        unreachable!();
+    }
+
+    let bytes = hex!("8d4840d6b8000000000000398466");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.to_bytes().unwrap(), bytes);
+    if let DF::ADSB(adsb) = frame.df {
+        assert_eq!(
+            adsb.me,
+            ME::TestMessage(TestMessage::Unknown {
+                subtype: 0,
+                data: [0; 6]
+            })
+        );
+    } else {
+        unreachable!();
+    }
+}
+
+#[test]
+fn testing_error_kind() {
+    use adsb_deku::ErrorKind;
+
+    let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.error_kind(), None);
+
+    // flipped bit in the ME field
+    let bytes = hex!("8da2c1bd587ba2adb31799cb812b");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.error_kind(), Some(ErrorKind::Crc));
+
+    // DF22 is not assigned
+    let bytes = hex!("b0a185b8582f86648c5bfe059abd");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.error_kind(), Some(ErrorKind::Unsupported));
+
+    // half of a DF17
+    let bytes = hex!("8da2c1bd587ba2");
+    let e = Frame::from_bytes((&bytes, 0)).unwrap_err();
+    assert_eq!(ErrorKind::from_deku(&bytes, &e), ErrorKind::Framing);
+
+    let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+    assert_eq!(
+        ErrorKind::from_deku(&bytes, &DekuError::Assertion("reserved".to_string())),
+        ErrorKind::Range
+    );
+    let e = DekuError::Parse("error parsing int".to_string());
+    assert_eq!(ErrorKind::from_deku(&bytes, &e), ErrorKind::Parse);
+
+    // TC 25 is reserved
+    let bytes = hex!("8da2c1bdc87ba2adb31799cb802b");
+    assert_eq!(ErrorKind::from_deku(&bytes, &e), ErrorKind::Unsupported);
+    assert_eq!(ErrorKind::Crc.to_string(), "CRC");
+}
+
+#[test]
+fn testing_nic() {
+    let bytes = hex!("8D40621D58C382D690C8AC2863A7");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::ADSB(adsb) = frame.df {
+        if let ME::AirbornePositionBaroAltitude(altitude) = adsb.me {
+            assert_eq!(
+                altitude.nic(0),
+                Some(Nic {
+                    nic: 8,
+                    rc: Some(185.2)
+                })
+            );
+            // NIC supplement-A without NIC supplement-B isn't used with type code 11
+            assert_eq!(altitude.nic(1), None);
+        } else {
+            unreachable!();
+        }
+    } else {
+        unreachable!();
+    }
+
+    let bytes = hex!("8C4841753A9A153237AEF0F275BE");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::ADSB(adsb) = frame.df {
+        if let ME::SurfacePosition(surface) = adsb.me {
+            assert_eq!(surface.nic(1, 0).unwrap().nic, 9);
+            assert_eq!(surface.nic(0, 0).unwrap().nic, 8);
+            assert_eq!(surface.nic(0, 1), None);
+        } else {
+            unreachable!();
+        }
+    } else {
+        unreachable!();
+    }
+
+    let expected = [
+        (9, false, false, 11, Some(7.5)),
+        (10, false, false, 10, Some(25.0)),
+        (11, true, true, 9, Some(75.0)),
+        (13, false, true, 6, Some(555.6)),
+        (13, false, false, 6, Some(926.0)),
+        (13, true, true, 6, Some(1111.2)),
+        (16, true, true, 3, Some(7408.0)),
+        (16, false, false, 2, Some(14816.0)),
+        (17, false, false, 1, Some(37040.0)),
+        (18, false, false, 0, None),
+        (20, false, false, 11, Some(7.5)),
+        (22, false, false, 0, None),
+    ];
+    for (tc, a, b, nic, rc) in expected {
+        assert_eq!(Nic::airborne(tc, a, b), Some(Nic { nic, rc }), "{tc}");
+    }
+    assert_eq!(Nic::airborne(19, false, false), None);
+    assert_eq!(Nic::surface(8, false, false).unwrap().rc, None);
+    assert_eq!(Nic::surface(9, false, false), None);
+}
+
+#[test]
+fn testing_position_quality() {
+    let bytes = hex!("8C4841753A9A153237AEF0F275BE");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::ADSB(adsb) = frame.df {
+        let nic = adsb.me.nic(1, 0).unwrap();
+        assert_eq!(nic.rc, Some(75.0));
+        assert_eq!(nic.quality(), PositionQuality::High);
+    } else {
+        unreachable!();
+    }
+
+    // no position
+    let bytes = hex!("8D485020994409940838175B284F");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::ADSB(adsb) = frame.df {
+        assert_eq!(adsb.me.nic(0, 0), None);
+    } else {
+        unreachable!();
+    }
+
+    let quality = |tc, a, b| Nic::airborne(tc, a, b).unwrap().quality();
+    assert_eq!(quality(12, false, false), PositionQuality::High);
+    assert_eq!(quality(13, true, true), PositionQuality::Medium);
+    assert_eq!(quality(14, false, false), PositionQuality::Medium);
+    assert_eq!(quality(15, false, false), PositionQuality::Low);
+    assert_eq!(quality(18, false, false), PositionQuality::Unknown);
+    assert!(PositionQuality::Medium > PositionQuality::Low);
+    assert_eq!(PositionQuality::Medium.to_string(), "medium");
+}
+
+#[test]
+fn testing_reserved_capability() {
+    // non-compliant transponders with CA=1 and CA=3
+    let bytes = hex!("89a2c1bd587ba2adb3179955b1c2");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert!(frame.is_valid());
+    assert_eq!(frame.capability(), Some(Capability::Reserved(1)));
+    assert_eq!(frame.icao(), Some("a2c1bd".parse().unwrap()));
+    assert_eq!(frame.to_bytes().unwrap(), bytes.to_vec());
+
+    let bytes = hex!("5ba039b495726b");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let capability = frame.capability().unwrap();
+    assert_eq!(capability, Capability::Reserved(3));
+    assert!(capability.is_reserved());
+    assert_eq!(capability.to_string(), "reserved");
+    assert_eq!(frame.to_bytes().unwrap(), bytes.to_vec());
+
+    assert!(!Capability::AG_AIRBORNE.is_reserved());
+    let bytes = hex!("200012b0d96e39");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    assert_eq!(frame.capability(), None);
+}
+
+#[test]
+fn testing_capability_air_ground() {
+    assert!(Capability::AG_AIRBORNE.is_airborne());
+    assert!(!Capability::AG_AIRBORNE.is_on_ground());
+    assert!(Capability::AG_GROUND.is_on_ground());
+    assert!(!Capability::AG_GROUND.is_airborne());
+    for capability in [
+        Capability::AG_UNCERTAIN,
+        Capability::Reserved(2),
+        Capability::AG_UNCERTAIN2,
+        Capability::AG_UNCERTAIN3,
+    ] {
+        assert!(!capability.is_airborne());
+        assert!(!capability.is_on_ground());
+    }
+
+    assert_eq!(
+        format!("{:#}", Capability::AG_UNCERTAIN),
+        "Level 1, airborne or on ground"
+    );
+    assert_eq!(format!("{:#}", Capability::Reserved(2)), "Reserved (2)");
+    assert_eq!(
+        format!("{:#}", Capability::AG_GROUND),
+        "Level 2+, on ground"
+    );
+    assert_eq!(
+        format!("{:#}", Capability::AG_UNCERTAIN3),
+        "Level 2+, DR/FS alert, airborne or on ground"
+    );
+    assert_eq!(Capability::AG_UNCERTAIN3.to_string(), "airborne?");
+}
+
+#[test]
+fn testing_geometric_altitude() {
+    let bytes = hex!("8d485020994409940838175b284f");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let mut velocity = match frame.df {
+        DF::ADSB(adsb) => match adsb.me {
+            ME::AirborneVelocity(velocity) => velocity,
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+    assert_eq!(velocity.gnss_sign, Sign::Positive);
+    assert_eq!(velocity.gnss_baro_diff, 550);
+
+    let bytes = hex!("8d40621d58c382d690c8ac2863a7");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let position = match frame.df {
+        DF::ADSB(adsb) => adsb,
+        _ => unreachable!(),
+    };
+    assert_eq!(position.geometric_altitude(&velocity), Some(38550));
+
+    velocity.gnss_sign = Sign::Negative;
+    assert_eq!(position.geometric_altitude(&velocity), Some(37450));
+
+    // GNSS delta unavailable
+    velocity.gnss_baro_diff = 0;
+    assert_eq!(position.geometric_altitude(&velocity), None);
+
+    // not a barometric altitude position
+    let bytes = hex!("8da08f94ea1b785e8f3c088ab467");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    if let DF::ADSB(adsb) = frame.df {
+        velocity.gnss_baro_diff = 550;
+        assert_eq!(adsb.geometric_altitude(&velocity), None);
+    } else {
+        unreachable!();
+    }
+}
+
+#[test]
+fn testing_accuracy_context() {
+    let me = |bytes: &[u8]| match Frame::from_bytes((bytes, 0)).unwrap().1.df {
+        DF::ADSB(adsb) => adsb.me,
+        _ => unreachable!(),
+    };
+    let status = match me(&hex!("8d0d097ef8230007005ab8547268")) {
+        ME::AircraftOperationStatus(status) => status,
+        _ => unreachable!(),
+    };
+    let context = AccuracyContext::new(&status).unwrap();
+    assert_eq!(context.version, ADSBVersion::DOC9871AppendixC);
+    assert!(context.nic_supplement_a);
+    assert_eq!(context.nac_p, 10);
+    assert_eq!(context.integrity_probability(), Some(1e-7));
+    assert_eq!(AccuracyContext::VERSION_0.integrity_probability(), None);
+
+    // type code 11 without NIC supplement-B
+    let position = me(&hex!("8d40621d58c382d690c8ac2863a7"));
+    assert_eq!(context.position(&position), None);
+    let context = AccuracyContext {
+        nic_supplement_a: false,
+        ..context
+    };
+    let accuracy = context.position(&position).unwrap();
+    assert_eq!(accuracy.containment, Some(185.2));
+    assert_eq!(accuracy.accuracy, Some(10.0));
+
+    // version 1 uses the NIC supplement of the operation status only
+    let context = AccuracyContext {
+        version: ADSBVersion::DOC9871AppendixB,
+        nic_supplement_a: true,
+        ..context
+    };
+    assert_eq!(context.position(&position).unwrap().containment, Some(75.0));
+
+    let velocity = me(&hex!("8d40621d994409940838174550b1"));
+    assert_eq!(context.position(&velocity), None);
+}
+
+#[test]
+fn testing_accuracy_context_nuc_p() {
+    let me = |bytes: &[u8]| match Frame::from_bytes((bytes, 0)).unwrap().1.df {
+        DF::ADSB(adsb) => adsb.me,
+        _ => unreachable!(),
+    };
+    let (altitude, surface) = match (
+        me(&hex!("8d40621d58c382d690c8ac2863a7")),
+        me(&hex!("8c4841753a9a153237aef0f275be")),
+    ) {
+        (ME::AirbornePositionBaroAltitude(altitude), ME::SurfacePosition(surface)) => {
+            (altitude, surface)
+        },
+        _ => unreachable!(),
+    };
+
+    // reference: DO-260 (Table 2-14), HPL and 95% accuracy in meters
+    for (tc, expected) in [
+        (5, Some((7.5, 3.0))),
+        (6, Some((25.0, 10.0))),
+        (7, Some((185.2, 92.6))),
+        (8, Some((370.4, 185.2))),
+        (9, Some((7.5, 3.0))),
+        (10, Some((25.0, 10.0))),
+        (11, Some((185.2, 92.6))),
+        (12, Some((370.4, 185.2))),
+        (13, Some((926.0, 463.0))),
+        (14, Some((1852.0, 926.0))),
+        (15, Some((3704.0, 1852.0))),
+        (16, Some((18520.0, 9260.0))),
+        (17, Some((37040.0, 18520.0))),
+        (18, None),
+        (20, Some((7.5, 3.0))),
+        (21, Some((25.0, 10.0))),
+        (22, None),
+    ] {
+        let me = match tc {
+            5..=8 => ME::SurfacePosition(SurfacePosition { tc, ..surface }),
+            9..=18 => ME::AirbornePositionBaroAltitude(Altitude { tc, ..altitude }),
+            _ => ME::AirbornePositionGNSSAltitude(Altitude { tc, ..altitude }),
+        };
+        let accuracy = AccuracyContext::VERSION_0.position(&me).unwrap();
+        assert_eq!(
+            (accuracy.containment, accuracy.accuracy),
+            expected.map_or((None, None), |(hpl, accuracy)| (Some(hpl), Some(accuracy))),
+            "tc {tc}"
+        );
+    }
+}
+
+#[test]
+fn testing_peek() {
+    for (bytes, expected) in [
+        (&hex!("8d40621d58c382d690c8ac2863a7")[..], "DF17 TC11 pos"),
+        (&hex!("8d40621d994409940838174550b1")[..], "DF17 TC19 vel"),
+        (&hex!("8d40621d232cc371c32ce0b57d4c")[..], "DF17 TC4 ident"),
+        (
+            &hex!("8d0d097ef8230007005ab8547268")[..],
+            "DF17 TC31 op status",
+        ),
+        (&hex!("8c4841753a9a153237aef0f275be")[..], "DF17 TC7 surf"),
+        (&hex!("5da58fd4561b39")[..], "DF11 all-call"),
+        (&hex!("a0001910204d7075d35820c25c0c")[..], "DF20 comm-b alt"),
+    ] {
+        assert_eq!(Peek::new(bytes).unwrap().to_string(), expected);
+    }
+    // all Comm-D formats
+    assert_eq!(Peek::new(&[0xff]).unwrap().df, 24);
+    // type code of a truncated frame
+    assert_eq!(Peek::new(&[0x8d, 0x40]).unwrap().tc, None);
+    assert_eq!(Peek::new(&[]), None);
+}
+
+#[test]
+fn testing_velocity_accuracy() {
+    let bytes = hex!("8d485020994409940838175b284f");
+    let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+    let mut velocity = match frame.df {
+        DF::ADSB(adsb) => match adsb.me {
+            ME::AirborneVelocity(velocity) => velocity,
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+    assert!(!velocity.intent_change);
+    assert!(velocity.ifr_capability);
+    assert_eq!(velocity.nac_v, 0);
+    assert_eq!(velocity.velocity_accuracy(), None);
+
+    velocity.nac_v = 2;
+    assert_eq!(velocity.velocity_accuracy(), Some(3.0));
+    velocity.nac_v = 4;
+    assert_eq!(velocity.velocity_accuracy(), Some(0.3));
+    // reserved
+    velocity.nac_v = 5;
+    assert_eq!(velocity.velocity_accuracy(), None);
+}
+
+#[test]
+fn testing_type_code_filter() {
+    use adsb_deku::adsb::TypeCodeFilter;
+
+    let filter = TypeCodeFilter::AIRBORNE_POSITION;
+    for tc in 0..32 {
+        assert_eq!(filter.contains(tc), matches!(tc, 9..=18 | 20..=22));
+    }
+    assert_eq!(
+        TypeCodeFilter::NONE.with_type_code(19),
+        TypeCodeFilter::AIRBORNE_VELOCITY
+    );
+
+    // airborne position
+    let bytes = hex!("8da2c1bd587ba2adb31799cb802b");
+    let frame = Frame::from_bytes_filtered(&bytes, filter).unwrap().unwrap();
+    if let DF::ADSB(adsb) = frame.df {
+        assert!(matches!(adsb.me, ME::AirbornePositionBaroAltitude(_)));
+    } else {
+        unreachable!();
+    }
+    // aircraft identification, skipped
+    let bytes = hex!("8da8b84c213b6db111382029a2e2");
+    assert_eq!(Frame::from_bytes_filtered(&bytes, filter), Ok(None));
+    // TIS-B identification, skipped
+    let bytes = hex!("96aae5ad213b8c310a0820c1a3db");
+    assert_eq!(Frame::from_bytes_filtered(&bytes, filter), Ok(None));
+    // only DF17/DF18 are filtered
+    let bytes = hex!("5da58fd4561b39");
+    assert!(Frame::from_bytes_filtered(&bytes, TypeCodeFilter::NONE)
+        .unwrap()
+        .is_some());
+}
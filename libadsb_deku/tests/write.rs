@@ -0,0 +1,101 @@
+use adsb_deku::adsb::{Identification, TypeCoding, ADSB, ME};
+use adsb_deku::deku::prelude::*;
+use adsb_deku::{Capability, Frame, DF, ICAO};
+use assert_hex::assert_eq_hex;
+use hexlit::hex;
+
+/// Decode `bytes`, and assert that writing the `Frame` results in the same bytes
+fn assert_roundtrip(bytes: &[u8]) {
+    let frame = Frame::from_bytes((bytes, 0)).unwrap().1;
+    assert_eq_hex!(bytes.to_vec(), frame.to_bytes().unwrap());
+}
+
+#[test]
+fn write_adsb_identification() {
+    let frame = Frame {
+        df: DF::ADSB(ADSB {
+            capability: Capability::AG_AIRBORNE,
+            icao: ICAO([0x76, 0xce, 0xed]),
+            me: ME::AircraftIdentification(Identification {
+                tc: TypeCoding::A,
+                ca: 5,
+                cn: "SIA12".to_string(),
+            }),
+            pi: ICAO([0xd2, 0x18, 0x69]),
+        }),
+        crc: 0,
+    };
+    let bytes = frame.to_bytes().unwrap();
+    assert_eq_hex!(hex!("8d76ceed254c9071ca0820d21869").to_vec(), bytes);
+    assert_eq!(frame, Frame::from_bytes((&bytes, 0)).unwrap().1);
+}
+
+#[test]
+fn write_adsb_identification_too_long() {
+    let me = ME::AircraftIdentification(Identification {
+        tc: TypeCoding::A,
+        ca: 5,
+        cn: "SIA123456".to_string(),
+    });
+    assert!(me.to_bytes().is_err());
+}
+
+#[test]
+fn roundtrip_adsb() {
+    // Aircraft Identification
+    assert_roundtrip(&hex!("8da8b84c213b6db111382029a2e2"));
+    // Airborne Position (barometric altitude)
+    assert_roundtrip(&hex!("8dac7e64589702ea2e0d910349b7"));
+    assert_roundtrip(&hex!("8D40621D58C382D690C8AC2863A7"));
+    // Airborne Velocity
+    assert_roundtrip(&hex!("8fadba8299109e881830065f4b02"));
+    assert_roundtrip(&hex!("8da4854f99106c81c81c04e03134"));
+    // Airborne Position (GNSS altitude)
+    assert_roundtrip(&hex!("8da145e3b01d52bfafdca4e6d11f"));
+    // Aircraft Status
+    assert_roundtrip(&hex!("8d76ceede1181300000000422fbd"));
+    // Target State and Status
+    assert_roundtrip(&hex!("8da2ebbdea3ab867595c0845115d"));
+    // Aircraft Operational Status
+    assert_roundtrip(&hex!("8dab9f6df82300030049b8968452"));
+    assert_roundtrip(&hex!("8da145e3f80000060059b0aedebe"));
+}
+
+#[test]
+fn roundtrip_tisb() {
+    // Aircraft Identification
+    assert_roundtrip(&hex!("96aae5ad213b8c310a0820c1a3db"));
+    // Surface Position
+    assert_roundtrip(&hex!("9531807b38f752851509cd67f9dc"));
+    // Airborne Position (barometric altitude)
+    assert_roundtrip(&hex!("96a8bb3b901b829273c87f5c9cb2"));
+    // Airborne Velocity
+    assert_roundtrip(&hex!("96a5e26099105085402c02a229e3"));
+    // Surface System Status
+    assert_roundtrip(&hex!("91adf9d0c1180528bc1e3d79091a"));
+}
+
+#[test]
+fn roundtrip_mode_s() {
+    // DF0
+    assert_roundtrip(&hex!("02c60b9ed4497c"));
+    // DF4
+    assert_roundtrip(&hex!("200005b78f9a3a"));
+    // DF5
+    assert_roundtrip(&hex!("28000eaf2c18fc"));
+    // DF11
+    assert_roundtrip(&hex!("5dad57202809f9"));
+    // DF16
+    assert_roundtrip(&hex!("80a185b8582f86648c5bfe059abd"));
+    // DF16, with Resolution Advisory
+    assert_roundtrip(&hex!("80e1969030c2010686cb0c000000"));
+    // DF20
+    assert_roundtrip(&hex!("a000039f20541332d71820e4b520"));
+    // DF21
+    assert_roundtrip(&hex!("a8001f3fda4a2d30bf0fe1c5acf0"));
+    // DF24
+    assert_roundtrip(&hex!("c7ea98c0bc4a12610049c89b43b7"));
+    // Unknown short and long formats
+    assert_roundtrip(&hex!("0800000b78f9a3"));
+    assert_roundtrip(&hex!("b0a185b8582f86648c5bfe059abd"));
+}
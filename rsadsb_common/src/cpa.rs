@@ -0,0 +1,127 @@
+//! Closest point of approach of an aircraft to a fixed point
+//!
+//! The aircraft is assumed to keep its current track and ground speed, such as for finding the
+//! aircraft that will fly closest over a house or an airfield. Distances are calculated on a flat
+//! projection around the aircraft, which is accurate enough within receiver range.
+
+use core::time::Duration;
+
+use adsb_deku::cpr;
+
+use crate::AirplaneState;
+
+/// Mean radius of the earth in kilometers
+const EARTH_RADIUS: f64 = 6371.0;
+
+/// Kilometers per hour in a knot
+const KMH_PER_KT: f64 = 1.852;
+
+/// Predicted closest point of approach, see [`closest_approach`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClosestApproach {
+    /// Distance in kilometers from the point at the closest approach
+    pub distance: f64,
+    /// Time until the closest approach, zero if the aircraft is moving away from the point
+    pub time: Duration,
+}
+
+impl AirplaneState {
+    /// Closest approach to `point` (lat, long) from the current position, heading and speed
+    ///
+    /// Returns `None` without a position, heading or speed.
+    #[must_use]
+    pub fn closest_approach(&self, point: (f64, f64)) -> Option<ClosestApproach> {
+        Some(closest_approach(
+            self.coords.position?,
+            self.heading?,
+            self.speed?,
+            point,
+        ))
+    }
+}
+
+/// Closest approach to `point` (lat, long) of an aircraft at `position`, flying `heading`
+/// (degrees) at `speed` (kt)
+#[must_use]
+pub fn closest_approach(
+    position: cpr::Position,
+    heading: f32,
+    speed: f32,
+    point: (f64, f64),
+) -> ClosestApproach {
+    // point relative to the aircraft in km
+    let mean_latitude = ((position.latitude + point.0) / 2.0).to_radians();
+    let north = (point.0 - position.latitude).to_radians() * EARTH_RADIUS;
+    let east =
+        (point.1 - position.longitude).to_radians() * EARTH_RADIUS * libm::cos(mean_latitude);
+
+    // velocity in km/s
+    let speed = f64::from(speed) * KMH_PER_KT / 3600.0;
+    let heading = f64::from(heading).to_radians();
+    let (velocity_east, velocity_north) = (speed * libm::sin(heading), speed * libm::cos(heading));
+
+    // time in seconds at which the aircraft is closest, along the projection of the point on
+    // the track
+    let squared_speed = speed * speed;
+    let seconds = if squared_speed > 0.0 {
+        ((east * velocity_east + north * velocity_north) / squared_speed).max(0.0)
+    } else {
+        0.0
+    };
+
+    let east = east - velocity_east * seconds;
+    let north = north - velocity_north * seconds;
+    ClosestApproach {
+        distance: libm::sqrt(east * east + north * north),
+        time: Duration::from_secs_f64(seconds),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(latitude: f64, longitude: f64) -> cpr::Position {
+        cpr::Position {
+            latitude,
+            longitude,
+        }
+    }
+
+    #[test]
+    fn approaching() {
+        // flying east at 360 kt along the equator, passing 1 degree (~111 km) north of the point
+        let approach = closest_approach(position(1.0, -1.0), 90.0, 360.0, (0.0, 0.0));
+        assert!((approach.distance - 111.19).abs() < 0.1, "{approach:?}");
+        // 111.19 km at 666.72 km/h
+        assert_eq!(approach.time.as_secs(), 600);
+
+        // flying straight over the point
+        let approach = closest_approach(position(-0.5, 0.0), 0.0, 360.0, (0.0, 0.0));
+        assert!(approach.distance < 0.001, "{approach:?}");
+        assert_eq!(approach.time.as_secs(), 300);
+    }
+
+    #[test]
+    fn moving_away() {
+        let approach = closest_approach(position(0.0, 1.0), 90.0, 360.0, (0.0, 0.0));
+        assert!((approach.distance - 111.19).abs() < 0.1, "{approach:?}");
+        assert_eq!(approach.time, Duration::ZERO);
+
+        // hovering
+        let approach = closest_approach(position(0.0, 1.0), 270.0, 0.0, (0.0, 0.0));
+        assert!((approach.distance - 111.19).abs() < 0.1, "{approach:?}");
+        assert_eq!(approach.time, Duration::ZERO);
+    }
+
+    #[test]
+    fn airplane_state() {
+        let mut state = AirplaneState::default();
+        assert_eq!(state.closest_approach((0.0, 0.0)), None);
+        state.coords.position = Some(position(-0.5, 0.0));
+        state.heading = Some(0.0);
+        state.speed = Some(360.0);
+        let approach = state.closest_approach((0.0, 0.0)).unwrap();
+        assert_eq!(approach.time.as_secs(), 300);
+    }
+}
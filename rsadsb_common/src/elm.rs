@@ -0,0 +1,249 @@
+//! Reassembly of downlink ELMs (Extended Length Messages)
+//!
+//! An aircraft announces a downlink ELM with the DR field of a DF4/DF5/DF20/DF21 reply, after
+//! which the ground station requests the segments, each received as a DF24
+//! [`CommDExtendedLengthMessage`]. Segments can arrive out of order or be retransmitted, so they
+//! are stored by their ND field until all announced segments have been received. Incomplete ELMs,
+//! including segments of which the announcement was missed, are removed by
+//! [`ElmReassembler::prune`].
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::{
+    clone::Clone,
+    default::Default,
+    fmt::Debug,
+    option::Option::{self, None, Some},
+    prelude::rust_2021::derive,
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, SystemTime};
+
+use adsb_deku::{CommDExtendedLengthMessage, DownlinkRequest, Frame, DF, ICAO, KE};
+
+/// Size of the MD field of one segment
+const SEGMENT_BYTES: usize = 10;
+
+/// Max amount of segments of one ELM, limited by the 4 bit ND field
+const MAX_SEGMENTS: usize = 16;
+
+#[derive(Debug, Clone)]
+struct Segments {
+    /// Amount of segments announced by the DR field, if seen yet
+    expected: Option<u8>,
+    segments: [Option<[u8; SEGMENT_BYTES]>; MAX_SEGMENTS],
+    /// Time of the last announcement or segment
+    #[cfg(feature = "std")]
+    last_time: SystemTime,
+}
+
+impl Default for Segments {
+    fn default() -> Self {
+        Self {
+            expected: None,
+            segments: Default::default(),
+            #[cfg(feature = "std")]
+            last_time: SystemTime::now(),
+        }
+    }
+}
+
+impl Segments {
+    /// Payload of all segments in order, if all `expected` segments were received
+    fn payload(&self) -> Option<Vec<u8>> {
+        let expected = usize::from(self.expected?);
+        let mut payload = Vec::with_capacity(expected * SEGMENT_BYTES);
+        for segment in &self.segments[..expected] {
+            payload.extend_from_slice(segment.as_ref()?);
+        }
+        Some(payload)
+    }
+}
+
+/// Stitches DF24 segments into the full downlink ELM payload, keyed by [`ICAO`]
+#[derive(Debug, Default, Clone)]
+pub struct ElmReassembler {
+    messages: BTreeMap<ICAO, Segments>,
+}
+
+impl ElmReassembler {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the ELM announcement or segment contained in `frame`
+    ///
+    /// Returns the address and payload of the ELM once all segments have been received. The
+    /// address of all these formats is recovered from the parity, so `frame` should be checked
+    /// for errors beforehand.
+    pub fn push(&mut self, frame: &Frame) -> Option<(ICAO, Vec<u8>)> {
+        let icao = frame.icao()?;
+        match &frame.df {
+            DF::SurveillanceAltitudeReply(reply) => self.announce(icao, reply.dr),
+            DF::SurveillanceIdentityReply(reply) => self.announce(icao, reply.dr),
+            DF::CommBAltitudeReply(reply) => self.announce(icao, reply.dr),
+            DF::CommBIdentityReply(reply) => self.announce(icao, reply.dr),
+            DF::CommDExtendedLengthMessage(message) => self.segment(icao, message),
+            _ => None,
+        }
+    }
+
+    /// Forget all received segments of `icao`
+    pub fn clear(&mut self, icao: ICAO) {
+        self.messages.remove(&icao);
+    }
+
+    /// Remove incomplete ELMs that have not received an announcement or segment since `timeout`,
+    /// whether their announcement was seen or not
+    #[cfg(feature = "std")]
+    pub fn prune(&mut self, timeout: Duration) {
+        self.messages.retain(|_, segments| {
+            segments
+                .last_time
+                .elapsed()
+                .map_or(false, |elapsed| elapsed < timeout)
+        });
+    }
+
+    /// Amount of aircraft with an incomplete ELM
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    fn announce(&mut self, icao: ICAO, dr: DownlinkRequest) -> Option<(ICAO, Vec<u8>)> {
+        let expected = dr.elm_segments()?;
+        let segments = self.messages.entry(icao).or_default();
+        // a different length is a new message, drop the segments of the previous one
+        if segments
+            .expected
+            .map_or(false, |previous| previous != expected)
+        {
+            *segments = Segments::default();
+        }
+        segments.expected = Some(expected);
+        #[cfg(feature = "std")]
+        {
+            segments.last_time = SystemTime::now();
+        }
+        self.complete(icao)
+    }
+
+    fn segment(
+        &mut self,
+        icao: ICAO,
+        message: &CommDExtendedLengthMessage,
+    ) -> Option<(ICAO, Vec<u8>)> {
+        if message.ke != KE::DownlinkELMTx {
+            return None;
+        }
+        let segments = self.messages.entry(icao).or_default();
+        segments.segments[usize::from(message.nd)] = Some(message.md);
+        #[cfg(feature = "std")]
+        {
+            segments.last_time = SystemTime::now();
+        }
+        self.complete(icao)
+    }
+
+    fn complete(&mut self, icao: ICAO) -> Option<(ICAO, Vec<u8>)> {
+        let payload = self.messages.get(&icao)?.payload()?;
+        self.messages.remove(&icao);
+        Some((icao, payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use adsb_deku::deku::{DekuContainerRead, DekuContainerWrite};
+    use adsb_deku::{crc, UtilityMessage, UtilityMessageType};
+
+    use super::*;
+
+    /// Write `df` with the AP field set to `icao` xor the parity
+    fn frame(df: DF, icao: ICAO) -> Frame {
+        let frame = Frame { df, crc: 0 };
+        let mut bytes = frame.to_bytes().unwrap();
+        let len = bytes.len();
+        let parity = crc::crc24(&bytes[..len - 3]);
+        let address = u32::from_be_bytes([0, icao.0[0], icao.0[1], icao.0[2]]);
+        bytes[len - 3..].copy_from_slice(&(parity ^ address).to_be_bytes()[1..]);
+        Frame::from_bytes((&bytes, 0)).unwrap().1
+    }
+
+    fn announcement(segments: u8, icao: ICAO) -> Frame {
+        frame(
+            DF::SurveillanceIdentityReply(adsb_deku::SurveillanceIdentityReply {
+                fs: adsb_deku::FlightStatus::NoAlertNoSPIAirborne,
+                dr: DownlinkRequest::Unknown(15 + segments),
+                um: UtilityMessage {
+                    iis: 0,
+                    ids: UtilityMessageType::NoInformation,
+                },
+                id: adsb_deku::Squawk(0x1200),
+                ap: ICAO([0; 3]),
+            }),
+            icao,
+        )
+    }
+
+    fn segment(nd: u8, md: [u8; SEGMENT_BYTES], icao: ICAO) -> Frame {
+        frame(
+            DF::CommDExtendedLengthMessage(CommDExtendedLengthMessage {
+                format: 0b11,
+                spare: 0,
+                ke: KE::DownlinkELMTx,
+                nd,
+                md,
+                ap: ICAO([0; 3]),
+            }),
+            icao,
+        )
+    }
+
+    #[test]
+    fn reassemble() {
+        let icao = ICAO([0xa0, 0x1f, 0x73]);
+        let mut elm = ElmReassembler::new();
+
+        // segments out of order, before the announcement
+        assert_eq!(elm.push(&segment(1, [2; SEGMENT_BYTES], icao)), None);
+        assert_eq!(elm.push(&announcement(3, icao)), None);
+        assert_eq!(elm.push(&segment(0, [1; SEGMENT_BYTES], icao)), None);
+        assert_eq!(elm.len(), 1);
+
+        let (from, payload) = elm.push(&segment(2, [3; SEGMENT_BYTES], icao)).unwrap();
+        assert_eq!(from, icao);
+        assert_eq!(payload.len(), 3 * SEGMENT_BYTES);
+        assert_eq!(payload[0], 1);
+        assert_eq!(payload[SEGMENT_BYTES], 2);
+        assert_eq!(payload[2 * SEGMENT_BYTES], 3);
+        assert!(elm.is_empty());
+    }
+
+    #[test]
+    fn prune() {
+        let announced = ICAO([0xa0, 0x1f, 0x73]);
+        let unannounced = ICAO([0x40, 0x62, 0x1d]);
+        let recent = ICAO([0x4b, 0xa9, 0x4c]);
+        let mut elm = ElmReassembler::new();
+
+        assert_eq!(elm.push(&announcement(3, announced)), None);
+        assert_eq!(elm.push(&segment(1, [2; SEGMENT_BYTES], unannounced)), None);
+        assert_eq!(elm.push(&segment(0, [1; SEGMENT_BYTES], recent)), None);
+        let past = SystemTime::now() - Duration::from_secs(60);
+        elm.messages.get_mut(&announced).unwrap().last_time = past;
+        elm.messages.get_mut(&unannounced).unwrap().last_time = past;
+
+        elm.prune(Duration::from_secs(30));
+        assert_eq!(elm.len(), 1);
+        assert!(elm.messages.contains_key(&recent));
+    }
+}
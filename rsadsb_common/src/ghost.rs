@@ -0,0 +1,132 @@
+//! Detection of "ghost" frames
+//!
+//! Not every frame received is from an aircraft answering a real interrogation. Ground test
+//! equipment (ramp testers), replies to our own interrogations and corrupted frames that still
+//! happen to decode all show up as valid looking frames. This uses the PI (parity/interrogator)
+//! field residue of DF11/DF17/DF18 frames, and the interrogator code of DF11 replies, to tag
+//! these frames so they can be excluded from tracking and statistics.
+
+#[cfg(feature = "alloc")]
+use alloc::{fmt, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::{
+    clone::Clone, default::Default, fmt::Debug, marker::Copy, option::Option::Some,
+    prelude::rust_2021::derive, write,
+};
+
+use adsb_deku::{Frame, DF, ICAO};
+
+/// Reason a [`Frame`] is suspected to not be from a real aircraft
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ghost {
+    /// DF17/DF18: PI field doesn't check out to zero, containing this residue
+    PiResidue(u32),
+    /// DF11: PI field doesn't check out to a valid interrogator code, containing this residue
+    InvalidInterrogator(u32),
+    /// DF11: Reply to an interrogator code (`(CL << 4) | IC`) configured as test equipment or
+    /// self-interrogation
+    TestInterrogator(u8),
+    /// All zeros or all ones address, which are never assigned to an aircraft
+    ReservedAddress(ICAO),
+}
+
+impl fmt::Display for Ghost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PiResidue(residue) => write!(f, "PI residue: {residue:06x}"),
+            Self::InvalidInterrogator(residue) => {
+                write!(f, "invalid interrogator code: {residue:06x}")
+            },
+            Self::TestInterrogator(ic) => write!(f, "test interrogator code: {ic:02x}"),
+            Self::ReservedAddress(icao) => write!(f, "reserved address: {icao}"),
+        }
+    }
+}
+
+/// Tag frames as [`Ghost`] frames
+#[derive(Debug, Default, Clone)]
+pub struct GhostDetector {
+    /// Interrogator codes (`(CL << 4) | IC`) of local ground test equipment or of our own
+    /// interrogator
+    pub test_interrogators: Vec<u8>,
+}
+
+impl GhostDetector {
+    #[must_use]
+    pub fn new(test_interrogators: Vec<u8>) -> Self {
+        Self { test_interrogators }
+    }
+
+    /// Check `frame`, returning the reason it is a ghost frame
+    ///
+    /// Only frames where the PI field can be checked are considered, for the Address/Parity
+    /// frames there is no way to tell a ghost frame from a new aircraft.
+    #[must_use]
+    pub fn check(&self, frame: &Frame) -> Option<Ghost> {
+        match &frame.df {
+            DF::ADSB(_) => {
+                if frame.is_valid() {
+                    reserved_address(frame)
+                } else {
+                    Some(Ghost::PiResidue(frame.crc()))
+                }
+            },
+            DF::TisB { .. } => (!frame.is_valid()).then(|| Ghost::PiResidue(frame.crc())),
+            DF::AllCallReply { .. } => {
+                if !frame.is_valid() {
+                    Some(Ghost::InvalidInterrogator(frame.crc()))
+                } else if self.test_interrogators.contains(&(frame.crc() as u8)) {
+                    Some(Ghost::TestInterrogator(frame.crc() as u8))
+                } else {
+                    reserved_address(frame)
+                }
+            },
+            _ => None,
+        }
+    }
+}
+
+fn reserved_address(frame: &Frame) -> Option<Ghost> {
+    match frame.icao() {
+        Some(icao @ ICAO([0x00, 0x00, 0x00] | [0xff, 0xff, 0xff])) => {
+            Some(Ghost::ReservedAddress(icao))
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use adsb_deku::deku::DekuContainerRead;
+
+    use super::*;
+
+    fn frame(bytes: &[u8]) -> Frame {
+        Frame::from_bytes((bytes, 0)).unwrap().1
+    }
+
+    #[test]
+    fn pi_residue() {
+        let detector = GhostDetector::default();
+        let mut bytes = [
+            0x8d, 0xa2, 0xc1, 0xbd, 0x58, 0x7b, 0xa2, 0xad, 0xb3, 0x17, 0x99, 0xcb, 0x80, 0x2b,
+        ];
+        assert_eq!(detector.check(&frame(&bytes)), None);
+
+        bytes[13] ^= 0x01;
+        assert_eq!(detector.check(&frame(&bytes)), Some(Ghost::PiResidue(0x01)));
+    }
+
+    #[test]
+    fn test_interrogator() {
+        let bytes = [0x5d, 0xa5, 0x8f, 0xd4, 0x56, 0x1b, 0x39];
+        let ic = frame(&bytes).crc as u8;
+        assert_eq!(GhostDetector::default().check(&frame(&bytes)), None);
+
+        let detector = GhostDetector::new(vec![ic]);
+        assert_eq!(
+            detector.check(&frame(&bytes)),
+            Some(Ghost::TestInterrogator(ic))
+        );
+    }
+}
@@ -0,0 +1,365 @@
+//! Pairing of interrogations with their replies
+//!
+//! A receiver capturing both 1030 MHz interrogations ([`UplinkFrame`]) and 1090 MHz replies
+//! ([`Frame`]) can tell which interrogator each reply answered: a reply of the format requested
+//! by an interrogation, from the interrogated address, received shortly after it. This pairs them
+//! and keeps activity statistics for each interrogator, identified by the II/SI code of the
+//! interrogation.
+//!
+//! All times are the receive time of the frame since an arbitrary epoch, such as the start of the
+//! capture, and frames are expected in chronological order.
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::time::Duration;
+#[cfg(feature = "alloc")]
+use core::{
+    clone::Clone,
+    default::Default,
+    fmt::Debug,
+    marker::Copy,
+    option::Option::{self, None, Some},
+    prelude::rust_2021::derive,
+};
+
+use adsb_deku::uplink::{UplinkFrame, UF};
+use adsb_deku::{Frame, InterrogatorCode, DF, ICAO};
+
+/// Default max time between an interrogation and its reply
+///
+/// Covers the 128 μs reply delay of the transponder, and the propagation of the interrogation
+/// and reply for aircraft a few hundred NM away.
+pub const DEFAULT_WINDOW: Duration = Duration::from_millis(4);
+
+/// Reply paired with the interrogation it answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pair {
+    /// Interrogator sending the interrogation, `None` if not identified by the interrogation
+    pub interrogator: Option<InterrogatorCode>,
+    /// Address of the replying aircraft
+    pub icao: ICAO,
+    /// Time between the interrogation and the reply
+    pub delay: Duration,
+}
+
+/// Activity of one interrogator
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InterrogatorStats {
+    /// Amount of interrogations sent
+    pub interrogations: u64,
+    /// Amount of interrogations answered by at least one reply
+    pub answered: u64,
+    /// Amount of replies paired with the interrogations, an all-call can be answered by many
+    /// aircraft
+    pub replies: u64,
+    /// Sum of the delays of all `replies`
+    pub total_delay: Duration,
+    /// Time of the first interrogation
+    pub first_seen: Duration,
+    /// Time of the last interrogation
+    pub last_seen: Duration,
+}
+
+impl InterrogatorStats {
+    /// Fraction of interrogations answered by at least one reply
+    #[must_use]
+    pub fn reply_rate(&self) -> f64 {
+        if self.interrogations == 0 {
+            return 0.0;
+        }
+        self.answered as f64 / self.interrogations as f64
+    }
+
+    /// Mean time between an interrogation and its replies
+    #[must_use]
+    pub fn mean_delay(&self) -> Option<Duration> {
+        let replies = u32::try_from(self.replies).ok().filter(|&n| n > 0)?;
+        Some(self.total_delay / replies)
+    }
+}
+
+/// Reply formats answering an interrogation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expected {
+    /// UF0: DF0, or DF16 if the reply length is long
+    ShortAirAir { long: bool },
+    /// UF16: DF16
+    LongAirAir,
+    /// UF4/UF20: DF4 or DF20
+    Altitude,
+    /// UF5/UF21: DF5 or DF21
+    Identity,
+    /// UF11: DF11 with the same interrogator code
+    AllCall,
+}
+
+impl Expected {
+    fn answered_by(self, df: &DF) -> bool {
+        matches!(
+            (self, df),
+            (
+                Self::ShortAirAir { long: false },
+                DF::ShortAirAirSurveillance { .. }
+            ) | (
+                Self::ShortAirAir { long: true } | Self::LongAirAir,
+                DF::LongAirAir { .. }
+            ) | (
+                Self::Altitude,
+                DF::SurveillanceAltitudeReply(_) | DF::CommBAltitudeReply(_)
+            ) | (
+                Self::Identity,
+                DF::SurveillanceIdentityReply(_) | DF::CommBIdentityReply(_)
+            ) | (Self::AllCall, DF::AllCallReply { .. })
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Pending {
+    time: Duration,
+    icao: ICAO,
+    interrogator: Option<InterrogatorCode>,
+    expected: Expected,
+    answered: bool,
+}
+
+/// Pair interrogations with the replies answering them, keeping [`InterrogatorStats`]
+#[derive(Debug, Clone)]
+pub struct InterrogationPairer {
+    window: Duration,
+    pending: Vec<Pending>,
+    stats: BTreeMap<Option<InterrogatorCode>, InterrogatorStats>,
+}
+
+impl Default for InterrogationPairer {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW)
+    }
+}
+
+impl InterrogationPairer {
+    /// Pair replies received at most `window` after the interrogation
+    #[must_use]
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: Vec::new(),
+            stats: BTreeMap::new(),
+        }
+    }
+
+    /// Add the interrogation `frame` received at `time`
+    ///
+    /// The address of all formats except the all-call is recovered from the parity, so `frame`
+    /// should be checked for errors beforehand. Invalid all-calls and formats not assigned by the
+    /// specification are ignored.
+    pub fn interrogation(&mut self, frame: &UplinkFrame, time: Duration) {
+        self.expire(time);
+        let (expected, interrogator) = match &frame.uf {
+            UF::ShortAirAirSurveillance { rl, .. } => (Expected::ShortAirAir { long: *rl }, None),
+            UF::LongAirAir { .. } => (Expected::LongAirAir, None),
+            UF::SurveillanceAltitudeRequest(request) => {
+                (Expected::Altitude, request.interrogator_identifier())
+            },
+            UF::SurveillanceIdentityRequest(request) => {
+                (Expected::Identity, request.interrogator_identifier())
+            },
+            UF::CommAAltitudeRequest(request) => {
+                (Expected::Altitude, request.interrogator_identifier())
+            },
+            UF::CommAIdentityRequest(request) => {
+                (Expected::Identity, request.interrogator_identifier())
+            },
+            UF::AllCall { .. } if frame.is_valid() => {
+                (Expected::AllCall, frame.uf.interrogator_code())
+            },
+            _ => return,
+        };
+        let icao = match frame.icao() {
+            Some(icao) => icao,
+            None => return,
+        };
+
+        let stats = self
+            .stats
+            .entry(interrogator)
+            .or_insert_with(|| InterrogatorStats {
+                first_seen: time,
+                ..InterrogatorStats::default()
+            });
+        stats.interrogations += 1;
+        stats.last_seen = time;
+
+        self.pending.push(Pending {
+            time,
+            icao,
+            interrogator,
+            expected,
+            answered: false,
+        });
+    }
+
+    /// Add the reply `frame` received at `time`, returning the interrogation it answers
+    ///
+    /// The most recent matching interrogation within the window is chosen. An interrogation
+    /// addressed to one aircraft is answered by one reply, while an all-call is paired with every
+    /// DF11 reply with the same interrogator code.
+    pub fn reply(&mut self, frame: &Frame, time: Duration) -> Option<Pair> {
+        self.expire(time);
+        let icao = frame.icao()?;
+        let interrogator_code = frame.interrogator_code();
+
+        let index = self.pending.iter().rposition(|pending| {
+            pending.time <= time
+                && pending.expected.answered_by(&frame.df)
+                && if pending.expected == Expected::AllCall {
+                    pending.interrogator == interrogator_code
+                } else {
+                    pending.icao == icao
+                }
+        })?;
+
+        let pending = &mut self.pending[index];
+        let delay = time - pending.time;
+        let interrogator = pending.interrogator;
+        let stats = self.stats.entry(interrogator).or_default();
+        if !pending.answered {
+            pending.answered = true;
+            stats.answered += 1;
+        }
+        stats.replies += 1;
+        stats.total_delay += delay;
+
+        // all-calls stay pending for the replies of other aircraft
+        if pending.expected != Expected::AllCall {
+            self.pending.remove(index);
+        }
+
+        Some(Pair {
+            interrogator,
+            icao,
+            delay,
+        })
+    }
+
+    /// Statistics of every interrogator, `None` for interrogations without an II/SI code
+    #[must_use]
+    pub fn stats(&self) -> &BTreeMap<Option<InterrogatorCode>, InterrogatorStats> {
+        &self.stats
+    }
+
+    /// Amount of interrogations still waiting for a reply
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Forget interrogations that can no longer be answered at `time`
+    fn expire(&mut self, time: Duration) {
+        let window = self.window;
+        self.pending.retain(|pending| pending.time + window >= time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use adsb_deku::deku::DekuContainerRead;
+
+    use super::*;
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn uplink(hex: &str) -> UplinkFrame {
+        UplinkFrame::from_bytes((&bytes(hex), 0)).unwrap().1
+    }
+
+    fn downlink(hex: &str) -> Frame {
+        Frame::from_bytes((&bytes(hex), 0)).unwrap().1
+    }
+
+    fn us(micros: u64) -> Duration {
+        Duration::from_micros(micros)
+    }
+
+    #[test]
+    fn addressed() {
+        let mut pairer = InterrogationPairer::default();
+        // UF4 to a835af by II 0, UF5 Comm-B request to 3c4dd2 by II 3
+        pairer.interrogation(&uplink("200000004fbbb1"), us(0));
+        pairer.interrogation(&uplink("28af30003664a3"), us(100));
+        assert_eq!(pairer.pending(), 2);
+
+        // DF5 from 3c4dd2
+        let pair = pairer.reply(&downlink("28001200e0f61c"), us(400));
+        assert_eq!(
+            pair,
+            Some(Pair {
+                interrogator: Some(InterrogatorCode::II(3)),
+                icao: ICAO([0x3c, 0x4d, 0xd2]),
+                delay: us(300),
+            })
+        );
+        // already answered
+        assert_eq!(pairer.reply(&downlink("28001200e0f61c"), us(500)), None);
+
+        // DF4 from a835af, after the window
+        let reply = downlink("20001338dbcc74");
+        assert_eq!(pairer.reply(&reply, DEFAULT_WINDOW + us(1)), None);
+        assert_eq!(pairer.pending(), 0);
+
+        let stats = &pairer.stats()[&Some(InterrogatorCode::II(3))];
+        assert_eq!(stats.interrogations, 1);
+        assert_eq!(stats.replies, 1);
+        assert_eq!(stats.mean_delay(), Some(us(300)));
+        assert_eq!(stats.first_seen, us(100));
+        let stats = &pairer.stats()[&Some(InterrogatorCode::II(0))];
+        assert_eq!(stats.interrogations, 1);
+        assert_eq!(stats.answered, 0);
+        assert_eq!(stats.reply_rate(), 0.0);
+        assert_eq!(stats.mean_delay(), None);
+    }
+
+    #[test]
+    fn wrong_format() {
+        let mut pairer = InterrogationPairer::default();
+        // UF4 to a835af, answered by a DF4 and not a DF5
+        pairer.interrogation(&uplink("200000004fbbb1"), us(0));
+        let pair = pairer.reply(&downlink("20001338dbcc74"), us(200));
+        assert_eq!(pair.map(|pair| pair.icao), Some(ICAO([0xa8, 0x35, 0xaf])));
+
+        pairer.interrogation(&uplink("200000004fbbb1"), us(1000));
+        assert_eq!(pairer.reply(&downlink("28001200e0f61c"), us(1200)), None);
+    }
+
+    #[test]
+    fn all_call() {
+        let mut pairer = InterrogationPairer::default();
+        // UF11 by II 5
+        pairer.interrogation(&uplink("58a800008b9986"), us(0));
+        // invalid all-call is ignored
+        pairer.interrogation(&uplink("580000009092c9"), us(10));
+        assert_eq!(pairer.pending(), 1);
+
+        // DF11 replies with II 5 from two aircraft
+        let pair = pairer.reply(&downlink("5da835afb4fd2a"), us(300)).unwrap();
+        assert_eq!(pair.interrogator, Some(InterrogatorCode::II(5)));
+        assert_eq!(pair.icao, ICAO([0xa8, 0x35, 0xaf]));
+        let pair = pairer.reply(&downlink("5d4840d6f8740a"), us(500)).unwrap();
+        assert_eq!(pair.icao, ICAO([0x48, 0x40, 0xd6]));
+        // DF11 reply to II 3
+        assert_eq!(pairer.reply(&downlink("5d4840d6f8740c"), us(600)), None);
+
+        let stats = &pairer.stats()[&Some(InterrogatorCode::II(5))];
+        assert_eq!(stats.interrogations, 1);
+        assert_eq!(stats.answered, 1);
+        assert_eq!(stats.replies, 2);
+        assert_eq!(stats.reply_rate(), 1.0);
+        assert_eq!(stats.mean_delay(), Some(us(400)));
+        assert_eq!(pairer.stats().len(), 1);
+    }
+}
@@ -4,8 +4,27 @@
 
 extern crate alloc;
 
+pub mod cpa;
+pub mod elm;
+pub mod ghost;
+pub mod interrogation;
+pub mod maneuver;
+pub mod performance;
+pub mod sanity;
+pub mod sector;
+#[cfg(feature = "std")]
+pub mod track;
+pub mod uat;
+
 #[cfg(feature = "alloc")]
-use alloc::{collections::BTreeMap, fmt, string::String, vec, vec::Vec};
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    string::String,
+    vec,
+    vec::Vec,
+};
+use core::time::Duration;
 #[cfg(feature = "alloc")]
 use core::{
     clone::Clone, default::Default, fmt::Debug, marker::Copy, prelude::rust_2021::derive,
@@ -14,9 +33,18 @@ use core::{
 #[cfg(feature = "std")]
 use std::time::SystemTime;
 
-use adsb_deku::adsb::{AirborneVelocity, Identification, ME};
-use adsb_deku::{cpr, Altitude, CPRFormat, Frame, DF, ICAO};
-use tracing::{debug, info, warn};
+use adsb_deku::adsb::{
+    AccuracyContext, AirborneVelocity, AircraftStatus, AircraftStatusData, EmergencyState,
+    EmitterCategory, Identification, OperationStatus, SurfacePosition, ME,
+};
+use adsb_deku::bds::{Register, TrackAndTurnReport};
+use adsb_deku::{
+    cpr, Altitude, CPRFormat, Capability, CommBAltitudeReply, CommBIdentityReply, Squawk,
+    SurveillanceStatus, ValidFrame, DF, ICAO,
+};
+use maneuver::Maneuver;
+use tracing::{debug, info, trace, trace_span, warn};
+use uat::UatFrame;
 
 // Max kilometer distance from the receiver to the aircraft. Any distance greater then this is
 // considered a "bad" new position and is not tracked.
@@ -25,6 +53,14 @@ const MAX_RECEIVER_DISTANCE: f64 = 500.0;
 // Max absurd distance an aircraft travelled between messages
 const MAX_AIRCRAFT_DISTANCE: f64 = 100.0;
 
+// Target of the CPR decision trace, enabled with `RUST_LOG=cpr=trace`. Each aircraft is in a
+// `cpr` span with an `icao` field, so a single aircraft is enabled with
+// `RUST_LOG='cpr[cpr{icao=a1b2c3}]=trace'`
+const CPR_TRACE: &str = "cpr";
+
+/// Max amount of events kept until [`Airplanes::take_events`], older events are dropped
+pub const MAX_EVENTS: usize = 256;
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Added {
     /// Airplane was not added
@@ -47,11 +83,118 @@ impl From<bool> for Added {
 /// Currently tracked means that within calling [`Self::action`], an aircraft is added to this data
 /// structure.
 #[derive(Debug, Default)]
-pub struct Airplanes(BTreeMap<ICAO, AirplaneState>);
+pub struct Airplanes {
+    aircraft: BTreeMap<ICAO, AirplaneState>,
+    /// Events since the last call to [`Self::take_events`], at most [`MAX_EVENTS`]
+    events: VecDeque<Event>,
+    limits: Limits,
+}
+
+/// Limits of the positions accepted by [`Airplanes`], see [`Airplanes::with_limits`]
+///
+/// The default limits are tuned for airliners and general aviation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// Max kilometer distance from the receiver to the aircraft
+    pub max_receiver_distance: f64,
+    /// Max kilometer distance an aircraft travelled between positions
+    pub max_aircraft_distance: f64,
+    /// Report the altitude of airborne positions with GNSS altitude, instead of barometric
+    /// altitude, when both are received
+    pub prefer_gnss_altitude: bool,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_receiver_distance: MAX_RECEIVER_DISTANCE,
+            max_aircraft_distance: MAX_AIRCRAFT_DISTANCE,
+            prefer_gnss_altitude: false,
+        }
+    }
+}
+
+impl Limits {
+    /// Limits for high-altitude balloons and suborbital launches, which are received from further
+    /// away, move faster, and often only have a reliable GNSS altitude
+    #[must_use]
+    pub fn high_altitude() -> Self {
+        Self {
+            max_receiver_distance: 1500.0,
+            max_aircraft_distance: 1000.0,
+            prefer_gnss_altitude: true,
+        }
+    }
+}
+
+/// Notable change in the state of an aircraft, see [`Airplanes::take_events`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Event {
+    /// Special Position Identification (ident) was activated by the pilot
+    Spi(ICAO),
+    /// An emergency was declared, or changed to another emergency
+    Emergency(ICAO, EmergencyState),
+    /// The emergency returned to [`EmergencyState::None`]
+    EmergencyCleared {
+        icao: ICAO,
+        /// Emergency that was cleared
+        emergency: EmergencyState,
+        /// Time since the emergency was declared, `None` without feature `std`
+        duration: Option<Duration>,
+    },
+    /// The load factor went above [`maneuver::HIGH_G`], only derived with feature `std`
+    HighG {
+        icao: ICAO,
+        /// Load factor in G, see [`Maneuver::load_factor`]
+        load_factor: f32,
+    },
+    /// Summary of the flight leg of an aircraft that was removed by [`Airplanes::prune`]
+    LegEnded {
+        icao: ICAO,
+        /// Amount of messages received during the leg
+        messages: u32,
+        /// Time in an emergency during the leg, see [`AirplaneState::total_emergency_duration`]
+        emergency_duration: Duration,
+    },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Spi(icao) => write!(f, "[{icao}] SPI (ident)"),
+            Self::Emergency(icao, emergency) => write!(f, "[{icao}] emergency: {emergency}"),
+            Self::EmergencyCleared {
+                icao,
+                emergency,
+                duration,
+            } => {
+                write!(f, "[{icao}] emergency cleared: {emergency}")?;
+                if let Some(duration) = duration {
+                    write!(f, " (after {}s)", duration.as_secs())?;
+                }
+                Ok(())
+            },
+            Self::HighG { icao, load_factor } => {
+                write!(f, "[{icao}] high-G maneuver: {load_factor:.1} G")
+            },
+            Self::LegEnded {
+                icao,
+                messages,
+                emergency_duration,
+            } => {
+                write!(f, "[{icao}] leg ended: {messages} messages")?;
+                if !emergency_duration.is_zero() {
+                    write!(f, ", {}s in emergency", emergency_duration.as_secs())?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
 
 impl fmt::Display for Airplanes {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for key in self.0.keys() {
+        for key in self.aircraft.keys() {
             let value = self.aircraft_details(*key);
             if let Some(value) = value {
                 writeln!(f, "{key}: {value:?}")?;
@@ -65,21 +208,47 @@ impl fmt::Display for Airplanes {
 impl Airplanes {
     #[must_use]
     pub fn new() -> Self {
-        Self(BTreeMap::new())
+        Self::with_limits(Limits::default())
+    }
+
+    /// New `Airplanes`, only accepting positions within `limits`
+    #[must_use]
+    pub fn with_limits(limits: Limits) -> Self {
+        Self {
+            aircraft: BTreeMap::new(),
+            events: VecDeque::new(),
+            limits,
+        }
+    }
+
+    /// All events since the last call, clearing them
+    ///
+    /// Only the last [`MAX_EVENTS`] events are kept, so users that don't need events don't have to
+    /// call this.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        self.events.drain(..).collect()
+    }
+
+    /// Add `event`, dropping the oldest event if there are already [`MAX_EVENTS`]
+    fn push_event(&mut self, event: Event) {
+        if self.events.len() == MAX_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
     }
 
     /// Tuple `iter()` of all `(ICAO, AirplanesState)`
     ///
     /// equivalent [`BTreeMap::iter`]
     pub fn iter(&self) -> alloc::collections::btree_map::Iter<'_, ICAO, AirplaneState> {
-        self.0.iter()
+        self.aircraft.iter()
     }
 
     /// Get all `ICAO` keys
     ///
     /// equivalent [`BTreeMap::keys`]
     pub fn keys(&self) -> alloc::collections::btree_map::Keys<'_, ICAO, AirplaneState> {
-        self.0.keys()
+        self.aircraft.keys()
     }
 
     /// From `ICAO`, get `AirplaneState`
@@ -87,7 +256,7 @@ impl Airplanes {
     /// equivalent [`BTreeMap::get`]
     #[must_use]
     pub fn get(&self, key: ICAO) -> Option<&AirplaneState> {
-        self.0.get(&key)
+        self.aircraft.get(&key)
     }
 
     /// Amount of currently tracked airplanes
@@ -95,25 +264,26 @@ impl Airplanes {
     /// equivalent [`BTreeMap::len`]
     #[must_use]
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.aircraft.len()
     }
 
     /// equivalent [`BTreeMap::is_empty`]
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.aircraft.is_empty()
     }
 
     /// Update `Airplanes` with new `Frame`
     ///
-    /// Take parsed `Frame` and read the `DF::ADSB` type and act upon the parsed message. This
-    /// updates the field that the `ME` value equates to within [`Self`]. This also adds
-    /// airplanes (`ICAO` and `AirplaneState`) when a new aircraft is detected.
+    /// Take parsed and validated `Frame` and read the `DF::ADSB` type and act upon the parsed
+    /// message. This updates the field that the `ME` value equates to within [`Self`]. This also
+    /// adds airplanes (`ICAO` and `AirplaneState`) when a new aircraft is detected, including from
+    /// the address announced in a `DF::AllCallReply`.
     ///
     /// `lat_long`: (latitude, longitude) of current receiver location
     ///
     /// Return true if entry was added into `Airplanes`
-    pub fn action(&mut self, frame: Frame, lat_long: (f64, f64)) -> Added {
+    pub fn action(&mut self, frame: ValidFrame, lat_long: (f64, f64)) -> Added {
         let mut airplane_added = Added::No;
         if let DF::ADSB(ref adsb) = frame.df {
             airplane_added = match &adsb.me {
@@ -121,18 +291,111 @@ impl Airplanes {
                     self.add_identification(adsb.icao, identification)
                 },
                 ME::AirborneVelocity(vel) => self.add_airborne_velocity(adsb.icao, vel),
-                ME::AirbornePositionGNSSAltitude(altitude)
-                | ME::AirbornePositionBaroAltitude(altitude) => {
-                    self.add_altitude(adsb.icao, altitude, lat_long)
+                ME::SurfacePosition(surface) => self.add_surface_position(adsb.icao, surface),
+                ME::AirbornePositionGNSSAltitude(altitude) => {
+                    self.add_altitude(adsb.icao, altitude, true, lat_long)
                 },
+                ME::AirbornePositionBaroAltitude(altitude) => {
+                    self.add_altitude(adsb.icao, altitude, false, lat_long)
+                },
+                ME::AircraftStatus(AircraftStatus {
+                    status:
+                        AircraftStatusData::EmergencyPriorityStatus {
+                            emergency_state,
+                            squawk,
+                        },
+                    ..
+                }) => self.add_emergency_status(adsb.icao, *emergency_state, *squawk),
+                ME::AircraftOperationStatus(status) => self.add_operation_status(adsb.icao, status),
                 _ => Added::No,
             };
             let incr_airplane_added = self.incr_messages(adsb.icao);
+            self.count_link(adsb.icao, Link::Es1090);
             airplane_added = if incr_airplane_added == Added::Yes || airplane_added == Added::Yes {
                 Added::Yes
             } else {
                 Added::No
             };
+        } else if let DF::AllCallReply { icao, .. } = frame.df {
+            airplane_added = self.incr_messages(icao);
+            self.count_link(icao, Link::Es1090);
+        } else if let DF::CommBAltitudeReply(CommBAltitudeReply { mb, .. })
+        | DF::CommBIdentityReply(CommBIdentityReply { mb, .. }) = &frame.df
+        {
+            if let Some(icao) = frame.icao() {
+                if let Some(callsign) = mb.callsign() {
+                    self.add_commb_identification(icao, callsign);
+                }
+                if mb.infer(false) == Register::TrackAndTurn {
+                    if let Some(report) = mb.track_and_turn_report() {
+                        self.add_commb_track_and_turn(icao, &report);
+                    }
+                }
+            }
+        }
+        if let (Some(icao), Some(capability)) = (frame.icao(), frame.capability()) {
+            self.add_capability(icao, capability);
+        }
+
+        airplane_added
+    }
+
+    /// Update `Airplanes` with a UAT ADS-B frame
+    ///
+    /// Only targets with an ICAO address are tracked, along with the 1090 frames of the same
+    /// address. The messages received on each link are counted in [`AirplaneState::links`].
+    ///
+    /// `lat_long`: (latitude, longitude) of current receiver location
+    ///
+    /// Return true if entry was added into `Airplanes`
+    pub fn action_uat(&mut self, frame: &UatFrame, lat_long: (f64, f64)) -> Added {
+        let icao = match frame.icao() {
+            Some(icao) => icao,
+            None => return Added::No,
+        };
+        let limits = self.limits;
+        let airplane_added = self.incr_messages(icao);
+        self.count_link(icao, Link::Uat);
+        let state = match self.aircraft.get_mut(&icao) {
+            Some(state) => state,
+            None => return airplane_added,
+        };
+        info!(
+            "[{icao}] with UAT position: {:?}, altitude: {:?}",
+            frame.position, frame.altitude
+        );
+
+        let altitude = frame
+            .altitude
+            .and_then(|altitude| u16::try_from(altitude).ok());
+        let mut temp_coords = state.coords;
+        if frame.gnss_altitude {
+            if altitude.is_some() {
+                state.gnss_altitude = altitude;
+            }
+        } else {
+            temp_coords.uat_altitude = altitude;
+        }
+        if let Some(position) = frame.position {
+            if temp_coords.check_position(position, lat_long, &limits) {
+                temp_coords.position = Some(position);
+                #[cfg(feature = "std")]
+                {
+                    temp_coords.last_time = Some(SystemTime::now());
+                }
+            } else {
+                return airplane_added;
+            }
+        }
+        if state.coords != temp_coords {
+            if state.coords.position.is_some() && state.coords.position != temp_coords.position {
+                if let Some(track) = &mut state.track {
+                    track.push(state.coords);
+                } else {
+                    state.track = Some(vec![state.coords]);
+                }
+            }
+            state.coords = temp_coords;
         }
 
         airplane_added
@@ -149,8 +412,12 @@ impl Airplanes {
             Some(airplane_state) => {
                 let track = &airplane_state.track;
                 let coor = &airplane_state.coords;
+                let altitude = match airplane_state.gnss_altitude {
+                    Some(altitude) if self.limits.prefer_gnss_altitude => Some(altitude),
+                    gnss_altitude => coor.altitude().or(gnss_altitude),
+                };
                 if let (Some(position), Some(altitude), Some(kilo_distance)) =
-                    (&coor.position, coor.altitude(), coor.kilo_distance)
+                    (&coor.position, altitude, coor.kilo_distance)
                 {
                     Some(AirplaneDetails {
                         position: *position,
@@ -181,22 +448,42 @@ impl Airplanes {
         all_lat_long
     }
 
-    /// Remove airplanes that have not been seen since `filter_time` seconds
+    /// Remove airplanes that have not been seen since `filter_time` seconds, returning them
+    ///
+    /// Pushes an `Event::LegEnded` for every removed airplane.
     #[cfg(feature = "std")]
-    pub fn prune(&mut self, filter_time: u64) {
-        self.0.retain(|k, v| {
-            if let Ok(time) = v.last_time.elapsed() {
-                if time < std::time::Duration::from_secs(filter_time) {
-                    true
+    pub fn prune(&mut self, filter_time: u64) -> Vec<(ICAO, AirplaneState)> {
+        let inactive: Vec<ICAO> = self
+            .aircraft
+            .iter()
+            .filter(|(k, v)| {
+                if let Ok(time) = v.last_time.elapsed() {
+                    if time < std::time::Duration::from_secs(filter_time) {
+                        false
+                    } else {
+                        info!("[{k}] non-active, removing");
+                        true
+                    }
                 } else {
-                    info!("[{k}] non-active, removing");
-                    false
+                    info!("[{k}] non-active(time error), removing");
+                    true
                 }
-            } else {
-                info!("[{k}] non-active(time error), removing");
-                false
+            })
+            .map(|(k, _)| *k)
+            .collect();
+
+        let mut removed = vec![];
+        for icao in inactive {
+            if let Some(state) = self.aircraft.remove(&icao) {
+                self.push_event(Event::LegEnded {
+                    icao,
+                    messages: state.num_messages,
+                    emergency_duration: state.total_emergency_duration(),
+                });
+                removed.push((icao, state));
             }
-        });
+        }
+        removed
     }
 }
 
@@ -204,7 +491,7 @@ impl Airplanes {
 impl Airplanes {
     // Return (matching state from icao, true if airplane added)
     fn entry_or_insert(&mut self, icao: ICAO) -> (&mut AirplaneState, Added) {
-        let entry = self.0.entry(icao);
+        let entry = self.aircraft.entry(icao);
         let airplane_added = Added::from(matches!(
             entry,
             alloc::collections::btree_map::Entry::Vacant(_)
@@ -212,7 +499,7 @@ impl Airplanes {
         if Added::Yes == airplane_added {
             info!("[{icao}] now tracking");
         }
-        (entry.or_insert_with(AirplaneState::default), airplane_added)
+        (entry.or_default(), airplane_added)
     }
 
     /// Increment message count of `ICAO`. If feature: `std`, set `last_time` to current time.
@@ -229,17 +516,144 @@ impl Airplanes {
         airplane_added
     }
 
+    /// Count a message of `icao` received on `link`
+    fn count_link(&mut self, icao: ICAO, link: Link) {
+        if let Some(state) = self.aircraft.get_mut(&icao) {
+            match link {
+                Link::Es1090 => state.links.es1090 += 1,
+                Link::Uat => state.links.uat += 1,
+            }
+        }
+    }
+
+    /// update `on_ground` from the capability of a DF11 or DF17 frame, when it tells either way
+    fn add_capability(&mut self, icao: ICAO, capability: Capability) {
+        if let Some(state) = self.aircraft.get_mut(&icao) {
+            if capability.is_on_ground() {
+                state.on_ground = Some(true);
+            } else if capability.is_airborne() {
+                state.on_ground = Some(false);
+            }
+        }
+    }
+
+    /// update `accuracy` from `ME::AircraftOperationStatus`
+    ///
+    /// Return true if entry was added into `Airplanes`
+    fn add_operation_status(&mut self, icao: ICAO, status: &OperationStatus) -> Added {
+        let (state, airplane_added) = self.entry_or_insert(icao);
+        if let Some(accuracy) = AccuracyContext::new(status) {
+            state.accuracy = accuracy;
+        }
+        airplane_added
+    }
+
     /// update from `ME::AircraftIdentification`
     ///
     /// Return true if entry was added into `Airplanes`
     fn add_identification(&mut self, icao: ICAO, identification: &Identification) -> Added {
         let (state, airplane_added) = self.entry_or_insert(icao);
         state.callsign = Some(identification.cn.clone());
+        state.emitter_category = Some(identification.emitter_category());
         info!("[{icao}] with identification: {}", identification.cn);
 
         airplane_added
     }
 
+    /// update from `AircraftStatusData::EmergencyPriorityStatus`
+    ///
+    /// Pushes an `Event::Emergency` when an emergency is declared or changes, and an
+    /// `Event::EmergencyCleared` when it returns to `EmergencyState::None`.
+    ///
+    /// Return true if entry was added into `Airplanes`
+    fn add_emergency_status(
+        &mut self,
+        icao: ICAO,
+        emergency_state: EmergencyState,
+        squawk: Squawk,
+    ) -> Added {
+        let (state, airplane_added) = self.entry_or_insert(icao);
+        state.squawk = Some(squawk);
+        let emergency = (emergency_state != EmergencyState::None).then(|| emergency_state);
+        if state.emergency == emergency {
+            return airplane_added;
+        }
+        info!("[{icao}] with emergency: {emergency_state}, squawk: {squawk}");
+
+        let event = match (state.emergency, emergency) {
+            (Some(cleared), None) => {
+                #[cfg(feature = "std")]
+                let duration = state
+                    .emergency_since
+                    .take()
+                    .and_then(|since| since.elapsed().ok());
+                #[cfg(not(feature = "std"))]
+                let duration = None;
+                if let Some(duration) = duration {
+                    state.emergency_duration += duration;
+                }
+                Event::EmergencyCleared {
+                    icao,
+                    emergency: cleared,
+                    duration,
+                }
+            },
+            (None, _) => {
+                #[cfg(feature = "std")]
+                {
+                    state.emergency_since = Some(SystemTime::now());
+                }
+                Event::Emergency(icao, emergency_state)
+            },
+            (Some(_), _) => Event::Emergency(icao, emergency_state),
+        };
+        state.emergency = emergency;
+        self.push_event(event);
+
+        airplane_added
+    }
+
+    /// update from BDS 2,0 of a `DF::CommBAltitudeReply` or `DF::CommBIdentityReply`
+    ///
+    /// The address of these is recovered from the parity, which is garbage for a corrupted frame,
+    /// so only aircraft that are already tracked are updated.
+    fn add_commb_identification(&mut self, icao: ICAO, callsign: &str) {
+        if let Some(state) = self.aircraft.get_mut(&icao) {
+            if state.callsign.as_deref() != Some(callsign) {
+                info!("[{icao}] with Comm-B identification: {callsign}");
+                state.callsign = Some(callsign.into());
+            }
+        }
+    }
+
+    /// update from BDS 5,0 of a `DF::CommBAltitudeReply` or `DF::CommBIdentityReply`
+    ///
+    /// Only aircraft that are already tracked, and don't send their velocity with ADS-B, are
+    /// updated.
+    fn add_commb_track_and_turn(&mut self, icao: ICAO, report: &TrackAndTurnReport) {
+        if let Some(state) = self.aircraft.get_mut(&icao) {
+            if matches!(
+                state.velocity_source,
+                Some(VelocitySource::Airborne | VelocitySource::Surface)
+            ) {
+                return;
+            }
+            let heading = report.true_track();
+            let speed = report.ground_speed();
+            if heading.is_some() || speed.is_some() {
+                info!(
+                    "[{icao}] with Comm-B track and turn: heading: {heading:?}, speed: {speed:?}"
+                );
+                if heading.is_some() {
+                    state.heading = heading;
+                }
+                state.speed = speed.map(f32::from);
+                state.velocity_source = Some(VelocitySource::CommB);
+                self.update_maneuver(icao);
+            }
+        }
+    }
+
     /// update from `ME::AirborneVelocity`
     ///
     /// Return true if entry was added into `Airplanes`
@@ -250,20 +664,98 @@ impl Airplanes {
             state.heading = Some(heading);
             state.speed = Some(ground_speed as f32);
             state.vert_speed = Some(vert_speed);
+            state.velocity_source = Some(VelocitySource::Airborne);
+            state.on_ground = Some(false);
+            self.update_maneuver(icao);
         }
 
         airplane_added
     }
 
-    /// update from `ME::AirbornePosition{GNSSAltitude, BaroAltitude}`
+    /// update from `ME::SurfacePosition`
     ///
     /// Return true if entry was added into `Airplanes`
-    fn add_altitude(&mut self, icao: ICAO, altitude: &Altitude, lat_long: (f64, f64)) -> Added {
+    fn add_surface_position(&mut self, icao: ICAO, surface: &SurfacePosition) -> Added {
         let (state, airplane_added) = self.entry_or_insert(icao);
+        let speed = surface.ground_speed();
+        let heading = surface.ground_track();
+        info!("[{icao}] with surface movement: heading: {heading:?}, speed: {speed:?}");
+        state.on_ground = Some(true);
+        if speed.is_some() || heading.is_some() {
+            // keep the last track while stopped, the aircraft didn't turn
+            if heading.is_some() {
+                state.heading = heading;
+            }
+            state.speed = speed;
+            state.vert_speed = None;
+            state.velocity_source = Some(VelocitySource::Surface);
+            self.update_maneuver(icao);
+        }
+
+        airplane_added
+    }
+
+    /// Derive the acceleration and turn rate from the updated speed and heading of `icao`
+    ///
+    /// Pushes an `Event::HighG` when the aircraft starts a high-G maneuver. Without feature `std`
+    /// there is no clock to derive the rates with, so nothing is updated.
+    fn update_maneuver(&mut self, icao: ICAO) {
+        #[cfg(feature = "std")]
+        self.update_maneuver_at(icao, SystemTime::now());
+        #[cfg(not(feature = "std"))]
+        let _ = icao;
+    }
+
+    #[cfg(feature = "std")]
+    fn update_maneuver_at(&mut self, icao: ICAO, now: SystemTime) {
+        if let Some(state) = self.aircraft.get_mut(&icao) {
+            if let (Some(speed), Some(heading)) = (state.speed, state.heading) {
+                let was_high_g = state.maneuver.is_high_g();
+                state.maneuver.update_at(now, speed, heading);
+                trace!("[{icao}] with maneuver: {:?}", state.maneuver);
+                match state.maneuver.load_factor() {
+                    Some(load_factor) if !was_high_g && state.maneuver.is_high_g() => {
+                        info!("[{icao}] with high-G maneuver: {load_factor:.1} G");
+                        self.push_event(Event::HighG { icao, load_factor });
+                    },
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    /// update from `ME::AirbornePosition{GNSSAltitude, BaroAltitude}`, `gnss` being set for
+    /// `ME::AirbornePositionGNSSAltitude`
+    ///
+    /// Return true if entry was added into `Airplanes`
+    fn add_altitude(
+        &mut self,
+        icao: ICAO,
+        altitude: &Altitude,
+        gnss: bool,
+        lat_long: (f64, f64),
+    ) -> Added {
+        let _span = trace_span!(target: CPR_TRACE, "cpr", icao = %icao).entered();
+        let limits = self.limits;
+        let (state, airplane_added) = self.entry_or_insert(icao);
+        if gnss && altitude.alt.is_some() {
+            state.gnss_altitude = altitude.alt;
+        }
         info!(
             "[{icao}] with altitude: {:?}, cpr lat: {}, cpr long: {}",
             altitude.alt, altitude.lat_cpr, altitude.lon_cpr
         );
+        trace!(
+            target: CPR_TRACE,
+            "frame: {:?}, lat_cpr: {}, lon_cpr: {}, alt: {:?}",
+            altitude.odd_flag,
+            altitude.lat_cpr,
+            altitude.lon_cpr,
+            altitude.alt
+        );
+        let spi = altitude.ss == SurveillanceStatus::SPICondition
+            && state.surveillance_status != Some(SurveillanceStatus::SPICondition);
+        state.surveillance_status = Some(altitude.ss);
         let mut temp_coords = match altitude.odd_flag {
             CPRFormat::Odd => AirplaneCoor {
                 altitudes: [state.coords.altitudes[0], Some(*altitude)],
@@ -275,7 +767,7 @@ impl Airplanes {
             },
         };
         // update the position from the new even/odd message if it's a good new position
-        if temp_coords.update_position(lat_long) {
+        if temp_coords.update_position(lat_long, &limits) {
             // don't bother updating if it's the same coords
             if state.coords != temp_coords {
                 // update track
@@ -289,9 +781,14 @@ impl Airplanes {
             }
         } else {
             // clear record
+            trace!(target: CPR_TRACE, "clearing frames and position");
             state.coords = AirplaneCoor::default();
         }
 
+        if spi {
+            self.push_event(Event::Spi(icao));
+        }
+
         airplane_added
     }
 }
@@ -306,26 +803,66 @@ pub struct AirplaneDetails {
     pub track: Option<Vec<AirplaneCoor>>,
 }
 
+/// Message the velocity of an [`AirplaneState`] was decoded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VelocitySource {
+    /// `ME::AirborneVelocity`
+    Airborne,
+    /// Movement and ground track of `ME::SurfacePosition`
+    Surface,
+    /// True track and ground speed of BDS 5,0 from a Comm-B reply
+    CommB,
+}
+
 /// Value in `BTreeMap` of `Airplanes`
 #[derive(Debug)]
 pub struct AirplaneState {
     // TODO: rename to coor
     pub coords: AirplaneCoor,
-    pub squawk: Option<u32>,
+    /// Mode A code from the last emergency/priority status
+    pub squawk: Option<Squawk>,
     pub callsign: Option<String>,
-    /// heading from `adsb::AirborneVelocity::calculate()`
+    /// Emitter category from the last `ME::AircraftIdentification`
+    pub emitter_category: Option<EmitterCategory>,
+    /// heading from `adsb::AirborneVelocity::calculate()`, the ground track from
+    /// `adsb::SurfacePosition::ground_track()`, or the true track from
+    /// `bds::TrackAndTurnReport::true_track()`
     ///
     /// 0 = Straight up
     /// 90 = Right, and so on
     pub heading: Option<f32>,
-    /// ground_speed from `adsb::AirborneVelocity::calculate()`
+    /// ground_speed from `adsb::AirborneVelocity::calculate()`,
+    /// `adsb::SurfacePosition::ground_speed()`, or `bds::TrackAndTurnReport::ground_speed()`
     ///
     /// Stored as a f64 in that library but we store as f32 for size reasons in this library
     pub speed: Option<f32>,
     /// vert_speed from `adsb::AirborneVelocity::calculate()`
     pub vert_speed: Option<i16>,
     pub on_ground: Option<bool>,
+    /// Message `heading` and `speed` were last updated from
+    pub velocity_source: Option<VelocitySource>,
+    /// Acceleration and turn rate derived from `heading` and `speed`, only derived with feature
+    /// `std`
+    pub maneuver: Maneuver,
+    /// `ss` from the last airborne position
+    pub surveillance_status: Option<SurveillanceStatus>,
+    /// altitude from the last `ME::AirbornePositionGNSSAltitude`, see
+    /// [`Limits::prefer_gnss_altitude`]
+    pub gnss_altitude: Option<u16>,
+    /// ADS-B version and accuracy fields from the last `ME::AircraftOperationStatus`, version 0
+    /// until one is received
+    pub accuracy: AccuracyContext,
+    /// Current emergency from the last emergency/priority status, `None` without an emergency
+    pub emergency: Option<EmergencyState>,
+    /// Time the current emergency was declared
+    #[cfg(feature = "std")]
+    pub emergency_since: Option<SystemTime>,
+    /// Total time of the cleared emergencies since the aircraft was first tracked, only measured
+    /// with feature `std`
+    pub emergency_duration: Duration,
     pub num_messages: u32,
+    /// Messages received on each link
+    pub links: LinkStats,
     #[cfg(feature = "std")]
     pub last_time: SystemTime,
     pub track: Option<Vec<AirplaneCoor>>,
@@ -337,11 +874,22 @@ impl Default for AirplaneState {
             coords: AirplaneCoor::default(),
             squawk: None,
             callsign: None,
+            emitter_category: None,
             heading: None,
             speed: None,
             vert_speed: None,
             on_ground: None,
+            velocity_source: None,
+            maneuver: Maneuver::default(),
+            surveillance_status: None,
+            gnss_altitude: None,
+            accuracy: AccuracyContext::VERSION_0,
+            emergency: None,
+            #[cfg(feature = "std")]
+            emergency_since: None,
+            emergency_duration: Duration::ZERO,
             num_messages: 0,
+            links: LinkStats::default(),
             #[cfg(feature = "std")]
             last_time: SystemTime::now(),
             track: None,
@@ -349,6 +897,56 @@ impl Default for AirplaneState {
     }
 }
 
+impl AirplaneState {
+    /// Time of the cleared emergencies, and of the current emergency until now
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn total_emergency_duration(&self) -> Duration {
+        let current = self
+            .emergency_since
+            .and_then(|since| since.elapsed().ok())
+            .unwrap_or_default();
+        self.emergency_duration + current
+    }
+}
+
+/// Radio link an aircraft is received on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Link {
+    /// 1090 MHz Mode S and extended squitter
+    Es1090,
+    /// 978 MHz UAT, see [`uat`]
+    Uat,
+}
+
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Es1090 => write!(f, "1090"),
+            Self::Uat => write!(f, "UAT"),
+        }
+    }
+}
+
+/// Messages of an aircraft received on each [`Link`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LinkStats {
+    pub es1090: u32,
+    pub uat: u32,
+}
+
+impl LinkStats {
+    /// The only link the aircraft is received on, `None` if it is received on both links
+    #[must_use]
+    pub const fn single_link(&self) -> Option<Link> {
+        match (self.es1090, self.uat) {
+            (_, 0) => Some(Link::Es1090),
+            (0, _) => Some(Link::Uat),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct AirplaneCoor {
     /// [odd, even]
@@ -360,42 +958,37 @@ pub struct AirplaneCoor {
     pub last_time: Option<SystemTime>,
     /// distance from receiver lat/long
     pub kilo_distance: Option<f64>,
+    /// barometric altitude from the last UAT frame, used without an altitude from `altitudes`
+    pub uat_altitude: Option<u16>,
 }
 
 impl AirplaneCoor {
     /// After checking the range of the new lat / long, new position from last position, update the
     /// position of an aircraft
-    fn update_position(&mut self, lat_long: (f64, f64)) -> bool {
+    fn update_position(&mut self, lat_long: (f64, f64), limits: &Limits) -> bool {
         if let [Some(odd), Some(even)] = self.altitudes {
             let test_position = cpr::get_position((&odd, &even));
-
-            // Check kilometer range from receiver
-            if let Some(test_position) = test_position {
-                let kilo_distance = Self::haversine_distance(
-                    lat_long,
-                    (test_position.latitude, test_position.longitude),
+            if let Some(zones) = cpr::zones((&odd, &even)) {
+                trace!(
+                    target: CPR_TRACE,
+                    "pair: lat index: {}, lat even: {}, lat odd: {}, nl even: {}, nl odd: {}, position: {test_position:?}",
+                    zones.lat_index,
+                    zones.lat_even,
+                    zones.lat_odd,
+                    zones.nl_even,
+                    zones.nl_odd
                 );
-                if kilo_distance > MAX_RECEIVER_DISTANCE {
-                    warn!("range: {kilo_distance} -  old: {lat_long:?} new: {test_position:?}");
-                    return false;
-                }
-                self.kilo_distance = Some(kilo_distance);
-                debug!("range: {kilo_distance}");
             }
 
-            // if previous position, check against for range. This is a non-great way of doing
-            // this, but maybe in the future we can check against the speed of the aircraft
-            if let (Some(current_position), Some(test_position)) = (self.position, test_position) {
-                let distance = Self::haversine_distance_position(current_position, test_position);
-                if distance > MAX_AIRCRAFT_DISTANCE {
-                    warn!("distance: {distance} old: {current_position:?}, invalid: {test_position:?}");
+            if let Some(test_position) = test_position {
+                if !self.check_position(test_position, lat_long, limits) {
                     return false;
                 }
-                debug!("distance: {distance}");
             }
 
             // Good new position!
             self.position = test_position;
+            trace!(target: CPR_TRACE, "accepted: {test_position:?}");
             debug!("update_position: odd: (lat: {}, long: {}), even: (lat: {}, long: {}), position: {:?}",
                 odd.lat_cpr,
                 odd.lon_cpr,
@@ -406,18 +999,61 @@ impl AirplaneCoor {
             {
                 self.last_time = Some(SystemTime::now());
             }
+        } else {
+            trace!(target: CPR_TRACE, "waiting for an even and odd frame");
+        }
+        true
+    }
+
+    /// Check the range of `test_position` from the receiver and from the last position, setting
+    /// `kilo_distance` if it is within range
+    fn check_position(
+        &mut self,
+        test_position: cpr::Position,
+        lat_long: (f64, f64),
+        limits: &Limits,
+    ) -> bool {
+        // Check kilometer range from receiver
+        let kilo_distance =
+            Self::haversine_distance(lat_long, (test_position.latitude, test_position.longitude));
+        if kilo_distance > limits.max_receiver_distance {
+            warn!("range: {kilo_distance} -  old: {lat_long:?} new: {test_position:?}");
+            trace!(
+                target: CPR_TRACE,
+                "rejected: {kilo_distance} km from receiver, max: {} km",
+                limits.max_receiver_distance
+            );
+            return false;
+        }
+        self.kilo_distance = Some(kilo_distance);
+        debug!("range: {kilo_distance}");
+
+        // if previous position, check against for range. This is a non-great way of doing
+        // this, but maybe in the future we can check against the speed of the aircraft
+        if let Some(current_position) = self.position {
+            let distance = Self::haversine_distance_position(current_position, test_position);
+            if distance > limits.max_aircraft_distance {
+                warn!("distance: {distance} old: {current_position:?}, invalid: {test_position:?}");
+                trace!(
+                    target: CPR_TRACE,
+                    "rejected: moved {distance} km from {current_position:?}, max: {} km",
+                    limits.max_aircraft_distance
+                );
+                return false;
+            }
+            debug!("distance: {distance}");
         }
         true
     }
 
-    /// Return altitude from Odd Altitude
+    /// Return altitude from Odd Altitude, or from the last UAT frame
     fn altitude(&self) -> Option<u16> {
         if let Some(odd) = self.altitudes[0] {
             if let Some(alt) = odd.alt {
                 return Some(alt);
             }
         }
-        None
+        self.uat_altitude
     }
 
     /// Calculate the kilometers between two lat/long points
@@ -453,3 +1089,382 @@ impl AirplaneCoor {
         r * c
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use adsb_deku::adsb::ADSBVersion;
+    use adsb_deku::deku::DekuContainerRead;
+    use adsb_deku::Frame;
+
+    use super::*;
+
+    fn frame_with_ss(ss: SurveillanceStatus) -> ValidFrame {
+        let bytes = [
+            0x8d, 0xa2, 0xc1, 0xbd, 0x58, 0x7b, 0xa2, 0xad, 0xb3, 0x17, 0x99, 0xcb, 0x80, 0x2b,
+        ];
+        let mut frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+        if let DF::ADSB(adsb) = &mut frame.df {
+            if let ME::AirbornePositionBaroAltitude(altitude) = &mut adsb.me {
+                altitude.ss = ss;
+            }
+        }
+        ValidFrame::new_unchecked(frame)
+    }
+
+    #[test]
+    fn spi_event() {
+        let mut airplanes = Airplanes::new();
+        airplanes.action(frame_with_ss(SurveillanceStatus::NoCondition), (0.0, 0.0));
+        assert_eq!(airplanes.take_events(), vec![]);
+
+        // only the start of SPI is an event
+        airplanes.action(frame_with_ss(SurveillanceStatus::SPICondition), (0.0, 0.0));
+        airplanes.action(frame_with_ss(SurveillanceStatus::SPICondition), (0.0, 0.0));
+        assert_eq!(
+            airplanes.take_events(),
+            vec![Event::Spi(ICAO([0xa2, 0xc1, 0xbd]))]
+        );
+        assert_eq!(airplanes.take_events(), vec![]);
+    }
+
+    fn emergency_frame(emergency: EmergencyState, code: u16) -> ValidFrame {
+        // emergency/priority status of c06800, no emergency and squawk 4016
+        let bytes = [
+            0x8d, 0xc0, 0x68, 0x00, 0xe1, 0x10, 0x85, 0x00, 0x00, 0x00, 0x00, 0xba, 0xa8, 0x1f,
+        ];
+        let mut frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+        if let DF::ADSB(adsb) = &mut frame.df {
+            if let ME::AircraftStatus(AircraftStatus {
+                status:
+                    AircraftStatusData::EmergencyPriorityStatus {
+                        emergency_state,
+                        squawk,
+                    },
+                ..
+            }) = &mut adsb.me
+            {
+                *emergency_state = emergency;
+                *squawk = Squawk(code);
+            }
+        }
+        ValidFrame::new_unchecked(frame)
+    }
+
+    #[test]
+    fn max_events() {
+        let mut airplanes = Airplanes::new();
+        for n in 0..MAX_EVENTS + 10 {
+            airplanes.push_event(Event::Spi(ICAO([0, 0, n as u8])));
+        }
+        let events = airplanes.take_events();
+        assert_eq!(events.len(), MAX_EVENTS);
+        assert_eq!(events[0], Event::Spi(ICAO([0, 0, 10])));
+        assert_eq!(airplanes.take_events(), vec![]);
+    }
+
+    #[test]
+    fn emergency_events() {
+        let icao = ICAO([0xc0, 0x68, 0x00]);
+        let mut airplanes = Airplanes::new();
+        airplanes.action(emergency_frame(EmergencyState::None, 0x4016), (0.0, 0.0));
+        assert_eq!(airplanes.take_events(), vec![]);
+        assert_eq!(airplanes.get(icao).unwrap().squawk, Some(Squawk(0x4016)));
+
+        // only the start of an emergency is an event
+        airplanes.action(emergency_frame(EmergencyState::General, 0x7700), (0.0, 0.0));
+        airplanes.action(emergency_frame(EmergencyState::General, 0x7700), (0.0, 0.0));
+        assert_eq!(
+            airplanes.take_events(),
+            vec![Event::Emergency(icao, EmergencyState::General)]
+        );
+        let state = airplanes.get(icao).unwrap();
+        assert_eq!(state.squawk, Some(Squawk::EMERGENCY));
+        assert_eq!(state.emergency, Some(EmergencyState::General));
+
+        // a different emergency, with the same squawk
+        airplanes.action(
+            emergency_frame(EmergencyState::MinimumFuel, 0x7700),
+            (0.0, 0.0),
+        );
+        assert_eq!(
+            airplanes.take_events(),
+            vec![Event::Emergency(icao, EmergencyState::MinimumFuel)]
+        );
+
+        // squawk changed back before the emergency is cleared
+        airplanes.action(
+            emergency_frame(EmergencyState::MinimumFuel, 0x4016),
+            (0.0, 0.0),
+        );
+        assert_eq!(airplanes.take_events(), vec![]);
+        assert_eq!(airplanes.get(icao).unwrap().squawk, Some(Squawk(0x4016)));
+
+        // declared a minute ago
+        let minute = Duration::from_secs(60);
+        if let Some(since) = &mut airplanes.aircraft.get_mut(&icao).unwrap().emergency_since {
+            *since -= minute;
+        }
+        airplanes.action(emergency_frame(EmergencyState::None, 0x4016), (0.0, 0.0));
+        let events = airplanes.take_events();
+        assert!(matches!(
+            events.as_slice(),
+            [Event::EmergencyCleared {
+                icao: cleared,
+                emergency: EmergencyState::MinimumFuel,
+                duration: Some(duration),
+            }] if *cleared == icao && *duration >= minute
+        ));
+        let state = airplanes.get(icao).unwrap();
+        assert_eq!(state.emergency, None);
+        assert_eq!(state.emergency_since, None);
+        assert!(state.emergency_duration >= minute);
+        assert!(state.emergency_duration < 2 * minute);
+        assert_eq!(airplanes.take_events(), vec![]);
+
+        // a second emergency adds to the duration, and the leg ends with the current one
+        airplanes.action(
+            emergency_frame(EmergencyState::Lifeguard, 0x7700),
+            (0.0, 0.0),
+        );
+        let state = airplanes.aircraft.get_mut(&icao).unwrap();
+        if let Some(since) = &mut state.emergency_since {
+            *since -= minute;
+        }
+        state.last_time -= Duration::from_secs(600);
+        assert!(state.total_emergency_duration() >= 2 * minute);
+        airplanes.take_events();
+        airplanes.prune(120);
+        assert!(airplanes.get(icao).is_none());
+        let events = airplanes.take_events();
+        assert!(matches!(
+            events.as_slice(),
+            [Event::LegEnded {
+                icao: ended,
+                messages: 7,
+                emergency_duration,
+            }] if *ended == icao && *emergency_duration >= 2 * minute
+        ));
+        assert!(events[0]
+            .to_string()
+            .ends_with("leg ended: 7 messages, 120s in emergency"));
+    }
+
+    #[test]
+    fn high_g_event() {
+        let icao = ICAO([0xae, 0x01, 0x23]);
+        let mut airplanes = Airplanes::new();
+        airplanes.entry_or_insert(icao);
+
+        // turn 10 degrees every second at 400 kt
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_646_136_000);
+        for (secs, heading) in [(0, 0.0), (1, 10.0), (2, 20.0)] {
+            let state = airplanes.aircraft.get_mut(&icao).unwrap();
+            state.speed = Some(400.0);
+            state.heading = Some(heading);
+            airplanes.update_maneuver_at(icao, start + Duration::from_secs(secs));
+        }
+
+        // only the start of the maneuver is an event
+        let events = airplanes.take_events();
+        assert!(matches!(
+            events.as_slice(),
+            [Event::HighG { icao: high_g, load_factor }] if *high_g == icao && *load_factor > 3.0
+        ));
+        let maneuver = airplanes.get(icao).unwrap().maneuver;
+        assert_eq!(maneuver.turn_rate, Some(10.0));
+        assert_eq!(maneuver.acceleration, Some(0.0));
+    }
+
+    #[test]
+    fn surface_velocity() {
+        let bytes = [
+            0x8c, 0x48, 0x41, 0x75, 0x3a, 0x9a, 0x15, 0x32, 0x37, 0xae, 0xf0, 0xf2, 0x75, 0xbe,
+        ];
+        let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+        let mut airplanes = Airplanes::new();
+        airplanes.action(frame.validate().unwrap(), (0.0, 0.0));
+
+        let state = airplanes.get(ICAO([0x48, 0x41, 0x75])).unwrap();
+        assert_eq!(state.speed, Some(17.0));
+        assert_eq!(state.heading, Some(92.8125));
+        assert_eq!(state.on_ground, Some(true));
+        assert_eq!(state.velocity_source, Some(VelocitySource::Surface));
+    }
+
+    #[test]
+    fn all_call_reply() {
+        let bytes = [0x5d, 0xa5, 0x8f, 0xd4, 0x56, 0x1b, 0x39];
+        let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+        let mut airplanes = Airplanes::new();
+        assert_eq!(
+            airplanes.action(frame.validate().unwrap(), (0.0, 0.0)),
+            Added::Yes
+        );
+        assert_eq!(
+            airplanes
+                .get(ICAO([0xa5, 0x8f, 0xd4]))
+                .unwrap()
+                .num_messages,
+            1
+        );
+        // CA 5, airborne
+        assert_eq!(
+            airplanes.get(ICAO([0xa5, 0x8f, 0xd4])).unwrap().on_ground,
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn operation_status() {
+        let bytes = [
+            0x8d, 0x0d, 0x09, 0x7e, 0xf8, 0x23, 0x00, 0x07, 0x00, 0x5a, 0xb8, 0x54, 0x72, 0x68,
+        ];
+        let icao = ICAO([0x0d, 0x09, 0x7e]);
+        let mut airplanes = Airplanes::new();
+        airplanes.incr_messages(icao);
+        assert_eq!(
+            airplanes.get(icao).unwrap().accuracy,
+            AccuracyContext::VERSION_0
+        );
+
+        let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+        airplanes.action(frame.validate().unwrap(), (0.0, 0.0));
+        let accuracy = airplanes.get(icao).unwrap().accuracy;
+        assert_eq!(accuracy.version, ADSBVersion::DOC9871AppendixC);
+        assert_eq!(accuracy.nac_p, 10);
+    }
+
+    #[test]
+    fn commb_identification() {
+        let all_call = [0x5d, 0xaa, 0x6f, 0x80, 0xa1, 0x13, 0x47];
+        let comm_b = [
+            0xa0, 0x00, 0x19, 0x10, 0x20, 0x4d, 0x70, 0x75, 0xd3, 0x58, 0x20, 0xc2, 0x5c, 0x0c,
+        ];
+        let icao = ICAO([0xaa, 0x6f, 0x80]);
+        let mut airplanes = Airplanes::new();
+
+        // not tracked yet, the address could be from a corrupted frame
+        let frame = Frame::from_bytes((&comm_b, 0)).unwrap().1;
+        assert_eq!(
+            airplanes.action(frame.validate().unwrap(), (0.0, 0.0)),
+            Added::No
+        );
+        assert!(airplanes.get(icao).is_none());
+
+        let frame = Frame::from_bytes((&all_call, 0)).unwrap().1;
+        airplanes.action(frame.validate().unwrap(), (0.0, 0.0));
+        let frame = Frame::from_bytes((&comm_b, 0)).unwrap().1;
+        airplanes.action(frame.validate().unwrap(), (0.0, 0.0));
+        assert_eq!(
+            airplanes.get(icao).unwrap().callsign.as_deref(),
+            Some("SWA545")
+        );
+    }
+
+    #[test]
+    fn commb_track_and_turn() {
+        let all_call = [0x5d, 0x3c, 0x4d, 0xd2, 0x9b, 0x8a, 0xd6];
+        let comm_b = [
+            0xa0, 0x00, 0x13, 0x93, 0x81, 0x95, 0x15, 0x36, 0xe0, 0x24, 0xd4, 0xcc, 0xf6, 0xb5,
+        ];
+        let icao = ICAO([0x3c, 0x4d, 0xd2]);
+        let mut airplanes = Airplanes::new();
+
+        let frame = Frame::from_bytes((&all_call, 0)).unwrap().1;
+        airplanes.action(frame.validate().unwrap(), (0.0, 0.0));
+        let frame = Frame::from_bytes((&comm_b, 0)).unwrap().1;
+        airplanes.action(frame.validate().unwrap(), (0.0, 0.0));
+        let state = airplanes.get(icao).unwrap();
+        assert_eq!(state.heading, Some(114.257_81));
+        assert_eq!(state.speed, Some(438.0));
+        assert_eq!(state.velocity_source, Some(VelocitySource::CommB));
+    }
+
+    /// Even airborne position at 52.2572, 3.9194 and 38000 ft, and odd airborne position with a
+    /// GNSS height of 3000 m (9843 ft)
+    fn high_altitude_pair() -> [ValidFrame; 2] {
+        let even = [
+            0x8d, 0x40, 0x62, 0x1d, 0x58, 0xc3, 0x82, 0xd6, 0x90, 0xc8, 0xac, 0x28, 0x63, 0xa7,
+        ];
+        let odd = [
+            0x8d, 0x40, 0x62, 0x1d, 0xa0, 0xbb, 0x86, 0x43, 0x5c, 0xc4, 0x12, 0xf5, 0x83, 0x05,
+        ];
+        [even, odd].map(|bytes| {
+            let frame = Frame::from_bytes((&bytes, 0)).unwrap().1;
+            frame.validate().unwrap()
+        })
+    }
+
+    #[test]
+    fn high_altitude_limits() {
+        // ~820 km east of the aircraft
+        let receiver = (52.25, 15.9);
+        let icao = ICAO([0x40, 0x62, 0x1d]);
+
+        let mut airplanes = Airplanes::new();
+        for frame in high_altitude_pair() {
+            airplanes.action(frame, receiver);
+        }
+        assert!(airplanes.aircraft_details(icao).is_none());
+
+        let mut airplanes = Airplanes::with_limits(Limits::high_altitude());
+        for frame in high_altitude_pair() {
+            airplanes.action(frame, receiver);
+        }
+        assert_eq!(airplanes.get(icao).unwrap().gnss_altitude, Some(9843));
+        let details = airplanes.aircraft_details(icao).unwrap();
+        assert_eq!(details.altitude, 9843);
+        assert!(details.kilo_distance > 800.0);
+    }
+
+    /// UAT Basic frame of `icao` at 40.0, -75.5 and 5500 ft barometric
+    fn uat_frame(icao: ICAO) -> UatFrame {
+        let mut bytes = [
+            0x00, 0xa1, 0x23, 0x45, 0x38, 0xe3, 0x8f, 0x94, 0x9f, 0x4a, 0x10, 0x50, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        bytes[1..4].copy_from_slice(&icao.0);
+        UatFrame::from_bytes(&bytes).unwrap()
+    }
+
+    #[test]
+    fn uat_only() {
+        let icao = ICAO([0xa1, 0x23, 0x45]);
+        let mut airplanes = Airplanes::new();
+        assert_eq!(
+            airplanes.action_uat(&uat_frame(icao), (40.1, -75.4)),
+            Added::Yes
+        );
+
+        let state = airplanes.get(icao).unwrap();
+        assert_eq!(state.links.single_link(), Some(Link::Uat));
+        let details = airplanes.aircraft_details(icao).unwrap();
+        assert_eq!(details.altitude, 5500);
+        assert!((details.position.latitude - 40.0).abs() < 0.0001);
+        assert!(details.kilo_distance < 20.0);
+    }
+
+    #[test]
+    fn uat_and_1090() {
+        let all_call = [0x5d, 0xa5, 0x8f, 0xd4, 0x56, 0x1b, 0x39];
+        let icao = ICAO([0xa5, 0x8f, 0xd4]);
+        let mut airplanes = Airplanes::new();
+
+        let frame = Frame::from_bytes((&all_call, 0)).unwrap().1;
+        airplanes.action(frame.validate().unwrap(), (40.1, -75.4));
+        assert_eq!(
+            airplanes.get(icao).unwrap().links.single_link(),
+            Some(Link::Es1090)
+        );
+
+        assert_eq!(
+            airplanes.action_uat(&uat_frame(icao), (40.1, -75.4)),
+            Added::No
+        );
+        let state = airplanes.get(icao).unwrap();
+        assert_eq!(state.links, LinkStats { es1090: 1, uat: 1 });
+        assert_eq!(state.links.single_link(), None);
+        assert_eq!(state.num_messages, 2);
+        assert_eq!(airplanes.len(), 1);
+    }
+}
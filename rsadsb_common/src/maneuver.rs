@@ -0,0 +1,190 @@
+//! Longitudinal acceleration and turn rate derived from successive velocity reports
+//!
+//! Ground speed and heading are only sent with a resolution of about a knot and a degree, so the
+//! rates between two reports are noisy. They are smoothed with an exponential moving average
+//! before being used, for example to find aircraft flying high-G maneuvers.
+
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::SystemTime;
+
+/// Standard gravity in m/s²
+const STANDARD_GRAVITY: f32 = 9.806_65;
+
+/// Meters per second in a knot
+const MPS_PER_KT: f32 = 0.514_444;
+
+/// Time constant in seconds of the smoothing
+const SMOOTHING: f32 = 4.0;
+
+/// Reports closer than this are skipped, their rates are mostly resolution noise
+const MIN_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Reports further apart than this restart the derivation, the rates in between are unknown
+const MAX_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Load factor above which a maneuver is flagged as high-G, see [`Maneuver::is_high_g`]
+///
+/// Airliners stay below 1.5 G, even in steep turns.
+pub const HIGH_G: f32 = 2.0;
+
+/// Smoothed rates of an aircraft, see [`crate::AirplaneState::maneuver`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Maneuver {
+    /// Longitudinal acceleration in kt/s, negative when slowing down
+    pub acceleration: Option<f32>,
+    /// Turn rate in degrees/s, positive when turning right
+    pub turn_rate: Option<f32>,
+    /// Ground speed in kt and heading of the last report
+    last: Option<(f32, f32)>,
+    /// Time of the last report
+    #[cfg(feature = "std")]
+    pub(crate) last_time: Option<SystemTime>,
+}
+
+impl Maneuver {
+    /// Update from a report of `speed` in kt and `heading`, received at `now`
+    #[cfg(feature = "std")]
+    pub(crate) fn update_at(&mut self, now: SystemTime, speed: f32, heading: f32) {
+        let elapsed = self
+            .last_time
+            .and_then(|last_time| now.duration_since(last_time).ok());
+        if self.update(elapsed, speed, heading) {
+            self.last_time = Some(now);
+        }
+    }
+
+    /// Update from a report of `speed` in kt and `heading`, `elapsed` after the last report
+    ///
+    /// Without `elapsed`, or after more than `MAX_INTERVAL`, the rates are restarted from this
+    /// report. Returns false if the report is skipped for being too close to the last report.
+    pub fn update(&mut self, elapsed: Option<Duration>, speed: f32, heading: f32) -> bool {
+        let (elapsed, (last_speed, last_heading)) = match (elapsed, self.last) {
+            (Some(elapsed), Some(last)) if elapsed <= MAX_INTERVAL => (elapsed, last),
+            _ => {
+                *self = Self {
+                    last: Some((speed, heading)),
+                    ..Self::default()
+                };
+                return true;
+            },
+        };
+        if elapsed < MIN_INTERVAL {
+            return false;
+        }
+
+        let seconds = elapsed.as_secs_f32();
+        let acceleration = (speed - last_speed) / seconds;
+        let turn_rate = heading_change(last_heading, heading) / seconds;
+
+        // exponential moving average, weighting the new rate by the time since the last report
+        let weight = 1.0 - libm::expf(-seconds / SMOOTHING);
+        let smooth =
+            |old: Option<f32>, new: f32| Some(old.map_or(new, |old| old + (new - old) * weight));
+        self.acceleration = smooth(self.acceleration, acceleration);
+        self.turn_rate = smooth(self.turn_rate, turn_rate);
+        self.last = Some((speed, heading));
+        true
+    }
+
+    /// Load factor in G from the acceleration and turn rate, assuming level flight
+    #[must_use]
+    pub fn load_factor(&self) -> Option<f32> {
+        let (speed, _) = self.last?;
+        let longitudinal = self.acceleration? * MPS_PER_KT;
+        // centripetal acceleration of the turn
+        let lateral = speed * MPS_PER_KT * self.turn_rate?.to_radians();
+        let total = libm::sqrtf(
+            STANDARD_GRAVITY * STANDARD_GRAVITY + lateral * lateral + longitudinal * longitudinal,
+        );
+        Some(total / STANDARD_GRAVITY)
+    }
+
+    /// Load factor is above [`HIGH_G`]
+    #[must_use]
+    pub fn is_high_g(&self) -> bool {
+        self.load_factor()
+            .map_or(false, |load_factor| load_factor > HIGH_G)
+    }
+}
+
+/// Change from heading `from` to `to` in degrees, within -180..=180
+fn heading_change(from: f32, to: f32) -> f32 {
+    let change = to - from;
+    if change > 180.0 {
+        change - 360.0
+    } else if change < -180.0 {
+        change + 360.0
+    } else {
+        change
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Option<Duration> = Some(Duration::from_secs(1));
+
+    #[test]
+    fn rates() {
+        let mut maneuver = Maneuver::default();
+        assert!(maneuver.update(None, 300.0, 350.0));
+        assert_eq!(maneuver.acceleration, None);
+        assert_eq!(maneuver.load_factor(), None);
+
+        // turning right through north
+        assert!(maneuver.update(SECOND, 302.0, 353.0));
+        assert_eq!(maneuver.acceleration, Some(2.0));
+        assert_eq!(maneuver.turn_rate, Some(3.0));
+        assert!(maneuver.update(SECOND, 302.0, 356.0));
+        assert_eq!(maneuver.turn_rate, Some(3.0));
+        assert!(maneuver.update(Some(Duration::from_secs(2)), 302.0, 2.0));
+        assert_eq!(maneuver.turn_rate, Some(3.0));
+        assert!(maneuver.acceleration.unwrap() < 2.0);
+        assert!(maneuver.acceleration.unwrap() > 0.0);
+
+        // standard rate turn of an airliner
+        let load_factor = maneuver.load_factor().unwrap();
+        assert!(load_factor > 1.2 && load_factor < 1.4, "{load_factor}");
+        assert!(!maneuver.is_high_g());
+    }
+
+    #[test]
+    fn smoothing() {
+        let mut maneuver = Maneuver::default();
+        maneuver.update(None, 300.0, 90.0);
+        maneuver.update(SECOND, 300.0, 93.0);
+        // a single jump in heading is smoothed out
+        maneuver.update(SECOND, 300.0, 113.0);
+        let turn_rate = maneuver.turn_rate.unwrap();
+        assert!(turn_rate > 3.0 && turn_rate < 20.0, "{turn_rate}");
+        // left turn
+        assert_eq!(heading_change(10.0, 350.0), -20.0);
+    }
+
+    #[test]
+    fn intervals() {
+        let mut maneuver = Maneuver::default();
+        maneuver.update(None, 300.0, 90.0);
+        // too close, skipped
+        assert!(!maneuver.update(Some(Duration::from_millis(100)), 310.0, 120.0));
+        assert_eq!(maneuver.turn_rate, None);
+        maneuver.update(SECOND, 300.0, 93.0);
+        assert_eq!(maneuver.turn_rate, Some(3.0));
+        // too far apart, restarted
+        maneuver.update(Some(Duration::from_secs(60)), 300.0, 180.0);
+        assert_eq!(maneuver.turn_rate, None);
+    }
+
+    #[test]
+    fn high_g() {
+        let mut maneuver = Maneuver::default();
+        maneuver.update(None, 400.0, 0.0);
+        // 10 degrees/s at 400 kt, about 3.8 G
+        maneuver.update(SECOND, 400.0, 10.0);
+        let load_factor = maneuver.load_factor().unwrap();
+        assert!(load_factor > 3.7 && load_factor < 3.9, "{load_factor}");
+        assert!(maneuver.is_high_g());
+    }
+}
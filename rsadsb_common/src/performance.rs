@@ -0,0 +1,204 @@
+//! Ground speed statistics by emitter category and altitude band
+//!
+//! [`PerformanceStats`] samples the ground speed of every airborne aircraft with an altitude,
+//! and aggregates the samples by the emitter category of the aircraft and its altitude band. The
+//! statistics are meant to be exported and reset periodically, such as for comparing the cruise
+//! performance of the aircraft types seen by a receiver.
+//!
+//! All times are since an arbitrary epoch, such as the start of the program, and updates are
+//! expected in chronological order.
+
+#[cfg(feature = "alloc")]
+use alloc::collections::BTreeMap;
+use core::time::Duration;
+#[cfg(feature = "alloc")]
+use core::{
+    clone::Clone,
+    cmp::PartialEq,
+    default::Default,
+    fmt::Debug,
+    iter::Iterator,
+    marker::Copy,
+    option::Option::{self, None, Some},
+    prelude::rust_2021::derive,
+};
+
+use adsb_deku::adsb::EmitterCategory;
+
+use crate::Airplanes;
+
+/// Emitter category and lowest altitude (ft) of an altitude band
+///
+/// The category is `None` for aircraft without an identification message.
+pub type Band = (Option<EmitterCategory>, u16);
+
+/// Ground speeds sampled within a [`Band`]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SpeedStats {
+    pub samples: u64,
+    /// Lowest ground speed in kt
+    pub min: f32,
+    /// Highest ground speed in kt
+    pub max: f32,
+    /// Sum of all ground speeds in kt
+    pub sum: f64,
+}
+
+impl SpeedStats {
+    fn add(&mut self, speed: f32) {
+        if self.samples == 0 {
+            self.min = speed;
+            self.max = speed;
+        } else {
+            self.min = self.min.min(speed);
+            self.max = self.max.max(speed);
+        }
+        self.samples += 1;
+        self.sum += f64::from(speed);
+    }
+
+    /// Mean ground speed in kt
+    #[must_use]
+    pub fn mean(&self) -> Option<f64> {
+        (self.samples != 0).then(|| self.sum / self.samples as f64)
+    }
+}
+
+/// Aggregates ground speeds by [`Band`], see the [module documentation](self)
+#[derive(Debug, Clone)]
+pub struct PerformanceStats {
+    /// Height of the altitude bands in ft
+    band_height: u16,
+    /// Time between two samples of all aircraft
+    interval: Duration,
+    /// Time of the last sample
+    last_sample: Option<Duration>,
+    bands: BTreeMap<Band, SpeedStats>,
+}
+
+impl PerformanceStats {
+    /// Sample all aircraft every `interval` into altitude bands of `band_height` ft
+    ///
+    /// # Panics
+    ///
+    /// If `band_height` is 0.
+    #[must_use]
+    pub fn new(band_height: u16, interval: Duration) -> Self {
+        assert!(band_height != 0, "altitude bands need a height");
+        Self {
+            band_height,
+            interval,
+            last_sample: None,
+            bands: BTreeMap::new(),
+        }
+    }
+
+    /// Sample the ground speed of the airborne aircraft, if the last sample is at least
+    /// `interval` old at `now`
+    ///
+    /// Aircraft on the ground, or without a position or ground speed, are not sampled.
+    pub fn update(&mut self, now: Duration, airplanes: &Airplanes) {
+        if let Some(last) = self.last_sample {
+            if now.saturating_sub(last) < self.interval {
+                return;
+            }
+        }
+        self.last_sample = Some(now);
+
+        for icao in airplanes.keys() {
+            let state = match airplanes.get(*icao) {
+                Some(state) if state.on_ground != Some(true) => state,
+                _ => continue,
+            };
+            let (speed, details) = match (state.speed, airplanes.aircraft_details(*icao)) {
+                (Some(speed), Some(details)) => (speed, details),
+                _ => continue,
+            };
+            let floor = details.altitude / self.band_height * self.band_height;
+            self.bands
+                .entry((state.emitter_category, floor))
+                .or_default()
+                .add(speed);
+        }
+    }
+
+    /// Height of the altitude bands in ft
+    #[must_use]
+    pub const fn band_height(&self) -> u16 {
+        self.band_height
+    }
+
+    /// Bands with at least one sample since the last reset, in order of category and altitude
+    pub fn bands(&self) -> impl Iterator<Item = (&Band, &SpeedStats)> {
+        self.bands.iter()
+    }
+
+    /// Start aggregating again, such as after exporting the statistics of a period
+    pub fn reset(&mut self) {
+        self.bands.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use adsb_deku::deku::DekuContainerRead;
+    use adsb_deku::Frame;
+
+    use super::*;
+
+    fn action(airplanes: &mut Airplanes, bytes: &[u8]) {
+        let frame = Frame::from_bytes((bytes, 0)).unwrap().1;
+        airplanes.action(frame.validate().unwrap(), (52.25, 3.91));
+    }
+
+    #[test]
+    fn speed_stats() {
+        let mut stats = SpeedStats::default();
+        assert_eq!(stats.mean(), None);
+        stats.add(420.0);
+        stats.add(460.0);
+        stats.add(440.0);
+        assert_eq!(stats.samples, 3);
+        assert_eq!((stats.min, stats.max), (420.0, 460.0));
+        assert_eq!(stats.mean(), Some(440.0));
+    }
+
+    #[test]
+    fn bands() {
+        let mut airplanes = Airplanes::new();
+        // position at 38000 ft and velocity, without identification
+        action(
+            &mut airplanes,
+            &[
+                0x8d, 0x40, 0x62, 0x1d, 0x58, 0xc3, 0x82, 0xd6, 0x90, 0xc8, 0xac, 0x28, 0x63, 0xa7,
+            ],
+        );
+        action(
+            &mut airplanes,
+            &[
+                0x8d, 0x40, 0x62, 0x1d, 0x58, 0xc3, 0x86, 0x43, 0x5c, 0xc4, 0x12, 0x69, 0x2a, 0xd6,
+            ],
+        );
+        action(
+            &mut airplanes,
+            &[
+                0x8d, 0x40, 0x62, 0x1d, 0x99, 0x44, 0x09, 0x94, 0x08, 0x38, 0x17, 0x45, 0x50, 0xb1,
+            ],
+        );
+
+        let mut stats = PerformanceStats::new(5000, Duration::from_secs(10));
+        stats.update(Duration::from_secs(0), &airplanes);
+        // within the interval
+        stats.update(Duration::from_secs(5), &airplanes);
+        stats.update(Duration::from_secs(10), &airplanes);
+
+        let bands: Vec<_> = stats.bands().collect();
+        assert_eq!(bands.len(), 1);
+        let ((category, floor), speeds) = bands[0];
+        assert_eq!((*category, *floor), (None, 35000));
+        assert_eq!(speeds.samples, 2);
+
+        stats.reset();
+        assert_eq!(stats.bands().count(), 0);
+    }
+}
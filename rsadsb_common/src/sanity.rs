@@ -0,0 +1,154 @@
+//! Sanity bounds of decoded values
+//!
+//! A frame can pass the parity check and still carry values no aircraft could report, from a
+//! corrupted frame that happens to check out or a misbehaving transponder. This checks the
+//! altitude, speed and vertical rate of ADS-B messages against [`Bounds`], so the values of a
+//! [`Suspect`] frame can be kept out of tracking. The default bounds fit airliners and general
+//! aviation, and can be widened for unusual traffic such as balloons or rockets.
+
+#[cfg(feature = "alloc")]
+use alloc::fmt;
+#[cfg(feature = "alloc")]
+use core::{
+    clone::Clone, default::Default, fmt::Debug, marker::Copy, option::Option::Some,
+    prelude::rust_2021::derive, write,
+};
+
+use adsb_deku::adsb::ME;
+use adsb_deku::{Frame, DF};
+
+/// Decoded value outside of the [`Bounds`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Suspect {
+    /// Altitude in feet
+    Altitude(i32),
+    /// Ground speed in knots
+    Speed(f64),
+    /// Vertical rate in feet per minute
+    VerticalRate(i16),
+}
+
+impl fmt::Display for Suspect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Altitude(altitude) => write!(f, "altitude: {altitude} ft"),
+            Self::Speed(speed) => write!(f, "speed: {speed:.0} kt"),
+            Self::VerticalRate(rate) => write!(f, "vertical rate: {rate} ft/min"),
+        }
+    }
+}
+
+/// Range of plausible decoded values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    /// Lowest altitude in feet
+    pub min_altitude: i32,
+    /// Highest altitude in feet
+    pub max_altitude: i32,
+    /// Highest ground speed in knots
+    pub max_speed: f64,
+    /// Highest climb or descent rate in feet per minute
+    pub max_vertical_rate: u16,
+}
+
+impl Default for Bounds {
+    fn default() -> Self {
+        Self {
+            min_altitude: -1500,
+            max_altitude: 60000,
+            max_speed: 1200.0,
+            max_vertical_rate: 20000,
+        }
+    }
+}
+
+impl Bounds {
+    /// Bounds for high-altitude balloons and suborbital launches
+    #[must_use]
+    pub fn high_altitude() -> Self {
+        Self {
+            min_altitude: -1500,
+            max_altitude: 150_000,
+            max_speed: 5000.0,
+            max_vertical_rate: u16::MAX,
+        }
+    }
+
+    /// Check `frame`, returning the first value outside of these bounds
+    ///
+    /// Only the values used for tracking, from the airborne position and velocity ADS-B messages,
+    /// are checked.
+    #[must_use]
+    pub fn check(&self, frame: &Frame) -> Option<Suspect> {
+        let me = match &frame.df {
+            DF::ADSB(adsb) => &adsb.me,
+            _ => return None,
+        };
+        match me {
+            ME::AirbornePositionBaroAltitude(altitude)
+            | ME::AirbornePositionGNSSAltitude(altitude) => {
+                let altitude = i32::from(altitude.alt?);
+                (altitude < self.min_altitude || altitude > self.max_altitude)
+                    .then(|| Suspect::Altitude(altitude))
+            },
+            ME::AirborneVelocity(velocity) => {
+                let (_, speed, rate) = velocity.calculate()?;
+                if speed > self.max_speed {
+                    Some(Suspect::Speed(speed))
+                } else if rate.unsigned_abs() > self.max_vertical_rate {
+                    Some(Suspect::VerticalRate(rate))
+                } else {
+                    None
+                }
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use adsb_deku::deku::DekuContainerRead;
+
+    use super::*;
+
+    fn frame(hex: &str) -> Frame {
+        let bytes: alloc::vec::Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        Frame::from_bytes((&bytes, 0)).unwrap().1
+    }
+
+    #[test]
+    fn altitude() {
+        // 38000 ft
+        let frame = frame("8D40621D58C382D690C8AC2863A7");
+        assert_eq!(Bounds::default().check(&frame), None);
+
+        let bounds = Bounds {
+            max_altitude: 30000,
+            ..Bounds::default()
+        };
+        assert_eq!(bounds.check(&frame), Some(Suspect::Altitude(38000)));
+    }
+
+    #[test]
+    fn velocity() {
+        // 159 kt, -832 ft/min
+        let frame = frame("8D485020994409940838175B284F");
+        assert_eq!(Bounds::default().check(&frame), None);
+
+        let bounds = Bounds {
+            max_vertical_rate: 500,
+            ..Bounds::default()
+        };
+        assert_eq!(bounds.check(&frame), Some(Suspect::VerticalRate(-832)));
+
+        let bounds = Bounds {
+            max_speed: 100.0,
+            ..Bounds::default()
+        };
+        assert!(matches!(bounds.check(&frame), Some(Suspect::Speed(_))));
+    }
+}
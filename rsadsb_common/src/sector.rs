@@ -0,0 +1,246 @@
+//! Traffic counting in named airspace sectors
+//!
+//! A [`Sector`] is a polygon of (lat, long) points between a floor and a ceiling altitude, such
+//! as the approach path over a neighbourhood. [`SectorCounter`] follows the aircraft inside every
+//! sector, counting the entries, the time spent inside by all aircraft, and the peak amount of
+//! aircraft inside at the same time. The counts are meant to be exported and reset periodically,
+//! such as for community noise and airspace usage studies.
+//!
+//! All times are since an arbitrary epoch, such as the start of the program, and updates are
+//! expected in chronological order.
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
+use core::time::Duration;
+#[cfg(feature = "alloc")]
+use core::{
+    clone::Clone,
+    default::Default,
+    fmt::Debug,
+    iter::Iterator,
+    option::Option::{self, None, Some},
+    prelude::rust_2021::derive,
+};
+
+use adsb_deku::{cpr, ICAO};
+
+use crate::Airplanes;
+
+/// Polygon of (lat, long) points between `floor` and `ceiling`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sector {
+    pub name: String,
+    /// Corners of the sector in order, the last corner is connected to the first
+    pub polygon: Vec<(f64, f64)>,
+    /// Lowest altitude inside the sector in ft
+    pub floor: u16,
+    /// Highest altitude inside the sector in ft
+    pub ceiling: u16,
+}
+
+impl Sector {
+    /// `position` at `altitude` (ft) is inside the sector
+    ///
+    /// The edges are straight lines of latitude and longitude, which is accurate enough for
+    /// sectors of a few tens of NM.
+    #[must_use]
+    pub fn contains(&self, position: cpr::Position, altitude: u16) -> bool {
+        if altitude < self.floor || altitude > self.ceiling {
+            return false;
+        }
+        let (lat, long) = (position.latitude, position.longitude);
+        // count the edges crossed by a ray from the position towards increasing longitude
+        let mut inside = false;
+        let mut previous = match self.polygon.last() {
+            Some(corner) => *corner,
+            None => return false,
+        };
+        for &corner in &self.polygon {
+            let ((lat_a, long_a), (lat_b, long_b)) = (previous, corner);
+            if (lat_a > lat) != (lat_b > lat) {
+                let crossing = long_a + (lat - lat_a) / (lat_b - lat_a) * (long_b - long_a);
+                if long < crossing {
+                    inside = !inside;
+                }
+            }
+            previous = corner;
+        }
+        inside
+    }
+}
+
+/// Counts of a [`Sector`] since the last [`SectorCounter::reset`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SectorStats {
+    /// Amount of times an aircraft entered the sector
+    pub entries: u64,
+    /// Time spent inside the sector, added up for all aircraft
+    pub occupancy: Duration,
+    /// Most aircraft inside the sector at the same time
+    pub peak: usize,
+    /// Aircraft currently inside the sector
+    pub inside: BTreeSet<ICAO>,
+}
+
+/// Counts traffic in every [`Sector`], see the [module documentation](self)
+#[derive(Debug, Clone)]
+pub struct SectorCounter {
+    sectors: Vec<(Sector, SectorStats)>,
+    /// Time of the last [`SectorCounter::update`]
+    last_update: Option<Duration>,
+}
+
+impl SectorCounter {
+    #[must_use]
+    pub fn new(sectors: Vec<Sector>) -> Self {
+        Self {
+            sectors: sectors
+                .into_iter()
+                .map(|sector| (sector, SectorStats::default()))
+                .collect(),
+            last_update: None,
+        }
+    }
+
+    /// Update the aircraft inside every sector at `now`
+    ///
+    /// The aircraft inside a sector since the last update are counted as inside until `now`.
+    /// Aircraft without a position are not inside any sector.
+    pub fn update(&mut self, now: Duration, airplanes: &Airplanes) {
+        let elapsed = self
+            .last_update
+            .map_or(Duration::ZERO, |last| now.saturating_sub(last));
+        self.last_update = Some(now);
+
+        let positions: Vec<_> = airplanes
+            .keys()
+            .filter_map(|icao| {
+                airplanes
+                    .aircraft_details(*icao)
+                    .map(|details| (*icao, details.position, details.altitude))
+            })
+            .collect();
+        for (sector, stats) in &mut self.sectors {
+            // PANIC: the amount of aircraft fits in a u32
+            stats.occupancy += elapsed * u32::try_from(stats.inside.len()).unwrap();
+            let inside: BTreeSet<ICAO> = positions
+                .iter()
+                .filter(|(_, position, altitude)| sector.contains(*position, *altitude))
+                .map(|(icao, _, _)| *icao)
+                .collect();
+            stats.entries += inside.difference(&stats.inside).count() as u64;
+            stats.peak = stats.peak.max(inside.len());
+            stats.inside = inside;
+        }
+    }
+
+    /// Sectors with their counts since the last reset
+    pub fn sectors(&self) -> impl Iterator<Item = (&Sector, &SectorStats)> {
+        self.sectors.iter().map(|(sector, stats)| (sector, stats))
+    }
+
+    /// Start counting again, such as after exporting the counts of a period
+    ///
+    /// Aircraft still inside a sector are not counted as a new entry, and start the peak of the
+    /// next period.
+    pub fn reset(&mut self) {
+        for (_, stats) in &mut self.sectors {
+            let inside = core::mem::take(&mut stats.inside);
+            *stats = SectorStats {
+                peak: inside.len(),
+                inside,
+                ..SectorStats::default()
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Sector {
+        Sector {
+            name: "approach".into(),
+            polygon: vec![(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
+            floor: 1000,
+            ceiling: 5000,
+        }
+    }
+
+    fn position(latitude: f64, longitude: f64) -> cpr::Position {
+        cpr::Position {
+            latitude,
+            longitude,
+        }
+    }
+
+    #[test]
+    fn contains() {
+        let sector = square();
+        assert!(sector.contains(position(0.5, 0.5), 3000));
+        assert!(!sector.contains(position(0.5, 0.5), 500));
+        assert!(!sector.contains(position(0.5, 0.5), 6000));
+        assert!(!sector.contains(position(1.5, 0.5), 3000));
+        assert!(!sector.contains(position(0.5, -0.5), 3000));
+
+        // concave L shape, without the upper right quarter
+        let sector = Sector {
+            polygon: vec![
+                (0.0, 0.0),
+                (0.0, 1.0),
+                (0.5, 1.0),
+                (0.5, 0.5),
+                (1.0, 0.5),
+                (1.0, 0.0),
+            ],
+            ..square()
+        };
+        assert!(sector.contains(position(0.25, 0.75), 3000));
+        assert!(sector.contains(position(0.75, 0.25), 3000));
+        assert!(!sector.contains(position(0.75, 0.75), 3000));
+    }
+
+    fn set_position(airplanes: &mut Airplanes, icao: ICAO, position: cpr::Position, altitude: u16) {
+        let (state, _) = airplanes.entry_or_insert(icao);
+        state.coords.position = Some(position);
+        state.coords.kilo_distance = Some(0.0);
+        state.coords.uat_altitude = Some(altitude);
+    }
+
+    #[test]
+    fn counts() {
+        let mut counter = SectorCounter::new(vec![square()]);
+        let at = Duration::from_secs;
+        let mut airplanes = Airplanes::new();
+        counter.update(at(0), &airplanes);
+
+        let a = ICAO([0xa0, 0x00, 0x01]);
+        let b = ICAO([0xa0, 0x00, 0x02]);
+        set_position(&mut airplanes, a, position(0.5, 0.5), 3000);
+        counter.update(at(10), &airplanes);
+        set_position(&mut airplanes, b, position(0.2, 0.2), 2000);
+        counter.update(at(20), &airplanes);
+        // a leaves and enters again
+        set_position(&mut airplanes, a, position(0.5, 0.5), 9000);
+        counter.update(at(30), &airplanes);
+        set_position(&mut airplanes, a, position(0.5, 0.5), 3000);
+        counter.update(at(40), &airplanes);
+
+        let (_, stats) = counter.sectors().next().unwrap();
+        assert_eq!(stats.entries, 3);
+        assert_eq!(stats.peak, 2);
+        // a for 10..30, b for 20..40
+        assert_eq!(stats.occupancy, at(40));
+
+        counter.reset();
+        let (_, stats) = counter.sectors().next().unwrap();
+        assert_eq!(stats.entries, 0);
+        assert_eq!(stats.peak, 2);
+        assert_eq!(stats.occupancy, Duration::ZERO);
+        counter.update(at(50), &airplanes);
+        let (_, stats) = counter.sectors().next().unwrap();
+        assert_eq!(stats.entries, 0);
+        assert_eq!(stats.occupancy, at(20));
+    }
+}
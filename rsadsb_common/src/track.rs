@@ -0,0 +1,151 @@
+//! Resampling of recorded tracks at fixed time steps
+//!
+//! Positions of an aircraft are recorded whenever a new position is decoded, which happens at
+//! irregular times. Analytics consumers usually want positions at fixed intervals instead, so this
+//! linearly interpolates between the recorded positions.
+
+use std::time::{Duration, SystemTime};
+
+use adsb_deku::cpr;
+
+use crate::{AirplaneCoor, AirplaneState};
+
+/// Interpolated position of an aircraft
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackSample {
+    pub time: SystemTime,
+    pub position: cpr::Position,
+    /// Barometric altitude, only interpolated if both surrounding positions contain one
+    pub altitude: Option<u16>,
+}
+
+impl AirplaneState {
+    /// Resample the track and current position of this aircraft every `step`, see [`resample`]
+    #[must_use]
+    pub fn resampled_track(&self, step: Duration) -> Vec<TrackSample> {
+        let mut coords = self.track.clone().unwrap_or_default();
+        coords.push(self.coords);
+        resample(&coords, step)
+    }
+}
+
+/// Resample `coords` every `step`, starting at the time of the first position
+///
+/// Only coordinates with both a position and a time are used, and are expected to be in
+/// chronological order. Returns an empty `Vec` if `step` is zero.
+#[must_use]
+pub fn resample(coords: &[AirplaneCoor], step: Duration) -> Vec<TrackSample> {
+    let points: Vec<(SystemTime, cpr::Position, Option<u16>)> = coords
+        .iter()
+        .filter_map(|coor| Some((coor.last_time?, coor.position?, coor.altitude())))
+        .collect();
+
+    let mut samples = vec![];
+    let (first, last) = match (points.first(), points.last()) {
+        (Some(first), Some(last)) if !step.is_zero() => (first.0, last.0),
+        _ => return samples,
+    };
+
+    let mut time = first;
+    let mut index = 0;
+    while time <= last {
+        // find the points surrounding time
+        while index + 1 < points.len() && points[index + 1].0 < time {
+            index += 1;
+        }
+        let (start_time, start, start_alt) = points[index];
+        let (end_time, end, end_alt) = points[(index + 1).min(points.len() - 1)];
+
+        let span = end_time.duration_since(start_time).unwrap_or_default();
+        let fraction = if span.is_zero() {
+            0.0
+        } else {
+            time.duration_since(start_time)
+                .unwrap_or_default()
+                .as_secs_f64()
+                / span.as_secs_f64()
+        };
+
+        samples.push(TrackSample {
+            time,
+            position: interpolate_position(start, end, fraction),
+            altitude: match (start_alt, end_alt) {
+                (Some(start), Some(end)) => {
+                    let start = f64::from(start);
+                    let end = f64::from(end);
+                    Some((start + (end - start) * fraction) as u16)
+                },
+                _ => None,
+            },
+        });
+
+        time += step;
+    }
+
+    samples
+}
+
+fn interpolate_position(start: cpr::Position, end: cpr::Position, fraction: f64) -> cpr::Position {
+    // take the shortest way around the antimeridian
+    let mut longitude_delta = end.longitude - start.longitude;
+    if longitude_delta > 180.0 {
+        longitude_delta -= 360.0;
+    } else if longitude_delta < -180.0 {
+        longitude_delta += 360.0;
+    }
+    let mut longitude = start.longitude + longitude_delta * fraction;
+    if longitude > 180.0 {
+        longitude -= 360.0;
+    } else if longitude < -180.0 {
+        longitude += 360.0;
+    }
+
+    cpr::Position {
+        latitude: start.latitude + (end.latitude - start.latitude) * fraction,
+        longitude,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coor(secs: u64, latitude: f64, longitude: f64) -> AirplaneCoor {
+        AirplaneCoor {
+            position: Some(cpr::Position {
+                latitude,
+                longitude,
+            }),
+            last_time: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs)),
+            ..AirplaneCoor::default()
+        }
+    }
+
+    #[test]
+    fn resample_track() {
+        let coords = [coor(0, 10.0, 179.0), coor(4, 14.0, -179.0)];
+        let samples = resample(&coords, Duration::from_secs(1));
+        let positions: Vec<(f64, f64)> = samples
+            .iter()
+            .map(|sample| (sample.position.latitude, sample.position.longitude))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![
+                (10.0, 179.0),
+                (11.0, 179.5),
+                (12.0, 180.0),
+                (13.0, -179.5),
+                (14.0, -179.0)
+            ]
+        );
+        assert_eq!(
+            samples[4].time,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(4)
+        );
+        assert!(samples.iter().all(|sample| sample.altitude.is_none()));
+
+        assert!(resample(&coords, Duration::ZERO).is_empty());
+        assert!(resample(&[], Duration::from_secs(1)).is_empty());
+    }
+}
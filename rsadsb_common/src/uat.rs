@@ -0,0 +1,152 @@
+//! UAT (978 MHz) ADS-B downlink frames
+//!
+//! In the United States, aircraft flying below 18000 ft can broadcast ADS-B on the 978 MHz UAT
+//! link instead of, or along with, 1090 MHz extended squitter. This decodes the header and state
+//! vector of the downlink frames demodulated by dump978, so the targets can be tracked and
+//! correlated with the 1090 targets by address, see [`crate::Airplanes::action_uat`].
+//!
+//! reference: dump978 `uat_decode.c`
+
+#[cfg(feature = "alloc")]
+use core::{
+    clone::Clone,
+    cmp::PartialEq,
+    fmt::Debug,
+    marker::Copy,
+    option::Option::{self, None, Some},
+    prelude::rust_2021::derive,
+};
+
+use adsb_deku::{cpr, ICAO};
+
+/// Length in bytes of a Basic UAT ADS-B frame, payload type 0
+pub const BASIC_LEN: usize = 18;
+
+/// Length in bytes of a Long UAT ADS-B frame, payload types 1..=31
+pub const LONG_LEN: usize = 34;
+
+/// Header and state vector of a UAT ADS-B downlink frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UatFrame {
+    /// Payload type code, 0 for a Basic frame
+    pub payload_type: u8,
+    /// Address qualifier, 0 for an ADS-B target with an ICAO address, see [`UatFrame::icao`]
+    pub address_qualifier: u8,
+    pub address: ICAO,
+    pub position: Option<cpr::Position>,
+    /// Altitude in feet
+    pub altitude: Option<i32>,
+    /// `altitude` is a GNSS altitude, instead of a barometric altitude
+    pub gnss_altitude: bool,
+}
+
+impl UatFrame {
+    /// Decode `bytes` of a Basic or Long frame, without the Reed-Solomon parity
+    ///
+    /// Returns `None` if `bytes` isn't the length of a Basic or Long frame, or the payload type
+    /// doesn't match the length.
+    #[must_use]
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let payload_type = bytes.first()? >> 3;
+        match (bytes.len(), payload_type) {
+            (BASIC_LEN, 0) => (),
+            (LONG_LEN, 1..=31) => (),
+            _ => return None,
+        }
+
+        let raw_lat =
+            u32::from(bytes[4]) << 15 | u32::from(bytes[5]) << 7 | u32::from(bytes[6]) >> 1;
+        let raw_lon = u32::from(bytes[6] & 0x01) << 23
+            | u32::from(bytes[7]) << 15
+            | u32::from(bytes[8]) << 7
+            | u32::from(bytes[9]) >> 1;
+        let position = (raw_lat != 0 || raw_lon != 0).then(|| {
+            let latitude = f64::from(raw_lat) * 360.0 / 16_777_216.0;
+            let longitude = f64::from(raw_lon) * 360.0 / 16_777_216.0;
+            cpr::Position {
+                latitude: if latitude > 90.0 {
+                    latitude - 180.0
+                } else {
+                    latitude
+                },
+                longitude: if longitude > 180.0 {
+                    longitude - 360.0
+                } else {
+                    longitude
+                },
+            }
+        });
+
+        let raw_alt = i32::from(bytes[10]) << 4 | i32::from(bytes[11] & 0xf0) >> 4;
+        let altitude = (raw_alt != 0).then(|| (raw_alt - 1) * 25 - 1000);
+
+        Some(Self {
+            payload_type,
+            address_qualifier: bytes[0] & 0x07,
+            address: ICAO([bytes[1], bytes[2], bytes[3]]),
+            position,
+            altitude,
+            gnss_altitude: bytes[9] & 0x01 == 1,
+        })
+    }
+
+    /// ICAO address of an ADS-B target, which is the same address as on 1090 MHz
+    ///
+    /// Returns `None` for the other address qualifiers, such as a self-assigned address or a
+    /// TIS-B target.
+    #[must_use]
+    pub const fn icao(&self) -> Option<ICAO> {
+        if self.address_qualifier == 0 {
+            Some(self.address)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Basic frame of a1b2c3 at 40.0, -75.5 and 5500 ft barometric
+    const BASIC: [u8; BASIC_LEN] = [
+        0x00, 0xa1, 0x23, 0x45, 0x38, 0xe3, 0x8f, 0x94, 0x9f, 0x4a, 0x10, 0x50, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn basic() {
+        let frame = UatFrame::from_bytes(&BASIC).unwrap();
+        assert_eq!(frame.payload_type, 0);
+        assert_eq!(frame.icao(), Some(ICAO([0xa1, 0x23, 0x45])));
+        let position = frame.position.unwrap();
+        assert!((position.latitude - 40.0).abs() < 0.0001);
+        assert!((position.longitude - -75.5).abs() < 0.0001);
+        assert_eq!(frame.altitude, Some(5500));
+        assert!(!frame.gnss_altitude);
+    }
+
+    #[test]
+    fn address_qualifier() {
+        let mut bytes = BASIC;
+        bytes[0] = 0x02;
+        let frame = UatFrame::from_bytes(&bytes).unwrap();
+        assert_eq!(frame.address_qualifier, 2);
+        assert_eq!(frame.icao(), None);
+    }
+
+    #[test]
+    fn length() {
+        assert_eq!(UatFrame::from_bytes(&BASIC[..17]), None);
+
+        // long payload type in a basic frame
+        let mut bytes = BASIC;
+        bytes[0] = 0x08;
+        assert_eq!(UatFrame::from_bytes(&bytes), None);
+
+        let mut bytes = [0; LONG_LEN];
+        bytes[..BASIC_LEN].copy_from_slice(&BASIC);
+        bytes[0] = 0x08;
+        assert_eq!(UatFrame::from_bytes(&bytes).unwrap().payload_type, 1);
+    }
+}